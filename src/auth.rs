@@ -0,0 +1,120 @@
+//! Headers, cookies and credentials for reaching pages behind authentication
+//! or a staging gate, given with `--header`, `--cookie`, `--basic-auth` and
+//! `--bearer`. They are only sent to the scanned site, never to third parties.
+
+use crate::psl;
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, COOKIE};
+use reqwest::RequestBuilder;
+use url::Url;
+
+#[derive(Debug, Clone, Default)]
+pub struct SiteAccess {
+    headers: HeaderMap,
+    cookies: Vec<(String, String)>,
+    basic_auth: Option<(String, String)>,
+    bearer: Option<String>,
+}
+
+impl SiteAccess {
+    /// Parse `Name: value` headers, `name=value` cookies, `user:password`
+    /// and a bearer token as given on the command line.
+    pub fn parse(
+        headers: &[String],
+        cookies: &[String],
+        basic_auth: Option<&str>,
+        bearer: Option<&str>,
+    ) -> Result<Self> {
+        let mut access = SiteAccess::default();
+        for header in headers {
+            let Some((name, value)) = header.split_once(':') else {
+                bail!("--header '{}' is not in the form 'Name: value'", header);
+            };
+            let name = HeaderName::from_bytes(name.trim().as_bytes())
+                .with_context(|| format!("Invalid header name in --header '{}'", header))?;
+            let value = HeaderValue::from_str(value.trim())
+                .with_context(|| format!("Invalid header value in --header '{}'", header))?;
+            access.headers.append(name, value);
+        }
+        for cookie in cookies {
+            let Some((name, value)) = cookie.split_once('=') else {
+                bail!("--cookie '{}' is not in the form 'name=value'", cookie);
+            };
+            access
+                .cookies
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+        if let Some(credentials) = basic_auth {
+            let (user, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+            access.basic_auth = Some((user.to_string(), password.to_string()));
+        }
+        access.bearer = bearer.map(str::to_string);
+        Ok(access)
+    }
+
+    /// Add everything to a request for `url` if it may receive them (see
+    /// `may_receive`).
+    pub fn apply(&self, mut request: RequestBuilder, url: &Url, site: &Url) -> RequestBuilder {
+        if !may_receive(url, site) {
+            return request;
+        }
+        request = request.headers(self.headers_for(url, site));
+        if !self.cookies.is_empty() {
+            let header = self
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join("; ");
+            request = request.header(COOKIE, header);
+        }
+        request
+    }
+
+    /// The `--header` headers and the `Authorization` header for a request
+    /// to `url`, or none if it may not receive them (see `may_receive`).
+    /// Cookies are left out.
+    pub fn headers_for(&self, url: &Url, site: &Url) -> HeaderMap {
+        if !may_receive(url, site) {
+            return HeaderMap::new();
+        }
+        let mut headers = self.headers.clone();
+        let authorization = match (&self.basic_auth, &self.bearer) {
+            (_, Some(token)) => Some(format!("Bearer {}", token)),
+            (Some((user, password)), None) => {
+                Some(format!("Basic {}", STANDARD.encode(format!("{}:{}", user, password))))
+            }
+            (None, None) => None,
+        };
+        if let Some(value) = authorization.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            headers.insert(AUTHORIZATION, value);
+        }
+        headers
+    }
+
+    pub fn cookies(&self) -> &[(String, String)] {
+        &self.cookies
+    }
+
+    /// Whether a cookie is one we sent, rather than one the site set.
+    pub fn is_own_cookie(&self, name: &str) -> bool {
+        self.cookies.iter().any(|(own, _)| own == name)
+    }
+}
+
+/// Whether a request for `url` may carry what is given for `site`: it must be
+/// on the same site (registrable domain), and over HTTPS unless the site
+/// itself was given as plain HTTP, so a downgrade never leaks them.
+fn may_receive(url: &Url, site: &Url) -> bool {
+    if url.scheme() != "https" && url.scheme() != site.scheme() {
+        return false;
+    }
+    match (url.host_str(), site.host_str()) {
+        (Some(host), Some(site_host)) => {
+            psl::registrable_domain(host) == psl::registrable_domain(site_host)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,305 @@
+//! Locating the consent banner in headless mode and capturing evidence of
+//! the choices it offers.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use chromiumoxide::element::Element;
+use chromiumoxide::Page;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Consent management platforms and the element holding their first layer.
+/// Generic fallbacks come last so a recognized CMP is always named.
+const BANNER_SELECTORS: &[(Option<&str>, &str)] = &[
+    (Some("OneTrust"), "#onetrust-banner-sdk"),
+    (Some("Cookiebot"), "#CybotCookiebotDialog"),
+    (Some("Didomi"), "#didomi-notice"),
+    (Some("Quantcast Choice"), ".qc-cmp2-container"),
+    (Some("TrustArc"), "#truste-consent-track"),
+    (Some("Usercentrics"), "#usercentrics-cmp-ui"),
+    (Some("Osano"), ".osano-cm-dialog"),
+    (Some("CookieYes"), ".cky-consent-container"),
+    (Some("Complianz"), ".cmplz-cookiebanner"),
+    (Some("Iubenda"), "#iubenda-cs-banner"),
+    (Some("Termly"), "[data-tid=\"banner-container\"]"),
+    (Some("Klaro"), ".klaro .cookie-notice"),
+    (Some("Sourcepoint"), "[id^=\"sp_message_container\"]"),
+    (None, "[id*=\"cookie\" i][id*=\"banner\" i]"),
+    (None, "[class*=\"cookie\" i][class*=\"banner\" i]"),
+    (None, "[id*=\"consent\" i]"),
+    (None, "[class*=\"consent\" i]"),
+    (None, "[aria-label*=\"cookie\" i]"),
+    (None, "[role=\"dialog\"]"),
+];
+
+/// Clickable elements whose labels make up the banner's choices
+const BUTTON_SELECTOR: &str =
+    "button, [role=\"button\"], a, input[type=\"button\"], input[type=\"submit\"]";
+
+/// The two first-layer answers a visitor can give.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsentChoice {
+    Accept,
+    Reject,
+}
+
+impl ConsentChoice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsentChoice::Accept => "accept",
+            ConsentChoice::Reject => "reject",
+        }
+    }
+
+    /// Buttons of the recognized CMPs, tried before guessing from labels
+    fn selectors(&self) -> &'static [&'static str] {
+        match self {
+            ConsentChoice::Accept => &[
+                "#onetrust-accept-btn-handler",
+                "#CybotCookiebotDialogBodyLevelButtonLevelOptinAllowAll",
+                "#didomi-notice-agree-button",
+                ".qc-cmp2-summary-buttons button[mode=\"primary\"]",
+                "[data-testid=\"uc-accept-all-button\"]",
+                ".cky-btn-accept",
+                ".cmplz-accept",
+                ".iubenda-cs-accept-btn",
+            ],
+            ConsentChoice::Reject => &[
+                "#onetrust-reject-all-handler",
+                "#CybotCookiebotDialogBodyButtonDecline",
+                "#didomi-notice-disagree-button",
+                ".qc-cmp2-summary-buttons button[mode=\"secondary\"]",
+                "[data-testid=\"uc-deny-all-button\"]",
+                ".cky-btn-reject",
+                ".cmplz-deny",
+                ".iubenda-cs-reject-btn",
+            ],
+        }
+    }
+}
+
+/// Label fragments of buttons that accept everything. "Allow" and "consent"
+/// only count as phrases, since on their own they also label "Allow
+/// selection" and "Manage consent".
+const ACCEPT_LABELS: &[&str] = &[
+    "accept",
+    "agree",
+    "allow all",
+    "allow cookies",
+    "i consent",
+    "got it",
+    "i understand",
+    "ok",
+    "akzeptieren",
+    "zustimmen",
+    "accepter",
+    "aceptar",
+    "accetta",
+];
+
+/// Label fragments of buttons that decline optional cookies. These win over
+/// accept labels so "Accept only necessary" counts as a rejection.
+const REJECT_LABELS: &[&str] = &[
+    "reject",
+    "decline",
+    "deny",
+    "refuse",
+    "disagree",
+    "necessary only",
+    "only necessary",
+    "essential only",
+    "only essential",
+    "continue without",
+    "ablehnen",
+    "refuser",
+    "rechazar",
+    "rifiuta",
+];
+
+/// What the consent banner showed on first load.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentBanner {
+    /// The consent management platform, when recognized
+    pub cmp: Option<String>,
+    /// The CSS selector that matched the banner
+    pub selector: String,
+    pub text: String,
+    /// Labels of the buttons and links offered on the first layer
+    pub buttons: Vec<String>,
+    /// Where the cropped screenshot was saved, if an evidence directory was given
+    pub screenshot: Option<PathBuf>,
+    /// Seconds since the Unix epoch
+    pub captured_at: u64,
+}
+
+/// Find the first visible banner on the page and, when `evidence_dir` is
+/// set, save its screenshot and details there.
+pub async fn capture(
+    page: &Page,
+    page_url: &str,
+    evidence_dir: Option<&Path>,
+) -> Result<Option<ConsentBanner>> {
+    let Some((cmp, selector, element)) = find_banner(page).await else {
+        return Ok(None);
+    };
+
+    let text = element
+        .inner_text()
+        .await
+        .ok()
+        .flatten()
+        .map(|t| collapse_whitespace(&t))
+        .unwrap_or_default();
+
+    let mut buttons = Vec::new();
+    for button in element
+        .find_elements(BUTTON_SELECTOR)
+        .await
+        .unwrap_or_default()
+    {
+        let label = element_label(&button).await;
+        if !label.is_empty() && !buttons.contains(&label) {
+            buttons.push(label);
+        }
+    }
+
+    let mut banner = ConsentBanner {
+        cmp: cmp.map(str::to_string),
+        selector: selector.to_string(),
+        text,
+        buttons,
+        screenshot: None,
+        captured_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    if let Some(dir) = evidence_dir {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create evidence directory {}", dir.display()))?;
+        let stem = evidence_stem(page_url);
+
+        let png = element
+            .screenshot(CaptureScreenshotFormat::Png)
+            .await
+            .context("Failed to screenshot the consent banner")?;
+        let png_path = dir.join(format!("{}-banner.png", stem));
+        std::fs::write(&png_path, png)
+            .with_context(|| format!("Failed to write {}", png_path.display()))?;
+        banner.screenshot = Some(png_path);
+
+        let json_path = dir.join(format!("{}-banner.json", stem));
+        let details = serde_json::json!({ "url": page_url, "banner": &banner });
+        std::fs::write(&json_path, serde_json::to_string_pretty(&details)?)
+            .with_context(|| format!("Failed to write {}", json_path.display()))?;
+    }
+
+    Ok(Some(banner))
+}
+
+/// The first selector that matches an element with a visible area.
+pub async fn find_banner(page: &Page) -> Option<(Option<&'static str>, &'static str, Element)> {
+    for (cmp, selector) in BANNER_SELECTORS {
+        let Ok(elements) = page.find_elements(*selector).await else {
+            continue;
+        };
+        for element in elements {
+            if is_visible(&element).await {
+                return Some((*cmp, *selector, element));
+            }
+        }
+    }
+    None
+}
+
+/// The visible button that gives `choice`, with its label. Known CMP
+/// buttons are looked up directly, then the banner's buttons are matched by
+/// label.
+pub async fn find_choice(page: &Page, choice: ConsentChoice) -> Option<(String, Element)> {
+    for selector in choice.selectors() {
+        let Ok(element) = page.find_element(*selector).await else {
+            continue;
+        };
+        if is_visible(&element).await {
+            let label = element_label(&element).await;
+            return Some((label, element));
+        }
+    }
+
+    let (_, _, banner) = find_banner(page).await?;
+    for button in banner.find_elements(BUTTON_SELECTOR).await.ok()? {
+        let label = element_label(&button).await;
+        if label_choice(&label) == Some(choice) && is_visible(&button).await {
+            return Some((label, button));
+        }
+    }
+    None
+}
+
+/// Whether a button label accepts or rejects, if it is either.
+pub fn label_choice(label: &str) -> Option<ConsentChoice> {
+    let label = label.to_lowercase();
+    // Whole words only, so "ok" does not match "cookies"
+    let words: Vec<&str> = label.split(|c: char| !c.is_alphanumeric()).collect();
+    let has_word = |fragment: &&str| {
+        if fragment.contains(' ') {
+            label.contains(*fragment)
+        } else {
+            words.contains(fragment)
+        }
+    };
+    if REJECT_LABELS.iter().any(has_word) {
+        Some(ConsentChoice::Reject)
+    } else if ACCEPT_LABELS.iter().any(has_word) {
+        Some(ConsentChoice::Accept)
+    } else {
+        None
+    }
+}
+
+async fn is_visible(element: &Element) -> bool {
+    element
+        .bounding_box()
+        .await
+        .is_ok_and(|b| b.width > 1.0 && b.height > 1.0)
+}
+
+/// The button's text, or its value for `<input>` buttons.
+async fn element_label(element: &Element) -> String {
+    let label = match element.inner_text().await.ok().flatten() {
+        Some(label) if !label.trim().is_empty() => label,
+        _ => element
+            .attribute("value")
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default(),
+    };
+    collapse_whitespace(&label)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A file-name-safe name for the page, such as `example.com-pricing`.
+fn evidence_stem(page_url: &str) -> String {
+    let readable = match Url::parse(page_url) {
+        Ok(url) => format!("{}{}", url.host_str().unwrap_or("page"), url.path()),
+        Err(_) => page_url.to_string(),
+    };
+    let stem: String = readable
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    stem.trim_matches('-').to_string()
+}
@@ -0,0 +1,142 @@
+//! Comparing a run with a known-good snapshot of an earlier one, so a scan
+//! reports only the trackers and cookies that came or went since, and the
+//! cookies that lost their `Secure` attribute. The snapshot is the JSON
+//! report itself, so any `--format json` output can serve as one.
+
+use crate::notify::{self, Delta};
+use crate::output::json;
+use crate::{calculate_privacy_score, AnalysisResult};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub struct Baseline {
+    pub path: PathBuf,
+    /// Successful results of the snapshot by URL
+    sites: HashMap<String, serde_json::Value>,
+}
+
+/// How one site differs from its entry in the baseline.
+#[derive(Debug, Serialize)]
+pub struct BaselineDiff {
+    #[serde(flatten)]
+    pub delta: Delta,
+    /// Whether the baseline has the site at all; without it, every finding
+    /// counts as added
+    pub in_baseline: bool,
+    pub privacy_score: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_score: Option<u32>,
+    /// Cookies the baseline saw with `Secure` that are now set without it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub newly_insecure: Vec<String>,
+}
+
+impl BaselineDiff {
+    pub fn is_empty(&self) -> bool {
+        self.delta.is_empty() && self.newly_insecure.is_empty()
+    }
+
+    /// Whether the site got worse: new trackers or cookies, or cookies that
+    /// lost `Secure`.
+    pub fn regressed(&self) -> bool {
+        !self.delta.added.is_empty() || !self.newly_insecure.is_empty()
+    }
+}
+
+/// The diff-only report `--baseline` prints in place of the full one.
+#[derive(Serialize)]
+pub struct BaselineReport<'a> {
+    tool: &'static str,
+    version: &'static str,
+    baseline: String,
+    results: Vec<DiffEntry<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum DiffEntry<'a> {
+    Success(BaselineDiff),
+    Failure { url: &'a str, error: &'a str },
+}
+
+/// Write this run's JSON report to `path` as a baseline for later runs.
+pub fn save(path: &Path, rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<()> {
+    std::fs::write(path, json::render(rows)?)
+        .with_context(|| format!("Failed to write baseline {}", path.display()))
+}
+
+impl Baseline {
+    /// Read a baseline written by `--save-baseline` or `--format json`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        let report: serde_json::Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid baseline {}", path.display()))?;
+        let results = report["results"]
+            .as_array()
+            .with_context(|| format!("{} is not a recon JSON report", path.display()))?;
+        let sites = results
+            .iter()
+            .filter(|entry| entry.get("error").is_none())
+            .filter_map(|entry| Some((entry["url"].as_str()?.to_string(), entry.clone())))
+            .collect();
+        Ok(Baseline {
+            path: path.to_path_buf(),
+            sites,
+        })
+    }
+
+    /// What `result` added, dropped and made insecure compared with the
+    /// baseline's scan of the same URL.
+    pub fn diff(&self, result: &AnalysisResult) -> BaselineDiff {
+        let empty = serde_json::Value::Null;
+        let previous = self.sites.get(&result.url);
+        let before = previous.unwrap_or(&empty);
+        let was_secure: Vec<&str> = before["cookies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|cookie| cookie["secure"].as_bool() == Some(true))
+            .filter_map(|cookie| cookie["name"].as_str())
+            .collect();
+        let mut newly_insecure: Vec<String> = result
+            .cookies
+            .iter()
+            .filter(|c| !c.secure && was_secure.contains(&c.name.as_str()))
+            .map(|c| c.name.clone())
+            .collect();
+        newly_insecure.sort();
+        newly_insecure.dedup();
+        BaselineDiff {
+            delta: notify::delta(&result.url, before, result),
+            in_baseline: previous.is_some(),
+            privacy_score: calculate_privacy_score(result),
+            baseline_score: previous
+                .and_then(|p| p["privacy_score"].as_u64())
+                .map(|score| score as u32),
+            newly_insecure,
+        }
+    }
+
+    /// The diff-only report, with errors for the sites that failed.
+    pub fn report<'a>(
+        &self,
+        rows: &'a [(String, Result<&'a AnalysisResult, String>)],
+    ) -> BaselineReport<'a> {
+        let results = rows
+            .iter()
+            .map(|(url, result)| match result {
+                Ok(result) => DiffEntry::Success(self.diff(result)),
+                Err(e) => DiffEntry::Failure { url, error: e },
+            })
+            .collect();
+        BaselineReport {
+            tool: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            baseline: self.path.display().to_string(),
+            results,
+        }
+    }
+}
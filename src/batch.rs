@@ -0,0 +1,97 @@
+//! Scanning lists of URLs and summarizing the results across sites.
+
+use crate::{calculate_privacy_score, AnalysisResult};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of trackers listed in the "most common" summary
+const TOP_TRACKERS: usize = 5;
+
+/// Read one URL per line from `path` (or stdin for `-`), ignoring blank
+/// lines and `#` comments.
+pub fn read_url_list(path: &Path) -> Result<Vec<String>> {
+    let contents = if path.as_os_str() == "-" {
+        let mut buffer = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buffer)
+            .context("Failed to read URLs from stdin")?;
+        buffer
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read URL list {}", path.display()))?
+    };
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Headline numbers across every site in a multi-URL scan.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub sites: usize,
+    pub failed: usize,
+    pub average_score: Option<f64>,
+    pub best: Option<(String, u32)>,
+    pub worst: Option<(String, u32)>,
+    /// Tracker descriptions and how many sites load them
+    pub common_trackers: Vec<(String, usize)>,
+}
+
+impl BatchSummary {
+    pub fn from_rows(rows: &[(String, Result<&AnalysisResult, String>)]) -> Self {
+        let scored: Vec<(&str, u32)> = rows
+            .iter()
+            .filter_map(|(url, result)| {
+                result
+                    .as_ref()
+                    .ok()
+                    .map(|r| (url.as_str(), calculate_privacy_score(r)))
+            })
+            .collect();
+
+        let average_score = if scored.is_empty() {
+            None
+        } else {
+            Some(scored.iter().map(|(_, s)| *s as f64).sum::<f64>() / scored.len() as f64)
+        };
+        let best = scored
+            .iter()
+            .max_by_key(|(_, score)| *score)
+            .map(|(url, score)| (url.to_string(), *score));
+        let worst = scored
+            .iter()
+            .min_by_key(|(_, score)| *score)
+            .map(|(url, score)| (url.to_string(), *score));
+
+        let mut tracker_sites: HashMap<&str, usize> = HashMap::new();
+        for (_, result) in rows {
+            if let Ok(result) = result {
+                for tracker in &result.trackers {
+                    *tracker_sites.entry(&tracker.description).or_default() += 1;
+                }
+            }
+        }
+        let mut common_trackers: Vec<(String, usize)> = tracker_sites
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        common_trackers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        common_trackers.truncate(TOP_TRACKERS);
+
+        BatchSummary {
+            sites: rows.len(),
+            failed: rows.len() - scored.len(),
+            average_score,
+            best,
+            worst,
+            common_trackers,
+        }
+    }
+}
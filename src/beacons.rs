@@ -0,0 +1,115 @@
+//! Hyperlink `ping` attributes and `navigator.sendBeacon` calls. Both send
+//! a request the visitor never sees, on a click or as the page closes, so
+//! they carry click tracking past the script and image checks.
+
+use crate::rules::RuleSet;
+use crate::{is_third_party, INLINE_SCRIPT_SOURCE};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::sync::OnceLock;
+use url::Url;
+
+static SEND_BEACON: OnceLock<Regex> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeaconKind {
+    /// `<a ping="...">`
+    Ping,
+    /// `navigator.sendBeacon(...)`
+    SendBeacon,
+}
+
+impl BeaconKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BeaconKind::Ping => "ping",
+            BeaconKind::SendBeacon => "sendBeacon",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Beacon {
+    pub kind: BeaconKind,
+    /// Destination host; `None` when a script computes the URL at runtime
+    pub domain: Option<String>,
+    pub third_party: bool,
+    /// The tracker rule the destination matches, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<String>,
+    /// The page or script it was found in
+    pub source: String,
+    /// Links or calls found with this destination
+    pub count: usize,
+}
+
+/// Every ping and beacon destination in the page and in `fetched` scripts.
+pub fn find(html: &str, page_url: &Url, fetched: &[(Url, String)], rules: &RuleSet) -> Vec<Beacon> {
+    let document = Html::parse_document(html);
+    let base_domain = page_url.domain().unwrap_or("");
+    let mut beacons: Vec<Beacon> = Vec::new();
+
+    let mut add = |kind: BeaconKind, url: Option<Url>, source: &str| {
+        let domain = url.as_ref().and_then(|u| u.host_str()).map(str::to_string);
+        if domain.as_deref().is_some_and(|d| rules.is_ignored(d)) {
+            return;
+        }
+        if let Some(existing) = beacons
+            .iter_mut()
+            .find(|b| b.kind == kind && b.domain == domain && b.source == source)
+        {
+            existing.count += 1;
+            return;
+        }
+        let tracker = url.as_ref().and_then(|u| {
+            let url = u.as_str().to_lowercase();
+            rules
+                .trackers
+                .iter()
+                .find(|rule| rule.matches_url(&url))
+                .map(|rule| rule.description.clone())
+        });
+        beacons.push(Beacon {
+            kind,
+            third_party: domain
+                .as_deref()
+                .is_some_and(|d| is_third_party(d, base_domain)),
+            domain,
+            tracker,
+            source: source.to_string(),
+            count: 1,
+        });
+    };
+
+    // `ping` holds a space-separated list of URLs
+    let anchors = Selector::parse("a[ping], area[ping]").unwrap();
+    for element in document.select(&anchors) {
+        let ping = element.value().attr("ping").unwrap_or("");
+        for target in ping.split_whitespace() {
+            if let Ok(url) = page_url.join(target) {
+                add(BeaconKind::Ping, Some(url), page_url.as_str());
+            }
+        }
+    }
+
+    let send_beacon = SEND_BEACON.get_or_init(|| {
+        Regex::new(r#"navigator\s*\.\s*sendBeacon\s*\(\s*(?:["'`]([^"'`$]+)["'`])?"#).unwrap()
+    });
+    let mut scan = |content: &str, base: &Url, source: &str| {
+        for captures in send_beacon.captures_iter(content) {
+            let url = captures.get(1).and_then(|m| base.join(m.as_str()).ok());
+            add(BeaconKind::SendBeacon, url, source);
+        }
+    };
+    let inline = Selector::parse("script:not([src])").unwrap();
+    for element in document.select(&inline) {
+        scan(&element.inner_html(), page_url, INLINE_SCRIPT_SOURCE);
+    }
+    for (url, body) in fetched {
+        scan(body, page_url, url.as_str());
+    }
+
+    beacons
+}
@@ -0,0 +1,214 @@
+//! Downloading and locating a managed headless Chromium, so `--headless`
+//! works without a system Chrome.
+
+use crate::build_client;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Chrome for Testing's index of the current release of each channel
+const CHANNELS_URL: &str = "https://googlechromelabs.github.io/chrome-for-testing/last-known-good-versions-with-downloads.json";
+
+/// Where Chrome for Testing publishes its builds
+const DOWNLOAD_BASE: &str = "https://storage.googleapis.com/chrome-for-testing-public";
+
+/// The smallest Chrome for Testing build, with no UI
+const ARTIFACT: &str = "chrome-headless-shell";
+
+/// The installed browser, recorded so later runs use exactly this build.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstalledBrowser {
+    pub version: String,
+    pub platform: String,
+    pub executable: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Channels {
+    channels: HashMap<String, Channel>,
+}
+
+#[derive(Deserialize)]
+struct Channel {
+    version: String,
+    downloads: HashMap<String, Vec<Download>>,
+}
+
+#[derive(Deserialize)]
+struct Download {
+    platform: String,
+    url: String,
+}
+
+/// Download the headless shell, the current stable release unless `version`
+/// is given, and pin it as the browser for `--headless`.
+pub async fn install(version: Option<&str>) -> Result<InstalledBrowser> {
+    let platform = platform()?;
+    let client = build_client()?;
+
+    let (version, url) = match version {
+        Some(version) => (
+            version.to_string(),
+            format!(
+                "{}/{}/{}/{}-{}.zip",
+                DOWNLOAD_BASE, version, platform, ARTIFACT, platform
+            ),
+        ),
+        None => stable_download(&client, platform).await?,
+    };
+
+    println!("  Downloading {} {} for {}...", ARTIFACT, version, platform);
+    let response = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(600))
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    if !response.status().is_success() {
+        bail!("Download of {} failed: HTTP {}", url, response.status());
+    }
+    let archive = response.bytes().await?;
+
+    let dir = install_root()?.join(&version);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .with_context(|| format!("Failed to replace {}", dir.display()))?;
+    }
+    extract(&archive, &dir)?;
+
+    let executable = dir
+        .join(format!("{}-{}", ARTIFACT, platform))
+        .join(format!("{}{}", ARTIFACT, std::env::consts::EXE_SUFFIX));
+    if !executable.is_file() {
+        bail!(
+            "The archive did not contain the expected {}",
+            executable.display()
+        );
+    }
+
+    let installed = InstalledBrowser {
+        version,
+        platform: platform.to_string(),
+        executable,
+    };
+    std::fs::write(pin_file()?, serde_json::to_string_pretty(&installed)?)?;
+    Ok(installed)
+}
+
+/// The pinned browser, if one was installed and is still on disk.
+pub fn installed() -> Option<InstalledBrowser> {
+    let pin = std::fs::read_to_string(pin_file().ok()?).ok()?;
+    let installed: InstalledBrowser = serde_json::from_str(&pin).ok()?;
+    installed.executable.is_file().then_some(installed)
+}
+
+/// The browser `--headless` should launch: `CHROME` if set, then the managed
+/// install. `None` leaves it to auto-detection of a system Chrome.
+pub fn executable() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("CHROME") {
+        return Some(PathBuf::from(path));
+    }
+    installed().map(|browser| browser.executable)
+}
+
+/// Run the browser with `--version` to check that it starts on this system.
+pub fn verify(executable: &Path) -> Result<String> {
+    let output = std::process::Command::new(executable)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("Failed to run {}", executable.display()))?;
+    if !output.status.success() {
+        bail!(
+            "{} exited with {}: {}",
+            executable.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Directory that holds managed browser installs, one per version.
+pub fn install_root() -> Result<PathBuf> {
+    let root = dirs::data_dir()
+        .ok_or_else(|| anyhow!("Could not determine the user data directory"))?
+        .join("recon")
+        .join("browser");
+    std::fs::create_dir_all(&root)
+        .with_context(|| format!("Failed to create {}", root.display()))?;
+    Ok(root)
+}
+
+fn pin_file() -> Result<PathBuf> {
+    Ok(install_root()?.join("installed.json"))
+}
+
+async fn stable_download(client: &reqwest::Client, platform: &str) -> Result<(String, String)> {
+    let body = client
+        .get(CHANNELS_URL)
+        .send()
+        .await
+        .context("Failed to fetch the Chrome for Testing release index")?
+        .text()
+        .await?;
+    let channels: Channels =
+        serde_json::from_str(&body).context("Unexpected Chrome for Testing release index")?;
+    let stable = channels
+        .channels
+        .get("Stable")
+        .ok_or_else(|| anyhow!("No stable channel in the Chrome for Testing release index"))?;
+    let download = stable
+        .downloads
+        .get(ARTIFACT)
+        .and_then(|downloads| downloads.iter().find(|d| d.platform == platform))
+        .ok_or_else(|| anyhow!("No {} build for {}", ARTIFACT, platform))?;
+    Ok((stable.version.clone(), download.url.clone()))
+}
+
+/// Chrome for Testing's name for the current platform.
+fn platform() -> Result<&'static str> {
+    use std::env::consts::{ARCH, OS};
+    match (OS, ARCH) {
+        ("linux", "x86_64") => Ok("linux64"),
+        ("macos", "aarch64") => Ok("mac-arm64"),
+        ("macos", "x86_64") => Ok("mac-x64"),
+        ("windows", "x86_64") => Ok("win64"),
+        ("windows", "x86") => Ok("win32"),
+        _ => bail!(
+            "No Chrome for Testing build for {} {}; install Chrome or Chromium and set CHROME",
+            OS,
+            ARCH
+        ),
+    }
+}
+
+fn extract(archive: &[u8], dir: &Path) -> Result<()> {
+    let mut zip =
+        zip::ZipArchive::new(Cursor::new(archive)).context("Downloaded archive is not a zip")?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let Some(relative) = entry.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+        let path = dir.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        std::io::copy(&mut entry, &mut file)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(())
+}
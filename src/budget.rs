@@ -0,0 +1,75 @@
+//! One concurrency and byte budget shared by every extra request a scan
+//! makes, so enabling `--fetch-scripts` and `--fetch-resources` together
+//! bounds the work instead of multiplying it.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Debug)]
+pub struct FetchBudget {
+    permits: Arc<Semaphore>,
+    bytes_left: AtomicUsize,
+    requests: AtomicUsize,
+    bytes: AtomicUsize,
+    skipped: AtomicUsize,
+}
+
+/// What a scan's extra requests used, and how many were cut by the budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    pub requests: usize,
+    pub bytes: usize,
+    /// Requests not made, or bodies cut short, because the byte budget ran out
+    pub skipped: usize,
+}
+
+impl FetchBudget {
+    pub fn new(max_concurrent: usize, max_bytes: usize) -> Arc<Self> {
+        Arc::new(FetchBudget {
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            bytes_left: AtomicUsize::new(max_bytes),
+            requests: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            skipped: AtomicUsize::new(0),
+        })
+    }
+
+    /// Wait for a free slot. `None` when the byte budget is already spent,
+    /// in which case the request should not be made.
+    pub async fn start(&self) -> Option<OwnedSemaphorePermit> {
+        if self.bytes_left.load(Ordering::Relaxed) == 0 {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        let permit = self.permits.clone().acquire_owned().await.ok()?;
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        Some(permit)
+    }
+
+    /// Claim up to `wanted` bytes of the budget and return how many were
+    /// granted. A short grant means the caller must stop reading.
+    pub fn take(&self, wanted: usize) -> usize {
+        let mut granted = 0;
+        let _ = self
+            .bytes_left
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                granted = wanted.min(left);
+                Some(left - granted)
+            });
+        self.bytes.fetch_add(granted, Ordering::Relaxed);
+        if granted < wanted {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        granted
+    }
+
+    pub fn usage(&self) -> BudgetUsage {
+        BudgetUsage {
+            requests: self.requests.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            skipped: self.skipped.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! What this installation can do, reported by `recon capabilities` so that
+//! orchestration systems can check an agent before dispatching scans to it.
+
+use crate::compliance::{ComplianceFramework, Profile};
+use crate::databases::{self, Kind, Manifest};
+use crate::output::OutputFormat;
+use crate::{browser, rules::RuleSet};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CapabilitiesFormat {
+    Text,
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub tool: &'static str,
+    pub version: &'static str,
+    pub browser: BrowserCapability,
+    /// Resolving third-party hosts to their networks; not built in yet
+    pub dns_enrichment: bool,
+    pub tracker_rules: usize,
+    pub databases: Vec<DatabaseCapability>,
+    pub output_formats: Vec<String>,
+    pub compliance_frameworks: Vec<String>,
+    pub profiles: Vec<String>,
+}
+
+/// Whether `--headless` can run, and with which browser.
+#[derive(Debug, Serialize)]
+pub struct BrowserCapability {
+    pub available: bool,
+    /// "env" (the CHROME variable), "managed" (`recon browser install`) or "system"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// What the browser reports with `--version`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseCapability {
+    pub name: &'static str,
+    /// "cached" (from `recon update-db`), "bundled" or "missing"
+    pub source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Unix timestamp, seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<u64>,
+    pub stale: bool,
+}
+
+/// Probe the browser, the database cache and the built-in feature set.
+pub fn detect() -> Capabilities {
+    let manifest = Manifest::load();
+    let databases = databases::SOURCES
+        .iter()
+        .map(|source| match manifest.databases.get(source.name) {
+            Some(db) => DatabaseCapability {
+                name: source.name,
+                source: "cached",
+                version: Some(db.version.clone()),
+                fetched_at: Some(db.fetched_at),
+                stale: db.age() > databases::MAX_AGE,
+            },
+            None => DatabaseCapability {
+                name: source.name,
+                source: match source.kind {
+                    Kind::TrackerRules | Kind::PublicSuffixList => "bundled",
+                    Kind::FilterList | Kind::Disconnect => "missing",
+                },
+                version: None,
+                fetched_at: None,
+                stale: false,
+            },
+        })
+        .collect();
+
+    let mut rules = RuleSet::builtin();
+    let tracker_rules = match manifest.apply(&mut rules, false) {
+        Ok(()) => rules.trackers.len(),
+        Err(_) => RuleSet::builtin().trackers.len(),
+    };
+
+    Capabilities {
+        tool: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        browser: detect_browser(),
+        dns_enrichment: false,
+        tracker_rules,
+        databases,
+        output_formats: names(OutputFormat::value_variants()),
+        compliance_frameworks: names(ComplianceFramework::value_variants()),
+        profiles: names(Profile::value_variants()),
+    }
+}
+
+fn detect_browser() -> BrowserCapability {
+    let found = if std::env::var_os("CHROME").is_some() {
+        browser::executable().map(|path| (path, "env"))
+    } else if let Some(path) = browser::executable() {
+        Some((path, "managed"))
+    } else {
+        chromiumoxide::detection::default_executable(Default::default())
+            .ok()
+            .map(|path| (path, "system"))
+    };
+    match found {
+        Some((path, source)) => {
+            let version = browser::verify(&path).ok();
+            BrowserCapability {
+                available: version.is_some(),
+                source: Some(source),
+                path: Some(path),
+                version,
+            }
+        }
+        None => BrowserCapability {
+            available: false,
+            source: None,
+            path: None,
+            version: None,
+        },
+    }
+}
+
+fn names<T: ValueEnum>(variants: &[T]) -> Vec<String> {
+    variants
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
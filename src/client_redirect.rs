@@ -0,0 +1,141 @@
+//! Redirects done by the page rather than the server: `<meta http-equiv=
+//! "refresh">` and scripts that assign `window.location`. The HTTP client
+//! does not follow them, so without this a shell page that sends the browser
+//! on elsewhere is analyzed in place of the site and looks spotless.
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use std::sync::OnceLock;
+use url::Url;
+
+static LOCATION_ASSIGNMENT: OnceLock<Regex> = OnceLock::new();
+
+/// Longest meta refresh delay followed, in seconds. Pages that reload
+/// themselves every few minutes are not redirects.
+const MAX_REFRESH_DELAY: f64 = 10.0;
+
+/// Most visible text a page may have for its script redirect to be followed.
+/// Real pages assign `location` in click handlers too.
+const SHELL_TEXT: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRedirectKind {
+    MetaRefresh,
+    Script,
+}
+
+impl ClientRedirectKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClientRedirectKind::MetaRefresh => "meta refresh",
+            ClientRedirectKind::Script => "script redirect",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientRedirect {
+    pub kind: ClientRedirectKind,
+    pub from: String,
+    pub to: String,
+}
+
+/// The redirect `html` makes from `page_url`, if it makes one to another page.
+pub fn find(html: &str, page_url: &Url) -> Option<ClientRedirect> {
+    let document = Html::parse_document(html);
+    let (kind, target) = meta_refresh(&document, page_url)
+        .map(|url| (ClientRedirectKind::MetaRefresh, url))
+        .or_else(|| {
+            script_redirect(&document, page_url).map(|url| (ClientRedirectKind::Script, url))
+        })?;
+
+    let mut from = page_url.clone();
+    from.set_fragment(None);
+    let mut to = target;
+    to.set_fragment(None);
+    if from == to || !matches!(to.scheme(), "http" | "https") {
+        return None;
+    }
+    Some(ClientRedirect {
+        kind,
+        from: page_url.to_string(),
+        to: to.to_string(),
+    })
+}
+
+/// `<meta http-equiv="refresh" content="0; url=/home">`
+fn meta_refresh(document: &Html, page_url: &Url) -> Option<Url> {
+    let meta = Selector::parse("meta[http-equiv][content]").unwrap();
+    document
+        .select(&meta)
+        .filter(|e| {
+            e.value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("refresh"))
+        })
+        .find_map(|e| {
+            let content = e.value().attr("content")?;
+            let (delay, rest) = content.split_once([';', ','])?;
+            let delay: f64 = delay.trim().parse().ok()?;
+            if delay > MAX_REFRESH_DELAY {
+                return None;
+            }
+            let rest = rest.trim();
+            let target = match rest.get(..3) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+                    rest[3..].trim_start().strip_prefix('=')?
+                }
+                _ => rest,
+            };
+            let target = target.trim().trim_matches(['\'', '"']);
+            if target.is_empty() {
+                return None;
+            }
+            page_url.join(target).ok()
+        })
+}
+
+/// `window.location = "..."`, `location.href = '...'` or
+/// `location.replace("...")` in an inline script of a page with next to no
+/// content of its own.
+fn script_redirect(document: &Html, page_url: &Url) -> Option<Url> {
+    if visible_text_len(document) > SHELL_TEXT {
+        return None;
+    }
+    let assignment = LOCATION_ASSIGNMENT.get_or_init(|| {
+        Regex::new(
+            r#"\blocation(?:\.href)?\s*=\s*["']([^"']+)["']|\blocation\.(?:replace|assign)\(\s*["']([^"']+)["']\s*\)"#,
+        )
+        .unwrap()
+    });
+    let inline = Selector::parse("script:not([src])").unwrap();
+    document.select(&inline).find_map(|element| {
+        let content = element.inner_html();
+        let captures = assignment.captures(&content)?;
+        let target = captures.get(1).or(captures.get(2))?.as_str();
+        page_url.join(target).ok()
+    })
+}
+
+/// Characters of text in the body outside scripts and styles, with runs of
+/// whitespace counted once.
+fn visible_text_len(document: &Html) -> usize {
+    let body = Selector::parse("body").unwrap();
+    let Some(body) = document.select(&body).next() else {
+        return 0;
+    };
+    body.descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let hidden = node.parent().and_then(ElementRef::wrap).is_some_and(|p| {
+                matches!(
+                    p.value().name(),
+                    "script" | "style" | "noscript" | "template"
+                )
+            });
+            (!hidden).then(|| text.split_whitespace().map(|w| w.len() + 1).sum::<usize>())
+        })
+        .sum()
+}
@@ -0,0 +1,391 @@
+//! Cross-checking observed cookie categories against the categories the
+//! site declares in its consent management platform (CMP) configuration.
+
+use crate::{throttle, usage, CookieCategory, CookieInfo};
+use regex::Regex;
+use reqwest::header::REFERER;
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The purpose a CMP declares a cookie for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum DeclaredCategory {
+    Necessary,
+    Preferences,
+    Statistics,
+    Marketing,
+    Unclassified,
+}
+
+impl DeclaredCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeclaredCategory::Necessary => "Strictly necessary",
+            DeclaredCategory::Preferences => "Preferences",
+            DeclaredCategory::Statistics => "Statistics",
+            DeclaredCategory::Marketing => "Marketing",
+            DeclaredCategory::Unclassified => "Unclassified",
+        }
+    }
+
+    /// Whether a cookie we classify as `observed` may honestly carry this
+    /// declaration. Unknown cookies are never contradicted.
+    fn allows(&self, observed: &CookieCategory) -> bool {
+        match observed {
+            CookieCategory::Essential => {
+                matches!(
+                    self,
+                    DeclaredCategory::Necessary | DeclaredCategory::Preferences
+                )
+            }
+            CookieCategory::Analytics => *self == DeclaredCategory::Statistics,
+            CookieCategory::Marketing | CookieCategory::Social => {
+                *self == DeclaredCategory::Marketing
+            }
+            CookieCategory::Unknown => true,
+        }
+    }
+
+    /// Classify a OneTrust group from its ID (C0001-C0005) or, for custom
+    /// groups, its name.
+    fn from_onetrust_group(id: &str, name: &str) -> Self {
+        match id {
+            "C0001" => return DeclaredCategory::Necessary,
+            "C0002" => return DeclaredCategory::Statistics,
+            "C0003" => return DeclaredCategory::Preferences,
+            "C0004" | "C0005" => return DeclaredCategory::Marketing,
+            _ => {}
+        }
+        let name = name.to_lowercase();
+        if name.contains("necessary") || name.contains("essential") {
+            DeclaredCategory::Necessary
+        } else if ["performance", "analytic", "statistic"]
+            .iter()
+            .any(|k| name.contains(k))
+        {
+            DeclaredCategory::Statistics
+        } else if name.contains("functional") || name.contains("preference") {
+            DeclaredCategory::Preferences
+        } else if ["target", "advertis", "marketing", "social"]
+            .iter()
+            .any(|k| name.contains(k))
+        {
+            DeclaredCategory::Marketing
+        } else {
+            DeclaredCategory::Unclassified
+        }
+    }
+}
+
+/// A cookie listed in the CMP configuration.
+#[derive(Debug, Clone)]
+struct DeclaredCookie {
+    /// May end in `#` (Cookiebot) or `xxx` (OneTrust) for a variable suffix
+    name: String,
+    category: DeclaredCategory,
+}
+
+impl DeclaredCookie {
+    fn matches(&self, cookie_name: &str) -> bool {
+        let prefix = match self.name.find('#') {
+            Some(i) => &self.name[..i],
+            None if self.name.ends_with("xxx") => self.name.trim_end_matches('x'),
+            None => return cookie_name == self.name,
+        };
+        !prefix.is_empty() && cookie_name.starts_with(prefix)
+    }
+}
+
+/// An observed cookie whose declared purpose contradicts what it does.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryMismatch {
+    pub cookie: String,
+    pub declared: DeclaredCategory,
+    pub observed: CookieCategory,
+    /// A tracking cookie declared strictly necessary, which CMPs set without
+    /// asking for consent
+    pub declared_necessary: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CmpCrossCheck {
+    pub cmp: String,
+    /// The configuration the declarations were read from
+    pub source: String,
+    /// How many cookies the configuration declares
+    pub declared_cookies: usize,
+    pub mismatches: Vec<CategoryMismatch>,
+    /// Observed cookies the configuration does not mention at all
+    pub undeclared: Vec<String>,
+}
+
+/// The cookies a site declares in its CMP configuration, fetched once and
+/// compared with the cookies of each page.
+#[derive(Debug)]
+pub struct Declarations {
+    cmp: &'static str,
+    source: String,
+    cookies: Vec<DeclaredCookie>,
+}
+
+/// Find a supported CMP on the page and fetch its public configuration.
+pub async fn declarations(client: &Client, html: &str, page_url: &Url) -> Option<Declarations> {
+    let (cmp, source, cookies) = match find_cmp(html, page_url)? {
+        CmpConfig::Cookiebot { cbid } => cookiebot(client, &cbid, page_url).await?,
+        CmpConfig::OneTrust { base, domain_id } => onetrust(client, &base, &domain_id).await?,
+    };
+    Some(Declarations {
+        cmp,
+        source,
+        cookies,
+    })
+}
+
+/// Compare the CMP's declarations with the observed cookies.
+pub fn cross_check(declarations: &Declarations, cookies: &[CookieInfo]) -> CmpCrossCheck {
+    let declared = &declarations.cookies;
+    let mut mismatches = Vec::new();
+    let mut undeclared = Vec::new();
+    for cookie in cookies {
+        match declared.iter().find(|d| d.matches(&cookie.name)) {
+            Some(declaration) if !declaration.category.allows(&cookie.category) => {
+                mismatches.push(CategoryMismatch {
+                    cookie: cookie.name.clone(),
+                    declared: declaration.category,
+                    observed: cookie.category.clone(),
+                    declared_necessary: declaration.category == DeclaredCategory::Necessary,
+                })
+            }
+            Some(_) => {}
+            None => {
+                if !undeclared.contains(&cookie.name) {
+                    undeclared.push(cookie.name.clone());
+                }
+            }
+        }
+    }
+
+    CmpCrossCheck {
+        cmp: declarations.cmp.to_string(),
+        source: declarations.source.clone(),
+        declared_cookies: declared.len(),
+        mismatches,
+        undeclared,
+    }
+}
+
+enum CmpConfig {
+    Cookiebot { cbid: String },
+    OneTrust { base: Url, domain_id: String },
+}
+
+/// Identify the CMP from its loader script tag.
+fn find_cmp(html: &str, page_url: &Url) -> Option<CmpConfig> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script[src]").unwrap();
+
+    for script in document.select(&selector) {
+        let Some(src) = script
+            .value()
+            .attr("src")
+            .and_then(|s| page_url.join(s).ok())
+        else {
+            continue;
+        };
+
+        if src.host_str() == Some("consent.cookiebot.com") {
+            let cbid = script
+                .value()
+                .attr("data-cbid")
+                .map(str::to_string)
+                .or_else(|| {
+                    src.query_pairs()
+                        .find(|(k, _)| k.eq_ignore_ascii_case("cbid"))
+                        .map(|(_, v)| v.into_owned())
+                });
+            if let Some(cbid) = cbid {
+                return Some(CmpConfig::Cookiebot { cbid });
+            }
+        }
+
+        // OneTrust and CookiePro share the loader and the CDN layout
+        if let Some(domain_id) = script.value().attr("data-domain-script") {
+            if src.path().contains("otSDKStub") {
+                let mut base = src.clone();
+                base.set_path("/");
+                base.set_query(None);
+                return Some(CmpConfig::OneTrust {
+                    base,
+                    domain_id: domain_id.to_string(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Cookiebot lists declarations as JavaScript arrays such as
+/// `cookieTableStatistics = [["_ga","example.com",...],...]`.
+async fn cookiebot(
+    client: &Client,
+    cbid: &str,
+    page_url: &Url,
+) -> Option<(&'static str, String, Vec<DeclaredCookie>)> {
+    let table = Regex::new(
+        r"(?s)cookieTable(Necessary|Preference|Statistics|Advertising|Unclassified)\s*=\s*(\[\s*\]|\[\[.*?\]\])",
+    )
+    .unwrap();
+
+    let source = format!("https://consent.cookiebot.com/{}/cd.js", cbid);
+    // The declaration is only served to the domains registered for the ID
+    let response = client
+        .get(&source)
+        .header(REFERER, page_url.as_str())
+        .send()
+        .await
+        .ok()?;
+    usage::request();
+    let body = response.text().await.ok()?;
+    usage::add_bytes(body.len());
+
+    let mut declared = Vec::new();
+    for captures in table.captures_iter(&body) {
+        let category = match &captures[1] {
+            "Necessary" => DeclaredCategory::Necessary,
+            "Preference" => DeclaredCategory::Preferences,
+            "Statistics" => DeclaredCategory::Statistics,
+            "Advertising" => DeclaredCategory::Marketing,
+            _ => DeclaredCategory::Unclassified,
+        };
+        let rows: Vec<Vec<serde_json::Value>> =
+            serde_json::from_str(&captures[2]).unwrap_or_default();
+        for row in rows {
+            if let Some(name) = row.first().and_then(|v| v.as_str()) {
+                declared.push(DeclaredCookie {
+                    name: name.to_string(),
+                    category,
+                });
+            }
+        }
+    }
+
+    (!declared.is_empty()).then_some(("Cookiebot", source, declared))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustRoot {
+    #[serde(default)]
+    rule_set: Vec<OneTrustRuleSet>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustRuleSet {
+    id: String,
+    #[serde(default)]
+    language_switcher_placeholder: Option<OneTrustLanguages>,
+}
+
+#[derive(Deserialize)]
+struct OneTrustLanguages {
+    default: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustLanguageFile {
+    domain_data: OneTrustDomainData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustDomainData {
+    #[serde(default)]
+    groups: Vec<OneTrustGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustGroup {
+    #[serde(default)]
+    group_name: String,
+    #[serde(default)]
+    optanon_group_id: String,
+    #[serde(default)]
+    first_party_cookies: Vec<OneTrustCookie>,
+    #[serde(default)]
+    hosts: Vec<OneTrustHost>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustHost {
+    #[serde(default)]
+    cookies: Vec<OneTrustCookie>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OneTrustCookie {
+    name: String,
+}
+
+/// OneTrust publishes `consent/<id>/<id>.json` naming the rule set, whose
+/// language file lists each consent group with its cookies.
+async fn onetrust(
+    client: &Client,
+    base: &Url,
+    domain_id: &str,
+) -> Option<(&'static str, String, Vec<DeclaredCookie>)> {
+    let root_url = base
+        .join(&format!("consent/{0}/{0}.json", domain_id))
+        .ok()?;
+    let root: OneTrustRoot = fetch_json(client, &root_url).await?;
+    let rule_set = root.rule_set.first()?;
+    let language = rule_set
+        .language_switcher_placeholder
+        .as_ref()
+        .and_then(|l| l.default.as_deref())
+        .unwrap_or("en");
+
+    let source = base
+        .join(&format!(
+            "consent/{}/{}/{}.json",
+            domain_id, rule_set.id, language
+        ))
+        .ok()?;
+    let file: OneTrustLanguageFile = fetch_json(client, &source).await?;
+
+    let mut declared = Vec::new();
+    for group in file.domain_data.groups {
+        let category =
+            DeclaredCategory::from_onetrust_group(&group.optanon_group_id, &group.group_name);
+        let cookies = group
+            .first_party_cookies
+            .into_iter()
+            .chain(group.hosts.into_iter().flat_map(|h| h.cookies));
+        for cookie in cookies {
+            declared.push(DeclaredCookie {
+                name: cookie.name,
+                category,
+            });
+        }
+    }
+
+    (!declared.is_empty()).then_some(("OneTrust", source.to_string(), declared))
+}
+
+async fn fetch_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &Url) -> Option<T> {
+    throttle::wait(url).await;
+    let response = client.get(url.clone()).send().await.ok()?;
+    usage::request();
+    if !response.status().is_success() {
+        return None;
+    }
+    let body = response.text().await.ok()?;
+    usage::add_bytes(body.len());
+    serde_json::from_str(&body).ok()
+}
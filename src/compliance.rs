@@ -0,0 +1,630 @@
+//! Mapping findings onto a regulatory checklist with a pass/warn/fail status
+//! per requirement.
+
+use crate::banner::{self, ConsentChoice};
+use crate::profiles;
+use crate::referrer::ReferrerLevel;
+use crate::vendors;
+use crate::{AnalysisResult, CookieCategory, ScanOptions};
+use clap::ValueEnum;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceFramework {
+    /// EU General Data Protection Regulation and the ePrivacy Directive
+    Gdpr,
+    /// California Consumer Privacy Act, as amended by the CPRA
+    Ccpa,
+}
+
+impl ComplianceFramework {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceFramework::Gdpr => "GDPR",
+            ComplianceFramework::Ccpa => "CCPA",
+        }
+    }
+}
+
+/// A regulator's reading of a framework, adding its own checks and thresholds
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    /// France: GDPR plus the CNIL cookie guidelines
+    Cnil,
+    /// Germany: GDPR plus TDDDG (formerly TTDSG) § 25 and the Impressum duty
+    Dsgvo,
+    /// United Kingdom: UK GDPR plus PECR
+    UkIco,
+    /// California: CCPA/CPRA
+    Ccpa,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Cnil => "CNIL",
+            Profile::Dsgvo => "DSGVO",
+            Profile::UkIco => "UK ICO",
+            Profile::Ccpa => "CCPA",
+        }
+    }
+
+    /// The framework the profile builds on.
+    pub fn framework(&self) -> ComplianceFramework {
+        match self {
+            Profile::Cnil | Profile::Dsgvo | Profile::UkIco => ComplianceFramework::Gdpr,
+            Profile::Ccpa => ComplianceFramework::Ccpa,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// The scan did not collect what the check needs, e.g. without `--headless`
+    NotChecked,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::NotChecked => "N/A",
+        }
+    }
+
+    /// Ordering used to keep the worst outcome when pages are merged
+    fn severity(&self) -> u8 {
+        match self {
+            CheckStatus::NotChecked => 0,
+            CheckStatus::Pass => 1,
+            CheckStatus::Warn => 2,
+            CheckStatus::Fail => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceCheck {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// The provision the check is based on
+    pub reference: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceReport {
+    pub framework: ComplianceFramework,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<Profile>,
+    pub checks: Vec<ComplianceCheck>,
+}
+
+impl ComplianceReport {
+    /// "GDPR", or "GDPR (CNIL)" with a profile that adds to the framework.
+    pub fn title(&self) -> String {
+        match self.profile {
+            Some(profile) if profile.as_str() != self.framework.as_str() => {
+                format!("{} ({})", self.framework.as_str(), profile.as_str())
+            }
+            _ => self.framework.as_str().to_string(),
+        }
+    }
+
+    /// Keep the worse result of each check, so a site fails if any page does.
+    pub fn merge(&mut self, other: ComplianceReport) {
+        for check in other.checks {
+            match self.checks.iter_mut().find(|c| c.id == check.id) {
+                Some(existing) if existing.status.severity() >= check.status.severity() => {}
+                Some(existing) => *existing = check,
+                None => self.checks.push(check),
+            }
+        }
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+/// Link text and URL fragments of privacy policy links, in the languages of
+/// the larger EU markets
+const PRIVACY_POLICY_HINTS: &[&str] = &[
+    "privacy",
+    "datenschutz",
+    "confidentialit",
+    "donnees-personnelles",
+    "privacidad",
+    "privacybeleid",
+    "informativa",
+    "prywatno",
+];
+
+/// Link text and URL fragments of the CCPA opt-out link
+const OPT_OUT_HINTS: &[&str] = &[
+    "do not sell",
+    "do-not-sell",
+    "do not share",
+    "your privacy choices",
+    "privacy-choices",
+    "opt out",
+    "opt-out",
+];
+
+/// EU member states plus the rest of the EEA
+const EEA_COUNTRIES: &[&str] = &[
+    "AT", "BE", "BG", "HR", "CY", "CZ", "DK", "EE", "FI", "FR", "DE", "GR", "HU", "IE", "IT", "LV",
+    "LT", "LU", "MT", "NL", "PL", "PT", "RO", "SK", "SI", "ES", "SE", "IS", "LI", "NO",
+];
+
+/// Build the checklist for one analyzed page.
+pub fn assess(
+    framework: ComplianceFramework,
+    profile: Option<Profile>,
+    result: &AnalysisResult,
+    html: &str,
+    page_url: &Url,
+    options: &ScanOptions,
+) -> ComplianceReport {
+    let mut checks = match framework {
+        ComplianceFramework::Gdpr => gdpr(result, html, page_url, options),
+        ComplianceFramework::Ccpa => ccpa(result, html, page_url),
+    };
+    if let Some(profile) = profile {
+        checks.extend(profiles::checks(profile, result, html, page_url));
+    }
+    ComplianceReport {
+        framework,
+        profile,
+        checks,
+    }
+}
+
+fn gdpr(
+    result: &AnalysisResult,
+    html: &str,
+    page_url: &Url,
+    options: &ScanOptions,
+) -> Vec<ComplianceCheck> {
+    let mut checks = Vec::new();
+
+    let tracking_cookies: Vec<&str> = result
+        .pre_consent
+        .iter()
+        .filter(|v| v.kind != "tracker")
+        .map(|v| v.name.as_str())
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "GDPR-01",
+        title: "No non-essential cookies before consent",
+        reference: "ePrivacy Directive Art. 5(3)",
+        status: if tracking_cookies.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        },
+        detail: if tracking_cookies.is_empty() {
+            "Only essential or unclassified cookies are set on first load".to_string()
+        } else {
+            format!("Set before any choice: {}", tracking_cookies.join(", "))
+        },
+    });
+
+    let trackers: Vec<&str> = result
+        .pre_consent
+        .iter()
+        .filter(|v| v.kind == "tracker")
+        .filter_map(|v| v.vendor.as_deref())
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "GDPR-02",
+        title: "No tracking scripts before consent",
+        reference: "GDPR Art. 6(1)(a), ePrivacy Directive Art. 5(3)",
+        status: if trackers.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        },
+        detail: if trackers.is_empty() {
+            "No analytics, advertising or social trackers load on first visit".to_string()
+        } else {
+            format!("Loaded before any choice: {}", trackers.join(", "))
+        },
+    });
+
+    let needs_consent = !tracking_cookies.is_empty() || !trackers.is_empty();
+    let rendered = options.headless && result.static_fallback.is_none();
+    let (status, detail) = match (&result.consent_banner, rendered) {
+        (_, false) if options.headless => (
+            CheckStatus::NotChecked,
+            "The headless browser failed, so no banner could be looked for".to_string(),
+        ),
+        (_, false) => (
+            CheckStatus::NotChecked,
+            "Run with --headless to look for a consent banner".to_string(),
+        ),
+        (Some(banner), true) => (
+            CheckStatus::Pass,
+            format!(
+                "{} banner found",
+                banner.cmp.as_deref().unwrap_or("Unrecognized")
+            ),
+        ),
+        (None, true) if needs_consent => (
+            CheckStatus::Fail,
+            "No consent banner, but the page uses cookies or trackers that need consent"
+                .to_string(),
+        ),
+        (None, true) => (
+            CheckStatus::Pass,
+            "No consent banner, and nothing on the page needs consent".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "GDPR-03",
+        title: "Consent is requested",
+        reference: "GDPR Art. 7",
+        status,
+        detail,
+    });
+
+    let reject_label = result
+        .consent_diff
+        .as_ref()
+        .and_then(|d| d.reject_button.clone())
+        .or_else(|| {
+            result.consent_banner.as_ref().and_then(|b| {
+                b.buttons
+                    .iter()
+                    .find(|label| banner::label_choice(label) == Some(ConsentChoice::Reject))
+                    .cloned()
+            })
+        });
+    let (status, detail) = match (&result.consent_banner, reject_label) {
+        (_, Some(label)) => (CheckStatus::Pass, format!("Offered as \"{}\"", label)),
+        (Some(_), None) => (
+            CheckStatus::Fail,
+            "The first layer has no button to refuse consent".to_string(),
+        ),
+        (None, None) => (
+            CheckStatus::NotChecked,
+            "No consent banner was captured".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "GDPR-04",
+        title: "Refusing is as easy as accepting",
+        reference: "GDPR Art. 7(3), EDPB Guidelines 05/2020",
+        status,
+        detail,
+    });
+
+    let (status, detail) = match &result.consent_diff {
+        None if result.consent_diff_error.is_some() => (
+            CheckStatus::NotChecked,
+            "The consent comparison could not be made".to_string(),
+        ),
+        None => (
+            CheckStatus::NotChecked,
+            "Run with --headless --consent-diff to test the reject button".to_string(),
+        ),
+        Some(diff) if diff.reject_button.is_none() => (
+            CheckStatus::NotChecked,
+            "No reject button to test".to_string(),
+        ),
+        Some(diff) if diff.tracking_after_reject.is_empty() => (
+            CheckStatus::Pass,
+            "No tracking seen after rejecting".to_string(),
+        ),
+        Some(diff) => {
+            let tracking = &diff.tracking_after_reject;
+            let names: Vec<&str> = tracking
+                .cookies
+                .iter()
+                .map(|c| c.name.as_str())
+                .chain(tracking.storage.iter().map(|s| s.key.as_str()))
+                .chain(tracking.hosts.iter().map(String::as_str))
+                .collect();
+            (
+                CheckStatus::Fail,
+                format!("Present after rejecting: {}", names.join(", ")),
+            )
+        }
+    };
+    checks.push(ComplianceCheck {
+        id: "GDPR-05",
+        title: "Rejection is respected",
+        reference: "GDPR Art. 6(1)(a), Art. 7(3)",
+        status,
+        detail,
+    });
+
+    // The CMP's declarations are structured, so they win over the policy page
+    let (status, detail) = match (&result.cmp_check, &result.cookie_policy) {
+        (Some(check), _) if check.mismatches.iter().any(|m| m.declared_necessary) => {
+            let names: Vec<&str> = check
+                .mismatches
+                .iter()
+                .filter(|m| m.declared_necessary)
+                .map(|m| m.cookie.as_str())
+                .collect();
+            (
+                CheckStatus::Fail,
+                format!(
+                    "Tracking cookies declared strictly necessary: {}",
+                    names.join(", ")
+                ),
+            )
+        }
+        (Some(check), _) if !check.mismatches.is_empty() || !check.undeclared.is_empty() => (
+            CheckStatus::Warn,
+            format!(
+                "{} miscategorized and {} undeclared cookies",
+                check.mismatches.len(),
+                check.undeclared.len()
+            ),
+        ),
+        (Some(check), _) => (
+            CheckStatus::Pass,
+            format!(
+                "Every observed cookie is declared correctly in {}",
+                check.cmp
+            ),
+        ),
+        (None, Some(policy)) if policy.url.is_none() => {
+            (CheckStatus::Fail, "No cookie policy found".to_string())
+        }
+        (None, Some(policy)) if !policy.undeclared.is_empty() => (
+            CheckStatus::Warn,
+            format!(
+                "Not named in the cookie policy: {}",
+                policy.undeclared.join(", ")
+            ),
+        ),
+        (None, Some(_)) => (
+            CheckStatus::Pass,
+            "Every observed cookie is named in the cookie policy".to_string(),
+        ),
+        (None, None) => (
+            CheckStatus::NotChecked,
+            "No consent platform configuration; run with --cookie-policy".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "GDPR-06",
+        title: "Cookies are accurately disclosed",
+        reference: "GDPR Art. 13",
+        status,
+        detail,
+    });
+
+    let (status, detail) = match privacy_policy_link(html, page_url) {
+        Some(link) => (CheckStatus::Pass, link),
+        None => (
+            CheckStatus::Fail,
+            "No link to a privacy policy found on the page".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "GDPR-07",
+        title: "Privacy policy is linked",
+        reference: "GDPR Art. 12, Art. 13",
+        status,
+        detail,
+    });
+
+    let leaks: Vec<String> = result
+        .pii_leaks
+        .iter()
+        .map(|l| format!("{} ({})", l.domain, l.kind))
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "GDPR-08",
+        title: "No personal data in third-party URLs",
+        reference: "GDPR Art. 5(1)(c), Art. 32",
+        status: if leaks.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        },
+        detail: if leaks.is_empty() {
+            "No personal data found in third-party query strings".to_string()
+        } else {
+            leaks.join(", ")
+        },
+    });
+
+    let full_url: Vec<&str> = result
+        .referrer
+        .exposures
+        .iter()
+        .filter(|e| e.level == ReferrerLevel::Full)
+        .map(|e| e.domain.as_str())
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "GDPR-09",
+        title: "Page URLs are not shared with third parties",
+        reference: "GDPR Art. 25",
+        status: if full_url.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        },
+        detail: if full_url.is_empty() {
+            "Third parties receive at most the origin as referrer".to_string()
+        } else {
+            format!("Full URL sent to: {}", full_url.join(", "))
+        },
+    });
+
+    let transfers = transfers_outside_eea(result);
+    checks.push(ComplianceCheck {
+        id: "GDPR-10",
+        title: "No data transfers outside the EU/EEA",
+        reference: "GDPR Chapter V (Art. 44-49)",
+        status: if transfers.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        },
+        detail: if transfers.is_empty() {
+            "No vendors based outside the EU/EEA were identified".to_string()
+        } else {
+            format!(
+                "Needs an adequacy decision or SCCs: {}",
+                transfers.join(", ")
+            )
+        },
+    });
+
+    let font_services: Vec<&str> = result
+        .remote_fonts
+        .iter()
+        .map(|f| f.service.as_str())
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "GDPR-11",
+        title: "Web fonts are self-hosted",
+        reference: "GDPR Art. 6(1); LG München I, 3 O 17493/20",
+        status: if result.remote_fonts.iter().any(|f| f.ruled_unlawful()) {
+            CheckStatus::Fail
+        } else if font_services.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        },
+        detail: if font_services.is_empty() {
+            "No fonts are loaded from font services".to_string()
+        } else {
+            format!("Visitor IP addresses go to: {}", font_services.join(", "))
+        },
+    });
+
+    checks
+}
+
+fn ccpa(result: &AnalysisResult, html: &str, page_url: &Url) -> Vec<ComplianceCheck> {
+    let mut checks = Vec::new();
+
+    // Advertising and social tracking is "sharing" for cross-context behavioral advertising
+    let sharing: Vec<&str> = result
+        .trackers
+        .iter()
+        .filter(|t| t.category.starts_with("Marketing") || t.category == "Social")
+        .map(|t| t.description.as_str())
+        .chain(
+            result
+                .cookies
+                .iter()
+                .filter(|c| matches!(c.category, CookieCategory::Marketing | CookieCategory::Social))
+                .map(|c| c.name.as_str()),
+        )
+        .collect();
+    let (status, detail) = match link_matching(html, page_url, OPT_OUT_HINTS) {
+        Some(link) => (CheckStatus::Pass, link),
+        None if sharing.is_empty() => (
+            CheckStatus::Pass,
+            "No opt-out link, but no advertising or social tracking was found".to_string(),
+        ),
+        None => (
+            CheckStatus::Fail,
+            format!(
+                "No \"Do Not Sell or Share\" link, but data is shared with: {}",
+                sharing.join(", ")
+            ),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "CCPA-01",
+        title: "Opt-out of sale or sharing is offered",
+        reference: "Cal. Civ. Code § 1798.120, § 1798.135(a)",
+        status,
+        detail,
+    });
+
+    let (status, detail) = match privacy_policy_link(html, page_url) {
+        Some(link) => (CheckStatus::Pass, link),
+        None => (
+            CheckStatus::Fail,
+            "No link to a privacy policy found on the page".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "CCPA-02",
+        title: "Privacy policy is linked",
+        reference: "Cal. Civ. Code § 1798.130(a)(5)",
+        status,
+        detail,
+    });
+
+    let (status, detail) = match &result.well_known {
+        None => (
+            CheckStatus::NotChecked,
+            "Run with --well-known to look for a GPC declaration".to_string(),
+        ),
+        Some(report) if report.gpc.as_ref().is_some_and(|g| g.gpc) => (
+            CheckStatus::Pass,
+            "Declares support in /.well-known/gpc.json".to_string(),
+        ),
+        Some(_) => (
+            CheckStatus::Warn,
+            "No GPC support declared in /.well-known/gpc.json".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "CCPA-03",
+        title: "Global Privacy Control is honored",
+        reference: "CCPA Regulations § 7025",
+        status,
+        detail,
+    });
+
+    checks
+}
+
+/// Vendors of the page's cookies and trackers that are based outside the
+/// EEA, as "Google (US)".
+fn transfers_outside_eea(result: &AnalysisResult) -> Vec<String> {
+    vendors::detected(result)
+        .into_iter()
+        .filter(|v| !EEA_COUNTRIES.contains(&v.country))
+        .map(|v| format!("{} ({})", v.name, v.country))
+        .collect()
+}
+
+/// The first link whose text or URL looks like a privacy policy.
+fn privacy_policy_link(html: &str, page_url: &Url) -> Option<String> {
+    link_matching(html, page_url, PRIVACY_POLICY_HINTS)
+}
+
+/// The first link whose text or URL contains one of `hints`, as an absolute URL.
+pub fn link_matching(html: &str, page_url: &Url, hints: &[&str]) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("a[href]").unwrap();
+
+    document.select(&selector).find_map(|link| {
+        let href = link.value().attr("href")?;
+        let text = link.text().collect::<String>().to_lowercase();
+        let href_lower = href.to_lowercase();
+        hints
+            .iter()
+            .any(|hint| text.contains(hint) || href_lower.contains(hint))
+            .then(|| {
+                page_url
+                    .join(href)
+                    .map(|u| u.to_string())
+                    .unwrap_or_else(|_| href.to_string())
+            })
+    })
+}
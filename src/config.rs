@@ -0,0 +1,287 @@
+//! Defaults from a `cookie-scout.toml` config file, so long command lines in
+//! CI scripts can move into a checked-in file. Options given on the command
+//! line always win over the file.
+
+use crate::gate::FailOn;
+use crate::notify::{NotifyOn, WebhookFormat};
+use crate::output::OutputFormat;
+use crate::owners::{self, Owners};
+use crate::presets::Preset;
+use crate::{Args, Confidence, BROWSER_USER_AGENT};
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, ValueEnum};
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "cookie-scout.toml";
+
+/// Request timeout when the config file sets none
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+static ACTIVE: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    user_agent: Option<String>,
+    /// Per-request timeout, e.g. "30s" or "2m"
+    timeout: Option<String>,
+    format: Option<String>,
+    /// Bundle of options, as with `--preset`
+    preset: Option<String>,
+    min_confidence: Option<String>,
+    /// Lowest privacy score a site may have, as with `--fail-on-score`
+    fail_on_score: Option<u32>,
+    /// Findings that fail the run, as with `--fail-on`
+    #[serde(default)]
+    fail_on: Vec<String>,
+    /// Where to post results, as with `--webhook`
+    webhook: Option<String>,
+    notify_on: Option<String>,
+    webhook_format: Option<String>,
+    #[serde(default)]
+    rating: RatingThresholds,
+    #[serde(default)]
+    cookie_budget: CookieBudget,
+    rules: Option<PathBuf>,
+    #[serde(default)]
+    trackers: Vec<PathBuf>,
+    #[serde(default)]
+    cookie_rules: Vec<PathBuf>,
+    #[serde(default)]
+    filter_lists: Vec<PathBuf>,
+    disconnect: Option<PathBuf>,
+    /// Hosts never reported as third parties or trackers, with their subdomains
+    #[serde(default)]
+    ignore_domains: Vec<String>,
+    /// Who new findings are routed to
+    #[serde(default, rename = "owner", alias = "owners")]
+    owners: Owners,
+    #[serde(skip)]
+    timeout_parsed: Option<Duration>,
+}
+
+/// Lowest score of each privacy rating.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RatingThresholds {
+    pub excellent: u32,
+    pub good: u32,
+    pub moderate: u32,
+    pub poor: u32,
+}
+
+impl Default for RatingThresholds {
+    fn default() -> Self {
+        RatingThresholds {
+            excellent: 90,
+            good: 70,
+            moderate: 50,
+            poor: 25,
+        }
+    }
+}
+
+/// Most cookies and largest `Cookie` header a domain should receive before
+/// the report warns.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CookieBudget {
+    pub max_per_domain: usize,
+    pub max_header_bytes: usize,
+}
+
+impl Default for CookieBudget {
+    fn default() -> Self {
+        // RFC 6265 asks browsers to keep at least 50 cookies per domain and
+        // 4096 bytes per cookie
+        CookieBudget {
+            max_per_domain: 50,
+            max_header_bytes: 4096,
+        }
+    }
+}
+
+/// `cookie-scout.toml` in the current directory, else in the user config
+/// directory (`$XDG_CONFIG_HOME/recon` on Linux).
+pub fn discover() -> Option<PathBuf> {
+    let local = PathBuf::from(FILE_NAME);
+    if local.is_file() {
+        return Some(local);
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join("recon").join(FILE_NAME))
+        .filter(|path| path.is_file())
+}
+
+impl Config {
+    /// Read and check a config file. Relative paths in it are resolved
+    /// against the file's directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config: Config = toml::from_str(&contents)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        let resolve = |p: &mut PathBuf| *p = base.join(&*p);
+        config.rules.iter_mut().for_each(resolve);
+        config.trackers.iter_mut().for_each(resolve);
+        config.cookie_rules.iter_mut().for_each(resolve);
+        config.filter_lists.iter_mut().for_each(resolve);
+        config.disconnect.iter_mut().for_each(resolve);
+        Ok(config)
+    }
+
+    fn validate(&mut self) -> Result<()> {
+        if let Some(agent) = &self.user_agent {
+            HeaderValue::from_str(agent).context("user_agent is not a valid header value")?;
+        }
+        if let Some(timeout) = &self.timeout {
+            self.timeout_parsed = Some(
+                humantime::parse_duration(timeout)
+                    .with_context(|| format!("Invalid timeout '{}'", timeout))?,
+            );
+        }
+        if let Some(format) = &self.format {
+            OutputFormat::from_str(format, true).map_err(|e| anyhow::anyhow!("format: {}", e))?;
+        }
+        if let Some(preset) = &self.preset {
+            Preset::from_str(preset, true).map_err(|e| anyhow::anyhow!("preset: {}", e))?;
+        }
+        if let Some(level) = &self.min_confidence {
+            Confidence::from_str(level, true)
+                .map_err(|e| anyhow::anyhow!("min_confidence: {}", e))?;
+        }
+        if self.fail_on_score.is_some_and(|score| score > 100) {
+            bail!("fail_on_score must be between 0 and 100");
+        }
+        for condition in &self.fail_on {
+            FailOn::from_str(condition, true).map_err(|e| anyhow::anyhow!("fail_on: {}", e))?;
+        }
+        if let Some(webhook) = &self.webhook {
+            url::Url::parse(webhook).with_context(|| format!("Invalid webhook '{}'", webhook))?;
+        }
+        if let Some(when) = &self.notify_on {
+            NotifyOn::from_str(when, true).map_err(|e| anyhow::anyhow!("notify_on: {}", e))?;
+        }
+        if let Some(format) = &self.webhook_format {
+            WebhookFormat::from_str(format, true)
+                .map_err(|e| anyhow::anyhow!("webhook_format: {}", e))?;
+        }
+        self.owners.validate()?;
+        let r = &self.rating;
+        if !(r.excellent > r.good && r.good > r.moderate && r.moderate > r.poor) {
+            bail!("rating thresholds must decrease from excellent to poor");
+        }
+        Ok(())
+    }
+
+    /// Fill in every option the command line left unset. List options such
+    /// as `trackers` put the file's entries before the command line's.
+    pub fn apply(&self, args: &mut Args, matches: &ArgMatches) {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+        if unset("format") {
+            if let Some(format) = &self.format {
+                args.format = OutputFormat::from_str(format, true).unwrap();
+            }
+        }
+        if unset("preset") {
+            if let Some(preset) = &self.preset {
+                args.preset = Some(Preset::from_str(preset, true).unwrap());
+            }
+        }
+        if unset("min_confidence") {
+            if let Some(level) = &self.min_confidence {
+                args.min_confidence = Confidence::from_str(level, true).unwrap();
+            }
+        }
+        if args.fail_on_score.is_none() {
+            args.fail_on_score = self.fail_on_score;
+        }
+        for condition in &self.fail_on {
+            let condition = FailOn::from_str(condition, true).unwrap();
+            if !args.fail_on.contains(&condition) {
+                args.fail_on.push(condition);
+            }
+        }
+        if args.webhook.is_none() {
+            args.webhook = self.webhook.clone();
+        }
+        if unset("notify_on") {
+            if let Some(when) = &self.notify_on {
+                args.notify_on = NotifyOn::from_str(when, true).unwrap();
+            }
+        }
+        if unset("webhook_format") {
+            if let Some(format) = &self.webhook_format {
+                args.webhook_format = WebhookFormat::from_str(format, true).unwrap();
+            }
+        }
+        if args.rules.is_none() {
+            args.rules = self.rules.clone();
+        }
+        if args.disconnect.is_none() {
+            args.disconnect = self.disconnect.clone();
+        }
+        prepend(&mut args.trackers, &self.trackers);
+        prepend(&mut args.cookie_rules, &self.cookie_rules);
+        prepend(&mut args.filter_list, &self.filter_lists);
+        prepend(&mut args.ignore_domain, &self.ignore_domains);
+    }
+
+    /// Replace the file's User-Agent with one from `--user-agent` or `--ua`.
+    pub fn set_user_agent(&mut self, agent: String) -> Result<()> {
+        HeaderValue::from_str(&agent).context("--user-agent is not a valid header value")?;
+        self.user_agent = Some(agent);
+        Ok(())
+    }
+
+    /// Make this the configuration behind `user_agent()`, `timeout()`,
+    /// `rating_thresholds()`, `cookie_budget()` and `owners()`. Only the first call has an effect.
+    pub fn activate(self) {
+        let _ = ACTIVE.set(self);
+    }
+}
+
+fn prepend<T: Clone>(list: &mut Vec<T>, defaults: &[T]) {
+    list.splice(0..0, defaults.iter().cloned());
+}
+
+fn active() -> Option<&'static Config> {
+    ACTIVE.get()
+}
+
+/// The User-Agent sent with every request and by the headless browser.
+pub fn user_agent() -> &'static str {
+    active()
+        .and_then(|c| c.user_agent.as_deref())
+        .unwrap_or(BROWSER_USER_AGENT)
+}
+
+/// Timeout for page requests.
+pub fn timeout() -> Duration {
+    active()
+        .and_then(|c| c.timeout_parsed)
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+pub fn rating_thresholds() -> RatingThresholds {
+    active().map(|c| c.rating).unwrap_or_default()
+}
+
+pub fn cookie_budget() -> CookieBudget {
+    active().map(|c| c.cookie_budget).unwrap_or_default()
+}
+
+pub fn owners() -> &'static Owners {
+    active().map_or(&owners::NONE, |c| &c.owners)
+}
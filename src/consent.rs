@@ -0,0 +1,130 @@
+//! Comparing what a page does after its consent banner is rejected with what
+//! it does after it is accepted.
+
+use crate::auth::SiteAccess;
+use crate::banner::ConsentChoice;
+use crate::headless;
+use crate::preconsent::needs_consent;
+use crate::rules::RuleSet;
+use crate::storage::StorageItem;
+use crate::visitor::Visitor;
+use crate::CookieInfo;
+use anyhow::Result;
+use serde::Serialize;
+
+/// What the page left behind after one consent choice.
+pub struct ConsentState {
+    /// Label of the button that was clicked, or `None` when the banner had no
+    /// such button
+    pub button: Option<String>,
+    pub cookies: Vec<CookieInfo>,
+    pub storage: Vec<StorageItem>,
+    /// Every host the page sent a request to, sorted
+    pub request_hosts: Vec<String>,
+}
+
+/// Cookies, storage keys and request hosts present in one state but not the other.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StateDelta {
+    pub cookies: Vec<CookieInfo>,
+    pub storage: Vec<StorageItem>,
+    pub hosts: Vec<String>,
+}
+
+impl StateDelta {
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty() && self.storage.is_empty() && self.hosts.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentDiff {
+    /// Label of the accept button, `None` if there was none to click
+    pub accept_button: Option<String>,
+    /// Label of the reject button, `None` if the first layer offered no way to refuse
+    pub reject_button: Option<String>,
+    /// What accepting adds
+    pub accept_only: StateDelta,
+    /// What is only present after rejecting
+    pub reject_only: StateDelta,
+    /// Tracking cookies, storage keys and tracker hosts seen despite rejecting
+    pub tracking_after_reject: StateDelta,
+}
+
+/// Load the page twice on fresh profiles, rejecting and then accepting
+/// consent, and diff the two states.
+pub async fn compare(
+    url: &str,
+    rules: &RuleSet,
+    access: &SiteAccess,
+    visitor: &Visitor,
+) -> Result<ConsentDiff> {
+    let reject = headless::render_with_consent(url, ConsentChoice::Reject, access, visitor).await?;
+    let accept = headless::render_with_consent(url, ConsentChoice::Accept, access, visitor).await?;
+    Ok(diff(&reject, &accept, rules))
+}
+
+fn diff(reject: &ConsentState, accept: &ConsentState, rules: &RuleSet) -> ConsentDiff {
+    let tracking_after_reject = StateDelta {
+        cookies: reject
+            .cookies
+            .iter()
+            .filter(|c| needs_consent(&c.category))
+            .cloned()
+            .collect(),
+        storage: reject
+            .storage
+            .iter()
+            .filter(|s| needs_consent(&s.category))
+            .cloned()
+            .collect(),
+        hosts: reject
+            .request_hosts
+            .iter()
+            .filter(|host| rules.trackers.iter().any(|rule| rule.matches_host(host)))
+            .cloned()
+            .collect(),
+    };
+
+    ConsentDiff {
+        accept_button: accept.button.clone(),
+        reject_button: reject.button.clone(),
+        accept_only: only_in(accept, reject),
+        reject_only: only_in(reject, accept),
+        tracking_after_reject,
+    }
+}
+
+/// Everything in `state` that `other` does not have.
+fn only_in(state: &ConsentState, other: &ConsentState) -> StateDelta {
+    StateDelta {
+        cookies: state
+            .cookies
+            .iter()
+            .filter(|c| {
+                !other
+                    .cookies
+                    .iter()
+                    .any(|o| o.name == c.name && o.domain == c.domain)
+            })
+            .cloned()
+            .collect(),
+        storage: state
+            .storage
+            .iter()
+            .filter(|s| {
+                !other
+                    .storage
+                    .iter()
+                    .any(|o| o.area == s.area && o.key == s.key)
+            })
+            .cloned()
+            .collect(),
+        hosts: state
+            .request_hosts
+            .iter()
+            .filter(|host| !other.request_hosts.contains(host))
+            .cloned()
+            .collect(),
+    }
+}
@@ -0,0 +1,227 @@
+//! Finding the site's cookie policy and comparing the cookies it declares
+//! with the cookies actually set.
+
+use crate::{throttle, usage, CookieInfo};
+use regex::Regex;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use url::Url;
+
+/// Where cookie policies usually live, tried when no link is found
+const POLICY_PATHS: &[&str] = &[
+    "/cookie-policy",
+    "/cookies",
+    "/cookie-notice",
+    "/cookies-policy",
+    "/legal/cookies",
+    "/privacy/cookies",
+];
+
+/// Words that, next to "cookie", mark a link as the cookie policy
+const POLICY_WORDS: &[&str] = &[
+    "policy",
+    "notice",
+    "declaration",
+    "statement",
+    "richtlinie",
+    "politique",
+    "política",
+    "informativa",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CookiePolicyReport {
+    /// `None` when no cookie policy could be found
+    pub url: Option<String>,
+    /// Cookie names listed in the policy's tables and code snippets
+    pub declared: Vec<String>,
+    /// Observed cookies the policy does not mention
+    pub undeclared: Vec<String>,
+    /// Declared cookies that were not observed on this scan
+    pub unobserved: Vec<String>,
+}
+
+/// Locate the cookie policy, from `policy_url` if given or else from the
+/// page's links and common paths, and compare it with `cookies`.
+pub async fn compare(
+    client: &Client,
+    html: &str,
+    page_url: &Url,
+    policy_url: Option<&Url>,
+    cookies: &[CookieInfo],
+) -> CookiePolicyReport {
+    let found = match policy_url {
+        Some(url) => fetch_html(client, url)
+            .await
+            .map(|body| (url.clone(), body)),
+        None => discover(client, html, page_url).await,
+    };
+    let (url, declared, text) = match found {
+        Some((url, body)) => {
+            let document = Html::parse_document(&body);
+            let text = document.root_element().text().collect::<String>();
+            (Some(url.to_string()), declared_names(&document), text)
+        }
+        None => (None, Vec::new(), String::new()),
+    };
+
+    let mut undeclared = Vec::new();
+    for cookie in cookies {
+        let mentioned =
+            declared.iter().any(|d| name_matches(d, &cookie.name)) || mentions(&text, &cookie.name);
+        if !mentioned && !undeclared.contains(&cookie.name) {
+            undeclared.push(cookie.name.clone());
+        }
+    }
+    let unobserved = declared
+        .iter()
+        .filter(|d| !cookies.iter().any(|c| name_matches(d, &c.name)))
+        .cloned()
+        .collect();
+
+    CookiePolicyReport {
+        url,
+        declared,
+        undeclared,
+        unobserved,
+    }
+}
+
+async fn discover(client: &Client, html: &str, page_url: &Url) -> Option<(Url, String)> {
+    if let Some(url) = policy_link(html, page_url) {
+        if let Some(body) = fetch_html(client, &url).await {
+            return Some((url, body));
+        }
+    }
+    for path in POLICY_PATHS {
+        let Ok(url) = page_url.join(path) else {
+            continue;
+        };
+        if let Some(body) = fetch_html(client, &url).await {
+            // Unknown paths often land on the home page or a soft 404
+            if body.to_lowercase().contains("cookie") {
+                return Some((url, body));
+            }
+        }
+    }
+    None
+}
+
+/// The first link that reads like a cookie policy, or failing that the
+/// first same-site link with "cookie" in its URL.
+fn policy_link(html: &str, page_url: &Url) -> Option<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("a[href]").unwrap();
+    let links: Vec<(String, Url)> = document
+        .select(&selector)
+        .filter_map(|link| {
+            let url = page_url.join(link.value().attr("href")?).ok()?;
+            let text = link.text().collect::<String>().to_lowercase();
+            Some((text, url))
+        })
+        .filter(|(_, url)| matches!(url.scheme(), "http" | "https"))
+        .collect();
+
+    let by_text = links.iter().find(|(text, _)| {
+        text.contains("cookie") && POLICY_WORDS.iter().any(|word| text.contains(word))
+    });
+    let by_url = || {
+        links.iter().find(|(_, url)| {
+            url.host_str() == page_url.host_str() && url.path().to_lowercase().contains("cookie")
+        })
+    };
+    by_text.or_else(by_url).map(|(_, url)| url.clone())
+}
+
+async fn fetch_html(client: &Client, url: &Url) -> Option<String> {
+    throttle::wait(url).await;
+    let response = client.get(url.clone()).send().await.ok()?;
+    usage::request();
+    let is_html = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"));
+    if !response.status().is_success() || !is_html {
+        return None;
+    }
+    let body = response.text().await.ok()?;
+    usage::add_bytes(body.len());
+    Some(body)
+}
+
+/// Cookie names from the "Name"/"Cookie" column of the policy's tables and
+/// from `<code>` elements.
+fn declared_names(document: &Html) -> Vec<String> {
+    let table_selector = Selector::parse("table").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let cell_selector = Selector::parse("th, td").unwrap();
+    let code_selector = Selector::parse("code").unwrap();
+
+    let mut names = Vec::new();
+    let mut add = |candidate: String| {
+        if looks_like_cookie_name(&candidate) && !names.contains(&candidate) {
+            names.push(candidate);
+        }
+    };
+
+    for table in document.select(&table_selector) {
+        let mut rows = table.select(&row_selector);
+        let Some(header) = rows.next() else {
+            continue;
+        };
+        let Some(column) = header.select(&cell_selector).position(|cell| {
+            let label = cell_text(cell).to_lowercase();
+            label.contains("name") || label.contains("cookie")
+        }) else {
+            continue;
+        };
+        for row in rows {
+            if let Some(cell) = row.select(&cell_selector).nth(column) {
+                // Cells sometimes list several names separated by commas
+                for name in cell_text(cell).split([',', '\n']) {
+                    add(name.trim().to_string());
+                }
+            }
+        }
+    }
+
+    for code in document.select(&code_selector) {
+        add(cell_text(code));
+    }
+
+    names
+}
+
+fn cell_text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
+/// Cookie names have no spaces and are rarely long; prose and headings are skipped.
+fn looks_like_cookie_name(candidate: &str) -> bool {
+    (2..=80).contains(&candidate.len())
+        && candidate
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.*#<>{}[]$".contains(c))
+}
+
+/// Whether a declared name covers an observed one. Policies write variable
+/// suffixes as `_ga_*`, `_ga_#`, `_ga_<container-id>` or `_ga_xxxxxxx`.
+fn name_matches(declared: &str, observed: &str) -> bool {
+    let wildcard = declared
+        .find(['*', '#', '<', '{', '['])
+        .or_else(|| declared.find("xxx"));
+    match wildcard {
+        Some(0) => false,
+        Some(i) => observed.starts_with(&declared[..i]),
+        None => declared == observed,
+    }
+}
+
+/// Whether the policy text names the cookie as a whole word.
+fn mentions(text: &str, name: &str) -> bool {
+    let pattern = format!(r"(^|[^A-Za-z0-9_]){}($|[^A-Za-z0-9_])", regex::escape(name));
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(text))
+}
@@ -0,0 +1,139 @@
+//! User cookie categorization rules loaded with `--cookie-rules`, which take
+//! precedence over the built-in name patterns and vendor list.
+
+use crate::storage::StorageItem;
+use crate::{Confidence, CookieCategory, CookieInfo};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CookieRulesFile {
+    #[serde(default, alias = "rules")]
+    rule: Vec<RuleDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDef {
+    /// Exact cookie name
+    name: Option<String>,
+    /// Regular expression matched against the cookie name
+    regex: Option<String>,
+    category: String,
+    vendor: Option<String>,
+    /// Hosts the rule applies to, including their subdomains; all when empty
+    #[serde(default)]
+    sites: Vec<String>,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Exact(String),
+    Regex(Regex),
+}
+
+#[derive(Debug)]
+struct CookieRule {
+    matcher: Matcher,
+    category: CookieCategory,
+    vendor: Option<String>,
+    sites: Vec<String>,
+}
+
+impl CookieRule {
+    fn matches(&self, name: &str, host: &str) -> bool {
+        let on_site = self.sites.is_empty()
+            || self
+                .sites
+                .iter()
+                .any(|site| host == site || host.ends_with(&format!(".{}", site)));
+        on_site
+            && match &self.matcher {
+                Matcher::Exact(exact) => name == exact,
+                Matcher::Regex(regex) => regex.is_match(name),
+            }
+    }
+}
+
+/// The rules from every `--cookie-rules` file, first match wins.
+#[derive(Debug, Default)]
+pub struct CookieRules {
+    rules: Vec<CookieRule>,
+}
+
+impl CookieRules {
+    /// Add the rules from a TOML file after those already loaded.
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cookie rules {}", path.display()))?;
+        let file: CookieRulesFile = toml::from_str(&contents)
+            .with_context(|| format!("Invalid cookie rules {}", path.display()))?;
+        for def in file.rule {
+            let rule = compile(def)
+                .with_context(|| format!("Invalid cookie rules {}", path.display()))?;
+            self.rules.push(rule);
+        }
+        Ok(())
+    }
+
+    /// Recategorize the cookies and storage keys of a page on `host` that a
+    /// rule matches. Matches are reported with high confidence.
+    pub fn apply(&self, host: &str, cookies: &mut [CookieInfo], storage: &mut [StorageItem]) {
+        if self.rules.is_empty() {
+            return;
+        }
+        let host = host.to_lowercase();
+        for cookie in cookies.iter_mut() {
+            if let Some(rule) = self.find(&cookie.name, &host) {
+                cookie.category = rule.category.clone();
+                if rule.vendor.is_some() {
+                    cookie.vendor = rule.vendor.clone();
+                }
+                cookie.confidence = Confidence::High;
+            }
+        }
+        for item in storage.iter_mut() {
+            if let Some(rule) = self.find(&item.key, &host) {
+                item.category = rule.category.clone();
+                if rule.vendor.is_some() {
+                    item.vendor = rule.vendor.clone();
+                }
+                item.confidence = Confidence::High;
+            }
+        }
+    }
+
+    fn find(&self, name: &str, host: &str) -> Option<&CookieRule> {
+        self.rules.iter().find(|rule| rule.matches(name, host))
+    }
+}
+
+fn compile(def: RuleDef) -> Result<CookieRule> {
+    let matcher = match (def.name, def.regex) {
+        (Some(name), None) => Matcher::Exact(name),
+        (None, Some(pattern)) => Matcher::Regex(
+            Regex::new(&pattern).with_context(|| format!("Invalid regex '{}'", pattern))?,
+        ),
+        _ => bail!("each rule needs exactly one of `name` or `regex`"),
+    };
+    let category = match def.category.to_lowercase().as_str() {
+        "essential" | "necessary" => CookieCategory::Essential,
+        "analytics" | "statistics" => CookieCategory::Analytics,
+        "marketing" | "advertising" => CookieCategory::Marketing,
+        "social" => CookieCategory::Social,
+        "unknown" => CookieCategory::Unknown,
+        other => bail!(
+            "unknown category '{}' (expected essential, analytics, marketing, social or unknown)",
+            other
+        ),
+    };
+    Ok(CookieRule {
+        matcher,
+        category,
+        vendor: def.vendor,
+        sites: def.sites.iter().map(|s| s.to_lowercase()).collect(),
+    })
+}
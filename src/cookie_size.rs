@@ -0,0 +1,87 @@
+//! Cookie count and `Cookie` header size per domain. Every cookie a domain
+//! matches rides along on every request to it, so a large jar slows down each
+//! page view, and servers reject requests once their headers pass 8-16 KB.
+
+use crate::config::CookieBudget;
+use crate::CookieInfo;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainCookies {
+    pub domain: String,
+    /// Cookies sent with a request to `domain`, including those of its
+    /// parent domains
+    pub count: usize,
+    /// Size of the `Cookie` header value those cookies make up
+    pub header_bytes: usize,
+    /// `count` is above the configured budget
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub over_count: bool,
+    /// `header_bytes` is above the configured budget
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub over_size: bool,
+}
+
+impl DomainCookies {
+    pub fn over_budget(&self) -> bool {
+        self.over_count || self.over_size
+    }
+}
+
+/// The domain a cookie belongs to. Cookies without a `Domain` attribute
+/// belong to the page's host.
+fn cookie_domain<'a>(cookie: &'a CookieInfo, page_host: &'a str) -> &'a str {
+    cookie
+        .domain
+        .as_deref()
+        .map_or(page_host, |d| d.trim_start_matches('.'))
+}
+
+/// Whether a cookie of `cookie_domain` is sent to `host`.
+fn domain_matches(host: &str, cookie_domain: &str) -> bool {
+    host.eq_ignore_ascii_case(cookie_domain)
+        || host
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", cookie_domain.to_ascii_lowercase()))
+}
+
+/// Count and size of the cookies each domain receives, largest header
+/// first, checked against `budget`.
+pub fn measure(
+    cookies: &[CookieInfo],
+    page_host: &str,
+    budget: CookieBudget,
+) -> Vec<DomainCookies> {
+    let mut domains: Vec<String> = Vec::new();
+    for cookie in cookies {
+        let domain = cookie_domain(cookie, page_host).to_ascii_lowercase();
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    let mut report: Vec<DomainCookies> = domains
+        .into_iter()
+        .map(|domain| {
+            let sent: Vec<&CookieInfo> = cookies
+                .iter()
+                .filter(|c| domain_matches(&domain, cookie_domain(c, page_host)))
+                .collect();
+            // "name=value" pairs joined with "; "
+            let header_bytes = sent
+                .iter()
+                .map(|c| c.name.len() + 1 + c.value_length)
+                .sum::<usize>()
+                + 2 * sent.len().saturating_sub(1);
+            DomainCookies {
+                count: sent.len(),
+                header_bytes,
+                over_count: sent.len() > budget.max_per_domain,
+                over_size: header_bytes > budget.max_header_bytes,
+                domain,
+            }
+        })
+        .collect();
+    report.sort_by_key(|size| std::cmp::Reverse(size.header_bytes));
+    report
+}
@@ -0,0 +1,442 @@
+//! Following internal links and merging per-page results into one
+//! site-wide report.
+
+use crate::dataflow;
+use crate::fingerprint;
+use crate::integrity::IntegrityReport;
+use crate::preconsent::ViolationSeverity;
+use crate::robots::RobotsCache;
+use crate::{analyze_url, is_third_party, AnalysisResult, ScanOptions};
+use anyhow::{Context, Result};
+use scraper::{Html, Selector};
+use std::collections::{HashSet, VecDeque};
+use url::Url;
+
+// Links to these file types are never HTML pages worth analyzing
+const SKIPPED_EXTENSIONS: &[&str] = &[
+    ".pdf", ".zip", ".gz", ".jpg", ".jpeg", ".png", ".gif", ".svg", ".webp", ".ico", ".mp4",
+    ".mp3", ".webm", ".css", ".js", ".json", ".xml", ".txt", ".doc", ".docx", ".xls", ".xlsx",
+];
+
+/// Limits for a same-site crawl
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    pub max_depth: usize,
+    pub max_pages: usize,
+    /// Skip pages the site's robots.txt disallows
+    pub respect_robots: bool,
+}
+
+/// Collect same-site `<a href>` targets, without fragments, that look like
+/// HTML pages.
+pub fn internal_links(html: &str, page_url: &Url) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("a[href]").unwrap();
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut seen = HashSet::new();
+    document
+        .select(&selector)
+        .filter_map(|a| a.value().attr("href"))
+        .filter_map(|href| page_url.join(href).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .filter(|url| {
+            url.host_str() == page_url.host_str()
+                || url
+                    .domain()
+                    .is_some_and(|d| !is_third_party(d, base_domain))
+        })
+        .filter(|url| {
+            let path = url.path().to_lowercase();
+            !SKIPPED_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+        })
+        .map(|mut url| {
+            url.set_fragment(None);
+            url.to_string()
+        })
+        .filter(|url| seen.insert(url.clone()))
+        .collect()
+}
+
+/// Breadth-first crawl from `start`, calling `progress` before each page.
+/// Only a failure on the start page is an error; other pages are skipped.
+/// Pages robots.txt disallows are skipped and listed in the result, except
+/// the start page, which was asked for by name.
+pub async fn crawl(
+    start: &str,
+    options: &ScanOptions,
+    crawl_options: &CrawlOptions,
+    progress: impl Fn(usize, &str),
+) -> Result<AnalysisResult> {
+    let mut queue = VecDeque::from([(start.to_string(), 0)]);
+    // In the form `internal_links` gives, so links back to the start page match it
+    let mut start_url = Url::parse(start).context("Invalid URL format")?;
+    start_url.set_fragment(None);
+    let mut visited = HashSet::from([start_url.to_string()]);
+    let mut site: Option<AnalysisResult> = None;
+    // Set once the start page has probed the site itself
+    let mut later_pages: Option<ScanOptions> = None;
+    let mut robots = RobotsCache::new(&options.visitor, &options.access);
+    let mut disallowed = Vec::new();
+
+    while let Some((url, depth)) = queue.pop_front() {
+        let scanned = site.as_ref().map_or(0, |s| s.pages.len());
+        if scanned >= crawl_options.max_pages {
+            break;
+        }
+        if depth > 0 && crawl_options.respect_robots && !robots.allows(&url).await {
+            disallowed.push(url);
+            continue;
+        }
+        progress(scanned + 1, &url);
+
+        let page = match analyze_url(&url, later_pages.as_ref().unwrap_or(options)).await {
+            Ok(page) => page,
+            Err(e) if site.is_none() => return Err(e),
+            Err(_) => continue,
+        };
+
+        if depth < crawl_options.max_depth {
+            for link in &page.internal_links {
+                if visited.insert(link.clone()) {
+                    queue.push_back((link.clone(), depth + 1));
+                }
+            }
+        }
+
+        match site.as_mut() {
+            Some(site) => merge(site, page),
+            None => {
+                later_pages = Some(options.for_later_pages(&page));
+                site = Some(page);
+            }
+        }
+    }
+
+    let mut site = site.expect("start page is always analyzed first");
+    site.robots_disallowed = disallowed;
+    Ok(site)
+}
+
+/// Analyze a fixed list of pages, such as those from a sitemap, into one
+/// result. As with crawling, only a failure on the first page is an error.
+pub async fn scan_pages(
+    pages: &[String],
+    options: &ScanOptions,
+    progress: impl Fn(usize, &str),
+) -> Result<AnalysisResult> {
+    let mut site: Option<AnalysisResult> = None;
+    let mut later_pages: Option<ScanOptions> = None;
+
+    for (i, url) in pages.iter().enumerate() {
+        progress(i + 1, url);
+        match analyze_url(url, later_pages.as_ref().unwrap_or(options)).await {
+            Ok(page) => match site.as_mut() {
+                Some(site) => merge(site, page),
+                None => {
+                    later_pages = Some(options.for_later_pages(&page));
+                    site = Some(page);
+                }
+            },
+            Err(e) if site.is_none() => return Err(e),
+            Err(_) => {}
+        }
+    }
+
+    site.context("No pages to scan")
+}
+
+/// Fold one page's findings into the site-wide result, dropping duplicates.
+pub fn merge(site: &mut AnalysisResult, page: AnalysisResult) {
+    site.pages.extend(page.pages);
+    site.content_hash = fingerprint::combine(&site.content_hash, &page.content_hash);
+    if let (Some(site_usage), Some(page_usage)) = (site.fetch_budget.as_mut(), page.fetch_budget) {
+        site_usage.requests += page_usage.requests;
+        site_usage.bytes += page_usage.bytes;
+        site_usage.skipped += page_usage.skipped;
+    }
+
+    for cookie in page.cookies {
+        if !site
+            .cookies
+            .iter()
+            .any(|c| c.name == cookie.name && c.domain == cookie.domain)
+        {
+            site.cookies.push(cookie);
+        }
+    }
+
+    for tracker in page.trackers {
+        if !site.trackers.iter().any(|t| t.name == tracker.name) {
+            site.trackers.push(tracker);
+        }
+    }
+
+    for domain in page.third_party_requests {
+        if !site.third_party_requests.contains(&domain) {
+            site.third_party_requests.push(domain);
+        }
+    }
+
+    for dependency in page.script_dependencies {
+        if !site
+            .script_dependencies
+            .iter()
+            .any(|d| d.script == dependency.script && d.url == dependency.url)
+        {
+            site.script_dependencies.push(dependency);
+        }
+    }
+
+    if let (Some(site_scripts), Some(page_scripts)) =
+        (site.script_inventory.as_mut(), page.script_inventory)
+    {
+        for script in page_scripts {
+            if !site_scripts.iter().any(|s| s.path == script.path) {
+                site_scripts.push(script);
+            }
+        }
+        site_scripts.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    match (site.integrity.take(), page.integrity) {
+        (Some(site_report), Some(page_report)) => {
+            let mut resources = site_report.resources;
+            for resource in page_report.resources {
+                if !resources.iter().any(|r| r.url == resource.url) {
+                    resources.push(resource);
+                }
+            }
+            site.integrity = Some(IntegrityReport::new(resources));
+        }
+        (site_report, page_report) => site.integrity = site_report.or(page_report),
+    }
+
+    for header in page.legacy_headers {
+        if !site
+            .legacy_headers
+            .iter()
+            .any(|h| h.name == header.name && h.value == header.value)
+        {
+            site.legacy_headers.push(header);
+        }
+    }
+
+    // Keep the most revealing referrer each third party receives anywhere
+    for exposure in page.referrer.exposures {
+        match site
+            .referrer
+            .exposures
+            .iter_mut()
+            .find(|e| e.domain == exposure.domain)
+        {
+            Some(existing) if existing.level >= exposure.level => {}
+            Some(existing) => *existing = exposure,
+            None => site.referrer.exposures.push(exposure),
+        }
+    }
+
+    if site.related_set.is_none() {
+        site.related_set = page.related_set;
+    }
+    if site.consent_banner.is_none() {
+        site.consent_banner = page.consent_banner;
+    }
+    match (site.cookie_policy.as_mut(), page.cookie_policy) {
+        (Some(site_policy), Some(page_policy)) => {
+            for name in page_policy.undeclared {
+                if !site_policy.undeclared.contains(&name) {
+                    site_policy.undeclared.push(name);
+                }
+            }
+            // Declared but unobserved only if no page observed it
+            site_policy
+                .unobserved
+                .retain(|name| page_policy.unobserved.contains(name));
+        }
+        (None, page_policy) => site.cookie_policy = page_policy,
+        _ => {}
+    }
+    match (site.compliance.as_mut(), page.compliance) {
+        (Some(site_report), Some(page_report)) => site_report.merge(page_report),
+        (None, page_report) => site.compliance = page_report,
+        _ => {}
+    }
+    if site.static_fallback.is_none() {
+        site.static_fallback = page.static_fallback;
+    }
+    if site.consent_diff.is_none() {
+        site.consent_diff = page.consent_diff;
+    }
+    if site.consent_diff_error.is_none() {
+        site.consent_diff_error = page.consent_diff_error;
+    }
+    match (site.cmp_check.as_mut(), page.cmp_check) {
+        (Some(site_check), Some(page_check)) => {
+            for mismatch in page_check.mismatches {
+                if !site_check
+                    .mismatches
+                    .iter()
+                    .any(|m| m.cookie == mismatch.cookie)
+                {
+                    site_check.mismatches.push(mismatch);
+                }
+            }
+            for name in page_check.undeclared {
+                if !site_check.undeclared.contains(&name) {
+                    site_check.undeclared.push(name);
+                }
+            }
+        }
+        (None, page_check) => site.cmp_check = page_check,
+        _ => {}
+    }
+    if site.tcf.as_ref().is_none_or(|t| t.consent.is_none()) && page.tcf.is_some() {
+        site.tcf = page.tcf;
+    }
+    if site.well_known.is_none() {
+        site.well_known = page.well_known;
+    }
+    if site.https.is_none() {
+        site.https = page.https;
+    }
+    if site.tls.is_none() {
+        site.tls = page.tls;
+    }
+    if site.cookie_refresh.is_none() {
+        site.cookie_refresh = page.cookie_refresh;
+    }
+
+    if let (Some(site_verdicts), Some(page_verdicts)) =
+        (site.cookie_readiness.as_mut(), page.cookie_readiness)
+    {
+        for verdict in page_verdicts {
+            if !site_verdicts
+                .iter()
+                .any(|v| v.cookie == verdict.cookie && v.domain == verdict.domain)
+            {
+                site_verdicts.push(verdict);
+            }
+        }
+        site_verdicts.sort_by(|a, b| {
+            a.status
+                .cmp(&b.status)
+                .then_with(|| a.cookie.cmp(&b.cookie))
+        });
+    }
+
+    for item in page.storage {
+        if !site
+            .storage
+            .iter()
+            .any(|i| i.area == item.area && i.key == item.key)
+        {
+            site.storage.push(item);
+        }
+    }
+
+    for leak in page.pii_leaks {
+        if !site.pii_leaks.iter().any(|l| {
+            l.domain == leak.domain && l.parameter == leak.parameter && l.kind == leak.kind
+        }) {
+            site.pii_leaks.push(leak);
+        }
+    }
+
+    dataflow::merge(&mut site.data_flows, page.data_flows);
+
+    for advice in page.cookie_prefixes {
+        if !site.cookie_prefixes.iter().any(|a| a.cookie == advice.cookie) {
+            site.cookie_prefixes.push(advice);
+        }
+    }
+
+    // A domain's jar as it was at its largest on any page
+    for size in page.cookie_sizes {
+        match site.cookie_sizes.iter_mut().find(|s| s.domain == size.domain) {
+            Some(existing) if existing.header_bytes < size.header_bytes => *existing = size,
+            Some(_) => {}
+            None => site.cookie_sizes.push(size),
+        }
+    }
+    site.cookie_sizes.sort_by_key(|size| std::cmp::Reverse(size.header_bytes));
+
+    for replay in page.session_replay {
+        match site.session_replay.iter_mut().find(|r| r.vendor == replay.vendor) {
+            Some(existing) => {
+                for item in replay.evidence {
+                    if !existing.evidence.contains(&item) {
+                        existing.evidence.push(item);
+                    }
+                }
+            }
+            None => site.session_replay.push(replay),
+        }
+    }
+
+    for pixel in page.suspected_pixels {
+        if !site.suspected_pixels.iter().any(|p| p.url == pixel.url) {
+            site.suspected_pixels.push(pixel);
+        }
+    }
+
+    for beacon in page.beacons {
+        if !site.beacons.iter().any(|b| {
+            b.kind == beacon.kind && b.domain == beacon.domain && b.source == beacon.source
+        }) {
+            site.beacons.push(beacon);
+        }
+    }
+
+    for hint in page.resource_hints {
+        if !site
+            .resource_hints
+            .iter()
+            .any(|h| h.rel == hint.rel && h.url == hint.url)
+        {
+            site.resource_hints.push(hint);
+        }
+    }
+
+    for endpoint in page.websockets {
+        match site.websockets.iter_mut().find(|e| e.url == endpoint.url) {
+            Some(existing) => existing.observed |= endpoint.observed,
+            None => site.websockets.push(endpoint),
+        }
+    }
+
+    for fonts in page.remote_fonts {
+        match site.remote_fonts.iter_mut().find(|f| f.service == fonts.service) {
+            Some(existing) => {
+                for host in fonts.hosts {
+                    if !existing.hosts.contains(&host) {
+                        existing.hosts.push(host);
+                    }
+                }
+                for url in fonts.urls {
+                    if !existing.urls.contains(&url) {
+                        existing.urls.push(url);
+                    }
+                }
+            }
+            None => site.remote_fonts.push(fonts),
+        }
+    }
+
+    for finding in page.skimmers {
+        if !site.skimmers.iter().any(|f| f.source == finding.source) {
+            site.skimmers.push(finding);
+        }
+    }
+
+    for violation in page.pre_consent {
+        if !site
+            .pre_consent
+            .iter()
+            .any(|v| v.kind == violation.kind && v.name == violation.name)
+        {
+            site.pre_consent.push(violation);
+        }
+    }
+    site.pre_consent.sort_by_key(|v| v.severity == ViolationSeverity::Medium);
+}
@@ -0,0 +1,284 @@
+//! External tracker and domain databases downloaded by `recon update-db`
+//! and cached in the user data directory, so detection keeps up with the
+//! web between releases. Scans fall back to the bundled copies when nothing
+//! has been downloaded.
+
+use crate::build_client;
+use crate::disconnect::DisconnectList;
+use crate::filters::FilterList;
+use crate::rules::RuleSet;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cached databases older than this are refreshed before a scan, unless
+/// `--offline` is given
+pub const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How long after a refresh, successful or not, scans wait before trying again
+const RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Older versions of each database kept next to the current one
+const KEEP_VERSIONS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    TrackerRules,
+    FilterList,
+    Disconnect,
+    PublicSuffixList,
+}
+
+pub struct Source {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub url: &'static str,
+}
+
+pub const SOURCES: &[Source] = &[
+    Source {
+        name: "trackers",
+        kind: Kind::TrackerRules,
+        url: "https://raw.githubusercontent.com/jconvery1/recon/main/rules/trackers.yaml",
+    },
+    Source {
+        name: "easyprivacy",
+        kind: Kind::FilterList,
+        url: "https://easylist.to/easylist/easyprivacy.txt",
+    },
+    Source {
+        name: "disconnect",
+        kind: Kind::Disconnect,
+        url: "https://raw.githubusercontent.com/disconnectme/disconnect-tracking-protection/master/services.json",
+    },
+    Source {
+        name: "public-suffix-list",
+        kind: Kind::PublicSuffixList,
+        url: "https://publicsuffix.org/list/public_suffix_list.dat",
+    },
+];
+
+/// What has been downloaded, stored as `manifest.json` in the cache directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub databases: BTreeMap<String, CachedDatabase>,
+    /// When `update` last ran, so a source that keeps failing is not
+    /// downloaded again on every scan; unix timestamp, seconds
+    #[serde(default)]
+    pub attempted_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDatabase {
+    /// The version the file declares, or the download time when it has none
+    pub version: String,
+    /// Unix timestamp, seconds
+    pub fetched_at: u64,
+    pub bytes: usize,
+    pub file: PathBuf,
+}
+
+impl CachedDatabase {
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched_at))
+    }
+}
+
+impl Manifest {
+    /// The manifest on disk; empty if `update-db` has never run.
+    pub fn load() -> Self {
+        cache_dir()
+            .ok()
+            .and_then(|dir| std::fs::read_to_string(dir.join("manifest.json")).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<()> {
+        let dir = cache_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join("manifest.json");
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether any downloaded database is older than `MAX_AGE` and no
+    /// refresh has been tried within `RETRY_AFTER`.
+    pub fn is_stale(&self) -> bool {
+        let since_attempt = Duration::from_secs(unix_now().saturating_sub(self.attempted_at));
+        since_attempt > RETRY_AFTER && self.databases.values().any(|db| db.age() > MAX_AGE)
+    }
+
+    /// The contents of a cached database, if it was downloaded.
+    pub fn read(&self, name: &str) -> Option<String> {
+        let db = self.databases.get(name)?;
+        std::fs::read_to_string(&db.file).ok()
+    }
+
+    /// Replace the bundled data with the cached databases. Called before the
+    /// command-line rules and lists are added, which take precedence;
+    /// `custom_rules` keeps a `--rules` file in place of the cached rules.
+    pub fn apply(&self, rules: &mut RuleSet, custom_rules: bool) -> Result<()> {
+        for source in SOURCES {
+            let Some(contents) = self.read(source.name) else {
+                continue;
+            };
+            let invalid = || format!("Invalid cached {} database", source.name);
+            match source.kind {
+                Kind::TrackerRules if custom_rules => {}
+                Kind::TrackerRules => *rules = RuleSet::parse(&contents).with_context(invalid)?,
+                Kind::FilterList => rules
+                    .filter_lists
+                    .push(FilterList::parse(&contents, source.name)),
+                Kind::Disconnect => {
+                    rules.disconnect = Some(DisconnectList::parse(&contents).with_context(invalid)?)
+                }
+                Kind::PublicSuffixList => crate::psl::use_list(&contents)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Download every database, check that it parses, and make it current.
+/// Sources that fail are reported and keep their previous version.
+pub async fn update(mut on_progress: impl FnMut(&Source, &Result<CachedDatabase>)) -> Result<()> {
+    let client = build_client()?;
+    let mut manifest = Manifest::load();
+    manifest.attempted_at = unix_now();
+    let mut failures = 0;
+    for source in SOURCES {
+        let result = fetch(&client, source).await;
+        on_progress(source, &result);
+        match result {
+            Ok(db) => {
+                manifest.databases.insert(source.name.to_string(), db);
+            }
+            Err(_) => failures += 1,
+        }
+    }
+    if failures == SOURCES.len() {
+        // Still recorded, so the next scans do not try again straight away
+        manifest.save()?;
+        bail!("No database could be downloaded");
+    }
+    manifest.save()?;
+    prune_versions(&manifest)?;
+    Ok(())
+}
+
+async fn fetch(client: &reqwest::Client, source: &Source) -> Result<CachedDatabase> {
+    let response = client
+        .get(source.url)
+        .timeout(Duration::from_secs(120))
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", source.url))?;
+    if !response.status().is_success() {
+        bail!(
+            "Download of {} failed: HTTP {}",
+            source.url,
+            response.status()
+        );
+    }
+    let contents = response.text().await?;
+    validate(source, &contents)?;
+
+    let fetched_at = unix_now();
+    let version =
+        declared_version(source.kind, &contents).unwrap_or_else(|| fetched_at.to_string());
+    let dir = cache_dir()?.join(source.name);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let file = dir.join(format!("{}-{}", fetched_at, file_name(source.url)));
+    std::fs::write(&file, &contents)
+        .with_context(|| format!("Failed to write {}", file.display()))?;
+    Ok(CachedDatabase {
+        version,
+        fetched_at,
+        bytes: contents.len(),
+        file,
+    })
+}
+
+/// Refuse downloads that would break scans, such as an HTML error page.
+fn validate(source: &Source, contents: &str) -> Result<()> {
+    match source.kind {
+        Kind::TrackerRules => {
+            RuleSet::parse(contents)?;
+        }
+        Kind::FilterList => {
+            if FilterList::parse(contents, source.name).filter_count == 0 {
+                bail!("{} contains no filters", source.name);
+            }
+        }
+        Kind::Disconnect => {
+            if DisconnectList::parse(contents)?.domain_count() == 0 {
+                bail!("{} lists no domains", source.name);
+            }
+        }
+        Kind::PublicSuffixList => {
+            if !contents.contains("===BEGIN ICANN DOMAINS===") {
+                bail!("{} is not a public suffix list", source.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The version a database states about itself.
+fn declared_version(kind: Kind, contents: &str) -> Option<String> {
+    let header = contents.lines().take(50);
+    match kind {
+        Kind::FilterList => header
+            .filter_map(|line| line.strip_prefix("! Version:"))
+            .map(|v| v.trim().to_string())
+            .next(),
+        Kind::PublicSuffixList => header
+            .filter_map(|line| line.strip_prefix("// VERSION:"))
+            .map(|v| v.trim().to_string())
+            .next(),
+        Kind::TrackerRules | Kind::Disconnect => None,
+    }
+}
+
+/// Delete all but the newest few downloads of each database.
+fn prune_versions(manifest: &Manifest) -> Result<()> {
+    let root = cache_dir()?;
+    for source in SOURCES {
+        let Ok(entries) = std::fs::read_dir(root.join(source.name)) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        // File names start with the download time, so this sorts newest first
+        files.sort_by(|a, b| b.cmp(a));
+        let current = manifest.databases.get(source.name).map(|db| &db.file);
+        for file in files.iter().skip(KEEP_VERSIONS + 1) {
+            if Some(file) != current {
+                let _ = std::fs::remove_file(file);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn file_name(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or("database")
+}
+
+/// `databases` in the recon user data directory.
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(dirs::data_dir()
+        .ok_or_else(|| anyhow!("Could not determine the user data directory"))?
+        .join("recon")
+        .join("databases"))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
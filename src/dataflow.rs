@@ -0,0 +1,192 @@
+//! Which categories of data each third party is likely to receive, for
+//! records of processing (RoPA) and DPIA documentation.
+
+use crate::pii::PiiLeak;
+use crate::referrer::{ReferrerLevel, ReferrerReport};
+use crate::{is_third_party, psl, CookieInfo};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Query parameters that carry a visitor, device or click identifier
+const ID_PARAMS: &[&str] = &[
+    "uid",
+    "user_id",
+    "userid",
+    "cid",
+    "client_id",
+    "clientid",
+    "vid",
+    "visitor_id",
+    "anonymous_id",
+    "anonymousid",
+    "device_id",
+    "deviceid",
+    "sid",
+    "session_id",
+    "gclid",
+    "fbclid",
+    "msclkid",
+    "fbp",
+    "_fbp",
+    "_ga",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataType {
+    /// Sent with every request
+    IpAndUserAgent,
+    /// The full page URL, through the Referer header
+    PageUrl,
+    /// Cookies scoped to the recipient or ID parameters in its URLs
+    Identifiers,
+    /// Email addresses, phone numbers or names in its URLs
+    PersonalData,
+    /// Submissions of a form that posts to the recipient
+    FormData,
+}
+
+impl DataType {
+    pub const ALL: [DataType; 5] = [
+        DataType::IpAndUserAgent,
+        DataType::PageUrl,
+        DataType::Identifiers,
+        DataType::PersonalData,
+        DataType::FormData,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataType::IpAndUserAgent => "IP + UA",
+            DataType::PageUrl => "Page URL",
+            DataType::Identifiers => "Identifiers",
+            DataType::PersonalData => "Personal data",
+            DataType::FormData => "Form data",
+        }
+    }
+}
+
+/// The data one third-party domain is likely to receive.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataFlow {
+    pub recipient: String,
+    pub data: Vec<DataType>,
+}
+
+impl DataFlow {
+    pub fn receives(&self, data_type: DataType) -> bool {
+        self.data.contains(&data_type)
+    }
+}
+
+/// Infer the data each third party receives from what the page loads and
+/// submits. Every recipient gets the visitor's IP address and User-Agent.
+pub fn infer(
+    html: &str,
+    page_url: &Url,
+    third_party_domains: &[String],
+    cookies: &[CookieInfo],
+    referrer: &ReferrerReport,
+    pii_leaks: &[PiiLeak],
+    extra_urls: &[String],
+) -> Vec<DataFlow> {
+    let document = Html::parse_document(html);
+    let resource_selector =
+        Selector::parse("script[src], img[src], iframe[src], link[href], source[src]").unwrap();
+    let form_selector = Selector::parse("form[action]").unwrap();
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut flows: BTreeMap<String, Vec<DataType>> = BTreeMap::new();
+    let mut add = |domain: &str, data_type: DataType| {
+        let data = flows.entry(domain.to_string()).or_default();
+        if !data.contains(&data_type) {
+            data.push(data_type);
+        }
+    };
+
+    for domain in third_party_domains {
+        add(domain, DataType::IpAndUserAgent);
+        let has_cookies = cookies
+            .iter()
+            .filter_map(|c| c.domain.as_deref())
+            .any(|cookie_domain| psl::same_site(cookie_domain, domain));
+        if has_cookies {
+            add(domain, DataType::Identifiers);
+        }
+    }
+
+    let mut urls: Vec<Url> = document
+        .select(&resource_selector)
+        .filter_map(|e| e.value().attr("src").or_else(|| e.value().attr("href")))
+        .filter_map(|src| page_url.join(src).ok())
+        .collect();
+    urls.extend(extra_urls.iter().filter_map(|u| Url::parse(u).ok()));
+    for url in &urls {
+        let Some(domain) = url.domain() else {
+            continue;
+        };
+        if !is_third_party(domain, base_domain) {
+            continue;
+        }
+        add(domain, DataType::IpAndUserAgent);
+        let has_id = url.query_pairs().any(|(key, value)| {
+            !value.is_empty() && ID_PARAMS.contains(&key.to_ascii_lowercase().as_str())
+        });
+        if has_id {
+            add(domain, DataType::Identifiers);
+        }
+    }
+
+    for exposure in &referrer.exposures {
+        if exposure.level == ReferrerLevel::Full {
+            add(&exposure.domain, DataType::PageUrl);
+        }
+    }
+    for leak in pii_leaks {
+        add(&leak.domain, DataType::PersonalData);
+    }
+    for form in document.select(&form_selector) {
+        let Some(action) = form.value().attr("action") else {
+            continue;
+        };
+        let Some(domain) = page_url
+            .join(action)
+            .ok()
+            .and_then(|url| url.domain().map(str::to_string))
+        else {
+            continue;
+        };
+        if is_third_party(&domain, base_domain) {
+            add(&domain, DataType::IpAndUserAgent);
+            add(&domain, DataType::FormData);
+        }
+    }
+
+    flows
+        .into_iter()
+        .map(|(recipient, mut data)| {
+            data.sort();
+            DataFlow { recipient, data }
+        })
+        .collect()
+}
+
+/// Fold another page's flows into `flows`, keeping recipients sorted.
+pub fn merge(flows: &mut Vec<DataFlow>, other: Vec<DataFlow>) {
+    for flow in other {
+        match flows.iter_mut().find(|f| f.recipient == flow.recipient) {
+            Some(existing) => {
+                for data_type in flow.data {
+                    if !existing.data.contains(&data_type) {
+                        existing.data.push(data_type);
+                    }
+                }
+                existing.data.sort();
+            }
+            None => flows.push(flow),
+        }
+    }
+    flows.sort_by(|a, b| a.recipient.cmp(&b.recipient));
+}
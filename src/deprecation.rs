@@ -0,0 +1,157 @@
+//! Classifying cookies by whether they survive the third-party cookie
+//! phase-out in Chrome.
+
+use crate::related_sets::RelatedWebsiteSet;
+use crate::{is_third_party, CookieInfo};
+use serde::Serialize;
+use url::Url;
+
+/// What happens to a cookie once third-party cookies are blocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    /// Third-party and unpartitioned; it stops working cross-site
+    Blocked,
+    /// First-party but `SameSite=None` and unpartitioned; it only matters in
+    /// cross-site requests and embeds, where it will be blocked
+    CrossSite,
+    /// Third-party but in the site's Related Website Set; it stays usable
+    /// across the set once the Storage Access API grants access
+    RelatedSet,
+    /// Third-party with the `Partitioned` attribute (CHIPS); it keeps working
+    /// but only within this top-level site
+    Partitioned,
+    /// Set for the site itself, which the phase-out does not affect
+    FirstParty,
+}
+
+impl ReadinessStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessStatus::Blocked => "Blocked",
+            ReadinessStatus::CrossSite => "Blocked when embedded",
+            ReadinessStatus::RelatedSet => "Related Website Set",
+            ReadinessStatus::Partitioned => "Partitioned (CHIPS)",
+            ReadinessStatus::FirstParty => "Unaffected first-party",
+        }
+    }
+}
+
+/// The readiness verdict for one observed cookie.
+#[derive(Debug, Clone, Serialize)]
+pub struct CookieReadiness {
+    pub cookie: String,
+    /// The domain the cookie belongs to
+    pub domain: String,
+    pub vendor: Option<String>,
+    /// Set with `SameSite=None`, so it is meant to be sent cross-site
+    pub same_site_none: bool,
+    pub partitioned: bool,
+    pub status: ReadinessStatus,
+    /// What to change to keep the cookie working, if anything
+    pub advice: Option<String>,
+}
+
+/// Classify every cookie against the page it was observed on, most affected
+/// first.
+pub fn assess(
+    cookies: &[CookieInfo],
+    page_url: &Url,
+    related_set: Option<&RelatedWebsiteSet>,
+) -> Vec<CookieReadiness> {
+    let page_host = page_url.host_str().unwrap_or("");
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut verdicts: Vec<CookieReadiness> = cookies
+        .iter()
+        .map(|cookie| {
+            // Host-only cookies belong to the host that set them
+            let domain = cookie
+                .domain
+                .as_deref()
+                .map(|d| d.trim_start_matches('.'))
+                .unwrap_or(page_host)
+                .to_string();
+            let third_party = !domain.is_empty() && is_third_party(&domain, base_domain);
+            let same_site_none = cookie
+                .same_site
+                .as_deref()
+                .is_some_and(|s| s.eq_ignore_ascii_case("none"));
+
+            let (status, advice) = if !third_party && same_site_none && !cookie.partitioned {
+                (
+                    ReadinessStatus::CrossSite,
+                    Some(
+                        "Use SameSite=Lax unless the site is embedded in other sites or \
+                         receives cross-site requests; if it is, add Partitioned"
+                            .to_string(),
+                    ),
+                )
+            } else if !third_party {
+                (ReadinessStatus::FirstParty, None)
+            } else if cookie.partitioned && cookie.secure {
+                (ReadinessStatus::Partitioned, None)
+            } else if related_set.is_some_and(|set| set.contains(&domain)) {
+                (
+                    ReadinessStatus::RelatedSet,
+                    Some(
+                        "Call requestStorageAccess() (or requestStorageAccessFor() from the \
+                         top-level site) before relying on it"
+                            .to_string(),
+                    ),
+                )
+            } else if cookie.partitioned {
+                (
+                    ReadinessStatus::Blocked,
+                    Some(
+                        "Partitioned cookies must also be Secure; add the Secure attribute"
+                            .to_string(),
+                    ),
+                )
+            } else {
+                (
+                    ReadinessStatus::Blocked,
+                    Some(
+                        "Add the Partitioned attribute if it only needs to work on this site, \
+                         otherwise move to first-party or server-side collection"
+                            .to_string(),
+                    ),
+                )
+            };
+
+            CookieReadiness {
+                cookie: cookie.name.clone(),
+                domain,
+                vendor: cookie.vendor.clone(),
+                same_site_none,
+                partitioned: cookie.partitioned,
+                status,
+                advice,
+            }
+        })
+        .collect();
+
+    verdicts.sort_by(|a, b| {
+        a.status
+            .cmp(&b.status)
+            .then_with(|| a.cookie.cmp(&b.cookie))
+    });
+    verdicts
+}
+
+/// Vendors behind the cookies that stop working, each once.
+pub fn broken_integrations(verdicts: &[CookieReadiness]) -> Vec<&str> {
+    let mut vendors: Vec<&str> = Vec::new();
+    for verdict in verdicts {
+        if matches!(
+            verdict.status,
+            ReadinessStatus::Blocked | ReadinessStatus::CrossSite
+        ) {
+            let vendor = verdict.vendor.as_deref().unwrap_or(&verdict.domain);
+            if !vendors.contains(&vendor) {
+                vendors.push(vendor);
+            }
+        }
+    }
+    vendors
+}
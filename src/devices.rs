@@ -0,0 +1,100 @@
+//! Scanning a page as a desktop and as a mobile visitor, since mobile pages
+//! often carry a different ad stack.
+
+use crate::crawl::merge;
+use crate::user_agents::UserAgentPreset;
+use crate::variants::{self, VariantFindings};
+use crate::visitor::Visitor;
+use crate::{analyze_url, AnalysisResult, ScanOptions};
+use anyhow::Result;
+use chromiumoxide::handler::viewport::Viewport;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Device {
+    Desktop,
+    Mobile,
+}
+
+impl Device {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Device::Desktop => "desktop",
+            Device::Mobile => "mobile",
+        }
+    }
+
+    pub fn user_agent(self) -> &'static str {
+        match self {
+            Device::Desktop => UserAgentPreset::ChromeDesktop.user_agent(),
+            Device::Mobile => UserAgentPreset::Iphone.user_agent(),
+        }
+    }
+
+    /// The screen the headless browser emulates.
+    pub fn viewport(self) -> Viewport {
+        match self {
+            Device::Desktop => Viewport {
+                width: 1366,
+                height: 768,
+                device_scale_factor: None,
+                emulating_mobile: false,
+                is_landscape: true,
+                has_touch: false,
+            },
+            Device::Mobile => Viewport {
+                width: 390,
+                height: 844,
+                device_scale_factor: Some(3.0),
+                emulating_mobile: true,
+                is_landscape: false,
+                has_touch: true,
+            },
+        }
+    }
+}
+
+/// Findings only one of the two devices received.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceComparison {
+    pub desktop: VariantFindings,
+    pub mobile: VariantFindings,
+}
+
+/// Analyze `url` as a desktop and as a mobile visitor and merge both scans
+/// into one result that records what was unique to each.
+pub async fn compare(
+    url: &str,
+    options: &ScanOptions,
+    progress: impl Fn(Device),
+) -> Result<AnalysisResult> {
+    let scan = |device: Device| {
+        progress(device);
+        let options = ScanOptions {
+            visitor: Visitor {
+                device: Some(device),
+                ..options.visitor.clone()
+            },
+            ..options.clone()
+        };
+        async move { analyze_url(url, &options).await }
+    };
+    let mut desktop = scan(Device::Desktop).await?;
+    let mobile = scan(Device::Mobile).await?;
+
+    let mut unique = variants::unique_findings(&[
+        (Device::Desktop.as_str(), &desktop),
+        (Device::Mobile.as_str(), &mobile),
+    ])
+    .into_iter();
+    let comparison = DeviceComparison {
+        desktop: unique.next().expect("one entry per device"),
+        mobile: unique.next().expect("one entry per device"),
+    };
+
+    merge(&mut desktop, mobile);
+    desktop.pages = vec![desktop.url.clone()];
+    desktop.device_comparison = Some(comparison);
+    Ok(desktop)
+}
@@ -0,0 +1,95 @@
+//! The Disconnect tracking protection list (`services.json`), which maps
+//! tracker domains to the company behind them and a category.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Disconnect categories that are loaded, with the tracker category each
+/// becomes. The list's other categories (fingerprinting, cryptomining,
+/// email) overlap with these and are left out.
+const CATEGORIES: &[(&str, &str)] = &[
+    ("Advertising", "Marketing"),
+    ("Analytics", "Analytics"),
+    ("Social", "Social"),
+    ("Content", "Content"),
+];
+
+/// The company behind a listed domain.
+#[derive(Debug)]
+pub struct Entity {
+    pub name: String,
+    /// The Disconnect category, e.g. `Advertising`
+    pub disconnect_category: &'static str,
+    /// The matching tracker category, e.g. `Marketing`
+    pub category: &'static str,
+}
+
+#[derive(Debug)]
+pub struct DisconnectList {
+    by_domain: HashMap<String, Entity>,
+}
+
+impl DisconnectList {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read Disconnect list {}", path.display()))?;
+        Self::parse(&contents)
+            .with_context(|| format!("Invalid Disconnect list {}", path.display()))
+    }
+
+    /// Parse `services.json`:
+    /// `{"categories": {"Advertising": [{"Company": {"https://homepage/": ["domain", ...]}}]}}`
+    pub fn parse(contents: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(contents)?;
+        let Some(categories) = root.get("categories").and_then(Value::as_object) else {
+            bail!("missing \"categories\" object");
+        };
+
+        let mut by_domain = HashMap::new();
+        for &(disconnect_category, category) in CATEGORIES {
+            let Some(entries) = categories
+                .get(disconnect_category)
+                .and_then(Value::as_array)
+            else {
+                continue;
+            };
+            let entities = entries.iter().filter_map(Value::as_object).flatten();
+            for (name, properties) in entities {
+                // Homepage keys hold the domain arrays; other keys are flags
+                let domains = properties
+                    .as_object()
+                    .into_iter()
+                    .flat_map(|p| p.values())
+                    .filter_map(Value::as_array)
+                    .flatten()
+                    .filter_map(Value::as_str);
+                for domain in domains {
+                    // A domain listed in several categories keeps the first
+                    by_domain
+                        .entry(domain.to_lowercase())
+                        .or_insert_with(|| Entity {
+                            name: name.clone(),
+                            disconnect_category,
+                            category,
+                        });
+                }
+            }
+        }
+        Ok(DisconnectList { by_domain })
+    }
+
+    pub fn domain_count(&self) -> usize {
+        self.by_domain.len()
+    }
+
+    /// The listed domain `host` falls under and its entity.
+    pub fn lookup(&self, host: &str) -> Option<(&str, &Entity)> {
+        let host = host.to_lowercase();
+        std::iter::once(host.as_str())
+            .chain(host.match_indices('.').map(|(i, _)| &host[i + 1..]))
+            .find_map(|suffix| self.by_domain.get_key_value(suffix))
+            .map(|(domain, entity)| (domain.as_str(), entity))
+    }
+}
@@ -0,0 +1,42 @@
+//! Whether a cookie value looks like a unique identifier. The estimate is
+//! the entropy the value could carry given the characters it uses, counted
+//! only over random-looking parts, so `GA1.2.1234567890.1700000000` counts
+//! its two numbers and `necessary,statistics` counts nothing.
+
+/// Values carrying at least this many bits are treated as identifiers
+const IDENTIFIER_BITS: f64 = 64.0;
+
+/// Estimated entropy of `value` in bits, and whether that makes it a likely
+/// unique identifier.
+pub fn estimate(value: &str) -> (u32, bool) {
+    let bits: f64 = value
+        .split(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '+' | '/' | '='))
+        .map(token_bits)
+        .sum();
+    (bits.round() as u32, bits >= IDENTIFIER_BITS)
+}
+
+/// Bits a token could carry, or 0 for tokens that look like words, flags or
+/// short counters.
+fn token_bits(token: &str) -> f64 {
+    let token = token.trim_end_matches('=');
+    let len = token.len();
+    let digits = token.chars().any(|c| c.is_ascii_digit());
+    let lower = token.chars().any(|c| c.is_ascii_lowercase());
+    let upper = token.chars().any(|c| c.is_ascii_uppercase());
+    let symbols = token.contains(['+', '/']);
+
+    // Random IDs of any length almost always contain a digit; words and
+    // camelCase names do not
+    if len < 8 || !digits {
+        return 0.0;
+    }
+    let alphabet: f64 = match (lower, upper) {
+        _ if symbols => 64.0,
+        (true, true) => 62.0,
+        (false, false) => 10.0,
+        _ if token.chars().all(|c| c.is_ascii_hexdigit()) => 16.0,
+        _ => 36.0,
+    };
+    len as f64 * alphabet.log2()
+}
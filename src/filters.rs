@@ -0,0 +1,300 @@
+//! Adblock Plus filter lists such as EasyList and EasyPrivacy, matched
+//! against resource URLs alongside the bundled tracker rules.
+//!
+//! Only network filters are used. Cosmetic (element hiding) filters, regular
+//! expression filters and filters with options that change what is blocked
+//! rather than whether (`csp=`, `redirect=`, `removeparam=`, ...) are skipped.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use url::Url;
+
+/// Resource type options; recon does not know request types precisely, so
+/// filters limited to a type are applied to every request
+const TYPE_OPTIONS: &[&str] = &[
+    "script",
+    "image",
+    "stylesheet",
+    "object",
+    "xmlhttprequest",
+    "xhr",
+    "subdocument",
+    "frame",
+    "ping",
+    "media",
+    "font",
+    "other",
+    "websocket",
+    "webrtc",
+    "beacon",
+    "match-case",
+    "important",
+    "all",
+];
+
+/// A parsed filter list.
+#[derive(Debug)]
+pub struct FilterList {
+    /// The list's `! Title:` header, or its file name
+    pub title: String,
+    /// Category given to trackers the list matches
+    pub category: String,
+    /// Host-anchored filters (`||host^...`), keyed by host
+    by_host: HashMap<String, Vec<Filter>>,
+    /// Every other blocking filter
+    generic: Vec<Filter>,
+    /// `@@` filters that unblock matching URLs
+    exceptions: Vec<Filter>,
+    pub filter_count: usize,
+}
+
+#[derive(Debug)]
+pub struct Filter {
+    /// The filter line as written, used as the tracker name
+    pub text: String,
+    pattern: String,
+    anchor: Anchor,
+    end_anchor: bool,
+    /// `Some(true)` for `$third-party`, `Some(false)` for `$~third-party`
+    third_party: Option<bool>,
+    /// `$domain=` restrictions on the page the request comes from
+    include_domains: Vec<String>,
+    exclude_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Anchor {
+    None,
+    /// `|`: the start of the URL
+    Start,
+    /// `||`: the start of the host or of one of its subdomain labels
+    Host,
+}
+
+impl FilterList {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read filter list {}", path.display()))?;
+        let fallback_title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Filter list".to_string());
+        Ok(Self::parse(&contents, &fallback_title))
+    }
+
+    pub fn parse(contents: &str, fallback_title: &str) -> Self {
+        let title = contents
+            .lines()
+            .take(50)
+            .find_map(|line| line.strip_prefix("! Title:"))
+            .map(|t| t.trim().to_string())
+            .unwrap_or_else(|| fallback_title.to_string());
+        // EasyPrivacy and its relatives target tracking; EasyList targets ads
+        let category = if title.to_lowercase().contains("privacy") {
+            "Analytics"
+        } else {
+            "Marketing"
+        };
+
+        let mut list = FilterList {
+            title,
+            category: category.to_string(),
+            by_host: HashMap::new(),
+            generic: Vec::new(),
+            exceptions: Vec::new(),
+            filter_count: 0,
+        };
+        for line in contents.lines() {
+            let Some((filter, exception)) = parse_filter(line.trim()) else {
+                continue;
+            };
+            list.filter_count += 1;
+            if exception {
+                list.exceptions.push(filter);
+            } else if let Some(host) = filter.indexed_host() {
+                list.by_host.entry(host).or_default().push(filter);
+            } else {
+                list.generic.push(filter);
+            }
+        }
+        list
+    }
+
+    /// The blocking filter matching a request to `url` from a page on
+    /// `page_domain`, unless an exception filter allows it.
+    pub fn matches(&self, url: &Url, page_domain: &str) -> Option<&Filter> {
+        let host = url.host_str()?;
+        let url_lower = url.as_str().to_lowercase();
+        let third_party = crate::is_third_party(host, page_domain);
+
+        let mut candidates = host_suffixes(host)
+            .filter_map(|suffix| self.by_host.get(suffix))
+            .flatten()
+            .chain(&self.generic);
+        let filter =
+            candidates.find(|f| f.matches(&url_lower, host, third_party, page_domain))?;
+        let allowed = self
+            .exceptions
+            .iter()
+            .any(|f| f.matches(&url_lower, host, third_party, page_domain));
+        (!allowed).then_some(filter)
+    }
+}
+
+impl Filter {
+    /// Whether the filter names a host, which makes its matches reliable.
+    pub fn is_host_filter(&self) -> bool {
+        self.anchor == Anchor::Host
+    }
+
+    /// Host-anchored filters on a literal host are looked up by that host.
+    fn indexed_host(&self) -> Option<String> {
+        if self.anchor != Anchor::Host {
+            return None;
+        }
+        let end = self
+            .pattern
+            .find(['^', '/', '*', '|', ':'])
+            .unwrap_or(self.pattern.len());
+        let host = &self.pattern[..end];
+        (host.contains('.') && !host.starts_with('.') && !host.ends_with('.'))
+            .then(|| host.to_string())
+    }
+
+    fn matches(&self, url: &str, host: &str, third_party: bool, page_domain: &str) -> bool {
+        if self.third_party.is_some_and(|wanted| wanted != third_party) {
+            return false;
+        }
+        let on_domain = |d: &String| page_domain == d || page_domain.ends_with(&format!(".{}", d));
+        if !self.include_domains.is_empty() && !self.include_domains.iter().any(on_domain) {
+            return false;
+        }
+        if self.exclude_domains.iter().any(on_domain) {
+            return false;
+        }
+
+        let pattern = self.pattern.as_bytes();
+        let url = url.as_bytes();
+        match self.anchor {
+            Anchor::Start => glob(pattern, url, self.end_anchor),
+            Anchor::Host => {
+                let Some(host_start) = url
+                    .windows(3)
+                    .position(|w| w == b"://")
+                    .map(|i| i + 3)
+                else {
+                    return false;
+                };
+                // The host itself and the start of each of its labels
+                std::iter::once(0)
+                    .chain(host.match_indices('.').map(|(i, _)| i + 1))
+                    .any(|offset| glob(pattern, &url[host_start + offset..], self.end_anchor))
+            }
+            Anchor::None => (0..url.len()).any(|start| {
+                (pattern.first().is_none_or(|&c| c == b'*' || c == b'^' || c == url[start]))
+                    && glob(pattern, &url[start..], self.end_anchor)
+            }),
+        }
+    }
+}
+
+/// Parse one line, returning the filter and whether it is an exception.
+fn parse_filter(line: &str) -> Option<(Filter, bool)> {
+    if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+        return None;
+    }
+    // Cosmetic and scriptlet filters
+    if ["##", "#@#", "#?#", "#$#", "#%#"].iter().any(|m| line.contains(m)) {
+        return None;
+    }
+
+    let (line, exception) = match line.strip_prefix("@@") {
+        Some(rest) => (rest, true),
+        None => (line, false),
+    };
+    let (pattern, options) = match line.rfind('$') {
+        Some(i) if !line.starts_with('/') => (&line[..i], Some(&line[i + 1..])),
+        _ => (line, None),
+    };
+    // Regular expression filters
+    if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+        return None;
+    }
+
+    let mut filter = Filter {
+        text: if exception {
+            format!("@@{}", line)
+        } else {
+            line.to_string()
+        },
+        pattern: String::new(),
+        anchor: Anchor::None,
+        end_anchor: false,
+        third_party: None,
+        include_domains: Vec::new(),
+        exclude_domains: Vec::new(),
+    };
+
+    for option in options.into_iter().flat_map(|o| o.split(',')) {
+        let option = option.trim().to_lowercase();
+        match option.as_str() {
+            "third-party" | "3p" => filter.third_party = Some(true),
+            "~third-party" | "first-party" | "1p" => filter.third_party = Some(false),
+            _ if option.starts_with("domain=") => {
+                for domain in option["domain=".len()..].split('|') {
+                    match domain.strip_prefix('~') {
+                        Some(excluded) => filter.exclude_domains.push(excluded.to_string()),
+                        None => filter.include_domains.push(domain.to_string()),
+                    }
+                }
+            }
+            _ if TYPE_OPTIONS.contains(&option.trim_start_matches('~')) => {}
+            // Anything else changes the response rather than blocking it
+            _ => return None,
+        }
+    }
+
+    let mut pattern = pattern.to_lowercase();
+    if let Some(rest) = pattern.strip_prefix("||") {
+        filter.anchor = Anchor::Host;
+        pattern = rest.to_string();
+    } else if let Some(rest) = pattern.strip_prefix('|') {
+        filter.anchor = Anchor::Start;
+        pattern = rest.to_string();
+    }
+    if let Some(rest) = pattern.strip_suffix('|') {
+        filter.end_anchor = true;
+        pattern = rest.to_string();
+    }
+    // A bare `*` or empty pattern would match every request
+    if pattern.trim_matches('*').is_empty() {
+        return None;
+    }
+    filter.pattern = pattern;
+    Some((filter, exception))
+}
+
+/// `a.b.example.com`, `b.example.com`, `example.com`, `com`.
+fn host_suffixes(host: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(host).chain(host.match_indices('.').map(move |(i, _)| &host[i + 1..]))
+}
+
+/// Match an Adblock pattern at the start of `text`: `*` is any run of
+/// characters and `^` a separator or the end of the URL.
+fn glob(pattern: &[u8], text: &[u8], end_anchor: bool) -> bool {
+    match pattern.first() {
+        None => !end_anchor || text.is_empty(),
+        Some(b'*') => (0..=text.len()).any(|i| glob(&pattern[1..], &text[i..], end_anchor)),
+        Some(b'^') => match text.first() {
+            None => glob(&pattern[1..], text, end_anchor),
+            Some(&c) if is_separator(c) => glob(&pattern[1..], &text[1..], end_anchor),
+            Some(_) => false,
+        },
+        Some(&c) => text.first() == Some(&c) && glob(&pattern[1..], &text[1..], end_anchor),
+    }
+}
+
+fn is_separator(c: u8) -> bool {
+    !(c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'%'))
+}
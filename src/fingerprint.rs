@@ -0,0 +1,49 @@
+//! A hash of a page's content that ignores per-request noise such as CSP
+//! nonces, CSRF tokens and timestamps, so two scans of an unchanged page
+//! get the same fingerprint and a tracking change can be told apart from an
+//! edit to the page itself.
+
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+/// Values that differ on every request without the page changing
+const NOISE: &[&str] = &[
+    // CSP nonces and integrity hashes on scripts and styles
+    r#"(?i)\s(nonce|integrity)\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#,
+    // CSRF and other token fields
+    r#"(?i)<(meta|input)\b[^>]*(csrf|xsrf|token|nonce)[^>]*>"#,
+    // Cache busters and build or request IDs
+    r#"(?i)[?&](v|ver|_|t|ts|cb|cachebust|timestamp)=[\w.-]+"#,
+    r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+    r"(?i)\b[0-9a-f]{24,}\b",
+    // Unix timestamps in seconds or milliseconds, and ISO 8601 times
+    r"\b1[0-9]{9}(?:[0-9]{3})?\b",
+    r"\b\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?\b",
+];
+
+/// The page with the noise removed and whitespace collapsed.
+fn normalize(html: &str) -> String {
+    let mut text = html.to_string();
+    for pattern in NOISE {
+        let noise = Regex::new(pattern).unwrap();
+        text = noise.replace_all(&text, "").into_owned();
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// SHA-256 of the normalized page, in hex.
+pub fn content_hash(html: &str) -> String {
+    hex(&Sha256::digest(normalize(html).as_bytes()))
+}
+
+/// One fingerprint for a crawl from the fingerprints of its pages.
+pub fn combine(site: &str, page: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(site.as_bytes());
+    hasher.update(page.as_bytes());
+    hex(&hasher.finalize())
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,140 @@
+//! Web fonts loaded from font services rather than the site itself. Each
+//! font request hands the visitor's IP address to the service, and in
+//! January 2022 the Munich Regional Court (LG München I, 3 O 17493/20) held
+//! that doing so for Google Fonts without consent breaches the GDPR.
+
+use crate::rules::RuleSet;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::sync::OnceLock;
+use url::Url;
+
+static CSS_URL: OnceLock<Regex> = OnceLock::new();
+
+/// (service, hosts it serves stylesheets, scripts or font files from,
+/// including subdomains)
+const SERVICES: &[(&str, &[&str])] = &[
+    (
+        "Google Fonts",
+        &["fonts.googleapis.com", "fonts.gstatic.com"],
+    ),
+    (
+        "Adobe Fonts",
+        &["use.typekit.net", "p.typekit.net", "use.typekit.com"],
+    ),
+    (
+        "Font Awesome",
+        &[
+            "use.fontawesome.com",
+            "kit.fontawesome.com",
+            "ka-f.fontawesome.com",
+        ],
+    ),
+    ("Monotype Fonts", &["fast.fonts.net", "fast.fonts.com"]),
+    ("Cloud.typography", &["cloud.typography.com"]),
+    ("Bunny Fonts", &["fonts.bunny.net"]),
+    ("Fontshare", &["api.fontshare.com", "cdn.fontshare.com"]),
+];
+
+/// The service courts have ruled on
+const GOOGLE_FONTS: &str = "Google Fonts";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteFonts {
+    pub service: String,
+    /// Hosts of the service the page loads from
+    pub hosts: Vec<String>,
+    /// Stylesheets, scripts and font files requested from the service
+    pub urls: Vec<String>,
+}
+
+impl RemoteFonts {
+    /// Whether a court has found loading this service without consent
+    /// unlawful.
+    pub fn ruled_unlawful(&self) -> bool {
+        self.service == GOOGLE_FONTS
+    }
+}
+
+fn service_for(host: &str) -> Option<&'static str> {
+    let host = host.to_ascii_lowercase();
+    SERVICES
+        .iter()
+        .find(|(_, hosts)| {
+            hosts
+                .iter()
+                .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+        })
+        .map(|(service, _)| *service)
+}
+
+/// Font services the page loads from through `<link>` and `<script>`
+/// elements, `@import` and `url()` in its styles, and `fetched` scripts.
+pub fn detect(
+    html: &str,
+    page_url: &Url,
+    fetched: &[(Url, String)],
+    rules: &RuleSet,
+) -> Vec<RemoteFonts> {
+    let document = Html::parse_document(html);
+    let mut urls: Vec<Url> = Vec::new();
+
+    let resources = Selector::parse("link[href], script[src]").unwrap();
+    for element in document.select(&resources) {
+        let target = element.value().attr("href").or(element.value().attr("src"));
+        urls.extend(target.and_then(|t| page_url.join(t.trim()).ok()));
+    }
+
+    // @import "..." and url(...) in <style> elements, style attributes and
+    // scripts that inject stylesheets
+    let css_url = CSS_URL.get_or_init(|| {
+        Regex::new(r#"(?:@import\s+|url\(\s*)["']?((?:https?:)?//[^"')\s]+)"#).unwrap()
+    });
+    let blocks = Selector::parse("style, script:not([src])").unwrap();
+    let attributes = Selector::parse("[style]").unwrap();
+    let mut text: Vec<String> = document.select(&blocks).map(|e| e.inner_html()).collect();
+    text.extend(
+        document
+            .select(&attributes)
+            .filter_map(|e| e.value().attr("style").map(str::to_string)),
+    );
+    let bodies = fetched.iter().map(|(_, body)| body.as_str());
+    for content in text.iter().map(String::as_str).chain(bodies) {
+        for captures in css_url.captures_iter(content) {
+            urls.extend(page_url.join(&captures[1]).ok());
+        }
+    }
+
+    let mut found: Vec<RemoteFonts> = Vec::new();
+    for url in urls {
+        let Some(host) = url.host_str() else {
+            continue;
+        };
+        let Some(service) = service_for(host) else {
+            continue;
+        };
+        if rules.is_ignored(host) {
+            continue;
+        }
+        let index = found
+            .iter()
+            .position(|f| f.service == service)
+            .unwrap_or_else(|| {
+                found.push(RemoteFonts {
+                    service: service.to_string(),
+                    hosts: Vec::new(),
+                    urls: Vec::new(),
+                });
+                found.len() - 1
+            });
+        let entry = &mut found[index];
+        if !entry.hosts.iter().any(|h| h == host) {
+            entry.hosts.push(host.to_string());
+        }
+        if !entry.urls.iter().any(|u| u == url.as_str()) {
+            entry.urls.push(url.to_string());
+        }
+    }
+    found
+}
@@ -0,0 +1,142 @@
+//! Failing the run when a scan breaks a threshold, so CI can block a
+//! deployment on the result. The reports are written first either way.
+
+use crate::baseline::Baseline;
+use crate::privacy_budget::PrivacyBudget;
+use crate::{calculate_privacy_score, AnalysisResult, CookieCategory};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Exit code of a run whose scans broke a `--fail-on` condition or the
+/// privacy budget
+pub const EXIT_VIOLATION: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    /// Any marketing or advertising tracker
+    MarketingTrackers,
+    /// Any marketing cookie
+    MarketingCookies,
+    /// Any cookie set without the Secure attribute
+    InsecureCookies,
+    /// Any session replay script
+    SessionReplay,
+    /// Any suspected payment skimmer
+    Skimmers,
+    /// The site can be browsed over plain HTTP
+    PlainHttp,
+    /// Any tracker or cookie the --baseline snapshot did not have, or a
+    /// cookie that lost Secure since
+    NewFindings,
+}
+
+/// What a run fails on; empty when nothing was asked for.
+#[derive(Debug, Clone, Default)]
+pub struct Gate {
+    pub min_score: Option<u32>,
+    pub conditions: Vec<FailOn>,
+    pub budget: Option<PrivacyBudget>,
+}
+
+/// The conditions one site broke.
+#[derive(Debug, Serialize)]
+pub struct SiteViolations {
+    pub url: String,
+    pub violations: Vec<String>,
+}
+
+impl Gate {
+    pub fn is_empty(&self) -> bool {
+        self.min_score.is_none() && self.conditions.is_empty() && self.budget.is_none()
+    }
+
+    /// Every condition `result` breaks, in plain words. `new-findings` is
+    /// checked against `baseline`.
+    pub fn check(&self, result: &AnalysisResult, baseline: Option<&Baseline>) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(min_score) = self.min_score {
+            let score = calculate_privacy_score(result);
+            if score < min_score {
+                violations.push(format!(
+                    "privacy score {} is below the required {}",
+                    score, min_score
+                ));
+            }
+        }
+        let diff = baseline.map(|b| b.diff(result));
+        for condition in &self.conditions {
+            let found: Vec<&str> = match condition {
+                FailOn::MarketingTrackers => result
+                    .trackers
+                    .iter()
+                    .filter(|t| matches!(t.category.as_str(), "Marketing" | "Marketing/CRM"))
+                    .map(|t| t.name.as_str())
+                    .collect(),
+                FailOn::MarketingCookies => result
+                    .cookies
+                    .iter()
+                    .filter(|c| c.category == CookieCategory::Marketing)
+                    .map(|c| c.name.as_str())
+                    .collect(),
+                FailOn::InsecureCookies => result
+                    .cookies
+                    .iter()
+                    .filter(|c| !c.secure)
+                    .map(|c| c.name.as_str())
+                    .collect(),
+                FailOn::SessionReplay => result
+                    .session_replay
+                    .iter()
+                    .map(|r| r.vendor.as_str())
+                    .collect(),
+                FailOn::Skimmers => result.skimmers.iter().map(|s| s.source.as_str()).collect(),
+                FailOn::PlainHttp => result
+                    .https
+                    .iter()
+                    .filter(|h| h.serves_plain_http())
+                    .map(|h| h.http_url.as_str())
+                    .collect(),
+                FailOn::NewFindings => diff
+                    .iter()
+                    .flat_map(|d| {
+                        let added = d.delta.added.iter().map(|f| f.rule.as_str());
+                        added.chain(d.newly_insecure.iter().map(String::as_str))
+                    })
+                    .collect(),
+            };
+            if !found.is_empty() {
+                violations.push(format!(
+                    "{}: {}",
+                    condition.to_possible_value().unwrap().get_name(),
+                    found.join(", ")
+                ));
+            }
+        }
+        if let Some(budget) = &self.budget {
+            violations.extend(budget.check(result));
+        }
+        violations
+    }
+
+    /// The sites of a run that broke a condition. A site that could not be
+    /// scanned counts as breaking them all.
+    pub fn check_run(
+        &self,
+        rows: &[(String, Result<&AnalysisResult, String>)],
+        baseline: Option<&Baseline>,
+    ) -> Vec<SiteViolations> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        rows.iter()
+            .map(|(url, result)| SiteViolations {
+                url: url.clone(),
+                violations: match result {
+                    Ok(analysis) => self.check(analysis, baseline),
+                    Err(e) => vec![format!("could not be scanned: {}", e)],
+                },
+            })
+            .filter(|site| !site.violations.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,52 @@
+//! Checks on the main document's response headers.
+
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+/// A deprecated privacy signal found in the response headers.
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyHeader {
+    pub name: String,
+    pub value: String,
+    /// Why the header no longer has any practical effect
+    pub note: String,
+}
+
+// Legacy privacy headers (name, display name, why they are ineffective today)
+const LEGACY_HEADERS: &[(&str, &str, &str)] = &[
+    (
+        "p3p",
+        "P3P",
+        "P3P compact policy; only ever honored by Internet Explorer and ignored by all current browsers",
+    ),
+    (
+        "tk",
+        "Tk",
+        "W3C Tracking Status Value; the Tracking Preference Expression standard was abandoned in 2019",
+    ),
+    (
+        "x-do-not-track",
+        "X-Do-Not-Track",
+        "Non-standard Do Not Track echo; carries no meaning for browsers or regulators",
+    ),
+    (
+        "dnt",
+        "DNT",
+        "Do Not Track is a request header; echoing it in a response has no effect",
+    ),
+];
+
+/// Find legacy privacy headers such as P3P and Tk in a response.
+pub fn legacy_privacy_headers(headers: &HeaderMap) -> Vec<LegacyHeader> {
+    let mut found = Vec::new();
+    for (name, display_name, note) in LEGACY_HEADERS {
+        for value in headers.get_all(*name) {
+            found.push(LegacyHeader {
+                name: display_name.to_string(),
+                value: value.to_str().unwrap_or("<non-ASCII value>").to_string(),
+                note: note.to_string(),
+            });
+        }
+    }
+    found
+}
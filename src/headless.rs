@@ -0,0 +1,448 @@
+//! Rendering pages in headless Chrome to see cookies set by JavaScript.
+
+use crate::auth::SiteAccess;
+use crate::banner::{self, ConsentBanner, ConsentChoice};
+use crate::browser;
+use crate::consent::ConsentState;
+use crate::storage::{self, RawStorageEntry, StorageItem};
+use crate::visitor::Visitor;
+use crate::{parse_cookie, CookieInfo, CookieSource};
+use anyhow::{anyhow, Context, Result};
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams, EventRequestPaused, HeaderEntry,
+};
+use chromiumoxide::cdp::browser_protocol::network::{
+    Cookie, CookieParam, CookiePriority, EventRequestWillBeSent, EventWebSocketCreated, Headers,
+    SetExtraHttpHeadersParams,
+};
+use chromiumoxide::Page;
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// How long to let scripts keep running after the page has loaded
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+static LAUNCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// The page as the browser sees it once its scripts have run.
+pub struct RenderedPage {
+    /// The serialized DOM, including elements inserted by scripts
+    pub html: String,
+    /// Every cookie in the browser's store, first- and third-party
+    pub cookies: Vec<CookieInfo>,
+    /// localStorage and sessionStorage keys for the page's origin
+    pub storage: Vec<StorageItem>,
+    /// The consent banner shown on load, if one was found
+    pub banner: Option<ConsentBanner>,
+    /// URLs of the WebSocket connections the page opened
+    pub websockets: Vec<String>,
+}
+
+/// What a headless scan lost by falling back to the static pipeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct StaticFallback {
+    /// Why the browser could not be used
+    pub reason: String,
+    /// Detection capabilities that were unavailable
+    pub unavailable: Vec<&'static str>,
+}
+
+impl StaticFallback {
+    /// Record a browser failure, listing what the requested browser features
+    /// would have found.
+    pub fn new(error: &anyhow::Error, evidence: bool, consent_diff: bool) -> Self {
+        let mut unavailable = vec![
+            "cookies set by JavaScript",
+            "trackers injected by scripts",
+            "localStorage and sessionStorage",
+            "consent banner detection",
+            "WebSocket connections opened by scripts",
+        ];
+        if evidence {
+            unavailable.push("banner screenshots");
+        }
+        if consent_diff {
+            unavailable.push("reject vs. accept comparison");
+        }
+        StaticFallback {
+            reason: format!("{:#}", error),
+            unavailable,
+        }
+    }
+}
+
+/// Load `url` in a fresh headless browser profile and collect its DOM,
+/// cookie store and consent banner, saving banner evidence to
+/// `evidence_dir` when given. The browser presents itself as `visitor`.
+pub async fn render(
+    url: &str,
+    evidence_dir: Option<&Path>,
+    access: &SiteAccess,
+    visitor: &Visitor,
+) -> Result<RenderedPage> {
+    let session = Session::launch(visitor).await?;
+    let rendered = load(&session.browser, url, evidence_dir, access, visitor).await;
+    session.close().await;
+    rendered
+}
+
+/// Load `url` in a fresh profile, answer the consent banner with `choice`
+/// and collect what the page stores and requests afterwards.
+pub async fn render_with_consent(
+    url: &str,
+    choice: ConsentChoice,
+    access: &SiteAccess,
+    visitor: &Visitor,
+) -> Result<ConsentState> {
+    let session = Session::launch(visitor).await?;
+    let state = load_with_consent(&session.browser, url, choice, access, visitor).await;
+    session.close().await;
+    state
+}
+
+/// A browser running on a throwaway profile, so no cookies carry over
+/// between pages or runs.
+struct Session {
+    browser: Browser,
+    events: JoinHandle<()>,
+    profile: PathBuf,
+}
+
+impl Session {
+    async fn launch(visitor: &Visitor) -> Result<Self> {
+        let profile = profile_dir();
+        let mut config = BrowserConfig::builder()
+            .user_data_dir(&profile)
+            .arg(format!("--user-agent={}", visitor.user_agent()));
+        if let Some(language) = visitor.accept_language() {
+            config = config
+                .arg(format!("--lang={}", language))
+                .arg(format!("--accept-lang={}", language));
+        }
+        if let Some(proxy) = visitor.proxy() {
+            config = config.arg(format!("--proxy-server={}", proxy));
+        }
+        if let Some(device) = visitor.device {
+            let viewport = device.viewport();
+            config = config
+                .window_size(viewport.width, viewport.height)
+                .viewport(viewport);
+        }
+        if let Some(executable) = browser::executable() {
+            config = config.chrome_executable(executable);
+        }
+        let config = config.build().map_err(|e| anyhow!(e)).context(
+            "No Chrome or Chromium found for --headless (run `recon browser install` or set CHROME to its path)",
+        )?;
+
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .context("Failed to launch headless browser")?;
+        let events = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Session {
+            browser,
+            events,
+            profile,
+        })
+    }
+
+    async fn close(mut self) {
+        let _ = self.browser.close().await;
+        let _ = self.browser.wait().await;
+        self.events.abort();
+        let _ = std::fs::remove_dir_all(&self.profile);
+    }
+}
+
+async fn load(
+    browser: &Browser,
+    url: &str,
+    evidence_dir: Option<&Path>,
+    access: &SiteAccess,
+    visitor: &Visitor,
+) -> Result<RenderedPage> {
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .context("Failed to open page in headless browser")?;
+    grant_access(&page, url, access).await?;
+    let interceptor = send_access_headers(&page, url, access).await?;
+    send_signals(&page, visitor).await?;
+
+    let mut sockets = page.event_listener::<EventWebSocketCreated>().await?;
+    let websockets = Arc::new(Mutex::new(Vec::new()));
+    let recorder = {
+        let websockets = Arc::clone(&websockets);
+        tokio::spawn(async move {
+            while let Some(event) = sockets.next().await {
+                websockets.lock().unwrap().push(event.url.clone());
+            }
+        })
+    };
+
+    page.goto(url)
+        .await
+        .context("Failed to open page in headless browser")?;
+    tokio::time::sleep(SETTLE_TIME).await;
+
+    let html = page.content().await?;
+    let cookies = cookie_store(browser, access).await?;
+    let storage = read_storage(&page).await;
+    let banner = banner::capture(&page, url, evidence_dir).await?;
+    recorder.abort();
+    if let Some(interceptor) = interceptor {
+        interceptor.abort();
+    }
+    let websockets = websockets.lock().unwrap().clone();
+
+    Ok(RenderedPage {
+        html,
+        cookies,
+        storage,
+        banner,
+        websockets,
+    })
+}
+
+async fn load_with_consent(
+    browser: &Browser,
+    url: &str,
+    choice: ConsentChoice,
+    access: &SiteAccess,
+    visitor: &Visitor,
+) -> Result<ConsentState> {
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .context("Failed to open page in headless browser")?;
+    grant_access(&page, url, access).await?;
+    let interceptor = send_access_headers(&page, url, access).await?;
+    send_signals(&page, visitor).await?;
+
+    // Listen before navigating so the page's first requests are seen too
+    let mut requests = page.event_listener::<EventRequestWillBeSent>().await?;
+    let hosts = Arc::new(Mutex::new(BTreeSet::new()));
+    let recorder = {
+        let hosts = Arc::clone(&hosts);
+        tokio::spawn(async move {
+            while let Some(event) = requests.next().await {
+                if let Some(host) = Url::parse(&event.request.url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(str::to_string))
+                {
+                    hosts.lock().unwrap().insert(host);
+                }
+            }
+        })
+    };
+
+    page.goto(url)
+        .await
+        .context("Failed to open page in headless browser")?;
+    tokio::time::sleep(SETTLE_TIME).await;
+
+    let button = match banner::find_choice(&page, choice).await {
+        Some((label, element)) => {
+            element
+                .click()
+                .await
+                .with_context(|| format!("Failed to click the {} button", choice.as_str()))?;
+            // Many CMPs reload the page once consent is stored
+            tokio::time::sleep(SETTLE_TIME).await;
+            Some(label)
+        }
+        None => None,
+    };
+
+    let cookies = cookie_store(browser, access).await?;
+    let storage = read_storage(&page).await;
+    recorder.abort();
+    if let Some(interceptor) = interceptor {
+        interceptor.abort();
+    }
+    let request_hosts = hosts.lock().unwrap().iter().cloned().collect();
+
+    Ok(ConsentState {
+        button,
+        cookies,
+        storage,
+        request_hosts,
+    })
+}
+
+/// Give the browser the `--cookie` cookies before it navigates.
+async fn grant_access(page: &Page, url: &str, access: &SiteAccess) -> Result<()> {
+    let cookies = access
+        .cookies()
+        .iter()
+        .map(|(name, value)| {
+            // Secure on HTTPS sites, so Chrome keeps them off plain-HTTP requests
+            CookieParam::builder()
+                .name(name)
+                .value(value)
+                .url(url)
+                .secure(url.starts_with("https://"))
+                .build()
+                .map_err(|e| anyhow!(e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !cookies.is_empty() {
+        page.set_cookies(cookies).await?;
+    }
+    Ok(())
+}
+
+/// Add the `--header`, `--bearer` and `--basic-auth` headers to the page's
+/// requests to the scanned site. Chrome's extra headers would go to every
+/// host, so requests are paused instead and only same-site ones continue
+/// with the headers added. Abort the returned task once the page is read.
+async fn send_access_headers(
+    page: &Page,
+    url: &str,
+    access: &SiteAccess,
+) -> Result<Option<JoinHandle<()>>> {
+    let site = Url::parse(url).context("Invalid URL format")?;
+    if access.headers_for(&site, &site).is_empty() {
+        return Ok(None);
+    }
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    page.execute(EnableParams::default()).await?;
+
+    let page = page.clone();
+    let access = access.clone();
+    Ok(Some(tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            let mut request = ContinueRequestParams::new(event.request_id.clone());
+            let extra = Url::parse(&event.request.url)
+                .map(|target| access.headers_for(&target, &site))
+                .unwrap_or_default();
+            if !extra.is_empty() {
+                let mut headers: Vec<HeaderEntry> = event
+                    .request
+                    .headers
+                    .inner()
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(name, _)| !extra.contains_key(name.as_str()))
+                    .filter_map(|(name, value)| Some(HeaderEntry::new(name, value.as_str()?)))
+                    .collect();
+                headers.extend(extra.iter().map(|(name, value)| {
+                    HeaderEntry::new(name.as_str(), String::from_utf8_lossy(value.as_bytes()))
+                }));
+                request.headers = Some(headers);
+            }
+            let _ = page.execute(request).await;
+        }
+    })))
+}
+
+/// Send the visitor's privacy signal headers with every request and expose
+/// them to scripts, as a browser with the setting turned on would.
+async fn send_signals(page: &Page, visitor: &Visitor) -> Result<()> {
+    let headers = visitor.signal_headers();
+    if headers.is_empty() {
+        return Ok(());
+    }
+    let headers: serde_json::Map<String, serde_json::Value> = headers
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.into()))
+        .collect();
+    page.execute(SetExtraHttpHeadersParams::new(Headers::new(headers)))
+        .await?;
+    if visitor.gpc {
+        page.evaluate_on_new_document(
+            "Object.defineProperty(Navigator.prototype, 'globalPrivacyControl', { get: () => true, configurable: true });",
+        )
+        .await?;
+    }
+    if visitor.dnt {
+        page.evaluate_on_new_document(
+            "Object.defineProperty(Navigator.prototype, 'doNotTrack', { get: () => '1', configurable: true });",
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// The browser's cookies, without the ones we gave it with `--cookie`.
+async fn cookie_store(browser: &Browser, access: &SiteAccess) -> Result<Vec<CookieInfo>> {
+    Ok(browser
+        .get_cookies()
+        .await?
+        .iter()
+        .filter(|cookie| !access.is_own_cookie(&cookie.name))
+        .map(to_cookie_info)
+        .collect())
+}
+
+/// Storage can be unavailable (e.g. sandboxed documents); that is not fatal.
+async fn read_storage(page: &Page) -> Vec<StorageItem> {
+    let entries: Vec<RawStorageEntry> = match page.evaluate(storage::ENUMERATE_SCRIPT).await {
+        Ok(result) => result.into_value().unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    storage::categorize(entries)
+}
+
+fn profile_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "recon-headless-{}-{}",
+        std::process::id(),
+        LAUNCHES.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Rebuild an equivalent Set-Cookie header so browser cookies go through the
+/// same parsing and categorization as header cookies.
+fn to_cookie_info(cookie: &Cookie) -> CookieInfo {
+    let mut header = format!(
+        "{}={}; Domain={}; Path={}",
+        cookie.name, cookie.value, cookie.domain, cookie.path
+    );
+    if !cookie.session {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        header.push_str(&format!(
+            "; Max-Age={}",
+            (cookie.expires - now).max(0.0) as u64
+        ));
+    }
+    if cookie.secure {
+        header.push_str("; Secure");
+    }
+    if cookie.http_only {
+        header.push_str("; HttpOnly");
+    }
+    if cookie.partition_key.is_some() {
+        header.push_str("; Partitioned");
+    }
+    if let Some(same_site) = &cookie.same_site {
+        header.push_str(&format!("; SameSite={}", same_site.as_ref()));
+    }
+    // Chrome reports Medium for cookies that never set a priority
+    if cookie.priority != CookiePriority::Medium {
+        header.push_str(&format!("; Priority={}", cookie.priority.as_ref()));
+    }
+
+    let mut info = parse_cookie(&header);
+    info.source = CookieSource::Browser;
+    info
+}
@@ -0,0 +1,112 @@
+//! Resource hints: `<link rel>` values that make the browser resolve, connect
+//! to or fetch an origin ahead of use. Sites often preconnect to advertising
+//! and social hosts whose scripts load later, so the hints name trackers the
+//! initial HTML does not otherwise show.
+
+use crate::is_third_party;
+use crate::rules::RuleSet;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use url::Url;
+
+/// Hint types reported, as they appear in `rel`
+const HINTS: &[&str] = &[
+    "preconnect",
+    "dns-prefetch",
+    "prefetch",
+    "preload",
+    "modulepreload",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceHint {
+    /// The hint type from `rel`
+    pub rel: &'static str,
+    pub url: String,
+    pub domain: String,
+    pub third_party: bool,
+    /// The tracker rule or list entry the hinted URL matches, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<String>,
+}
+
+impl ResourceHint {
+    /// Whether the browser downloads the hinted resource rather than only
+    /// resolving or connecting to its host.
+    pub fn fetches(&self) -> bool {
+        matches!(self.rel, "prefetch" | "preload" | "modulepreload")
+    }
+}
+
+/// The tracker `url` belongs to: a tracker rule matching the URL or its host,
+/// then the Disconnect list and the filter lists.
+fn identify(url: &Url, host: &str, base_domain: &str, rules: &RuleSet) -> Option<String> {
+    let url_lower = url.as_str().to_lowercase();
+    let host_lower = host.to_lowercase();
+    if let Some(rule) = rules
+        .trackers
+        .iter()
+        .find(|rule| rule.matches_url(&url_lower) || rule.matches_host(&host_lower))
+    {
+        return Some(rule.description.clone());
+    }
+    let listed = rules
+        .disconnect
+        .as_ref()
+        .filter(|_| is_third_party(host, base_domain))
+        .and_then(|list| list.lookup(host));
+    if let Some((_, entity)) = listed {
+        return Some(format!(
+            "{} (Disconnect {})",
+            entity.name, entity.disconnect_category
+        ));
+    }
+    rules
+        .filter_match(url, base_domain)
+        .map(|(list, _)| format!("{} filter", list.title))
+}
+
+/// Every preconnect, dns-prefetch, prefetch and preload hint in the page,
+/// with the tracker each hinted URL matches.
+pub fn find(html: &str, page_url: &Url, rules: &RuleSet) -> Vec<ResourceHint> {
+    let document = Html::parse_document(html);
+    let link = Selector::parse("link[rel][href]").unwrap();
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut hints: Vec<ResourceHint> = Vec::new();
+    for element in document.select(&link) {
+        let rel = element.value().attr("rel").unwrap_or("").to_lowercase();
+        // `rel` is a token list, and "preconnect dns-prefetch" is a common pair
+        let kinds = HINTS
+            .iter()
+            .copied()
+            .filter(|hint| rel.split_whitespace().any(|token| token == *hint));
+        // Hints are often protocol-relative ("//host")
+        let Some(url) = element
+            .value()
+            .attr("href")
+            .and_then(|href| page_url.join(href.trim()).ok())
+        else {
+            continue;
+        };
+        let Some(host) = url.host_str() else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") || rules.is_ignored(host) {
+            continue;
+        }
+        for kind in kinds {
+            if hints.iter().any(|h| h.rel == kind && h.url == url.as_str()) {
+                continue;
+            }
+            hints.push(ResourceHint {
+                rel: kind,
+                url: url.to_string(),
+                domain: host.to_string(),
+                third_party: is_third_party(host, base_domain),
+                tracker: identify(&url, host, base_domain, rules),
+            });
+        }
+    }
+    hints
+}
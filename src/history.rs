@@ -0,0 +1,393 @@
+//! Scan history in a local SQLite database, so repeated scans of a site can
+//! be kept and compared over time.
+
+use crate::inventory::{self, ScriptChange};
+use crate::owners::NewFinding;
+use crate::variance::{self, ScoreSpread, Volatility};
+use crate::{calculate_privacy_score, AnalysisResult};
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS scans (
+    id INTEGER PRIMARY KEY,
+    url TEXT NOT NULL,
+    scanned_at INTEGER NOT NULL,
+    privacy_score INTEGER NOT NULL,
+    cookies INTEGER NOT NULL,
+    trackers INTEGER NOT NULL,
+    third_party_domains INTEGER NOT NULL,
+    report TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS scans_url_time ON scans (url, scanned_at);
+";
+
+/// Runs, including the latest, that volatility is measured over
+const VOLATILITY_RUNS: usize = 10;
+
+/// Fewer runs than this are too few to call a site volatile
+const MIN_VOLATILITY_RUNS: usize = 3;
+
+pub struct History {
+    conn: Connection,
+}
+
+/// The named findings of a scan, for spotting what changed between runs.
+#[derive(Debug, Default)]
+pub struct Findings {
+    /// Tracker descriptions
+    pub trackers: BTreeSet<String>,
+    /// Cookie names
+    pub cookies: BTreeSet<String>,
+    /// Fingerprint of the page content; missing in reports from older versions
+    pub content_hash: Option<String>,
+    /// First-party script paths and hashes, when the scan fetched scripts
+    pub scripts: Option<BTreeMap<String, String>>,
+}
+
+/// How a site differs from its previous recorded scan.
+pub struct SiteChange {
+    pub url: String,
+    /// Whether the page content changed, when both scans have a fingerprint
+    pub content_changed: Option<bool>,
+    /// Trackers and cookies the previous scan did not have
+    pub findings: Vec<NewFinding>,
+    /// First-party scripts added, removed or modified, when both scans
+    /// fetched scripts
+    pub scripts: Vec<ScriptChange>,
+    /// How much the site varies across its recent `watch` runs
+    pub volatility: Option<Volatility>,
+}
+
+impl Findings {
+    pub fn of(result: &AnalysisResult) -> Self {
+        Findings {
+            trackers: result.trackers.iter().map(|t| t.description.clone()).collect(),
+            cookies: result.cookies.iter().map(|c| c.name.clone()).collect(),
+            content_hash: Some(result.content_hash.clone()),
+            scripts: result.script_inventory.as_ref().map(|files| {
+                files
+                    .iter()
+                    .map(|f| (f.path.clone(), f.sha256.clone()))
+                    .collect()
+            }),
+        }
+    }
+
+    /// What `self` has that `previous` did not.
+    pub fn added_since(&self, previous: &Findings) -> Findings {
+        Findings {
+            trackers: self.trackers.difference(&previous.trackers).cloned().collect(),
+            cookies: self.cookies.difference(&previous.cookies).cloned().collect(),
+            content_hash: self.content_hash.clone(),
+            scripts: self.scripts.clone(),
+        }
+    }
+
+    /// First-party scripts that differ from `previous`; empty unless both
+    /// scans fetched scripts.
+    pub fn script_changes_since(&self, previous: &Findings) -> Vec<ScriptChange> {
+        match (&previous.scripts, &self.scripts) {
+            (Some(previous), Some(current)) => inventory::diff(previous, current),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the page content differs from `previous`, or `None` when
+    /// either scan has no fingerprint.
+    pub fn content_changed_since(&self, previous: &Findings) -> Option<bool> {
+        Some(self.content_hash.as_ref()? != previous.content_hash.as_ref()?)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trackers.is_empty() && self.cookies.is_empty()
+    }
+
+    /// Read the findings back from a stored JSON report.
+    pub fn from_report(report: &serde_json::Value) -> Self {
+        let names = |list: &str, field: &str| -> BTreeSet<String> {
+            report[list]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item[field].as_str().map(str::to_string))
+                .collect()
+        };
+        Findings {
+            trackers: names("trackers", "description"),
+            cookies: names("cookies", "name"),
+            content_hash: report["content_hash"].as_str().map(str::to_string),
+            scripts: report["script_inventory"].as_array().map(|files| {
+                files
+                    .iter()
+                    .filter_map(|file| {
+                        Some((
+                            file["path"].as_str()?.to_string(),
+                            file["sha256"].as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// The headline metrics of one recorded scan, for `history <url>`.
+#[derive(Debug, Serialize)]
+pub struct TrendPoint {
+    /// Unix timestamp, seconds
+    pub scanned_at: i64,
+    pub privacy_score: u32,
+    pub cookies: u32,
+    pub trackers: u32,
+    pub third_party_domains: u32,
+}
+
+/// One scan as written by `history export`, one JSON object per line.
+#[derive(Serialize, Deserialize)]
+struct ExportedScan {
+    url: String,
+    /// Unix timestamp, seconds
+    scanned_at: i64,
+    privacy_score: u32,
+    cookies: u32,
+    trackers: u32,
+    third_party_domains: u32,
+    report: serde_json::Value,
+}
+
+impl History {
+    /// `history.db` in the user data directory.
+    pub fn default_path() -> Result<PathBuf> {
+        let dir = dirs::data_dir()
+            .ok_or_else(|| anyhow!("Could not determine the user data directory"))?
+            .join("recon");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(dir.join("history.db"))
+    }
+
+    /// Open the database at `path`, or the default one, creating it if needed.
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::default_path()?,
+        };
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open history database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to initialize history database {}", path.display()))?;
+        Ok(History { conn })
+    }
+
+    /// Store one scan with its headline metrics and the full report.
+    pub fn record(&self, url: &str, result: &AnalysisResult) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO scans (url, scanned_at, privacy_score, cookies, trackers, third_party_domains, report)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                url,
+                unix_now(),
+                calculate_privacy_score(result),
+                result.cookies.len(),
+                result.trackers.len(),
+                result.third_party_requests.len(),
+                serde_json::to_string(result)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The findings of the most recent scan of `url`, if it was scanned before.
+    pub fn latest_findings(&self, url: &str) -> Result<Option<Findings>> {
+        Ok(self.latest_report(url)?.as_ref().map(Findings::from_report))
+    }
+
+    /// The full report of the most recent scan of `url`, if it was scanned before.
+    pub fn latest_report(&self, url: &str) -> Result<Option<serde_json::Value>> {
+        let report: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT report FROM scans WHERE url = ?1 ORDER BY scanned_at DESC, id DESC LIMIT 1",
+                params![url],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match report {
+            Some(report) => Ok(Some(serde_json::from_str(&report)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Score spread and intermittent findings across `current` and the
+    /// scans of `url` recorded before it, or `None` when there are too few.
+    pub fn volatility(&self, url: &str, current: &AnalysisResult) -> Result<Option<Volatility>> {
+        let mut statement = self.conn.prepare(
+            "SELECT privacy_score, report FROM scans WHERE url = ?1
+             ORDER BY scanned_at DESC, id DESC LIMIT ?2",
+        )?;
+        let recorded = statement
+            .query_map(params![url, (VOLATILITY_RUNS - 1) as i64], |row| {
+                Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if recorded.len() + 1 < MIN_VOLATILITY_RUNS {
+            return Ok(None);
+        }
+
+        let mut scores = vec![calculate_privacy_score(current)];
+        let mut runs = vec![variance::findings_of(current)];
+        for (score, report) in &recorded {
+            scores.push(*score);
+            runs.push(variance::findings_in_report(&serde_json::from_str(report)?));
+        }
+        Ok(ScoreSpread::from_scores(&scores).map(|score| Volatility {
+            runs: runs.len(),
+            score,
+            intermittent: variance::intermittent(runs),
+        }))
+    }
+
+    /// The metrics of the latest `limit` scans of `url`, oldest first.
+    pub fn trend(&self, url: &str, limit: usize) -> Result<Vec<TrendPoint>> {
+        let mut statement = self.conn.prepare(
+            "SELECT scanned_at, privacy_score, cookies, trackers, third_party_domains
+             FROM scans WHERE url = ?1 ORDER BY scanned_at DESC, id DESC LIMIT ?2",
+        )?;
+        let mut points = statement
+            .query_map(params![url, limit as i64], |row| {
+                Ok(TrendPoint {
+                    scanned_at: row.get(0)?,
+                    privacy_score: row.get(1)?,
+                    cookies: row.get(2)?,
+                    trackers: row.get(3)?,
+                    third_party_domains: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        points.reverse();
+        Ok(points)
+    }
+
+    /// Delete scans older than `keep`, returning how many were removed.
+    pub fn prune(&self, keep: Duration) -> Result<usize> {
+        let cutoff = unix_now().saturating_sub(keep.as_secs() as i64);
+        let removed = self
+            .conn
+            .execute("DELETE FROM scans WHERE scanned_at < ?1", params![cutoff])?;
+        if removed > 0 {
+            // Give the space back rather than letting the file stay at its peak size
+            self.conn.execute_batch("VACUUM")?;
+        }
+        Ok(removed)
+    }
+
+    /// Write every scan, oldest first, as JSON Lines. Returns the count.
+    pub fn export(&self, out: &mut impl Write) -> Result<usize> {
+        let mut statement = self.conn.prepare(
+            "SELECT url, scanned_at, privacy_score, cookies, trackers, third_party_domains, report
+             FROM scans ORDER BY scanned_at, id",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let report: String = row.get(6)?;
+            let scan = ExportedScan {
+                url: row.get(0)?,
+                scanned_at: row.get(1)?,
+                privacy_score: row.get(2)?,
+                cookies: row.get(3)?,
+                trackers: row.get(4)?,
+                third_party_domains: row.get(5)?,
+                report: serde_json::from_str(&report)?,
+            };
+            serde_json::to_writer(&mut *out, &scan)?;
+            writeln!(out)?;
+            count += 1;
+        }
+        out.flush()?;
+        Ok(count)
+    }
+
+    /// Load scans written by `export`, keeping their original timestamps.
+    /// Scans already present (the same row, report included) are skipped, so
+    /// importing the same dump twice is harmless, while separate scans made
+    /// within the same second are all kept. Returns (imported, skipped).
+    pub fn import(&mut self, input: impl BufRead) -> Result<(usize, usize)> {
+        let tx = self.conn.transaction()?;
+        // Only rows from before the import count as already present, each
+        // standing in for one imported scan
+        let last_existing: i64 =
+            tx.query_row("SELECT COALESCE(MAX(id), 0) FROM scans", [], |row| row.get(0))?;
+        let mut matched = BTreeSet::new();
+        let (mut imported, mut skipped) = (0, 0);
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let scan: ExportedScan = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid scan on line {}", i + 1))?;
+            let existing = {
+                let mut statement = tx.prepare_cached(
+                    "SELECT id, privacy_score, cookies, trackers, third_party_domains, report FROM scans
+                     WHERE url = ?1 AND scanned_at = ?2 AND id <= ?3",
+                )?;
+                let rows = statement.query_map(params![scan.url, scan.scanned_at, last_existing], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?),
+                        row.get::<_, String>(5)?,
+                    ))
+                })?;
+                let counts = (scan.privacy_score, scan.cookies, scan.trackers, scan.third_party_domains);
+                let mut found = None;
+                for row in rows {
+                    let (id, row_counts, report) = row?;
+                    // Compared parsed, as the export does not keep the stored text byte for byte
+                    if !matched.contains(&id)
+                        && row_counts == counts
+                        && serde_json::from_str::<serde_json::Value>(&report).ok().as_ref() == Some(&scan.report)
+                    {
+                        found = Some(id);
+                        break;
+                    }
+                }
+                found
+            };
+            if let Some(id) = existing {
+                matched.insert(id);
+                skipped += 1;
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO scans (url, scanned_at, privacy_score, cookies, trackers, third_party_domains, report)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    scan.url,
+                    scan.scanned_at,
+                    scan.privacy_score,
+                    scan.cookies,
+                    scan.trackers,
+                    scan.third_party_domains,
+                    scan.report.to_string(),
+                ],
+            )?;
+            imported += 1;
+        }
+        tx.commit()?;
+        Ok((imported, skipped))
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,194 @@
+//! Whether a site keeps visitors on HTTPS: what its plain-HTTP address does,
+//! and whether the page sends `Strict-Transport-Security` strong enough for
+//! the browsers' HSTS preload list. Cookies and page views on plain HTTP are
+//! readable and rewritable by anyone on the network path.
+
+use crate::visitor::Visitor;
+use crate::{config, throttle, usage};
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use serde::Serialize;
+use url::Url;
+
+/// Redirects followed from the HTTP address before giving up
+const MAX_HOPS: usize = 5;
+
+/// Smallest `max-age` the preload list accepts: one year
+const PRELOAD_MAX_AGE: u64 = 31_536_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpBehavior {
+    /// Redirects to an HTTPS address
+    Upgrades,
+    /// Serves content over plain HTTP
+    Plain,
+    /// Nothing answers on plain HTTP
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hsts {
+    pub max_age: u64,
+    pub include_subdomains: bool,
+    pub preload: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpsReport {
+    /// The plain-HTTP address requested
+    pub http_url: String,
+    pub http_behavior: HttpBehavior,
+    /// The first redirect goes straight to HTTPS on the same host, as the
+    /// preload list requires
+    pub direct_upgrade: bool,
+    /// The page's `Strict-Transport-Security` header; `None` when missing,
+    /// malformed, or the page is not served over HTTPS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hsts: Option<Hsts>,
+    /// Meets the requirements of hstspreload.org
+    pub preload_ready: bool,
+    /// What stands between the site and HTTPS-only, in plain words
+    pub issues: Vec<String>,
+}
+
+impl HttpsReport {
+    /// Whether the site can be browsed over plain HTTP.
+    pub fn serves_plain_http(&self) -> bool {
+        self.http_behavior == HttpBehavior::Plain
+    }
+
+    /// Whether browsers will remember to use HTTPS for the host.
+    pub fn has_hsts(&self) -> bool {
+        self.hsts.as_ref().is_some_and(|h| h.max_age > 0)
+    }
+}
+
+/// Parse a `Strict-Transport-Security` value. Browsers ignore the header
+/// without a valid `max-age`.
+fn parse_hsts(value: &str) -> Option<Hsts> {
+    let mut max_age = None;
+    let mut include_subdomains = false;
+    let mut preload = false;
+    for directive in value.split(';') {
+        let (name, arg) = directive
+            .split_once('=')
+            .map_or((directive, None), |(n, a)| (n, Some(a)));
+        match name.trim().to_ascii_lowercase().as_str() {
+            "max-age" => max_age = arg.and_then(|a| a.trim().trim_matches('"').parse().ok()),
+            "includesubdomains" => include_subdomains = true,
+            "preload" => preload = true,
+            _ => {}
+        }
+    }
+    Some(Hsts {
+        max_age: max_age?,
+        include_subdomains,
+        preload,
+    })
+}
+
+/// Request the plain-HTTP address of `page_url` and assess it together with
+/// the `Strict-Transport-Security` header the page was served with. The
+/// probe goes out without the `--header`, `--cookie` or credential options,
+/// which must never be sent in cleartext.
+pub async fn assess(
+    page_url: &Url,
+    hsts_header: Option<&str>,
+    visitor: &Visitor,
+) -> Option<HttpsReport> {
+    let mut http_url = page_url.clone();
+    http_url.set_scheme("http").ok()?;
+    if page_url.scheme() == "https" {
+        http_url.set_port(None).ok()?;
+    }
+    let client = visitor
+        .client_builder()
+        .ok()?
+        .redirect(Policy::none())
+        .timeout(config::timeout())
+        .build()
+        .ok()?;
+
+    let mut http_behavior = HttpBehavior::Unreachable;
+    let mut direct_upgrade = false;
+    let mut current = http_url.clone();
+    for hop in 0..MAX_HOPS {
+        throttle::wait(&current).await;
+        let Ok(response) = client.get(current.clone()).send().await else {
+            break;
+        };
+        usage::request();
+        let next = response
+            .status()
+            .is_redirection()
+            .then(|| response.headers().get(LOCATION))
+            .flatten()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| current.join(location).ok());
+        match next {
+            Some(next) if next.scheme() == "https" => {
+                direct_upgrade = hop == 0 && next.host_str() == http_url.host_str();
+                http_behavior = HttpBehavior::Upgrades;
+                break;
+            }
+            // Still on plain HTTP unless a later hop upgrades
+            Some(next) => {
+                http_behavior = HttpBehavior::Plain;
+                current = next;
+            }
+            None => {
+                http_behavior = HttpBehavior::Plain;
+                break;
+            }
+        }
+    }
+
+    let hsts = hsts_header
+        .filter(|_| page_url.scheme() == "https")
+        .and_then(parse_hsts);
+    let mut issues = Vec::new();
+    match http_behavior {
+        HttpBehavior::Plain => issues.push(format!(
+            "{} is served without redirecting to HTTPS",
+            http_url
+        )),
+        HttpBehavior::Upgrades if !direct_upgrade => issues.push(
+            "The HTTP address does not redirect straight to HTTPS on the same host".to_string(),
+        ),
+        _ => {}
+    }
+    match &hsts {
+        None => issues.push("No Strict-Transport-Security header".to_string()),
+        Some(hsts) if hsts.max_age == 0 => {
+            issues.push("Strict-Transport-Security max-age=0 turns HSTS off".to_string())
+        }
+        Some(hsts) => {
+            if hsts.max_age < PRELOAD_MAX_AGE {
+                issues.push(format!(
+                    "HSTS max-age of {} days is below the one year preloading needs",
+                    hsts.max_age / 86_400
+                ));
+            }
+            if !hsts.include_subdomains {
+                issues.push("HSTS without includeSubDomains cannot be preloaded".to_string());
+            }
+            if !hsts.preload {
+                issues.push("HSTS without the preload directive".to_string());
+            }
+        }
+    }
+    let preload_ready = (http_behavior == HttpBehavior::Unreachable || direct_upgrade)
+        && hsts
+            .as_ref()
+            .is_some_and(|h| h.max_age >= PRELOAD_MAX_AGE && h.include_subdomains && h.preload);
+
+    Some(HttpsReport {
+        http_url: http_url.to_string(),
+        http_behavior,
+        direct_upgrade,
+        hsts,
+        preload_ready,
+        issues,
+    })
+}
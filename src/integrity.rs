@@ -0,0 +1,109 @@
+//! Subresource Integrity coverage: which external scripts and stylesheets
+//! pin their contents with an `integrity` hash. A third-party script without
+//! one runs whatever its host serves next, trackers included.
+
+use crate::is_third_party;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Script,
+    Stylesheet,
+}
+
+impl ResourceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResourceKind::Script => "script",
+            ResourceKind::Stylesheet => "stylesheet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalResource {
+    pub kind: ResourceKind,
+    pub url: String,
+    pub third_party: bool,
+    /// Whether the element has a non-empty `integrity` attribute
+    pub integrity: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub resources: Vec<ExternalResource>,
+    /// Share of `resources` with an `integrity` attribute, 0-100
+    pub coverage: u8,
+    /// The same share among third-party resources only, if there are any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub third_party_coverage: Option<u8>,
+}
+
+impl IntegrityReport {
+    pub fn new(resources: Vec<ExternalResource>) -> Self {
+        let third_party: Vec<&ExternalResource> =
+            resources.iter().filter(|r| r.third_party).collect();
+        IntegrityReport {
+            coverage: percent(
+                resources.iter().filter(|r| r.integrity).count(),
+                resources.len(),
+            ),
+            third_party_coverage: (!third_party.is_empty()).then(|| {
+                percent(
+                    third_party.iter().filter(|r| r.integrity).count(),
+                    third_party.len(),
+                )
+            }),
+            resources,
+        }
+    }
+
+    /// Third-party scripts and stylesheets loaded without an integrity hash.
+    pub fn unprotected(&self) -> impl Iterator<Item = &ExternalResource> {
+        self.resources
+            .iter()
+            .filter(|r| r.third_party && !r.integrity)
+    }
+}
+
+fn percent(part: usize, whole: usize) -> u8 {
+    (part * 100).checked_div(whole).map_or(100, |p| p as u8)
+}
+
+/// Every `<script src>` and `<link rel=stylesheet>` on the page. `None` when
+/// the page loads neither.
+pub fn analyze(page_url: &Url, html: &str) -> Option<IntegrityReport> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(r#"script[src], link[rel~="stylesheet" i][href]"#).unwrap();
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut resources: Vec<ExternalResource> = Vec::new();
+    for element in document.select(&selector) {
+        let element = element.value();
+        let (kind, src) = match element.name() {
+            "script" => (ResourceKind::Script, element.attr("src")),
+            _ => (ResourceKind::Stylesheet, element.attr("href")),
+        };
+        let Some(url) = src.and_then(|src| page_url.join(src).ok()) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https")
+            || resources.iter().any(|r| r.url == url.as_str())
+        {
+            continue;
+        }
+        resources.push(ExternalResource {
+            kind,
+            third_party: is_third_party(url.host_str().unwrap_or(""), base_domain),
+            url: url.to_string(),
+            integrity: element
+                .attr("integrity")
+                .is_some_and(|value| !value.trim().is_empty()),
+        });
+    }
+
+    (!resources.is_empty()).then(|| IntegrityReport::new(resources))
+}
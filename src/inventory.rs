@@ -0,0 +1,99 @@
+//! Inventory of the site's own external scripts. Injected skimmers and
+//! trackers often arrive as an edit to a first-party bundle, which the
+//! tracker rules alone cannot tell from a routine deploy; comparing hashes
+//! between recorded scans at least shows which file changed.
+
+use crate::fingerprint;
+use crate::is_third_party;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use url::Url;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptFile {
+    /// URL path, prefixed with the host when it is not the page's own
+    pub path: String,
+    /// Bytes read
+    pub size: usize,
+    /// SHA-256 of the bytes read, in hex
+    pub sha256: String,
+    /// Whether the script reached `--max-script-kb`, so `size` and `sha256`
+    /// may only cover its start
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ScriptChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScriptChangeKind::Added => "added",
+            ScriptChangeKind::Removed => "removed",
+            ScriptChangeKind::Modified => "modified",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptChange {
+    pub path: String,
+    pub kind: ScriptChangeKind,
+}
+
+/// The first-party scripts among those fetched with `--fetch-scripts`.
+pub fn first_party(fetched: &[(Url, String)], page_url: &Url, max_bytes: usize) -> Vec<ScriptFile> {
+    let base_domain = page_url.domain().unwrap_or("");
+    let mut files: Vec<ScriptFile> = fetched
+        .iter()
+        .filter(|(url, _)| !is_third_party(url.host_str().unwrap_or(""), base_domain))
+        .map(|(url, body)| ScriptFile {
+            path: if url.host_str() == page_url.host_str() {
+                url.path().to_string()
+            } else {
+                format!("{}{}", url.host_str().unwrap_or(""), url.path())
+            },
+            size: body.len(),
+            sha256: fingerprint::hex(&Sha256::digest(body.as_bytes())),
+            truncated: body.len() >= max_bytes,
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    // Cache-busting query strings make one file look like several
+    files.dedup_by(|a, b| a.path == b.path);
+    files
+}
+
+/// Scripts added, removed or modified between two inventories, keyed by
+/// path with their hashes.
+pub fn diff(
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<ScriptChange> {
+    let mut changes = Vec::new();
+    for (path, hash) in current {
+        let kind = match previous.get(path) {
+            None => ScriptChangeKind::Added,
+            Some(previous) if previous != hash => ScriptChangeKind::Modified,
+            Some(_) => continue,
+        };
+        changes.push(ScriptChange {
+            path: path.clone(),
+            kind,
+        });
+    }
+    for path in previous.keys().filter(|path| !current.contains_key(*path)) {
+        changes.push(ScriptChange {
+            path: path.clone(),
+            kind: ScriptChangeKind::Removed,
+        });
+    }
+    changes
+}
@@ -0,0 +1,125 @@
+//! Scanning a page with different `Accept-Language` headers, optionally
+//! through a proxy in each region, since sites often only show consent
+//! banners or suppress trackers for some visitors.
+
+use crate::crawl::merge;
+use crate::variants::{self, VariantFindings};
+use crate::visitor::Visitor;
+use crate::{analyze_url, AnalysisResult, ScanOptions};
+use anyhow::{bail, Context, Result};
+use reqwest::header::HeaderValue;
+use serde::Serialize;
+use url::Url;
+
+#[derive(Debug, Clone)]
+pub struct Locale {
+    /// Sent as `Accept-Language`, e.g. "de-DE" or "en-US,en;q=0.9"
+    pub language: String,
+    pub proxy: Option<Url>,
+}
+
+/// Parse `--compare-locales` languages and `LANGUAGE=URL` proxies for them.
+pub fn parse(languages: &[String], proxies: &[String]) -> Result<Vec<Locale>> {
+    if languages.len() < 2 {
+        bail!("--compare-locales needs at least two languages to compare");
+    }
+    let mut locales = Vec::new();
+    for language in languages {
+        HeaderValue::from_str(language)
+            .with_context(|| format!("'{}' is not a valid Accept-Language value", language))?;
+        locales.push(Locale {
+            language: language.clone(),
+            proxy: None,
+        });
+    }
+    for proxy in proxies {
+        let Some((language, url)) = proxy.split_once('=') else {
+            bail!(
+                "--locale-proxy '{}' is not in the form 'LANGUAGE=URL'",
+                proxy
+            );
+        };
+        let Some(locale) = locales.iter_mut().find(|l| l.language == language) else {
+            bail!(
+                "--locale-proxy names '{}', which is not in --compare-locales",
+                language
+            );
+        };
+        locale.proxy = Some(
+            Url::parse(url)
+                .with_context(|| format!("Invalid proxy URL in --locale-proxy '{}'", proxy))?,
+        );
+    }
+    Ok(locales)
+}
+
+/// What one locale's scan had that the others did not.
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleFindings {
+    #[serde(flatten)]
+    pub findings: VariantFindings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Whether a consent banner was shown; only known in headless mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consent_banner: Option<bool>,
+}
+
+/// Findings only some of the locales received.
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleComparison {
+    pub locales: Vec<LocaleFindings>,
+}
+
+/// Analyze `url` once per locale and merge the scans into one result that
+/// records what was unique to each. Any failed scan fails the comparison,
+/// since a missing locale would make the others' findings look unique.
+pub async fn compare(
+    url: &str,
+    options: &ScanOptions,
+    locales: &[Locale],
+    progress: impl Fn(&Locale),
+) -> Result<AnalysisResult> {
+    let mut runs = Vec::new();
+    for locale in locales {
+        progress(locale);
+        let options = ScanOptions {
+            visitor: Visitor {
+                locale: Some(locale.clone()),
+                ..options.visitor.clone()
+            },
+            ..options.clone()
+        };
+        let result = analyze_url(url, &options)
+            .await
+            .with_context(|| format!("Scan as {} failed", locale.language))?;
+        runs.push(result);
+    }
+
+    let labelled: Vec<(&str, &AnalysisResult)> = locales
+        .iter()
+        .map(|l| l.language.as_str())
+        .zip(runs.iter())
+        .collect();
+    let comparison = LocaleComparison {
+        locales: variants::unique_findings(&labelled)
+            .into_iter()
+            .zip(locales.iter().zip(runs.iter()))
+            .map(|(findings, (locale, run))| LocaleFindings {
+                findings,
+                proxy: locale.proxy.as_ref().map(Url::to_string),
+                consent_banner: (options.headless && run.static_fallback.is_none())
+                    .then_some(run.consent_banner.is_some()),
+            })
+            .collect(),
+    };
+
+    let mut runs = runs.into_iter();
+    let mut site = runs.next().context("No locales to scan")?;
+    for run in runs {
+        merge(&mut site, run);
+    }
+    site.pages = vec![site.url.clone()];
+    site.locale_comparison = Some(comparison);
+    Ok(site)
+}
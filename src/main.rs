@@ -1,39 +1,856 @@
-use anyhow::{Context, Result};
-use clap::Parser;
-use console::Term;
-use indicatif::{ProgressBar, ProgressStyle};
+mod auth;
+mod banner;
+mod baseline;
+mod batch;
+mod beacons;
+mod browser;
+mod budget;
+mod capabilities;
+mod client_redirect;
+mod cmp;
+mod compliance;
+mod config;
+mod consent;
+mod cookie_policy;
+mod cookie_rules;
+mod cookie_size;
+mod crawl;
+mod databases;
+mod dataflow;
+mod disconnect;
+mod entropy;
+mod deprecation;
+mod devices;
+mod filters;
+mod fingerprint;
+mod fonts;
+mod gate;
+mod headers;
+mod headless;
+mod hints;
+mod history;
+mod https;
+mod integrity;
+mod inventory;
+mod locales;
+mod metrics;
+mod notify;
+mod output;
+mod owners;
+mod pii;
+mod pixels;
+mod presets;
+mod preconsent;
+mod privacy_budget;
+mod prefixes;
+mod profiles;
+mod psl;
+mod redirects;
+mod referrer;
+mod refresh;
+mod related_sets;
+mod remediation;
+mod replay;
+mod retry;
+mod robots;
+mod rules;
+mod sampling;
+mod schedule;
+mod scripts;
+mod serve;
+mod signals;
+mod skimmer;
+mod sitemap;
+mod storage;
+mod subresources;
+mod tcf;
+mod throttle;
+mod tls;
+mod triage;
+mod tui;
+mod usage;
+mod user_agents;
+mod variance;
+mod variants;
+mod vendors;
+mod visitor;
+mod websockets;
+mod wellknown;
+
+use anyhow::{bail, Context, Result};
+use auth::SiteAccess;
+use banner::ConsentBanner;
+use baseline::Baseline;
+use beacons::Beacon;
+use cmp::CmpCrossCheck;
+use budget::{BudgetUsage, FetchBudget};
+use client_redirect::ClientRedirect;
+use compliance::{ComplianceFramework, ComplianceReport, Profile};
+use config::Config;
+use consent::ConsentDiff;
+use cookie_policy::CookiePolicyReport;
+use cookie_rules::CookieRules;
+use cookie_size::DomainCookies;
+use clap::builder::Resettable;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use crawl::CrawlOptions;
+use dataflow::DataFlow;
+use deprecation::CookieReadiness;
+use devices::DeviceComparison;
+use disconnect::DisconnectList;
+use filters::FilterList;
+use fonts::RemoteFonts;
+use futures::stream::{FuturesUnordered, StreamExt};
+use gate::{FailOn, Gate};
+use headers::LegacyHeader;
+use headless::StaticFallback;
+use hints::ResourceHint;
+use history::{Findings, History, SiteChange};
+use https::HttpsReport;
+use indicatif::MultiProgress;
+use integrity::IntegrityReport;
+use inventory::ScriptFile;
+use locales::LocaleComparison;
+use metrics::Metrics;
+use notify::{NotifyOn, WebhookFormat};
+use output::sink::{self, OutputSink, Run};
+use output::{terminal, OutputFormat};
+use pii::PiiLeak;
+use pixels::SuspectedPixel;
+use preconsent::{PreConsentViolation, ViolationSeverity};
+use presets::Preset;
+use privacy_budget::PrivacyBudget;
+use prefixes::PrefixAdvice;
+use redirects::RedirectHop;
+use referrer::ReferrerReport;
+use refresh::CookieRefresh;
+use related_sets::RelatedWebsiteSet;
+use replay::SessionReplay;
+use rules::{RuleSet, TrackerRule};
+use sampling::SampleReport;
+use schedule::HostLimits;
+use scripts::ScriptDependency;
+use signals::{Signal, SignalTest};
+use skimmer::SkimmerFinding;
+use storage::StorageItem;
+use tcf::TcfReport;
+use tls::TlsReport;
+use usage::ResourceUsage;
+use user_agents::UserAgentPreset;
+use visitor::Visitor;
+use websockets::WebSocketEndpoint;
+use wellknown::WellKnownReport;
 use owo_colors::OwoColorize;
-use regex::Regex;
-use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE, USER_AGENT};
+use reqwest::header::{REFERRER_POLICY, SET_COOKIE, STRICT_TRANSPORT_SECURITY};
 use scraper::{Html, Selector};
+use serde::Serialize;
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
 /// Recon - Website Privacy Analysis Tool
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage the headless browser used by --headless
+    #[command(subcommand)]
+    Browser(BrowserCommand),
+    /// Describe what this install can do: browser, databases and output formats
+    Capabilities {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: capabilities::CapabilitiesFormat,
+    },
+    /// Show how a site's score, cookies and trackers changed across the scans
+    /// recorded with --history, or manage the history
+    History(HistoryArgs),
+    /// Download the latest tracker rules, EasyPrivacy, the Disconnect list and
+    /// the Public Suffix List for scans to use instead of the bundled copies
+    UpdateDb,
+    /// Run an HTTP API that scans URLs on request: POST /scan queues a scan,
+    /// GET /scan/{id} returns its status and JSON result
+    Serve(ServeArgs),
+    /// Keep rescanning the URLs every --interval, recording each run in the
+    /// history and reporting what changed since the previous one
+    Watch(Box<WatchArgs>),
+}
+
+#[derive(Subcommand, Debug)]
+enum BrowserCommand {
+    /// Download a headless Chromium into the user data directory and use it for --headless
+    Install {
+        /// Chrome for Testing version to install instead of the current stable release
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Show which browser --headless will use and check that it runs
+    Status,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+struct HistoryArgs {
+    #[command(subcommand)]
+    command: Option<HistoryCommand>,
+    /// Site whose recorded scans to show
+    url: Option<String>,
+    /// Show at most the latest N scans
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    limit: usize,
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "text")]
+    format: TrendFormat,
+    /// History database to use instead of the one in the user data directory
+    #[arg(long, value_name = "FILE")]
+    db: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum TrendFormat {
+    Text,
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+struct ServeArgs {
+    /// Port to listen on
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+    /// Address to listen on; the default only accepts local connections
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1")]
+    bind: std::net::IpAddr,
+    /// Scans to run at once
+    #[arg(long, value_name = "N", default_value_t = 2,
+          value_parser = clap::value_parser!(u16).range(1..))]
+    workers: u16,
+    /// Scans that may wait for a worker before new ones are refused
+    #[arg(long, value_name = "N", default_value_t = 100,
+          value_parser = clap::value_parser!(u16).range(1..))]
+    queue: u16,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(mut_arg("history", |arg| arg.hide(true)))]
+#[command(mut_arg("history_db", |arg| arg.requires(Resettable::Reset)))]
+#[command(mut_arg("history_keep", |arg| arg.requires(Resettable::Reset)))]
+struct WatchArgs {
+    /// Time from the start of one run to the start of the next
+    #[arg(long, value_name = "DURATION", default_value = "24h")]
+    interval: humantime::Duration,
+    /// Serve Prometheus gauges for each watched site at /metrics on this
+    /// address (e.g. 127.0.0.1:9184)
+    #[arg(long, value_name = "ADDR")]
+    metrics_addr: Option<std::net::SocketAddr>,
+    #[command(flatten)]
+    scan: Args,
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommand {
+    /// Delete recorded scans older than a retention period
+    Prune {
+        /// How long to keep scans, e.g. 90d or 12weeks
+        #[arg(long, value_name = "DURATION")]
+        keep: humantime::Duration,
+        /// History database to use instead of the one in the user data directory
+        #[arg(long, value_name = "FILE")]
+        db: Option<PathBuf>,
+    },
+    /// Write every recorded scan to a JSON Lines file
+    Export {
+        /// File to write
+        file: PathBuf,
+        /// History database to use instead of the one in the user data directory
+        #[arg(long, value_name = "FILE")]
+        db: Option<PathBuf>,
+    },
+    /// Add the scans from a file written by `history export`
+    Import {
+        /// File to read
+        file: PathBuf,
+        /// History database to use instead of the one in the user data directory
+        #[arg(long, value_name = "FILE")]
+        db: Option<PathBuf>,
+    },
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "recon")]
 #[command(author, version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+/// What to scan and how, for a single run or each run of `watch`
+#[derive(clap::Args, Debug)]
 struct Args {
-    /// The URL to analyze (e.g., https://example.com)
-    url: String,
+    /// The URL(s) to analyze (e.g., https://example.com)
+    #[arg(required_unless_present = "input", num_args = 1..)]
+    urls: Vec<String>,
+
+    /// Start from a bundle of options: quick, standard, deep or compliance.
+    /// Options given alongside it override the bundle's
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+
+    /// Hide tracker matches and cookie categorizations below this confidence
+    #[arg(long, value_enum, default_value_t = Confidence::Low)]
+    min_confidence: Confidence,
+
+    /// Load tracker detection rules from a YAML file instead of the bundled set
+    #[arg(long, value_name = "FILE")]
+    rules: Option<PathBuf>,
+
+    /// Add tracker patterns from a TOML file to the rules in use (repeatable)
+    #[arg(long, value_name = "FILE")]
+    trackers: Vec<PathBuf>,
+
+    /// Categorize cookies and storage keys with rules from a TOML file before
+    /// the built-in patterns (repeatable)
+    #[arg(long, value_name = "FILE")]
+    cookie_rules: Vec<PathBuf>,
+
+    /// Also match resources against an Adblock Plus filter list such as
+    /// EasyPrivacy or EasyList (repeatable)
+    #[arg(long, value_name = "FILE")]
+    filter_list: Vec<PathBuf>,
+
+    /// Categorize third-party domains with the Disconnect tracking protection
+    /// list (services.json)
+    #[arg(long, value_name = "FILE")]
+    disconnect: Option<PathBuf>,
+
+    /// Never report requests to this domain or its subdomains as third-party
+    /// or tracking, e.g. your own CDN (repeatable)
+    #[arg(long, value_name = "DOMAIN")]
+    ignore_domain: Vec<String>,
+
+    /// Send this User-Agent instead of the default desktop Chrome one
+    #[arg(long, value_name = "STRING", conflicts_with = "ua")]
+    user_agent: Option<String>,
+
+    /// Send a named User-Agent preset instead of the default desktop Chrome one
+    #[arg(long, value_enum, value_name = "PRESET")]
+    ua: Option<UserAgentPreset>,
+
+    /// Read defaults from this file instead of the discovered cookie-scout.toml
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Ignore any cookie-scout.toml config file
+    #[arg(long, conflicts_with = "config")]
+    no_config: bool,
+
+    /// Fail the run when a site goes over the limits in this file instead
+    /// of a privacy-budget.toml in the current directory
+    #[arg(long, value_name = "FILE")]
+    budget: Option<PathBuf>,
+
+    /// Use the databases cached by `recon update-db` as they are, without
+    /// refreshing stale ones
+    #[arg(long)]
+    offline: bool,
+
+    /// Follow same-site links and merge every page into one site-wide report
+    #[arg(long, group = "site_scan")]
+    crawl: bool,
+
+    /// Scan the pages listed in the site's sitemap.xml and merge them into one report
+    #[arg(long, group = "site_scan")]
+    sitemap: bool,
+
+    /// How many links deep to follow when crawling
+    #[arg(long, default_value_t = 2, requires = "crawl")]
+    max_depth: usize,
+
+    /// Crawl pages even when the site's robots.txt disallows them
+    #[arg(long, requires = "crawl")]
+    ignore_robots: bool,
+
+    /// Maximum number of pages to analyze per site when crawling or using the
+    /// sitemap (large sitemaps are sampled evenly)
+    #[arg(long, default_value_t = 20, requires = "site_scan")]
+    max_pages: usize,
+
+    /// Load each page this many times and report which findings appear on
+    /// every load and which are intermittent
+    #[arg(long, value_name = "N", default_value_t = 1, conflicts_with = "site_scan",
+          value_parser = clap::value_parser!(u16).range(1..))]
+    samples: u16,
+
+    /// Scan each page as a desktop and as a mobile visitor and report the
+    /// cookies and trackers only one of them receives
+    #[arg(long, conflicts_with_all = ["site_scan", "samples", "user_agent", "ua"])]
+    compare_devices: bool,
+
+    /// Scan each page once per Accept-Language value and report the cookies,
+    /// trackers and consent banners only some of them receive (comma-separated)
+    #[arg(long, value_name = "LANG", value_delimiter = ',',
+          conflicts_with_all = ["site_scan", "samples", "compare_devices"])]
+    compare_locales: Vec<String>,
+
+    /// Scan each page once more sending `Sec-GPC: 1` and report whether
+    /// Global Privacy Control stops its tracking
+    #[arg(long, conflicts_with_all = ["site_scan", "samples", "compare_devices", "compare_locales"])]
+    test_gpc: bool,
+
+    /// Scan each page once more sending `DNT: 1` and report whether Do Not
+    /// Track stops its tracking
+    #[arg(long, conflicts_with_all = ["site_scan", "samples", "compare_devices", "compare_locales"])]
+    test_dnt: bool,
+
+    /// Route one --compare-locales language through a proxy in its region,
+    /// e.g. "de-DE=http://proxy.de.example:3128" (repeatable)
+    #[arg(long, value_name = "LANG=URL", requires = "compare_locales")]
+    locale_proxy: Vec<String>,
+
+    /// Start every sample with an empty cookie jar instead of keeping cookies
+    /// between loads like a returning visitor
+    #[arg(long, requires = "samples")]
+    fresh_jar: bool,
+
+    /// Read additional URLs from a file, one per line ("-" for stdin)
+    #[arg(short, long, value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// Scan up to N sites at the same time
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    concurrency: usize,
+
+    /// Never scan more than N URLs of the same domain at the same time, so a
+    /// long list from one site does not hold up the others or hammer it
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    max_per_host: usize,
+
+    /// Retry each page up to N times after a timeout, dropped connection or
+    /// 5xx response, waiting longer before each attempt
+    #[arg(long, value_name = "N", default_value_t = 2)]
+    retries: u32,
+
+    /// Send each domain at most N requests per second, across all scans
+    #[arg(long, value_name = "N", conflicts_with = "delay")]
+    rate: Option<f64>,
+
+    /// Wait at least this long between two requests to the same domain,
+    /// e.g. "500ms" or "2s"
+    #[arg(long, value_name = "DURATION")]
+    delay: Option<humantime::Duration>,
 
     /// Show detailed information about each cookie
     #[arg(short, long)]
     verbose: bool,
+
+    /// Print at most N entries of each list in the text report, worst first
+    /// [default: 15, or all with --verbose]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    limit: Option<u16>,
+
+    /// Which page of --limit entries to print
+    #[arg(long, value_name = "M", default_value_t = 1, requires = "limit",
+          value_parser = clap::value_parser!(u16).range(1..))]
+    page: u16,
+
+    /// Output format for the report
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Download external scripts and report tracker URLs they reference
+    #[arg(long)]
+    fetch_scripts: bool,
+
+    /// Largest script body to download and scan, in kilobytes
+    #[arg(long, value_name = "KB", default_value_t = 2048, requires = "fetch_scripts")]
+    max_script_kb: usize,
+
+    /// Request the page's scripts, images and iframes and record the cookies they set
+    #[arg(long)]
+    fetch_resources: bool,
+
+    /// Send this header with requests to the scanned site, e.g.
+    /// "X-Staging-Bypass: 1" (repeatable)
+    #[arg(long, value_name = "NAME: VALUE")]
+    header: Vec<String>,
+
+    /// Send this cookie to the scanned site, e.g. "session=abc" (repeatable)
+    #[arg(long, value_name = "NAME=VALUE")]
+    cookie: Vec<String>,
+
+    /// Log in to the scanned site with HTTP basic authentication
+    #[arg(long, value_name = "USER:PASSWORD", conflicts_with = "bearer")]
+    basic_auth: Option<String>,
+
+    /// Send this bearer token to the scanned site
+    #[arg(long, value_name = "TOKEN")]
+    bearer: Option<String>,
+
+    /// Most script and resource requests in flight at once, shared by
+    /// --fetch-scripts and --fetch-resources
+    #[arg(long, value_name = "N", default_value_t = 8)]
+    max_concurrent_fetches: usize,
+
+    /// Total download budget for script and resource requests per page, in
+    /// kilobytes; requests beyond it are skipped
+    #[arg(long, value_name = "KB", default_value_t = 16384)]
+    fetch_budget_kb: usize,
+
+    /// Load each page in headless Chrome to capture cookies and trackers added by JavaScript
+    #[arg(long)]
+    headless: bool,
+
+    /// Save a screenshot and the text of each page's consent banner to this directory
+    #[arg(long, value_name = "DIR", requires = "headless")]
+    evidence_dir: Option<PathBuf>,
+
+    /// Load each page twice, rejecting and then accepting the consent banner,
+    /// and diff the cookies, storage and requests
+    #[arg(long, requires = "headless")]
+    consent_diff: bool,
+
+    /// Find the site's cookie policy and list cookies it does not declare
+    #[arg(long)]
+    cookie_policy: bool,
+
+    /// Use this page as the cookie policy instead of searching for it
+    #[arg(long, value_name = "URL", requires = "cookie_policy")]
+    cookie_policy_url: Option<String>,
+
+    /// Check the findings against a regulation's requirements and report
+    /// pass/warn/fail for each
+    #[arg(long, value_enum, value_name = "FRAMEWORK")]
+    compliance: Option<ComplianceFramework>,
+
+    /// Run a regulator's compliance checks: its framework's checklist plus
+    /// the jurisdiction's own rules and thresholds
+    #[arg(long, value_enum, value_name = "PROFILE", conflicts_with = "compliance")]
+    profile: Option<Profile>,
+
+    /// Report whether each cookie survives Chrome's third-party cookie phase-out
+    #[arg(long)]
+    cookiepocalypse: bool,
+
+    /// Load each page a second time with the cookies of the first visit and
+    /// report which cookies are set again, and whether their expiry moves
+    #[arg(long)]
+    probe_refresh: bool,
+
+    /// Follow up to N meta refresh and script redirects from shell pages
+    /// before analyzing (0 analyzes the first page served)
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    max_client_redirects: usize,
+
+    /// Probe .well-known privacy endpoints such as gpc.json and dnt-policy.txt
+    #[arg(long)]
+    well_known: bool,
+
+    /// Endpoint to probe instead of the default list (repeatable)
+    #[arg(long, value_name = "PATH", requires = "well_known")]
+    well_known_path: Vec<String>,
+
+    /// Write a copy of the page's HTML with the tags behind each tracker
+    /// finding highlighted (one file per site when scanning several)
+    #[arg(long, value_name = "FILE")]
+    annotate: Option<PathBuf>,
+
+    /// Write every cookie, storage key, tracker and third-party domain found
+    /// to a file for triage (.csv for CSV, anything else for JSON)
+    #[arg(long, value_name = "FILE")]
+    export_findings: Option<PathBuf>,
+
+    /// Only export findings fuzzy-matching this query, e.g. "marketing
+    /// google" (repeatable; a finding matching any query is kept)
+    #[arg(long, value_name = "QUERY", requires = "export_findings")]
+    select: Vec<String>,
+
+    /// Browse the findings in an interactive view once the scan is done:
+    /// filter, mark and export them to CSV or JSON
+    #[arg(long)]
+    triage: bool,
+
+    /// Write the page → resource → tracker graph to a file
+    /// (.mmd/.mermaid for Mermaid, anything else for GraphViz DOT)
+    #[arg(long, value_name = "FILE")]
+    graph: Option<PathBuf>,
+
+    /// Render the score, cookies, trackers and recommendations into a PDF
+    #[arg(long, value_name = "FILE")]
+    report_pdf: Option<PathBuf>,
+
+    /// Write a portfolio roll-up of all scanned sites for leadership
+    /// (.html for HTML, anything else for Markdown)
+    #[arg(long, value_name = "FILE")]
+    executive_summary: Option<PathBuf>,
+
+    /// Save this run's JSON report as a known-good snapshot for --baseline
+    #[arg(long, value_name = "FILE")]
+    save_baseline: Option<PathBuf>,
+
+    /// Report only the trackers and cookies added or removed, and cookies
+    /// that lost Secure, since a snapshot from --save-baseline
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Record each successful scan in the history database
+    #[arg(long)]
+    history: bool,
+
+    /// History database to use instead of the one in the user data directory
+    #[arg(long, value_name = "FILE", requires = "history")]
+    history_db: Option<PathBuf>,
+
+    /// After recording, delete scans older than this (e.g. 90d) so the
+    /// history does not grow without bound
+    #[arg(long, value_name = "DURATION", requires = "history")]
+    history_keep: Option<humantime::Duration>,
+
+    /// POST the JSON report to this URL after each run
+    #[arg(long, value_name = "URL")]
+    webhook: Option<String>,
+
+    /// When to notify the webhook: after every run with the full report, or
+    /// only when findings changed or a threshold was broken, with just those
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = NotifyOn::Always)]
+    notify_on: NotifyOn,
+
+    /// What to post to the webhook: recon's JSON, or a Slack message
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = WebhookFormat::Json)]
+    webhook_format: WebhookFormat,
+
+    /// Exit with status 2 when a site's privacy score is below SCORE
+    #[arg(long, value_name = "SCORE", value_parser = clap::value_parser!(u32).range(0..=100))]
+    fail_on_score: Option<u32>,
+
+    /// Exit with status 2 when a site has any of these findings
+    /// (comma-separated)
+    #[arg(long, value_enum, value_name = "CONDITION", value_delimiter = ',')]
+    fail_on: Vec<FailOn>,
+}
+
+/// When `watch` scans again and where it serves its gauges
+struct Schedule {
+    interval: Duration,
+    metrics_addr: Option<std::net::SocketAddr>,
+}
+
+/// What the first page of a crawl or sitemap scan found out about the site
+/// itself, for the later pages to reuse instead of probing it again
+#[derive(Debug, Clone)]
+struct SiteProbes {
+    related_set: Option<RelatedWebsiteSet>,
+    cmp_declarations: Option<Arc<cmp::Declarations>>,
 }
 
+/// Options that control how much work a single analysis does
 #[derive(Debug, Clone)]
+struct ScanOptions {
+    fetch_scripts: bool,
+    max_script_bytes: usize,
+    fetch_resources: bool,
+    max_concurrent_fetches: usize,
+    fetch_budget_bytes: usize,
+    /// Headers, cookies and credentials for the scanned site
+    access: Arc<SiteAccess>,
+    headless: bool,
+    /// Device, language and region the scan presents itself as
+    visitor: Visitor,
+    evidence_dir: Option<PathBuf>,
+    consent_diff: bool,
+    compliance: Option<ComplianceFramework>,
+    profile: Option<Profile>,
+    /// Compare against the cookie policy, found automatically when the URL is `None`
+    cookie_policy: Option<Option<Url>>,
+    cookie_readiness: bool,
+    /// Request the page again to see which cookies are set on every visit
+    probe_refresh: bool,
+    /// Meta refresh and script redirects to follow before analyzing
+    max_client_redirects: usize,
+    /// Extra attempts at the page request after a transient failure
+    retries: u32,
+    /// `.well-known` paths to probe, or `None` to skip probing
+    well_known_paths: Option<Vec<String>>,
+    min_confidence: Confidence,
+    rules: Arc<RuleSet>,
+    /// `--cookie-rules` overrides for cookie and storage categories
+    cookie_rules: Arc<CookieRules>,
+    /// Set for the pages after the first of a crawl or sitemap scan, which
+    /// skip the TLS, HTTPS, `.well-known` and redirect probes whose results
+    /// the first page already has, and reuse its related set and CMP
+    /// configuration
+    site_probes: Option<SiteProbes>,
+}
+
+impl ScanOptions {
+    fn from_args(args: &Args) -> Result<Self> {
+        let mut rules = match &args.rules {
+            Some(path) => RuleSet::load(path)?,
+            None => RuleSet::builtin(),
+        };
+        databases::Manifest::load().apply(&mut rules, args.rules.is_some())?;
+        for path in &args.trackers {
+            rules.add_toml(path)?;
+        }
+        for path in &args.filter_list {
+            rules.filter_lists.push(FilterList::load(path)?);
+        }
+        if let Some(path) = &args.disconnect {
+            rules.disconnect = Some(DisconnectList::load(path)?);
+        }
+        rules.ignored_domains = args.ignore_domain.iter().map(|d| d.to_lowercase()).collect();
+        let mut cookie_rules = CookieRules::default();
+        for path in &args.cookie_rules {
+            cookie_rules.load(path)?;
+        }
+        Ok(ScanOptions {
+            fetch_scripts: args.fetch_scripts,
+            max_script_bytes: args.max_script_kb * 1024,
+            max_concurrent_fetches: args.max_concurrent_fetches,
+            fetch_budget_bytes: args.fetch_budget_kb * 1024,
+            access: Arc::new(SiteAccess::parse(
+                &args.header,
+                &args.cookie,
+                args.basic_auth.as_deref(),
+                args.bearer.as_deref(),
+            )?),
+            fetch_resources: args.fetch_resources,
+            headless: args.headless,
+            visitor: Visitor::default(),
+            evidence_dir: args.evidence_dir.clone(),
+            consent_diff: args.consent_diff,
+            compliance: args
+                .compliance
+                .or_else(|| args.profile.map(|p| p.framework())),
+            profile: args.profile,
+            cookie_policy: match (&args.cookie_policy, &args.cookie_policy_url) {
+                (false, _) => None,
+                (true, None) => Some(None),
+                (true, Some(url)) => Some(Some(
+                    Url::parse(&normalize_url(url)).context("Invalid --cookie-policy-url")?,
+                )),
+            },
+            cookie_readiness: args.cookiepocalypse,
+            probe_refresh: args.probe_refresh,
+            max_client_redirects: args.max_client_redirects,
+            retries: args.retries,
+            well_known_paths: args.well_known.then(|| {
+                if args.well_known_path.is_empty() {
+                    wellknown::DEFAULT_PATHS.iter().map(|p| p.to_string()).collect()
+                } else {
+                    args.well_known_path.clone()
+                }
+            }),
+            min_confidence: args.min_confidence,
+            rules: Arc::new(rules),
+            cookie_rules: Arc::new(cookie_rules),
+            site_probes: None,
+        })
+    }
+
+    /// The options for the pages after `first` in a crawl or sitemap scan.
+    fn for_later_pages(&self, first: &AnalysisResult) -> ScanOptions {
+        ScanOptions {
+            site_probes: Some(SiteProbes {
+                related_set: first.related_set.clone(),
+                cmp_declarations: first.cmp_declarations.clone(),
+            }),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct CookieInfo {
     name: String,
+    /// The Set-Cookie header exactly as the server sent it, or an equivalent
+    /// rebuilt from the browser's cookie store
+    raw_header: String,
     domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// `Expires` as an HTTP date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<String>,
+    /// `Max-Age` in seconds, which takes precedence over `Expires`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_age: Option<i64>,
+    /// Whether the cookie outlives the browser session
+    persistent: bool,
+    /// Days the cookie lives from when it was observed; `None` for session cookies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifetime_days: Option<u64>,
     secure: bool,
     http_only: bool,
     same_site: Option<String>,
+    /// Stored per top-level site (CHIPS)
+    partitioned: bool,
+    /// Chrome's `Priority` attribute: low, medium or high
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    value_length: usize,
+    /// Estimated entropy of the value, counting only its random-looking parts
+    entropy_bits: u32,
+    /// Whether the value looks like a unique identifier rather than a flag
+    /// or preference
+    identifier: bool,
     category: CookieCategory,
+    vendor: Option<String>,
+    confidence: Confidence,
+    source: CookieSource,
+    /// The subresource URL whose response set the cookie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_by: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl CookieInfo {
+    /// How long the cookie lives from now, from `Max-Age` or else `Expires`.
+    /// `None` for session cookies.
+    fn lifetime(&self) -> Option<Duration> {
+        if let Some(max_age) = self.max_age {
+            return Some(Duration::from_secs(max_age.max(0) as u64));
+        }
+        let expires = httpdate::parse_http_date(self.expires.as_deref()?).ok()?;
+        Some(
+            expires
+                .duration_since(std::time::SystemTime::now())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// "session" or the lifetime in days, for reports.
+    fn lifespan(&self) -> String {
+        match self.lifetime_days {
+            Some(days) => format!("{} days", days),
+            None => "session".to_string(),
+        }
+    }
+
+    /// Whether the cookie persists longer than [`MAX_COOKIE_LIFETIME`].
+    fn is_long_lived(&self) -> bool {
+        self.lifetime().is_some_and(|l| l > MAX_COOKIE_LIFETIME)
+    }
+}
+
+/// Cookies persisting longer than this, 12 months, are flagged
+const MAX_COOKIE_LIFETIME: Duration = Duration::from_secs(365 * 86_400);
+
+/// Where a cookie was observed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CookieSource {
+    /// A Set-Cookie header on the page response
+    Header,
+    /// The headless browser's cookie store, set by scripts or subresources
+    Browser,
+    /// A Set-Cookie header on a script, image or iframe response
+    Subresource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 enum CookieCategory {
     Essential,
     Analytics,
@@ -54,73 +871,183 @@ impl CookieCategory {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 struct TrackerInfo {
     name: String,
     category: String,
     description: String,
+    /// The resource URL that matched, or "inline script"
+    source: String,
+    confidence: Confidence,
+    /// Pre-consent severity set by the rule, overriding the category's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<ViolationSeverity>,
+}
+
+/// How much a finding can be trusted, based on how it was detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+enum Confidence {
+    /// Generic keyword match that is often a false positive
+    Low,
+    /// Specific pattern found in a URL path or script content
+    Medium,
+    /// Exact cookie name or tracker host match
+    High,
+}
+
+impl Confidence {
+    fn as_str(&self) -> &str {
+        match self {
+            Confidence::Low => "Low",
+            Confidence::Medium => "Medium",
+            Confidence::High => "High",
+        }
+    }
 }
 
+#[derive(Serialize)]
 struct AnalysisResult {
     url: String,
+    /// Every page whose findings are included (more than one when crawling)
+    pages: Vec<String>,
+    /// Pages the crawl found but skipped because robots.txt disallows them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    robots_disallowed: Vec<String>,
+    /// Meta refresh and script redirects followed to reach the analyzed page
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    client_redirects: Vec<ClientRedirect>,
+    /// HTTP redirects in front of the page, ending with the page itself
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    redirect_chain: Vec<RedirectHop>,
+    /// Requests and bytes used by --fetch-scripts and --fetch-resources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fetch_budget: Option<BudgetUsage>,
+    /// What the scan itself cost: requests, bytes, wall time and peak memory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_usage: Option<ResourceUsage>,
+    /// Fingerprint of the served HTML with per-request noise removed, to tell
+    /// page changes from tracking changes between scans
+    content_hash: String,
     cookies: Vec<CookieInfo>,
+    /// `__Host-` and `__Secure-` cookies that break their prefix's rules, and
+    /// session cookies that could adopt one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cookie_prefixes: Vec<PrefixAdvice>,
+    /// Cookie count and `Cookie` header size per domain, largest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cookie_sizes: Vec<DomainCookies>,
     trackers: Vec<TrackerInfo>,
+    /// Images shaped like tracking pixels that no tracker rule matches
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suspected_pixels: Vec<SuspectedPixel>,
+    /// Hyperlink `ping` and `navigator.sendBeacon` destinations
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    beacons: Vec<Beacon>,
+    /// Preconnect, dns-prefetch, prefetch and preload hints
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    resource_hints: Vec<ResourceHint>,
+    /// `ws://` and `wss://` endpoints in scripts or opened in the browser
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    websockets: Vec<WebSocketEndpoint>,
+    /// Web fonts loaded from font services such as Google Fonts
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    remote_fonts: Vec<RemoteFonts>,
     third_party_requests: Vec<String>,
+    script_dependencies: Vec<ScriptDependency>,
+    /// The site's own external scripts with their hashes, with `--fetch-scripts`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    script_inventory: Option<Vec<ScriptFile>>,
+    /// Subresource Integrity coverage of external scripts and stylesheets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    integrity: Option<IntegrityReport>,
+    legacy_headers: Vec<LegacyHeader>,
+    referrer: ReferrerReport,
+    pii_leaks: Vec<PiiLeak>,
+    /// Categories of data each third party likely receives
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    data_flows: Vec<DataFlow>,
+    /// Consent-requiring cookies and trackers active on first load
+    pre_consent: Vec<PreConsentViolation>,
+    /// Scripts matching payment-card skimmer signatures
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skimmers: Vec<SkimmerFinding>,
+    /// Session replay tools, which record keystrokes and form content
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    session_replay: Vec<SessionReplay>,
+    /// Set when headless mode was requested but the browser failed, so only
+    /// the static analysis ran
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_fallback: Option<StaticFallback>,
+    /// The consent banner's text and choices, only captured in headless mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    consent_banner: Option<ConsentBanner>,
+    /// Differences between rejecting and accepting consent, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    consent_diff: Option<ConsentDiff>,
+    /// Why the consent comparison could not be made, when it was requested
+    /// and its browser sessions failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    consent_diff_error: Option<String>,
+    /// Cookie categories compared with the site's CMP configuration, when it is retrievable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cmp_check: Option<CmpCrossCheck>,
+    /// IAB TCF participation and the decoded consent string, when present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tcf: Option<TcfReport>,
+    /// Cookies compared with the site's cookie policy, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cookie_policy: Option<CookiePolicyReport>,
+    /// Web Storage keys, only collected in headless mode
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    storage: Vec<StorageItem>,
+    /// The site's declared Related Website Set, if it publishes one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    related_set: Option<RelatedWebsiteSet>,
+    /// Privacy declarations found under `/.well-known`, when probed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    well_known: Option<WellKnownReport>,
+    /// The HTTPS connection and certificate; `None` for plain HTTP pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsReport>,
+    /// What the plain-HTTP address does and the page's HSTS policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    https: Option<HttpsReport>,
+    /// Which cookies are set again on a repeat visit, with `--probe-refresh`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cookie_refresh: Option<Vec<CookieRefresh>>,
+    /// Third-party cookie phase-out verdicts, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cookie_readiness: Option<Vec<CookieReadiness>>,
+    /// Findings only the desktop or the mobile scan saw, with `--compare-devices`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_comparison: Option<DeviceComparison>,
+    /// Findings only some languages or regions saw, with `--compare-locales`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale_comparison: Option<LocaleComparison>,
+    /// Whether the site honors privacy signals, with `--test-gpc` or `--test-dnt`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    signal_tests: Vec<SignalTest>,
+    /// Stability of the findings across repeated loads, with `--samples`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sampling: Option<SampleReport>,
+    /// Regulatory checklist, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compliance: Option<ComplianceReport>,
+    /// Same-site links found on the page, used to drive crawling
+    #[serde(skip)]
+    internal_links: Vec<String>,
+    /// The CMP configuration `cmp_check` was made against, for the later
+    /// pages of a crawl
+    #[serde(skip)]
+    cmp_declarations: Option<Arc<cmp::Declarations>>,
+    /// The HTML the trackers were detected in, rendered when headless
+    #[serde(skip)]
+    html: String,
 }
 
-// Known tracker patterns
-const TRACKER_PATTERNS: &[(&str, &str, &str)] = &[
-    // Analytics
-    ("google-analytics", "Analytics", "Google Analytics tracking"),
-    ("googletagmanager", "Analytics", "Google Tag Manager"),
-    ("gtag", "Analytics", "Google Global Site Tag"),
-    ("analytics", "Analytics", "Generic analytics"),
-    ("hotjar", "Analytics", "Hotjar behavior analytics"),
-    ("mixpanel", "Analytics", "Mixpanel analytics"),
-    ("segment", "Analytics", "Segment analytics"),
-    ("amplitude", "Analytics", "Amplitude analytics"),
-    ("plausible", "Analytics", "Plausible analytics"),
-    ("matomo", "Analytics", "Matomo analytics"),
-    ("heap", "Analytics", "Heap analytics"),
-    ("fullstory", "Analytics", "FullStory session replay"),
-    ("clarity", "Analytics", "Microsoft Clarity"),
-    // Marketing
-    ("doubleclick", "Marketing", "Google DoubleClick advertising"),
-    ("facebook.*pixel", "Marketing", "Facebook Pixel"),
-    ("fbevents", "Marketing", "Facebook Events"),
-    ("ads", "Marketing", "Advertising scripts"),
-    ("adsense", "Marketing", "Google AdSense"),
-    ("adwords", "Marketing", "Google AdWords"),
-    ("criteo", "Marketing", "Criteo retargeting"),
-    ("taboola", "Marketing", "Taboola content ads"),
-    ("outbrain", "Marketing", "Outbrain content ads"),
-    ("pinterest", "Marketing", "Pinterest tracking"),
-    ("linkedin.*insight", "Marketing", "LinkedIn Insight Tag"),
-    ("twitter.*pixel", "Marketing", "Twitter Pixel"),
-    ("tiktok", "Marketing", "TikTok tracking"),
-    ("snapchat", "Marketing", "Snapchat tracking"),
-    // Social
-    ("facebook.com", "Social", "Facebook integration"),
-    ("twitter.com", "Social", "Twitter integration"),
-    ("linkedin.com", "Social", "LinkedIn integration"),
-    ("instagram.com", "Social", "Instagram integration"),
-    ("youtube.com", "Social", "YouTube embeds"),
-    ("vimeo.com", "Social", "Vimeo embeds"),
-    // Other
-    ("recaptcha", "Security", "Google reCAPTCHA"),
-    ("hcaptcha", "Security", "hCaptcha"),
-    ("cloudflare", "CDN/Security", "Cloudflare services"),
-    ("sentry", "Error Tracking", "Sentry error tracking"),
-    ("bugsnag", "Error Tracking", "Bugsnag error tracking"),
-    ("intercom", "Customer Support", "Intercom chat"),
-    ("drift", "Customer Support", "Drift chat"),
-    ("zendesk", "Customer Support", "Zendesk support"),
-    ("hubspot", "Marketing/CRM", "HubSpot tracking"),
-    ("marketo", "Marketing", "Marketo tracking"),
-    ("pardot", "Marketing", "Pardot tracking"),
-    ("optimizely", "A/B Testing", "Optimizely experiments"),
-    ("vwo", "A/B Testing", "VWO experiments"),
-];
+/// `TrackerInfo::source` for trackers found in inline script content
+const INLINE_SCRIPT_SOURCE: &str = "inline script";
 
 // Known cookie patterns for categorization
 const COOKIE_PATTERNS: &[(&str, CookieCategory)] = &[
@@ -166,56 +1093,206 @@ const COOKIE_PATTERNS: &[(&str, CookieCategory)] = &[
     ("instagram", CookieCategory::Social),
 ];
 
-fn categorize_cookie(name: &str) -> CookieCategory {
+// Known cookie names and the vendor that sets them. A trailing `*` matches any
+// cookie starting with the prefix; everything else must match exactly.
+const COOKIE_VENDORS: &[(&str, &str)] = &[
+    // Google
+    ("_ga", "Google Analytics"),
+    ("_ga_*", "Google Analytics"),
+    ("_gid", "Google Analytics"),
+    ("_gat*", "Google Analytics"),
+    ("__utm*", "Google Analytics"),
+    ("_dc_gtm_*", "Google Tag Manager"),
+    ("_gcl_*", "Google Ads"),
+    ("__gads", "Google AdSense"),
+    ("__gpi", "Google AdSense"),
+    ("IDE", "Google DoubleClick"),
+    ("DSID", "Google DoubleClick"),
+    ("test_cookie", "Google DoubleClick"),
+    ("NID", "Google"),
+    ("1P_JAR", "Google"),
+    ("AEC", "Google"),
+    // Meta
+    ("_fbp", "Meta (Facebook)"),
+    ("_fbc", "Meta (Facebook)"),
+    ("fr", "Meta (Facebook)"),
+    // Microsoft
+    ("_clck", "Microsoft Clarity"),
+    ("_clsk", "Microsoft Clarity"),
+    ("CLID", "Microsoft Clarity"),
+    ("MUID", "Microsoft Advertising"),
+    ("_uetsid", "Microsoft Advertising"),
+    ("_uetvid", "Microsoft Advertising"),
+    // Analytics vendors
+    ("_hj*", "Hotjar"),
+    ("mp_*", "Mixpanel"),
+    ("amp_*", "Amplitude"),
+    ("ajs_*", "Segment"),
+    ("_pk_*", "Matomo"),
+    ("_ym_*", "Yandex Metrica"),
+    ("fs_uid", "FullStory"),
+    ("__qca", "Quantcast"),
+    // Marketing and social
+    ("hubspotutk", "HubSpot"),
+    ("__hs*", "HubSpot"),
+    ("_pin_unauth", "Pinterest"),
+    ("_pinterest_*", "Pinterest"),
+    ("bcookie", "LinkedIn"),
+    ("bscookie", "LinkedIn"),
+    ("lidc", "LinkedIn"),
+    ("li_*", "LinkedIn"),
+    ("UserMatchHistory", "LinkedIn"),
+    ("_ttp", "TikTok"),
+    ("_scid", "Snapchat"),
+    ("guest_id", "X (Twitter)"),
+    ("personalization_id", "X (Twitter)"),
+    ("cto_bundle", "Criteo"),
+    ("t_gid", "Taboola"),
+    ("optimizely*", "Optimizely"),
+    ("_vwo_*", "VWO"),
+    ("intercom-*", "Intercom"),
+    // Infrastructure and consent
+    ("__cf_bm", "Cloudflare"),
+    ("cf_clearance", "Cloudflare"),
+    ("__cfruid", "Cloudflare"),
+    ("AWSALB*", "Amazon Web Services"),
+    ("_shopify_*", "Shopify"),
+    ("__stripe_*", "Stripe"),
+    ("OptanonConsent", "OneTrust"),
+    ("OptanonAlertBoxClosed", "OneTrust"),
+    ("CookieConsent", "Cookiebot"),
+];
+
+fn attribute_vendor(name: &str) -> Option<String> {
+    COOKIE_VENDORS
+        .iter()
+        .find(|(pattern, _)| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *pattern,
+        })
+        .map(|(_, vendor)| vendor.to_string())
+}
+
+fn categorize_cookie(name: &str) -> (CookieCategory, Confidence) {
     let name_lower = name.to_lowercase();
     for (pattern, category) in COOKIE_PATTERNS {
         if name_lower.contains(pattern) {
-            return category.clone();
+            // Short fragments like "fr" or "ads" show up inside unrelated names
+            let confidence = if pattern.len() >= 4 {
+                Confidence::Medium
+            } else {
+                Confidence::Low
+            };
+            return (category.clone(), confidence);
         }
     }
-    CookieCategory::Unknown
+    (CookieCategory::Unknown, Confidence::Low)
+}
+
+fn tracker_confidence(rule: &TrackerRule, host: Option<&str>) -> Confidence {
+    if rule.generic {
+        Confidence::Low
+    } else if host.is_some_and(|host| rule.matches_host(host)) {
+        Confidence::High
+    } else {
+        Confidence::Medium
+    }
 }
 
 fn parse_cookie(cookie_str: &str) -> CookieInfo {
-    let parts: Vec<&str> = cookie_str.split(';').collect();
-    let name = parts
-        .first()
-        .and_then(|p| p.split('=').next())
-        .unwrap_or("unknown")
-        .trim()
-        .to_string();
-
-    let mut domain = None;
-    let mut secure = false;
-    let mut http_only = false;
-    let mut same_site = None;
-
-    for part in parts.iter().skip(1) {
-        let part = part.trim().to_lowercase();
-        if part.starts_with("domain=") {
-            domain = Some(part.replace("domain=", ""));
-        } else if part == "secure" {
-            secure = true;
-        } else if part == "httponly" {
-            http_only = true;
-        } else if part.starts_with("samesite=") {
-            same_site = Some(part.replace("samesite=", ""));
-        }
-    }
-
-    let category = categorize_cookie(&name);
-
-    CookieInfo {
+    // Headers the cookie crate rejects, such as ones with an empty name, still
+    // get a name and are reported without attributes
+    let parsed = cookie::Cookie::parse(cookie_str).ok();
+    let name = match &parsed {
+        Some(parsed) => parsed.name().to_string(),
+        None => cookie_str
+            .split(';')
+            .next()
+            .and_then(|p| p.split('=').next())
+            .unwrap_or("unknown")
+            .trim()
+            .to_string(),
+    };
+
+    let domain = parsed
+        .as_ref()
+        .and_then(|c| c.domain())
+        .map(str::to_lowercase);
+    let path = parsed.as_ref().and_then(|c| c.path()).map(str::to_string);
+    let expires = parsed
+        .as_ref()
+        .and_then(|c| c.expires_datetime())
+        .map(|expires| httpdate::fmt_http_date(expires.into()));
+    let max_age = parsed
+        .as_ref()
+        .and_then(|c| c.max_age())
+        .map(|max_age| max_age.whole_seconds());
+    let secure = parsed.as_ref().and_then(|c| c.secure()).unwrap_or(false);
+    let http_only = parsed.as_ref().and_then(|c| c.http_only()).unwrap_or(false);
+    let partitioned = parsed.as_ref().and_then(|c| c.partitioned()).unwrap_or(false);
+    let same_site = parsed
+        .as_ref()
+        .and_then(|c| c.same_site())
+        .map(|s| s.to_string().to_lowercase());
+    let value = match &parsed {
+        Some(parsed) => parsed.value().to_string(),
+        None => cookie_str
+            .split(';')
+            .next()
+            .and_then(|p| p.split_once('='))
+            .map_or(String::new(), |(_, v)| v.trim().to_string()),
+    };
+    let (entropy_bits, identifier) = entropy::estimate(&value);
+    // The cookie crate does not know `Priority`
+    let priority = cookie_str.split(';').skip(1).find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("priority")
+            .then(|| value.trim().to_lowercase())
+    });
+
+    let (category, pattern_confidence) = categorize_cookie(&name);
+    let vendor = attribute_vendor(&name);
+    let confidence = if vendor.is_some() {
+        Confidence::High
+    } else {
+        pattern_confidence
+    };
+
+    let mut info = CookieInfo {
         name,
+        raw_header: cookie_str.to_string(),
         domain,
+        path,
+        persistent: expires.is_some() || max_age.is_some(),
+        expires,
+        max_age,
+        lifetime_days: None,
         secure,
         http_only,
         same_site,
+        partitioned,
+        priority,
+        value_length: value.len(),
+        entropy_bits,
+        identifier,
         category,
-    }
+        vendor,
+        confidence,
+        source: CookieSource::Header,
+        set_by: None,
+    };
+    info.lifetime_days = info.lifetime().map(|l| l.as_secs() / 86_400);
+    info
 }
 
-fn detect_trackers(html: &str, base_url: &Url) -> (Vec<TrackerInfo>, Vec<String>) {
+/// Whether `domain` belongs to a different site than `base_domain`, comparing
+/// registrable domains (eTLD+1).
+fn is_third_party(domain: &str, base_domain: &str) -> bool {
+    !base_domain.is_empty() && !psl::same_site(domain, base_domain)
+}
+
+fn detect_trackers(html: &str, base_url: &Url, rules: &RuleSet) -> (Vec<TrackerInfo>, Vec<String>) {
     let mut trackers = Vec::new();
     let mut third_party = HashSet::new();
     let mut found_trackers = HashSet::new();
@@ -231,7 +1308,7 @@ fn detect_trackers(html: &str, base_url: &Url) -> (Vec<TrackerInfo>, Vec<String>
     // Check script sources
     for element in document.select(&script_selector) {
         if let Some(src) = element.value().attr("src") {
-            check_url_for_trackers(src, base_domain, &mut trackers, &mut third_party, &mut found_trackers);
+            check_url_for_trackers(src, base_domain, rules, &mut trackers, &mut third_party, &mut found_trackers);
         }
     }
 
@@ -239,31 +1316,37 @@ fn detect_trackers(html: &str, base_url: &Url) -> (Vec<TrackerInfo>, Vec<String>
     let inline_script_selector = Selector::parse("script").unwrap();
     for element in document.select(&inline_script_selector) {
         let script_content = element.inner_html();
-        check_content_for_trackers(&script_content, &mut trackers, &mut found_trackers);
+        check_content_for_trackers(&script_content, INLINE_SCRIPT_SOURCE, rules, &mut trackers, &mut found_trackers);
     }
 
     // Check images (tracking pixels)
     for element in document.select(&img_selector) {
         if let Some(src) = element.value().attr("src") {
-            check_url_for_trackers(src, base_domain, &mut trackers, &mut third_party, &mut found_trackers);
+            check_url_for_trackers(src, base_domain, rules, &mut trackers, &mut third_party, &mut found_trackers);
         }
     }
 
     // Check iframes
     for element in document.select(&iframe_selector) {
         if let Some(src) = element.value().attr("src") {
-            check_url_for_trackers(src, base_domain, &mut trackers, &mut third_party, &mut found_trackers);
+            check_url_for_trackers(src, base_domain, rules, &mut trackers, &mut third_party, &mut found_trackers);
         }
     }
 
-    // Check stylesheets and other linked resources
+    // Check stylesheets and other linked resources. Prefetched and preloaded
+    // resources are downloaded, so they are matched like scripts; connection
+    // hints are reported separately by `hints`
     for element in document.select(&link_selector) {
-        if let Some(href) = element.value().attr("href") {
-            if let Ok(url) = Url::parse(href) {
-                if let Some(domain) = url.domain() {
-                    if !domain.contains(base_domain) && !base_domain.contains(domain) {
-                        third_party.insert(domain.to_string());
-                    }
+        if let Some(url) = element.value().attr("href").and_then(|href| base_url.join(href.trim()).ok()) {
+            let rel = element.value().attr("rel").unwrap_or("").to_lowercase();
+            if rel
+                .split_whitespace()
+                .any(|token| matches!(token, "prefetch" | "preload" | "modulepreload"))
+            {
+                check_url_for_trackers(url.as_str(), base_domain, rules, &mut trackers, &mut third_party, &mut found_trackers);
+            } else if let Some(domain) = url.domain() {
+                if is_third_party(domain, base_domain) && !rules.is_ignored(domain) {
+                    third_party.insert(domain.to_string());
                 }
             }
         }
@@ -275,437 +1358,481 @@ fn detect_trackers(html: &str, base_url: &Url) -> (Vec<TrackerInfo>, Vec<String>
 fn check_url_for_trackers(
     url_str: &str,
     base_domain: &str,
+    rules: &RuleSet,
     trackers: &mut Vec<TrackerInfo>,
     third_party: &mut HashSet<String>,
     found_trackers: &mut HashSet<String>,
 ) {
     let url_lower = url_str.to_lowercase();
+    let parsed = Url::parse(url_str).ok();
+    let host = parsed.as_ref().and_then(|u| u.host_str());
+    if host.is_some_and(|host| rules.is_ignored(host)) {
+        return;
+    }
 
     // Check if it's a third-party request
-    if let Ok(url) = Url::parse(url_str) {
+    if let Some(url) = &parsed {
         if let Some(domain) = url.domain() {
-            if !domain.contains(base_domain) && !base_domain.contains(domain) {
+            if is_third_party(domain, base_domain) {
                 third_party.insert(domain.to_string());
             }
         }
     }
 
     // Check for known trackers
-    for (pattern, category, description) in TRACKER_PATTERNS {
-        if let Ok(re) = Regex::new(&format!("(?i){}", pattern)) {
-            if re.is_match(&url_lower) && !found_trackers.contains(*pattern) {
-                found_trackers.insert(pattern.to_string());
+    let mut matched_rule = false;
+    for rule in &rules.trackers {
+        if rule.matches_url(&url_lower) {
+            matched_rule = true;
+            if found_trackers.insert(rule.name.clone()) {
                 trackers.push(TrackerInfo {
-                    name: pattern.to_string(),
-                    category: category.to_string(),
-                    description: description.to_string(),
+                    name: rule.name.clone(),
+                    category: rule.category.clone(),
+                    description: rule.description.clone(),
+                    source: url_str.to_string(),
+                    confidence: tracker_confidence(rule, host),
+                    severity: rule.severity,
                 });
             }
         }
     }
+
+    // External lists catch what the rules above do not know about
+    if matched_rule {
+        return;
+    }
+    let listed = rules
+        .disconnect
+        .as_ref()
+        .zip(host)
+        .filter(|(_, host)| is_third_party(host, base_domain))
+        .and_then(|(list, host)| list.lookup(host));
+    if let Some((domain, entity)) = listed {
+        if found_trackers.insert(domain.to_string()) {
+            trackers.push(TrackerInfo {
+                name: domain.to_string(),
+                category: entity.category.to_string(),
+                description: format!("{} (Disconnect {})", entity.name, entity.disconnect_category),
+                source: url_str.to_string(),
+                confidence: Confidence::High,
+                severity: None,
+            });
+        }
+        return;
+    }
+    let Some((list, filter)) = parsed
+        .as_ref()
+        .and_then(|url| rules.filter_match(url, base_domain))
+    else {
+        return;
+    };
+    if found_trackers.insert(filter.text.clone()) {
+        trackers.push(TrackerInfo {
+            name: filter.text.clone(),
+            category: list.category.clone(),
+            description: format!("{} filter", list.title),
+            source: url_str.to_string(),
+            confidence: if filter.is_host_filter() {
+                Confidence::High
+            } else {
+                Confidence::Medium
+            },
+            severity: None,
+        });
+    }
 }
 
 fn check_content_for_trackers(
     content: &str,
+    source: &str,
+    rules: &RuleSet,
     trackers: &mut Vec<TrackerInfo>,
     found_trackers: &mut HashSet<String>,
 ) {
     let content_lower = content.to_lowercase();
 
-    for (pattern, category, description) in TRACKER_PATTERNS {
-        if let Ok(re) = Regex::new(&format!("(?i){}", pattern)) {
-            if re.is_match(&content_lower) && !found_trackers.contains(*pattern) {
-                found_trackers.insert(pattern.to_string());
-                trackers.push(TrackerInfo {
-                    name: pattern.to_string(),
-                    category: category.to_string(),
-                    description: description.to_string(),
-                });
-            }
+    for rule in &rules.trackers {
+        if rule.matches_content(&content_lower) && !found_trackers.contains(&rule.name) {
+            found_trackers.insert(rule.name.clone());
+            trackers.push(TrackerInfo {
+                name: rule.name.clone(),
+                category: rule.category.clone(),
+                description: rule.description.clone(),
+                source: source.to_string(),
+                confidence: tracker_confidence(rule, None),
+                severity: rule.severity,
+            });
         }
     }
 }
 
-async fn analyze_url(url_str: &str) -> Result<AnalysisResult> {
-    let url = Url::parse(url_str).context("Invalid URL format")?;
+/// User-Agent sent by the HTTP client and the headless browser alike
+const BROWSER_USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
 
-    // Build HTTP client with custom headers
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        USER_AGENT,
-        HeaderValue::from_static(
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
-        ),
-    );
+/// HTTP client presenting the same browser User-Agent for every request
+fn build_client() -> Result<reqwest::Client> {
+    build_client_for(&Visitor::default())
+}
 
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
+/// HTTP client presenting `visitor` on every request
+fn build_client_for(visitor: &Visitor) -> Result<reqwest::Client> {
+    let client = visitor
+        .client_builder()?
         .cookie_store(true)
-        .timeout(Duration::from_secs(30))
+        .timeout(config::timeout())
         .danger_accept_invalid_certs(false)
         .build()?;
-
-    // Make the request
-    let response = client.get(url_str).send().await?;
-
-    // Extract cookies from headers
-    let mut cookies = Vec::new();
-    for cookie in response.headers().get_all(SET_COOKIE) {
-        if let Ok(cookie_str) = cookie.to_str() {
-            cookies.push(parse_cookie(cookie_str));
-        }
-    }
-
-    // Get HTML content
-    let html = response.text().await?;
-
-    // Detect trackers
-    let (trackers, third_party_requests) = detect_trackers(&html, &url);
-
-    Ok(AnalysisResult {
-        url: url_str.to_string(),
-        cookies,
-        trackers,
-        third_party_requests,
-    })
-}
-
-fn print_header() {
-    use owo_colors::OwoColorize;
-    
-    let term = Term::stdout();
-    let _ = term.clear_screen();
-
-    // Light brown cookie color
-    let cookie = owo_colors::Rgb(210, 170, 120);
-
-    println!();
-    println!(
-        "  {} {}",
-        "🥷".color(cookie),
-        "Recon".white().bold()
-    );
-    println!(
-        "  {}",
-        "Website Cookie & Tracker Analyzer".bright_black()
-    );
-    println!();
-}
-
-fn create_spinner(message: &str) -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .tick_strings(&[
-                "⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏",
-            ])
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    pb.set_message(message.to_string());
-    pb.enable_steady_tick(Duration::from_millis(80));
-    pb
+    Ok(client)
 }
 
-fn print_divider() {
-    println!(
-        "{}",
-        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-            .bright_black()
-    );
+async fn analyze_url(url_str: &str, options: &ScanOptions) -> Result<AnalysisResult> {
+    analyze_url_with(&build_client_for(&options.visitor)?, url_str, options).await
 }
 
-fn print_section_header(title: &str) {
-    println!();
-    println!(
-        "  {}",
-        title.bright_white().bold()
-    );
-    print_divider();
-}
+/// Analyze a page using `client`, whose cookie jar may already hold cookies
+/// from earlier requests.
+async fn analyze_url_with(
+    client: &reqwest::Client,
+    url_str: &str,
+    options: &ScanOptions,
+) -> Result<AnalysisResult> {
+    let url = Url::parse(url_str).context("Invalid URL format")?;
 
-fn print_results(result: &AnalysisResult, verbose: bool) {
-    println!();
-    print_divider();
-    println!(
-        "  {} {}",
-        "Analysis Complete:".bright_blue(),
-        result.url.bright_white().bold()
-    );
-    print_divider();
+    // Make the request
+    let mut response = retry::send(options.retries, &url, || options.access.apply(client.get(url.clone()), &url, &url)).await?;
+    let mut cookies = Vec::new();
 
-    // Summary stats
-    println!();
-    println!("  ╭─────────────────────────────────────────────────────────────────────────╮");
-    println!(
-        "  │  {} {:<20} {} {:<20} {} {:<15} │",
-        "Cookies:".bright_yellow(),
-        result.cookies.len(),
-        "Trackers:".bright_red(),
-        result.trackers.len(),
-        "3rd Party:".bright_blue(),
-        result.third_party_requests.len()
-    );
-    println!("  ╰─────────────────────────────────────────────────────────────────────────╯");
+    // The client followed redirects on its own; walk them again to see each hop
+    let mut redirect_chain = Vec::new();
+    if response.url() != &url && options.site_probes.is_none() {
+        let (hops, set_on_the_way) = redirects::trace(&url, &options.visitor, &options.access).await;
+        redirect_chain = hops;
+        cookies = set_on_the_way;
+    }
+    let mut client_redirects: Vec<ClientRedirect> = Vec::new();
+    let (final_url, legacy_headers, referrer_policy, hsts_header, mut html) = loop {
+        // Extract cookies from headers, replacing those set earlier on the way
+        for cookie in response.headers().get_all(SET_COOKIE) {
+            if let Ok(cookie_str) = cookie.to_str() {
+                let cookie = parse_cookie(cookie_str);
+                cookies.retain(|c: &CookieInfo| c.name != cookie.name || c.domain != cookie.domain);
+                cookies.push(cookie);
+            }
+        }
 
-    // Privacy Score
-    let privacy_score = calculate_privacy_score(result);
-    print_privacy_score(privacy_score);
+        let legacy_headers = headers::legacy_privacy_headers(response.headers());
+        let final_url = response.url().clone();
+        let referrer_policy = response
+            .headers()
+            .get(REFERRER_POLICY)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let hsts_header = response
+            .headers()
+            .get(STRICT_TRANSPORT_SECURITY)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        // Get HTML content
+        let html = response.text().await?;
+        usage::add_bytes(html.len());
+
+        // Shell pages that send the browser on are followed to the real page
+        let redirect = (client_redirects.len() < options.max_client_redirects)
+            .then(|| client_redirect::find(&html, &final_url))
+            .flatten()
+            .filter(|r| !client_redirects.iter().any(|seen| seen.from == r.to));
+        let Some(redirect) = redirect else {
+            break (final_url, legacy_headers, referrer_policy, hsts_header, html);
+        };
+        let target = Url::parse(&redirect.to)?;
+        response = retry::send(options.retries, &target, || options.access.apply(client.get(target.clone()), &target, &url))
+            .await
+            .with_context(|| format!("Failed to follow {} to {}", redirect.kind.as_str(), target))?;
+        client_redirects.push(redirect);
+    };
 
-    // Cookies section
-    print_section_header("COOKIES DETECTED");
-    
-    if result.cookies.is_empty() {
-        println!("  {} No cookies detected on initial page load", "[OK]".green());
+    // A second visit with the first one's cookies, before anything else adds to the jar
+    let cookie_refresh = if options.probe_refresh {
+        Some(refresh::probe(client, &final_url, &options.access, &cookies).await?)
     } else {
-        // Group cookies by category
-        let mut essential = Vec::new();
-        let mut analytics = Vec::new();
-        let mut marketing = Vec::new();
-        let mut social = Vec::new();
-        let mut unknown = Vec::new();
-
-        for cookie in &result.cookies {
-            match cookie.category {
-                CookieCategory::Essential => essential.push(cookie),
-                CookieCategory::Analytics => analytics.push(cookie),
-                CookieCategory::Marketing => marketing.push(cookie),
-                CookieCategory::Social => social.push(cookie),
-                CookieCategory::Unknown => unknown.push(cookie),
+        None
+    };
+    let content_hash = fingerprint::content_hash(&html);
+    let mut storage = Vec::new();
+    let mut consent_banner = None;
+
+    let mut static_fallback = None;
+    let mut consent_diff = None;
+    let mut consent_diff_error = None;
+    let mut opened_websockets = Vec::new();
+
+    // Let a real browser run the page's scripts, then read its DOM and cookie jar.
+    // Without one (no Chrome, sandbox restrictions) the static analysis still runs.
+    if options.headless {
+        throttle::wait(&final_url).await;
+        match headless::render(final_url.as_str(), options.evidence_dir.as_deref(), &options.access, &options.visitor).await {
+            Ok(rendered) => {
+                html = rendered.html;
+                storage = rendered.storage;
+                consent_banner = rendered.banner;
+                opened_websockets = rendered.websockets;
+                // A header cookie without a Domain attribute belongs to the page's host,
+                // where the browser files it
+                let host = final_url.host_str().unwrap_or_default();
+                for cookie in rendered.cookies {
+                    if !cookies.iter().any(|c: &CookieInfo| {
+                        c.name == cookie.name && c.domain.as_deref().unwrap_or(host) == cookie.domain.as_deref().unwrap_or(host)
+                    }) {
+                        cookies.push(cookie);
+                    }
+                }
+                // The comparison needs more browser sessions; if they fail, keep the rest of the scan
+                if options.consent_diff {
+                    match consent::compare(final_url.as_str(), &options.rules, &options.access, &options.visitor).await {
+                        Ok(diff) => consent_diff = Some(diff),
+                        Err(e) => consent_diff_error = Some(format!("{:#}", e)),
+                    }
+                }
+            }
+            Err(e) => {
+                static_fallback = Some(StaticFallback::new(
+                    &e,
+                    options.evidence_dir.is_some(),
+                    options.consent_diff,
+                ));
             }
         }
-
-        print_cookie_category("Essential", &essential, "green", verbose);
-        print_cookie_category("Analytics", &analytics, "yellow", verbose);
-        print_cookie_category("Marketing", &marketing, "red", verbose);
-        print_cookie_category("Social", &social, "blue", verbose);
-        print_cookie_category("Unknown", &unknown, "white", verbose);
     }
 
-    // Trackers section
-    print_section_header("TRACKERS DETECTED");
-    
-    if result.trackers.is_empty() {
-        println!("  {} No known trackers detected", "[OK]".green());
-    } else {
-        for tracker in &result.trackers {
-            let category_color = match tracker.category.as_str() {
-                "Analytics" => "yellow",
-                "Marketing" => "red",
-                "Social" => "blue",
-                _ => "white",
-            };
-            
-            let prefix = match tracker.category.as_str() {
-                "Analytics" => "[ANALYTICS]",
-                "Marketing" => "[MARKETING]",
-                "Social" => "[SOCIAL]",
-                "Security" => "[SECURITY]",
-                "CDN/Security" => "[CDN]",
-                "Error Tracking" => "[ERROR]",
-                "Customer Support" => "[SUPPORT]",
-                "A/B Testing" => "[A/B TEST]",
-                "Marketing/CRM" => "[CRM]",
-                _ => "[OTHER]",
-            };
-
-            let colored_prefix = match category_color {
-                "yellow" => prefix.yellow().to_string(),
-                "red" => prefix.red().to_string(),
-                "blue" => prefix.blue().to_string(),
-                _ => prefix.white().to_string(),
-            };
-
-            if verbose {
-                println!(
-                    "  {} {}",
-                    colored_prefix,
-                    tracker.name.bright_white(),
-                );
-                println!(
-                    "       {} {}",
-                    "Description:".bright_black(),
-                    tracker.description.cyan()
-                );
-                let privacy_impact = match tracker.category.as_str() {
-                    "Marketing" | "Marketing/CRM" => "High - Tracks users across websites for advertising",
-                    "Analytics" => "Medium - Collects usage data and behavior patterns",
-                    "Social" => "Medium - May share data with social networks",
-                    "A/B Testing" => "Low - Used for page optimization experiments",
-                    "Security" | "CDN/Security" => "Low - Used for site protection",
-                    "Error Tracking" => "Low - Collects error reports for debugging",
-                    "Customer Support" => "Low - Enables support chat functionality",
-                    _ => "Unknown - Impact could not be determined",
-                };
-                println!(
-                    "       {} {}",
-                    "Privacy Impact:".bright_black(),
-                    privacy_impact.bright_black()
-                );
-                println!();
-            } else {
-                println!(
-                    "  {} {} - {}",
-                    colored_prefix,
-                    tracker.name.bright_white(),
-                    tracker.description.bright_black()
-                );
-            }
+    // Subresource cookies and script bodies are fetched together, sharing one budget
+    let budget = FetchBudget::new(options.max_concurrent_fetches, options.fetch_budget_bytes);
+    let resource_cookies = async {
+        if options.fetch_resources {
+            let resources = subresources::resource_urls(&html, &final_url);
+            subresources::collect_cookies(resources, &budget, &options.visitor, &options.access, &url).await
+        } else {
+            Ok(Vec::new())
+        }
+    };
+    let script_bodies = async {
+        if options.fetch_scripts {
+            let script_urls = scripts::script_urls(&html, &final_url);
+            scripts::fetch_scripts(client, script_urls, options.max_script_bytes, &budget, &options.access, &url).await
+        } else {
+            Vec::new()
+        }
+    };
+    let (resource_cookies, fetched) = tokio::join!(resource_cookies, script_bodies);
+
+    // Cookies set by third-party scripts, pixels and iframes
+    for cookie in resource_cookies? {
+        if !cookies
+            .iter()
+            .any(|c| c.name == cookie.name && c.domain == cookie.domain)
+        {
+            cookies.push(cookie);
         }
     }
 
-    // Third-party domains section
-    print_section_header("THIRD-PARTY DOMAINS");
-    
-    if result.third_party_requests.is_empty() {
-        println!("  {} No third-party domains detected", "[OK]".green());
-    } else {
-        let display_limit = if verbose { result.third_party_requests.len() } else { 15 };
-        for (i, domain) in result.third_party_requests.iter().take(display_limit).enumerate() {
-            println!("  {}. {}", i + 1, domain.bright_cyan());
-            if verbose {
-                // Categorize the third-party domain
-                let domain_lower = domain.to_lowercase();
-                let domain_type = if domain_lower.contains("google") || domain_lower.contains("gstatic") {
-                    ("Google Services", "Analytics, fonts, APIs, or advertising")
-                } else if domain_lower.contains("facebook") || domain_lower.contains("fbcdn") {
-                    ("Facebook/Meta", "Social plugins or tracking")
-                } else if domain_lower.contains("cloudflare") {
-                    ("Cloudflare", "CDN and security services")
-                } else if domain_lower.contains("cdn") || domain_lower.contains("akamai") || domain_lower.contains("fastly") {
-                    ("CDN", "Content delivery network")
-                } else if domain_lower.contains("analytics") || domain_lower.contains("tracking") {
-                    ("Analytics", "User tracking and analytics")
-                } else if domain_lower.contains("ads") || domain_lower.contains("doubleclick") {
-                    ("Advertising", "Ad serving and tracking")
-                } else if domain_lower.contains("twitter") || domain_lower.contains("linkedin") {
-                    ("Social Media", "Social network integration")
-                } else if domain_lower.contains("stripe") || domain_lower.contains("paypal") {
-                    ("Payment", "Payment processing")
-                } else if domain_lower.contains("sentry") || domain_lower.contains("bugsnag") {
-                    ("Error Tracking", "Error monitoring service")
-                } else {
-                    ("External", "Third-party resource")
-                };
-                println!(
-                    "      {} {} - {}",
-                    "Type:".bright_black(),
-                    domain_type.0.yellow(),
-                    domain_type.1.bright_black()
-                );
-            }
+    // Detect trackers
+    let (mut trackers, mut third_party_requests) = detect_trackers(&html, &final_url, &options.rules);
+
+    let mut referrer = referrer::analyze(&final_url, referrer_policy.as_deref(), &html);
+    referrer
+        .exposures
+        .retain(|e| !options.rules.is_ignored(&e.domain));
+    let integrity = integrity::analyze(&final_url, &html);
+
+    // Follow external scripts one level down for trackers they pull in
+    let mut script_dependencies = Vec::new();
+    let script_inventory = options
+        .fetch_scripts
+        .then(|| inventory::first_party(&fetched, &final_url, options.max_script_bytes));
+    if options.fetch_scripts {
+        let base_domain = final_url.domain().unwrap_or("");
+        script_dependencies = scripts::extract_dependencies(&fetched, base_domain, &options.rules);
+
+        let mut third_party: HashSet<String> = third_party_requests.drain(..).collect();
+        let mut found_trackers: HashSet<String> = trackers.iter().map(|t| t.name.clone()).collect();
+        for dependency in &script_dependencies {
+            check_url_for_trackers(
+                &dependency.url,
+                base_domain,
+                &options.rules,
+                &mut trackers,
+                &mut third_party,
+                &mut found_trackers,
+            );
         }
-        if !verbose && result.third_party_requests.len() > 15 {
-            println!(
-                "  ... and {} more",
-                (result.third_party_requests.len() - 15).to_string().bright_yellow()
+        // Bundlers inline tracking SDKs into opaque app bundles
+        for (script_url, body) in &fetched {
+            check_content_for_trackers(
+                body,
+                script_url.as_str(),
+                &options.rules,
+                &mut trackers,
+                &mut found_trackers,
             );
         }
+        third_party_requests = third_party.into_iter().collect();
     }
 
-    println!();
-    print_divider();
-    if verbose {
-        println!(
-            "  {} {}",
-            "Verbose mode:".bright_green(),
-            "Showing detailed information for all items".bright_black()
-        );
-    } else {
-        println!(
-            "  {} {}",
-            "Tip:".bright_yellow(),
-            "Use -v for detailed cookie, tracker, and domain information".bright_black()
-        );
+    // Chat widgets and analytics that talk over WebSockets load nothing else
+    let websockets = websockets::find(&html, &final_url, &fetched, &opened_websockets, &options.rules);
+    for endpoint in websockets.iter().filter(|e| e.third_party) {
+        if !third_party_requests.contains(&endpoint.domain) {
+            third_party_requests.push(endpoint.domain.clone());
+        }
     }
-    print_divider();
-    println!();
-}
 
-fn print_cookie_category(name: &str, cookies: &[&CookieInfo], color: &str, verbose: bool) {
-    if cookies.is_empty() {
-        return;
+    options
+        .cookie_rules
+        .apply(final_url.host_str().unwrap_or(""), &mut cookies, &mut storage);
+
+    // Drop noisy matches the caller asked us to ignore
+    cookies.retain(|c| {
+        !c.domain
+            .as_deref()
+            .is_some_and(|d| options.rules.is_ignored(d.trim_start_matches('.')))
+    });
+    trackers.retain(|t| t.confidence >= options.min_confidence);
+    for cookie in &mut cookies {
+        if cookie.confidence < options.min_confidence {
+            cookie.category = CookieCategory::Unknown;
+            cookie.vendor = None;
+        }
+    }
+    for item in &mut storage {
+        if item.confidence < options.min_confidence {
+            item.category = CookieCategory::Unknown;
+            item.vendor = None;
+        }
     }
 
-    let count_str = format!("({} cookies)", cookies.len());
-    let header = match color {
-        "green" => format!("  ├─ {} {}", name.green(), count_str.bright_black()),
-        "yellow" => format!("  ├─ {} {}", name.yellow(), count_str.bright_black()),
-        "red" => format!("  ├─ {} {}", name.red(), count_str.bright_black()),
-        "blue" => format!("  ├─ {} {}", name.blue(), count_str.bright_black()),
-        _ => format!("  ├─ {} {}", name.white(), count_str.bright_black()),
+    let cookie_prefixes = prefixes::check(&cookies);
+    let cookie_sizes = cookie_size::measure(
+        &cookies,
+        final_url.host_str().unwrap_or(""),
+        config::cookie_budget(),
+    );
+    let pre_consent = preconsent::detect(&cookies, &storage, &trackers);
+    let skimmers = skimmer::scan(&html, &fetched);
+    let suspected_pixels = pixels::suspect(&html, &final_url, &options.rules);
+    let beacons = beacons::find(&html, &final_url, &fetched, &options.rules);
+    let resource_hints = hints::find(&html, &final_url, &options.rules);
+    let remote_fonts = fonts::detect(&html, &final_url, &fetched, &options.rules);
+    let session_replay = replay::detect(&third_party_requests, &trackers, &cookies);
+
+    let dependency_urls: Vec<String> = script_dependencies.iter().map(|d| d.url.clone()).collect();
+    let pii_leaks = pii::scan(&html, &final_url, &dependency_urls);
+    let mut data_flows = dataflow::infer(
+        &html,
+        &final_url,
+        &third_party_requests,
+        &cookies,
+        &referrer,
+        &pii_leaks,
+        &dependency_urls,
+    );
+    data_flows.retain(|flow| !options.rules.is_ignored(&flow.recipient));
+    let internal_links = crawl::internal_links(&html, &final_url);
+    let related_set = match &options.site_probes {
+        Some(probes) => probes.related_set.clone(),
+        None => related_sets::discover(client, &final_url).await,
+    };
+    let cmp_declarations = match options.site_probes.as_ref().and_then(|p| p.cmp_declarations.clone()) {
+        Some(declarations) => Some(declarations),
+        None => cmp::declarations(client, &html, &final_url).await.map(Arc::new),
     };
-    println!("{}", header);
+    let cmp_check = cmp_declarations.as_deref().map(|d| cmp::cross_check(d, &cookies));
+    let tcf = tcf::detect(&html, &cookies);
+    let cookie_policy = match &options.cookie_policy {
+        Some(policy_url) => Some(
+            cookie_policy::compare(client, &html, &final_url, policy_url.as_ref(), &cookies)
+                .await,
+        ),
+        None => None,
+    };
+    let probe_site = options.site_probes.is_none();
+    let well_known = match &options.well_known_paths {
+        Some(paths) if probe_site => Some(wellknown::probe(client, &final_url, paths).await),
+        _ => None,
+    };
+    let tls = if probe_site { tls::inspect(&final_url).await } else { None };
+    let https = if probe_site {
+        https::assess(&final_url, hsts_header.as_deref(), &options.visitor).await
+    } else {
+        None
+    };
+    let cookie_readiness = options
+        .cookie_readiness
+        .then(|| deprecation::assess(&cookies, &final_url, related_set.as_ref()));
 
-    for cookie in cookies {
-        println!(
-            "  │   • {}",
-            cookie.name.bright_white()
-        );
-        
-        if verbose {
-            // Show domain
-            if let Some(ref domain) = cookie.domain {
-                println!(
-                    "  │       {} {}",
-                    "Domain:".bright_black(),
-                    domain.cyan()
-                );
-            }
-            
-            // Show security attributes
-            let secure_status = if cookie.secure {
-                "Yes".green().to_string()
-            } else {
-                "No".red().to_string()
-            };
-            println!(
-                "  │       {} {}",
-                "Secure:".bright_black(),
-                secure_status
-            );
-            
-            let http_only_status = if cookie.http_only {
-                "Yes".green().to_string()
-            } else {
-                "No".yellow().to_string()
-            };
-            println!(
-                "  │       {} {}",
-                "HttpOnly:".bright_black(),
-                http_only_status
-            );
-            
-            // Show SameSite
-            let same_site_value = cookie.same_site.as_ref().map(|s| s.as_str()).unwrap_or("not set");
-            let same_site_colored = match same_site_value.to_lowercase().as_str() {
-                "strict" => same_site_value.green().to_string(),
-                "lax" => same_site_value.yellow().to_string(),
-                "none" => same_site_value.red().to_string(),
-                _ => same_site_value.bright_black().to_string(),
-            };
-            println!(
-                "  │       {} {}",
-                "SameSite:".bright_black(),
-                same_site_colored
-            );
-            
-            // Show category explanation
-            let category_desc = match cookie.category {
-                CookieCategory::Essential => "Required for basic site functionality",
-                CookieCategory::Analytics => "Used to track user behavior and site performance",
-                CookieCategory::Marketing => "Used for advertising and tracking across sites",
-                CookieCategory::Social => "Related to social media integrations",
-                CookieCategory::Unknown => "Purpose could not be determined",
-            };
-            println!(
-                "  │       {} {}",
-                "Purpose:".bright_black(),
-                category_desc.bright_black()
-            );
-            println!("  │");
-        }
+    let mut result = AnalysisResult {
+        url: url_str.to_string(),
+        pages: vec![url_str.to_string()],
+        robots_disallowed: Vec::new(),
+        client_redirects,
+        redirect_chain,
+        content_hash,
+        fetch_budget: (options.fetch_scripts || options.fetch_resources).then(|| budget.usage()),
+        resource_usage: None,
+        cookies,
+        cookie_prefixes,
+        cookie_sizes,
+        trackers,
+        suspected_pixels,
+        beacons,
+        resource_hints,
+        websockets,
+        remote_fonts,
+        third_party_requests,
+        script_dependencies,
+        script_inventory,
+        integrity,
+        legacy_headers,
+        referrer,
+        pii_leaks,
+        data_flows,
+        pre_consent,
+        skimmers,
+        session_replay,
+        static_fallback,
+        consent_banner,
+        consent_diff,
+        consent_diff_error,
+        cmp_check,
+        tcf,
+        cookie_policy,
+        storage,
+        related_set,
+        well_known,
+        tls,
+        https,
+        cookie_refresh,
+        cookie_readiness,
+        device_comparison: None,
+        locale_comparison: None,
+        signal_tests: Vec::new(),
+        sampling: None,
+        compliance: None,
+        internal_links,
+        cmp_declarations,
+        html,
+    };
+    if let Some(framework) = options.compliance {
+        result.compliance = Some(compliance::assess(
+            framework,
+            options.profile,
+            &result,
+            &result.html,
+            &final_url,
+            options,
+        ));
     }
+    Ok(result)
 }
 
 fn calculate_privacy_score(result: &AnalysisResult) -> u32 {
@@ -717,6 +1844,18 @@ fn calculate_privacy_score(result: &AnalysisResult) -> u32 {
     // Extra deduction for marketing/tracking cookies
     for cookie in &result.cookies {
         match cookie.category {
+            CookieCategory::Marketing => score -= 5,
+            CookieCategory::Analytics => score -= 3,
+            CookieCategory::Social => score -= 2,
+            // An unrecognized cookie holding a unique ID can track like an analytics one
+            CookieCategory::Unknown if cookie.identifier => score -= 3,
+            _ => {}
+        }
+    }
+
+    // Identifiers kept in Web Storage count like the equivalent cookies
+    for item in &result.storage {
+        match item.category {
             CookieCategory::Marketing => score -= 5,
             CookieCategory::Analytics => score -= 3,
             CookieCategory::Social => score -= 2,
@@ -728,97 +1867,667 @@ fn calculate_privacy_score(result: &AnalysisResult) -> u32 {
     score -= (result.trackers.len() as i32) * 5;
 
     // Deduct for third-party domains
-    score -= (result.third_party_requests.len() as i32) * 1;
+    score -= result.third_party_requests.len() as i32;
+
+    // Recording every visit goes well beyond an analytics hit
+    score -= (result.session_replay.len() as i32) * 10;
+
+    // Plain HTTP exposes cookies and page views to anyone on the network path
+    if let Some(https) = &result.https {
+        if https.serves_plain_http() {
+            score -= 10;
+        }
+        if !https.has_hsts() {
+            score -= 5;
+        }
+    }
+
+    // A skimmer outweighs everything else on the page
+    score -= (result.skimmers.len() as i32) * 50;
 
-    score.max(0).min(100) as u32
+    score.clamp(0, 100) as u32
 }
 
-fn print_privacy_score(score: u32) {
-    println!();
-    let (color, label) = match score {
-        90..=100 => ("green", "EXCELLENT"),
-        70..=89 => ("yellow", "GOOD"),
-        50..=69 => ("yellow", "MODERATE"),
-        25..=49 => ("red", "POOR"),
-        _ => ("red", "CRITICAL"),
-    };
+fn privacy_rating(score: u32) -> &'static str {
+    let thresholds = config::rating_thresholds();
+    match score {
+        s if s >= thresholds.excellent => "Excellent",
+        s if s >= thresholds.good => "Good",
+        s if s >= thresholds.moderate => "Moderate",
+        s if s >= thresholds.poor => "Poor",
+        _ => "Critical",
+    }
+}
+
+fn normalize_url(raw: &str) -> String {
+    if !raw.starts_with("http://") && !raw.starts_with("https://") {
+        format!("https://{}", raw)
+    } else {
+        raw.to_string()
+    }
+}
 
-    let bar_width = 40;
-    let filled = (score as usize * bar_width) / 100;
-    let empty = bar_width - filled;
+/// The start page followed by an even sample of the site's sitemap entries.
+async fn sitemap_pages(start: &str, max_pages: usize, access: &SiteAccess) -> Result<Vec<String>> {
+    let start_url = Url::parse(start).context("Invalid URL format")?;
+    let listed: Vec<String> = sitemap::page_urls(&build_client()?, &start_url, access)
+        .await?
+        .into_iter()
+        .filter(|page| Url::parse(page).map_or(true, |url| url != start_url))
+        .collect();
+
+    let mut pages = vec![start.to_string()];
+    pages.extend(sitemap::sample(listed, max_pages.saturating_sub(1)));
+    Ok(pages)
+}
 
-    let bar = format!(
-        "{}{}",
-        "█".repeat(filled),
-        "░".repeat(empty)
+async fn run_browser_command(command: &BrowserCommand) -> Result<()> {
+    match command {
+        BrowserCommand::Install { version } => {
+            let installed = browser::install(version.as_deref()).await?;
+            let reported = browser::verify(&installed.executable)?;
+            println!(
+                "  {} {} ({})",
+                "Installed:".bright_green(),
+                installed.executable.display().to_string().bright_white(),
+                reported
+            );
+            println!("  --headless will use this browser unless CHROME is set.");
+        }
+        BrowserCommand::Status => {
+            if std::env::var_os("CHROME").is_some() {
+                println!("  {} CHROME environment variable", "Source:".bright_black());
+            } else if browser::installed().is_some() {
+                println!("  {} managed install", "Source:".bright_black());
+            }
+            match browser::executable() {
+                Some(executable) => {
+                    println!(
+                        "  {} {}",
+                        "Browser:".bright_black(),
+                        executable.display().to_string().bright_white()
+                    );
+                    let reported = browser::verify(&executable)?;
+                    println!("  {} {}", "[OK]".green(), reported);
+                }
+                None => println!(
+                    "  {} No managed browser; --headless will look for a system Chrome. Run `recon browser install` to download one.",
+                    "[?]".yellow()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_capabilities(format: capabilities::CapabilitiesFormat) -> Result<()> {
+    let caps = capabilities::detect();
+    match format {
+        capabilities::CapabilitiesFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&caps)?)
+        }
+        capabilities::CapabilitiesFormat::Yaml => print!("{}", serde_yaml::to_string(&caps)?),
+        capabilities::CapabilitiesFormat::Text => {
+            println!("  {} {}", caps.tool.bright_white(), caps.version);
+            let browser = &caps.browser;
+            match (&browser.path, &browser.version) {
+                (Some(path), Some(version)) => println!(
+                    "  {} {} {} ({}, {})",
+                    "[OK]".green(),
+                    "Browser".bright_white(),
+                    version,
+                    browser.source.unwrap_or_default(),
+                    path.display()
+                ),
+                (Some(path), None) => println!(
+                    "  {} {} {} does not start",
+                    "[FAIL]".red(),
+                    "Browser".bright_white(),
+                    path.display()
+                ),
+                _ => println!(
+                    "  {} {} none found; --headless is unavailable",
+                    "[--]".bright_black(),
+                    "Browser".bright_white()
+                ),
+            }
+            println!(
+                "  {} {} not available",
+                "[--]".bright_black(),
+                "DNS enrichment".bright_white()
+            );
+            for db in &caps.databases {
+                let marker = match (db.source, db.stale) {
+                    ("cached", false) => "[OK]".green().to_string(),
+                    ("cached", true) => "[OLD]".yellow().to_string(),
+                    ("bundled", _) => "[OK]".green().to_string(),
+                    _ => "[--]".bright_black().to_string(),
+                };
+                match &db.version {
+                    Some(version) => {
+                        println!("  {} {} {} ({})", marker, db.name.bright_white(), version, db.source)
+                    }
+                    None => println!("  {} {} {}", marker, db.name.bright_white(), db.source),
+                }
+            }
+            println!("  {} {}", "Tracker rules:".bright_black(), caps.tracker_rules);
+            println!(
+                "  {} {}",
+                "Output formats:".bright_black(),
+                caps.output_formats.join(", ")
+            );
+            println!(
+                "  {} {}",
+                "Compliance:".bright_black(),
+                caps.compliance_frameworks.join(", ")
+            );
+            println!("  {} {}", "Profiles:".bright_black(), caps.profiles.join(", "));
+        }
+    }
+    Ok(())
+}
+
+async fn run_update_db() -> Result<()> {
+    databases::update(print_database_update).await?;
+    println!(
+        "  {} {}",
+        "Databases stored in".bright_black(),
+        databases::cache_dir()?.display().to_string().bright_cyan()
     );
+    Ok(())
+}
 
-    println!("  ╭─────────────────────────────────────────────────────────────────────────╮");
-    
-    let colored_label = match color {
-        "green" => label.green().to_string(),
-        "yellow" => label.yellow().to_string(),
-        "red" => label.red().to_string(),
-        _ => label.white().to_string(),
+/// Refresh the databases from `update-db` once they are stale, and say
+/// whether anything new was downloaded. A failed refresh keeps the cached
+/// copies and is reported on stderr, whatever the output format.
+async fn refresh_stale_databases(format: OutputFormat) -> bool {
+    if !databases::Manifest::load().is_stale() {
+        return false;
+    }
+    let result = if format == OutputFormat::Text {
+        println!("  Refreshing tracker databases (skip with --offline)...");
+        databases::update(print_database_update).await
+    } else {
+        databases::update(|source, result| {
+            if let Err(e) = result {
+                eprintln!("  {} {} {} ({})", "[FAIL]".red(), source.name, e, e.root_cause());
+            }
+        })
+        .await
     };
+    if let Err(e) = &result {
+        eprintln!(
+            "  {} {:#}; scanning with the cached databases",
+            "[WARN]".yellow(),
+            e
+        );
+    }
+    result.is_ok()
+}
+
+fn print_database_update(source: &databases::Source, result: &Result<databases::CachedDatabase>) {
+    match result {
+        Ok(db) => println!(
+            "  {} {} {} ({} KB)",
+            "[OK]".green(),
+            source.name.bright_white(),
+            db.version,
+            db.bytes / 1024
+        ),
+        Err(e) => println!(
+            "  {} {} {} ({})",
+            "[FAIL]".red(),
+            source.name.bright_white(),
+            e,
+            e.root_cause()
+        ),
+    }
+}
 
-    let colored_bar = match color {
-        "green" => bar.green().to_string(),
-        "yellow" => bar.yellow().to_string(),
-        "red" => bar.red().to_string(),
-        _ => bar.white().to_string(),
+fn run_history_command(args: &HistoryArgs) -> Result<()> {
+    let Some(command) = &args.command else {
+        let url = normalize_url(args.url.as_deref().unwrap_or_default());
+        let points = History::open(args.db.as_deref())?.trend(&url, args.limit)?;
+        match args.format {
+            TrendFormat::Text => terminal::print_trend(&url, &points),
+            TrendFormat::Json => println!("{}", serde_json::to_string_pretty(&points)?),
+        }
+        return Ok(());
     };
-    
-    println!(
-        "  │  PRIVACY SCORE: {}/100 - {}",
-        score,
-        colored_label
-    );
-    println!("  │  [{}]", colored_bar);
-    println!("  ╰─────────────────────────────────────────────────────────────────────────╯");
+    match command {
+        HistoryCommand::Prune { keep, db } => {
+            let history = History::open(db.as_deref())?;
+            let removed = history.prune((*keep).into())?;
+            println!("  {} {} scan(s)", "Pruned".bright_green(), removed);
+        }
+        HistoryCommand::Export { file, db } => {
+            let history = History::open(db.as_deref())?;
+            let out = std::fs::File::create(file)
+                .with_context(|| format!("Failed to create {}", file.display()))?;
+            let count = history.export(&mut std::io::BufWriter::new(out))?;
+            println!(
+                "  {} {} scan(s) to {}",
+                "Exported".bright_green(),
+                count,
+                file.display().to_string().bright_cyan()
+            );
+        }
+        HistoryCommand::Import { file, db } => {
+            let mut history = History::open(db.as_deref())?;
+            let input = std::fs::File::open(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let (imported, skipped) = history.import(std::io::BufReader::new(input))?;
+            println!(
+                "  {} {} scan(s), skipped {} already present",
+                "Imported".bright_green(),
+                imported,
+                skipped
+            );
+        }
+    }
+    Ok(())
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+async fn main() -> Result<ExitCode> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches)?;
+    // `watch` scans with its own copy of the options
+    let (command, mut args, matches, watch) = match cli.command {
+        Some(Command::Watch(watch)) => {
+            let watch = *watch;
+            let matches = matches.subcommand_matches("watch").unwrap().clone();
+            let schedule = Schedule {
+                interval: watch.interval.into(),
+                metrics_addr: watch.metrics_addr,
+            };
+            (None, watch.scan, matches, Some(schedule))
+        }
+        command => (command, cli.args, matches, None),
+    };
+    let config_path = match (&args.config, args.no_config) {
+        (_, true) => None,
+        (Some(path), false) => Some(path.clone()),
+        (None, false) => config::discover(),
+    };
+    let mut config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    config.apply(&mut args, &matches);
+    if let Some(preset) = args.preset {
+        preset.apply(&mut args, &matches);
+    }
+    let user_agent = args
+        .user_agent
+        .clone()
+        .or_else(|| args.ua.map(|preset| preset.user_agent().to_string()));
+    if let Some(agent) = user_agent {
+        config.set_user_agent(agent)?;
+    }
+    config.activate();
+    if let Some(Command::Browser(command)) = &command {
+        return run_browser_command(command).await.map(|()| ExitCode::SUCCESS);
+    }
+    if let Some(Command::Capabilities { format }) = &command {
+        return run_capabilities(*format).map(|()| ExitCode::SUCCESS);
+    }
+    if let Some(Command::History(command)) = &command {
+        return run_history_command(command).map(|()| ExitCode::SUCCESS);
+    }
+    if let Some(Command::UpdateDb) = &command {
+        return run_update_db().await.map(|()| ExitCode::SUCCESS);
+    }
+    if !args.offline {
+        refresh_stale_databases(args.format).await;
+    }
+    let mut options = ScanOptions::from_args(&args)?;
+    if let Some(Command::Serve(serve)) = &command {
+        let serve = serve::ServeOptions {
+            bind: serve.bind,
+            port: serve.port,
+            workers: serve.workers.into(),
+            queue: serve.queue.into(),
+        };
+        return serve::run(&serve, options).await.map(|()| ExitCode::SUCCESS);
+    }
+    let interval = match (args.rate, args.delay) {
+        (Some(rate), _) => match Duration::try_from_secs_f64(1.0 / rate) {
+            Ok(interval) if rate > 0.0 => interval,
+            _ => bail!("--rate must be a positive number of requests per second"),
+        },
+        (None, Some(delay)) => delay.into(),
+        (None, None) => Duration::ZERO,
+    };
+    throttle::configure(interval);
+    let signals: Vec<Signal> = [(args.test_gpc, Signal::Gpc), (args.test_dnt, Signal::Dnt)]
+        .into_iter()
+        .filter_map(|(enabled, signal)| enabled.then_some(signal))
+        .collect();
+    let locales = if args.compare_locales.is_empty() {
+        Vec::new()
+    } else {
+        locales::parse(&args.compare_locales, &args.locale_proxy)?
+    };
+    let crawl_options = CrawlOptions {
+        max_depth: args.max_depth,
+        max_pages: args.max_pages,
+        respect_robots: !args.ignore_robots,
+    };
+    let budget_path = args.budget.clone().or_else(privacy_budget::discover);
+    let gate = Gate {
+        min_score: args.fail_on_score,
+        conditions: args.fail_on.clone(),
+        budget: budget_path.as_deref().map(PrivacyBudget::load).transpose()?,
+    };
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+    if gate.conditions.contains(&FailOn::NewFindings) && baseline.is_none() {
+        bail!("--fail-on new-findings needs a --baseline to compare with");
+    }
+    if args.webhook.is_none()
+        && (args.notify_on != NotifyOn::Always || args.webhook_format != WebhookFormat::Json)
+    {
+        bail!("--notify-on and --webhook-format need a --webhook to post to");
+    }
+    if args.notify_on == NotifyOn::Change && !args.history && watch.is_none() {
+        bail!("--notify-on change needs --history or the watch command to compare with");
+    }
+    if args.triage && watch.is_some() {
+        bail!("--triage cannot be used with the watch command, which never finishes");
+    }
+    let text_output = args.format == OutputFormat::Text;
 
-    print_header();
+    if text_output {
+        terminal::print_header();
+        if let Some(path) = &config_path {
+            println!("  {} {}", "Config:".bright_black(), path.display());
+        }
+        if let Some(path) = &budget_path {
+            println!("  {} {}", "Privacy budget:".bright_black(), path.display());
+        }
+        if let Some(preset) = args.preset {
+            println!("  {} {}", "Preset:".bright_black(), preset.as_str());
+        }
+        if config::user_agent() != BROWSER_USER_AGENT {
+            println!("  {} {}", "User-Agent:".bright_black(), config::user_agent());
+        }
+        for list in &options.rules.filter_lists {
+            println!(
+                "  {} {} ({} filters)",
+                "Filter list:".bright_black(),
+                list.title,
+                list.filter_count
+            );
+        }
+        if let Some(list) = &options.rules.disconnect {
+            println!(
+                "  {} {} domains",
+                "Disconnect list:".bright_black(),
+                list.domain_count()
+            );
+        }
+    }
 
-    // Normalize URL
-    let url = if !args.url.starts_with("http://") && !args.url.starts_with("https://") {
-        format!("https://{}", args.url)
+    let mut urls = args.urls.clone();
+    if let Some(path) = &args.input {
+        urls.extend(batch::read_url_list(path)?);
+    }
+
+    // Open the database up front so a bad path fails before scanning
+    let history = if args.history || watch.is_some() {
+        Some(History::open(args.history_db.as_deref())?)
     } else {
-        args.url.clone()
+        None
     };
 
-    println!("  {} {}", "Analyzing:".bright_green(), url.bright_cyan());
-    println!();
-
-    let spinner = create_spinner("Analyzing website...");
+    let mut sinks: Vec<Box<dyn OutputSink + '_>> = vec![Box::new(sink::Stdout {
+        format: args.format,
+        verbose: args.verbose,
+        paging: terminal::Paging::new(
+            args.limit.map(usize::from),
+            usize::from(args.page),
+            args.verbose,
+        ),
+        baseline: baseline.as_ref(),
+    })];
+    if let Some(path) = &args.save_baseline {
+        sinks.push(Box::new(sink::SaveBaseline(path.clone())));
+    }
+    if let Some(path) = &args.report_pdf {
+        sinks.push(Box::new(sink::PdfReport(path.clone())));
+    }
+    if let Some(path) = &args.executive_summary {
+        sinks.push(Box::new(sink::ExecutiveSummary(path.clone())));
+    }
+    if let Some(path) = &args.annotate {
+        sinks.push(Box::new(sink::AnnotatedHtml {
+            path: path.clone(),
+            rules: options.rules.clone(),
+        }));
+    }
+    if let Some(path) = &args.export_findings {
+        sinks.push(Box::new(sink::FindingsExport {
+            path: path.clone(),
+            queries: args.select.clone(),
+        }));
+    }
+    if args.triage {
+        sinks.push(Box::new(sink::TriageView));
+    }
+    if let Some(path) = &args.graph {
+        sinks.push(Box::new(sink::Graph(path.clone())));
+    }
+    if let Some(url) = &args.webhook {
+        sinks.push(Box::new(sink::Webhook {
+            url: url.clone(),
+            notify_on: args.notify_on,
+            format: args.webhook_format,
+        }));
+    }
+    if let Some(address) = watch.as_ref().and_then(|w| w.metrics_addr) {
+        let metrics = Arc::new(Metrics::default());
+        metrics.listen(address).await?;
+        sinks.push(Box::new(sink::MetricsGauges(metrics)));
+    }
+    // Last, so a run whose reports failed is not recorded as seen
+    if let Some(history) = &history {
+        sinks.push(Box::new(sink::HistoryDb {
+            history,
+            keep: args.history_keep.map(Into::into),
+        }));
+    }
 
-    let result = analyze_url(&url).await;
+    let limits = HostLimits::new(args.concurrency, args.max_per_host);
+    let (args, crawl_options) = (&args, &crawl_options);
+    let (signals, locales) = (&signals, &locales);
+    let mut first_run = true;
+    loop {
+        // A long watch keeps its databases current too
+        if !first_run && !args.offline && refresh_stale_databases(args.format).await {
+            match ScanOptions::from_args(args) {
+                Ok(refreshed) => options = refreshed,
+                Err(e) => eprintln!("  {} {:#}", "Error:".red().bold(), e),
+            }
+        }
+        first_run = false;
+        let options = &options;
+        let started = tokio::time::Instant::now();
+        let run = async {
+            // One line per scan in flight, above the batch's overall progress
+            let progress = MultiProgress::new();
+            let batch = (urls.len() > 1).then(|| progress.add(terminal::create_batch_bar(urls.len())));
+            let (progress, batch) = (&progress, &batch);
+
+            let mut scans: FuturesUnordered<_> = urls
+                .iter()
+                .enumerate()
+                .map(|(index, raw_url)| {
+                    let url = normalize_url(raw_url);
+                    let limits = &limits;
+                    async move {
+                        let _slot = limits.acquire(&url).await;
+                        let spinner = terminal::create_scan_spinner(progress, batch.as_ref(), &url);
+
+                        let (result, cost) = usage::measure(async {
+                            if args.crawl {
+                                crawl::crawl(&url, options, crawl_options, |n, page| {
+                                    spinner.set_message(format!("Crawling page {}/{}: {}", n, crawl_options.max_pages, page));
+                                })
+                                .await
+                            } else if args.sitemap {
+                                spinner.set_message("Reading sitemap...");
+                                match sitemap_pages(&url, crawl_options.max_pages, &options.access).await {
+                                    Ok(pages) => {
+                                        crawl::scan_pages(&pages, options, |n, page| {
+                                            spinner.set_message(format!("Scanning page {}/{}: {}", n, pages.len(), page));
+                                        })
+                                        .await
+                                    }
+                                    Err(e) => Err(e),
+                                }
+                            } else if !signals.is_empty() {
+                                signals::test(&url, options, signals, |signal| match signal {
+                                    Some(signal) => spinner.set_message(format!("Loading with {}...", signal.header())),
+                                    None => spinner.set_message("Loading without privacy signals..."),
+                                })
+                                .await
+                            } else if !locales.is_empty() {
+                                locales::compare(&url, options, locales, |locale| {
+                                    spinner.set_message(format!("Loading as {}...", locale.language));
+                                })
+                                .await
+                            } else if args.compare_devices {
+                                devices::compare(&url, options, |device| {
+                                    spinner.set_message(format!("Loading as {}...", device.as_str()));
+                                })
+                                .await
+                            } else if args.samples > 1 {
+                                sampling::sample(&url, options, args.samples.into(), args.fresh_jar, |n| {
+                                    spinner.set_message(format!("Loading sample {}/{}...", n, args.samples));
+                                })
+                                .await
+                            } else {
+                                analyze_url(&url, options).await
+                            }
+                        })
+                        .await;
+                        spinner.finish_and_clear();
+                        progress.remove(&spinner);
+                        let result = result.map(|mut analysis| {
+                            analysis.resource_usage = Some(cost);
+                            analysis
+                        });
+                        (index, url, result)
+                    }
+                })
+                .collect();
+
+            // Reported as each scan finishes, kept in list order for the summaries
+            let mut results = Vec::new();
+            while let Some((index, url, result)) = scans.next().await {
+                progress.suspend(|| {
+                    for sink in &sinks {
+                        sink.site(&url, &result);
+                    }
+                });
+                if let Some(batch) = batch {
+                    batch.inc(1);
+                }
+                results.push((index, url, result));
+            }
+            drop(scans);
+            progress.clear()?;
+            results.sort_by_key(|(index, _, _)| *index);
+            let results: Vec<(String, Result<AnalysisResult>)> =
+                results.into_iter().map(|(_, url, result)| (url, result)).collect();
+
+            // Sites with findings their previous recorded scan did not have
+            let mut changes = Vec::new();
+            let mut site_changes = Vec::new();
+            let mut deltas = Vec::new();
+            if let Some(history) = &history {
+                for (url, result) in &results {
+                    if let Ok(analysis) = result {
+                        // Across watch runs, so a change can be told from noise
+                        let volatility = match watch {
+                            Some(_) => history.volatility(url, analysis)?,
+                            None => None,
+                        };
+                        if args.notify_on == NotifyOn::Change {
+                            if let Some(previous) = history.latest_report(url)? {
+                                let mut delta = notify::delta(url, &previous, analysis);
+                                if !delta.is_empty() {
+                                    delta.volatility = volatility.clone();
+                                    deltas.push(delta);
+                                }
+                            }
+                        }
+                        if let Some(previous) = history.latest_findings(url)? {
+                            let current = Findings::of(analysis);
+                            let added = current.added_since(&previous);
+                            let content_changed = current.content_changed_since(&previous);
+                            let scripts = current.script_changes_since(&previous);
+                            if !added.is_empty() || content_changed == Some(true) || !scripts.is_empty() {
+                                site_changes.push(SiteChange {
+                                    url: url.clone(),
+                                    content_changed,
+                                    findings: owners::route(&added, analysis, config::owners()),
+                                    scripts,
+                                    volatility,
+                                });
+                            }
+                            if !added.is_empty() {
+                                changes.push((url.clone(), added));
+                            }
+                        }
+                    }
+                }
+            }
 
-    spinner.finish_and_clear();
+            let rows: Vec<(String, Result<&AnalysisResult, String>)> = results
+                .iter()
+                .map(|(url, result)| (url.clone(), result.as_ref().map_err(|e| e.to_string())))
+                .collect();
+            let violations = gate.check_run(&rows, baseline.as_ref());
+            let run = Run {
+                rows: &rows,
+                changes: history.is_some().then_some(&changes[..]),
+                site_changes: &site_changes,
+                deltas: &deltas,
+                violations: &violations,
+                text_output,
+            };
+            for sink in &sinks {
+                sink.finish(&run).await?;
+            }
 
-    match result {
-        Ok(analysis) => {
-            print_results(&analysis, args.verbose);
+            // After the reports, so a failing run still leaves them for inspection
+            for site in &violations {
+                for violation in &site.violations {
+                    eprintln!("  {} {} {}", "[FAIL]".red().bold(), site.url.bright_white(), violation);
+                }
+            }
+            Ok(!violations.is_empty())
+        };
+        let failed = run.await;
+        let Some(watch) = &watch else {
+            return failed.map(|failed| {
+                if failed { ExitCode::from(gate::EXIT_VIOLATION) } else { ExitCode::SUCCESS }
+            });
+        };
+        // A failed run does not stop the watch; the next one may well succeed
+        if let Err(e) = failed {
+            eprintln!("  {} {:#}", "Error:".red().bold(), e);
         }
-        Err(e) => {
-            println!();
+        let next = started + watch.interval;
+        if text_output {
+            let at = std::time::SystemTime::now() + next.saturating_duration_since(tokio::time::Instant::now());
             println!(
-                "  {} {}",
-                "[ERROR]".bright_red(),
-                format!("Error analyzing URL: {}", e).red()
-            );
-            println!();
-            println!(
-                "  {} Make sure the URL is correct and accessible",
-                "Tip:".bright_yellow()
+                "  {} next scan at {}",
+                "Watching:".bright_black(),
+                humantime::format_rfc3339_seconds(at)
             );
-            println!();
         }
+        tokio::time::sleep_until(next).await;
     }
-
-    Ok(())
 }
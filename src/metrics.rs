@@ -0,0 +1,209 @@
+//! Prometheus gauges for each monitored site, exposed at `/metrics` by
+//! `recon serve` and `recon watch --metrics-addr`, so privacy regressions can be
+//! alerted on from an existing Grafana or Alertmanager setup.
+
+use crate::{calculate_privacy_score, AnalysisResult, CookieCategory};
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Sites with gauges; the one recorded longest ago is forgotten to make room
+const MAX_SITES: usize = 1000;
+
+const CATEGORIES: [CookieCategory; 5] = [
+    CookieCategory::Essential,
+    CookieCategory::Analytics,
+    CookieCategory::Marketing,
+    CookieCategory::Social,
+    CookieCategory::Unknown,
+];
+
+/// The latest successful scan of a site.
+struct Scan {
+    privacy_score: u32,
+    cookies: [usize; CATEGORIES.len()],
+    trackers: usize,
+    third_party_domains: usize,
+    /// Unix time of the last successful scan
+    scanned_at: u64,
+}
+
+#[derive(Default)]
+struct Site {
+    latest: Option<Scan>,
+    /// Whether the most recent scan failed
+    failing: bool,
+    /// When the site was last recorded, as a count of recordings
+    recorded: u64,
+}
+
+#[derive(Default)]
+struct Sites {
+    recordings: u64,
+    by_url: BTreeMap<String, Site>,
+}
+
+impl Sites {
+    /// The site's entry, forgetting the site recorded longest ago when a new
+    /// one would go beyond `MAX_SITES`.
+    fn record(&mut self, url: &str) -> &mut Site {
+        if !self.by_url.contains_key(url) && self.by_url.len() >= MAX_SITES {
+            let stalest = self
+                .by_url
+                .iter()
+                .min_by_key(|(_, site)| site.recorded)
+                .map(|(url, _)| url.clone());
+            if let Some(stalest) = stalest {
+                self.by_url.remove(&stalest);
+            }
+        }
+        self.recordings += 1;
+        let site = self.by_url.entry(url.to_string()).or_default();
+        site.recorded = self.recordings;
+        site
+    }
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    sites: Mutex<Sites>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Metrics {
+    pub fn record(&self, url: &str, result: &AnalysisResult) {
+        let scan = Scan {
+            privacy_score: calculate_privacy_score(result),
+            cookies: CATEGORIES.map(|category| {
+                result
+                    .cookies
+                    .iter()
+                    .filter(|c| c.category == category)
+                    .count()
+            }),
+            trackers: result.trackers.len(),
+            third_party_domains: result.third_party_requests.len(),
+            scanned_at: unix_now(),
+        };
+        let mut sites = self.sites.lock().unwrap();
+        let site = sites.record(url);
+        site.latest = Some(scan);
+        site.failing = false;
+    }
+
+    /// Mark a site's scan as failed. Its gauges keep the values of the last
+    /// scan that succeeded; a site that never did has only
+    /// `recon_scan_failing`.
+    pub fn record_failure(&self, url: &str) {
+        self.sites.lock().unwrap().record(url).failing = true;
+    }
+
+    /// Every gauge in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let sites = &self.sites.lock().unwrap().by_url;
+        let mut out = String::new();
+        let mut gauge =
+            |name: &str, help: &str, values: &mut dyn Iterator<Item = (String, u64)>| {
+                let _ = writeln!(out, "# HELP {} {}", name, help);
+                let _ = writeln!(out, "# TYPE {} gauge", name);
+                for (labels, value) in values {
+                    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+                }
+            };
+        let url = |url: &str| format!("url=\"{}\"", escape(url));
+        let scanned = || {
+            sites
+                .iter()
+                .filter_map(|(u, site)| Some((u, site.latest.as_ref()?)))
+        };
+
+        gauge(
+            "recon_privacy_score",
+            "Privacy score of the latest scan, from 0 to 100",
+            &mut scanned().map(|(u, s)| (url(u), s.privacy_score.into())),
+        );
+        gauge(
+            "recon_cookies",
+            "Cookies set by the site in the latest scan, by category",
+            &mut scanned().flat_map(|(u, s)| {
+                CATEGORIES
+                    .iter()
+                    .zip(s.cookies)
+                    .map(move |(category, count)| {
+                        let labels = format!(
+                            "{},category=\"{}\"",
+                            url(u),
+                            category.as_str().to_lowercase()
+                        );
+                        (labels, count as u64)
+                    })
+            }),
+        );
+        gauge(
+            "recon_trackers",
+            "Trackers found in the latest scan",
+            &mut scanned().map(|(u, s)| (url(u), s.trackers as u64)),
+        );
+        gauge(
+            "recon_third_party_domains",
+            "Third-party domains contacted in the latest scan",
+            &mut scanned().map(|(u, s)| (url(u), s.third_party_domains as u64)),
+        );
+        gauge(
+            "recon_last_scan_timestamp_seconds",
+            "Unix time of the latest successful scan",
+            &mut scanned().map(|(u, s)| (url(u), s.scanned_at)),
+        );
+        gauge(
+            "recon_scan_failing",
+            "1 when the most recent scan of the site failed",
+            &mut sites.iter().map(|(u, s)| (url(u), s.failing.into())),
+        );
+        out
+    }
+
+    /// A router serving `GET /metrics`.
+    pub fn router(self: &Arc<Self>) -> Router {
+        Router::new()
+            .route("/metrics", get(exposition))
+            .with_state(self.clone())
+    }
+
+    /// Serve `/metrics` on `address` in the background.
+    pub async fn listen(self: &Arc<Self>, address: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .with_context(|| format!("Failed to listen on {}", address))?;
+        let app = self.router();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Ok(())
+    }
+}
+
+async fn exposition(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics.render())
+}
+
+/// Escape a label value as the exposition format requires.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,301 @@
+//! Posting scan results to a webhook, either the full report after every run
+//! or only what changed since each site's previous recorded scan and the
+//! thresholds broken, as JSON or as a Slack message.
+
+use crate::gate::SiteViolations;
+use crate::history::Findings;
+use crate::inventory::ScriptChange;
+use crate::output::json;
+use crate::variance::Volatility;
+use crate::{build_client, calculate_privacy_score, privacy_rating, AnalysisResult};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum NotifyOn {
+    /// Post the full report after every run
+    Always,
+    /// Post only added and removed findings and broken thresholds, and only
+    /// when there are any
+    Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum WebhookFormat {
+    /// recon's own JSON documents
+    Json,
+    /// A message for Slack incoming webhooks, which Mattermost and Rocket.Chat
+    /// also accept
+    Slack,
+}
+
+/// A tracker or cookie that appeared or disappeared between two scans.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedFinding {
+    /// "tracker" or "cookie"
+    pub kind: &'static str,
+    /// The tracker rule that matched, or the cookie's name
+    pub rule: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+}
+
+/// How one site's findings differ from its previous recorded scan.
+#[derive(Debug, Serialize)]
+pub struct Delta {
+    pub url: String,
+    pub added: Vec<ChangedFinding>,
+    pub removed: Vec<ChangedFinding>,
+    /// First-party scripts added, removed or modified, when both scans
+    /// fetched scripts
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<ScriptChange>,
+    /// How much the site varies across its recent `watch` runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volatility: Option<Volatility>,
+}
+
+impl Delta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.scripts.is_empty()
+    }
+}
+
+/// Trackers and cookies of a result, keyed by kind and rule.
+fn findings_of(result: &AnalysisResult) -> BTreeMap<(&'static str, String), ChangedFinding> {
+    let trackers = result.trackers.iter().map(|t| ChangedFinding {
+        kind: "tracker",
+        rule: t.name.clone(),
+        category: t.category.clone(),
+        vendor: Some(t.description.clone()),
+    });
+    let cookies = result.cookies.iter().map(|c| ChangedFinding {
+        kind: "cookie",
+        rule: c.name.clone(),
+        category: c.category.as_str().to_string(),
+        vendor: c.vendor.clone(),
+    });
+    trackers
+        .chain(cookies)
+        .map(|f| ((f.kind, f.rule.clone()), f))
+        .collect()
+}
+
+/// The same, read back from a report stored in the history database.
+fn findings_of_report(
+    report: &serde_json::Value,
+) -> BTreeMap<(&'static str, String), ChangedFinding> {
+    let mut findings = BTreeMap::new();
+    let lists = [
+        ("tracker", "trackers", "description"),
+        ("cookie", "cookies", "vendor"),
+    ];
+    for (kind, list, vendor) in lists {
+        for item in report[list].as_array().into_iter().flatten() {
+            let Some(rule) = item["name"].as_str() else {
+                continue;
+            };
+            let finding = ChangedFinding {
+                kind,
+                rule: rule.to_string(),
+                category: item["category"].as_str().unwrap_or_default().to_string(),
+                vendor: item[vendor].as_str().map(str::to_string),
+            };
+            findings.insert((kind, rule.to_string()), finding);
+        }
+    }
+    findings
+}
+
+/// What `current` added and dropped compared with the `previous` report.
+pub fn delta(url: &str, previous: &serde_json::Value, current: &AnalysisResult) -> Delta {
+    let before = findings_of_report(previous);
+    let after = findings_of(current);
+    Delta {
+        url: url.to_string(),
+        added: after
+            .iter()
+            .filter(|(key, _)| !before.contains_key(*key))
+            .map(|(_, f)| f.clone())
+            .collect(),
+        removed: before
+            .iter()
+            .filter(|(key, _)| !after.contains_key(*key))
+            .map(|(_, f)| f.clone())
+            .collect(),
+        scripts: Findings::of(current).script_changes_since(&Findings::from_report(previous)),
+        volatility: None,
+    }
+}
+
+#[derive(Serialize)]
+struct ReportPayload<'a> {
+    #[serde(flatten)]
+    report: json::Report<'a>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    violations: &'a [SiteViolations],
+}
+
+#[derive(Serialize)]
+struct ChangePayload<'a> {
+    changes: &'a [Delta],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    violations: &'a [SiteViolations],
+}
+
+#[derive(Serialize)]
+struct SlackMessage {
+    text: String,
+}
+
+/// Post the full report of this run, with the thresholds its sites broke.
+pub async fn send_report(
+    webhook: &str,
+    format: WebhookFormat,
+    rows: &[(String, Result<&AnalysisResult, String>)],
+    violations: &[SiteViolations],
+) -> Result<()> {
+    match format {
+        WebhookFormat::Json => {
+            let report = json::build(rows);
+            post(webhook, &ReportPayload { report, violations }).await
+        }
+        WebhookFormat::Slack => {
+            let text = slack_report(rows, violations);
+            post(webhook, &SlackMessage { text }).await
+        }
+    }
+}
+
+/// Post the sites whose findings changed or that broke a threshold. Nothing
+/// is sent when there are none. Returns whether a notification went out.
+pub async fn send_changes(
+    webhook: &str,
+    format: WebhookFormat,
+    deltas: &[Delta],
+    violations: &[SiteViolations],
+) -> Result<bool> {
+    if deltas.is_empty() && violations.is_empty() {
+        return Ok(false);
+    }
+    match format {
+        WebhookFormat::Json => {
+            let changes = deltas;
+            post(webhook, &ChangePayload { changes, violations }).await?
+        }
+        WebhookFormat::Slack => {
+            let text = slack_changes(deltas, violations);
+            post(webhook, &SlackMessage { text }).await?
+        }
+    }
+    Ok(true)
+}
+
+/// Escape the characters Slack's mrkdwn gives a meaning of their own.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn site_heading(url: &str) -> String {
+    format!("*{}*", escape(url))
+}
+
+fn finding_line(verb: &str, finding: &ChangedFinding) -> String {
+    let mut line = format!(
+        "• {} {} `{}` ({}",
+        verb,
+        finding.kind,
+        escape(&finding.rule),
+        escape(&finding.category)
+    );
+    if let Some(vendor) = &finding.vendor {
+        line.push_str(&format!(", {}", escape(vendor)));
+    }
+    line.push(')');
+    line
+}
+
+fn violation_lines<'a>(
+    url: &'a str,
+    violations: &'a [SiteViolations],
+) -> impl Iterator<Item = String> + 'a {
+    violations
+        .iter()
+        .filter(move |site| site.url == url)
+        .flat_map(|site| &site.violations)
+        .map(|violation| format!("• :x: {}", escape(violation)))
+}
+
+fn slack_report(
+    rows: &[(String, Result<&AnalysisResult, String>)],
+    violations: &[SiteViolations],
+) -> String {
+    let mut lines = vec![format!("*recon* scanned {} site(s)", rows.len())];
+    for (url, result) in rows {
+        lines.push(match result {
+            Ok(result) => {
+                let score = calculate_privacy_score(result);
+                format!(
+                    "{}: privacy score {} ({}), {} cookies, {} trackers, {} third-party domains",
+                    site_heading(url),
+                    score,
+                    privacy_rating(score),
+                    result.cookies.len(),
+                    result.trackers.len(),
+                    result.third_party_requests.len()
+                )
+            }
+            Err(e) => format!("{}: scan failed: {}", site_heading(url), escape(e)),
+        });
+        lines.extend(violation_lines(url, violations));
+    }
+    lines.join("\n")
+}
+
+fn slack_changes(deltas: &[Delta], violations: &[SiteViolations]) -> String {
+    let mut urls: Vec<&str> = deltas.iter().map(|d| d.url.as_str()).collect();
+    for site in violations {
+        if !urls.contains(&site.url.as_str()) {
+            urls.push(&site.url);
+        }
+    }
+    let mut lines = vec![format!("*recon*: {} site(s) changed or broke a threshold", urls.len())];
+    for url in urls {
+        lines.push(site_heading(url));
+        if let Some(delta) = deltas.iter().find(|d| d.url == url) {
+            lines.extend(delta.added.iter().map(|f| finding_line("New", f)));
+            lines.extend(delta.removed.iter().map(|f| finding_line("Removed", f)));
+            lines.extend(delta.scripts.iter().map(|change| {
+                format!("• Script {} `{}`", change.kind.as_str(), escape(&change.path))
+            }));
+            if let Some(volatility) = delta.volatility.as_ref().filter(|v| v.is_volatile()) {
+                lines.push(format!(
+                    "• _Volatile over the last {} runs: score {}-{}, {} finding(s) come and go_",
+                    volatility.runs,
+                    volatility.score.min,
+                    volatility.score.max,
+                    volatility.intermittent.len()
+                ));
+            }
+        }
+        lines.extend(violation_lines(url, violations));
+    }
+    lines.join("\n")
+}
+async fn post(webhook: &str, payload: &impl Serialize) -> Result<()> {
+    build_client()?
+        .post(webhook)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(payload)?)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to notify webhook {}", webhook))?;
+    Ok(())
+}
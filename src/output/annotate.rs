@@ -0,0 +1,215 @@
+//! A copy of the analyzed HTML with the tags behind each tracker finding
+//! highlighted and labelled, for finding them in the site's templates.
+
+use crate::rules::RuleSet;
+use crate::{
+    check_content_for_trackers, check_url_for_trackers, vendors, AnalysisResult, TrackerInfo,
+    INLINE_SCRIPT_SOURCE,
+};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Scripts, frames, pixels and connections are blocked so opening the copy
+/// does not fire the trackers it points out.
+const CONTENT_SECURITY_POLICY: &str =
+    "default-src 'none'; style-src 'unsafe-inline' http: https:; font-src http: https:";
+
+const STYLE: &str = "[data-recon-tracker]{outline:3px solid #e5484d !important;outline-offset:2px}\
+#recon-annotations{position:fixed;bottom:0;left:0;right:0;max-height:40vh;overflow:auto;z-index:2147483647;\
+background:#fff;color:#111;border-top:3px solid #e5484d;font:13px/1.4 system-ui,sans-serif;padding:8px 12px}\
+#recon-annotations table{border-collapse:collapse;width:100%}\
+#recon-annotations td,#recon-annotations th{border-bottom:1px solid #ddd;padding:3px 6px;text-align:left;vertical-align:top}\
+#recon-annotations code{font:12px monospace;word-break:break-all}";
+
+/// A tag that produced one or more tracker findings.
+struct Annotation {
+    /// 1-based line of the tag in the analyzed HTML
+    line: usize,
+    tag: String,
+    trackers: Vec<TrackerInfo>,
+}
+
+/// Write the annotated copy of `result`'s HTML to `path`. Returns how many
+/// tags were annotated.
+pub fn write(path: &Path, result: &AnalysisResult, rules: &RuleSet) -> Result<usize> {
+    let (html, count) = annotate(result, rules);
+    std::fs::write(path, html).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(count)
+}
+
+/// `path` with the site's host added to the file name, e.g.
+/// `annotated-example.com.html`, for scans of several sites. `n` tells
+/// apart several scans of the same host.
+pub fn path_for(path: &Path, url: &str, n: usize) -> PathBuf {
+    let mut host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?;
+            Some(match u.port() {
+                Some(port) => format!("{}-{}", host, port),
+                None => host.to_string(),
+            })
+        })
+        .unwrap_or_else(|| "page".to_string());
+    if n > 1 {
+        host = format!("{}-{}", host, n);
+    }
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("annotated");
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}-{}.{}", stem, host, extension),
+        None => format!("{}-{}", stem, host),
+    };
+    path.with_file_name(name)
+}
+
+fn annotate(result: &AnalysisResult, rules: &RuleSet) -> (String, usize) {
+    let html = &result.html;
+    let tag_re = Regex::new(r"(?is)<(script|img|iframe)\b[^>]*>").unwrap();
+    let script_end_re = Regex::new(r"(?i)</script").unwrap();
+    let src_re = Regex::new(r#"(?is)\ssrc\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap();
+    let base_domain = url::Url::parse(&result.url)
+        .ok()
+        .and_then(|u| u.domain().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut out = String::with_capacity(html.len() + 4096);
+    let mut annotations = Vec::new();
+    let mut copied = 0;
+    for caps in tag_re.captures_iter(html) {
+        let tag = caps.get(0).unwrap();
+        let name = caps.get(1).unwrap();
+        let mut found = Vec::new();
+        let mut names = HashSet::new();
+        match src_re.captures(tag.as_str()) {
+            Some(src) => {
+                let src = src
+                    .get(1)
+                    .or_else(|| src.get(2))
+                    .or_else(|| src.get(3))
+                    .map_or("", |m| m.as_str());
+                check_url_for_trackers(
+                    src,
+                    &base_domain,
+                    rules,
+                    &mut found,
+                    &mut HashSet::new(),
+                    &mut names,
+                );
+            }
+            None if name.as_str().eq_ignore_ascii_case("script") => {
+                let rest = &html[tag.end()..];
+                let end = script_end_re.find(rest).map_or(rest.len(), |m| m.start());
+                check_content_for_trackers(
+                    &rest[..end],
+                    INLINE_SCRIPT_SOURCE,
+                    rules,
+                    &mut found,
+                    &mut names,
+                );
+            }
+            None => {}
+        }
+        // Only what the report lists, after confidence and ignore filtering
+        found.retain(|t| result.trackers.iter().any(|r| r.name == t.name));
+        if found.is_empty() {
+            continue;
+        }
+
+        let number = annotations.len() + 1;
+        let name_end = name.end();
+        out.push_str(&html[copied..name_end]);
+        let _ = write!(
+            out,
+            " data-recon-tracker=\"{}\" title=\"{}\"",
+            number,
+            escape_html(&tooltip(&found))
+        );
+        copied = name_end;
+        annotations.push(Annotation {
+            line: html[..tag.start()].matches('\n').count() + 1,
+            tag: tag.as_str().to_string(),
+            trackers: found,
+        });
+    }
+    out.push_str(&html[copied..]);
+
+    let head = format!(
+        "<meta http-equiv=\"Content-Security-Policy\" content=\"{}\"><base href=\"{}\"><style>{}</style>",
+        CONTENT_SECURITY_POLICY,
+        escape_html(&result.url),
+        STYLE
+    );
+    // Into <head>, else straight after <html>, else at the very start
+    let head_re = Regex::new(r"(?i)<head\b[^>]*>").unwrap();
+    let html_re = Regex::new(r"(?i)<html\b[^>]*>").unwrap();
+    out = match head_re.find(&out).or_else(|| html_re.find(&out)) {
+        Some(m) => format!("{}{}{}", &out[..m.end()], head, &out[m.end()..]),
+        None => format!("{}{}", head, out),
+    };
+
+    let legend = legend(&result.url, &annotations);
+    let body_end = Regex::new(r"(?i)</body")
+        .unwrap()
+        .find_iter(&out)
+        .last()
+        .map(|m| m.start());
+    match body_end {
+        Some(at) => out.insert_str(at, &legend),
+        None => out.push_str(&legend),
+    }
+    (out, annotations.len())
+}
+
+/// "rule: vendor (category)" for each tracker the tag triggered.
+fn tooltip(trackers: &[TrackerInfo]) -> String {
+    trackers
+        .iter()
+        .map(|t| format!("{}: {} ({})", t.name, vendor(t), t.category))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn vendor(tracker: &TrackerInfo) -> &str {
+    vendors::lookup(&tracker.description).map_or(&tracker.description, |v| v.name)
+}
+
+fn legend(url: &str, annotations: &[Annotation]) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<aside id=\"recon-annotations\"><strong>recon: {} tag(s) triggered tracker findings on {}</strong>\
+         <table><tr><th>#</th><th>Line</th><th>Tag</th><th>Rule</th><th>Vendor</th><th>Category</th></tr>",
+        annotations.len(),
+        escape_html(url)
+    );
+    for (index, annotation) in annotations.iter().enumerate() {
+        for tracker in &annotation.trackers {
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                index + 1,
+                annotation.line,
+                escape_html(&annotation.tag),
+                escape_html(&tracker.name),
+                escape_html(vendor(tracker)),
+                escape_html(&tracker.category)
+            );
+        }
+    }
+    out.push_str("</table></aside>");
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
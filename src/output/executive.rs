@@ -0,0 +1,333 @@
+//! One-page roll-up of a batch scan for leadership: portfolio score, worst
+//! offenders, the trackers most sites share and what is new since last run.
+
+use crate::batch::BatchSummary;
+use crate::history::Findings;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult};
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Number of lowest-scoring sites listed
+const WORST_OFFENDERS: usize = 5;
+
+const RATINGS: [&str; 5] = ["Excellent", "Good", "Moderate", "Poor", "Critical"];
+
+struct Offender<'a> {
+    url: &'a str,
+    score: u32,
+    trackers: usize,
+    pre_consent: usize,
+}
+
+struct Portfolio<'a> {
+    summary: BatchSummary,
+    /// Sites per rating, in `RATINGS` order
+    ratings: [usize; 5],
+    worst: Vec<Offender<'a>>,
+    failed: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Portfolio<'a> {
+    fn new(rows: &'a [(String, Result<&AnalysisResult, String>)]) -> Self {
+        let mut ratings = [0; 5];
+        let mut scored = Vec::new();
+        let mut failed = Vec::new();
+        for (url, result) in rows {
+            match result {
+                Ok(result) => {
+                    let score = calculate_privacy_score(result);
+                    let rating = privacy_rating(score);
+                    if let Some(i) = RATINGS.iter().position(|r| *r == rating) {
+                        ratings[i] += 1;
+                    }
+                    scored.push(Offender {
+                        url,
+                        score,
+                        trackers: result.trackers.len(),
+                        pre_consent: result.pre_consent.len(),
+                    });
+                }
+                Err(e) => failed.push((url.as_str(), e.as_str())),
+            }
+        }
+        scored.sort_by_key(|site| site.score);
+        scored.truncate(WORST_OFFENDERS);
+
+        Portfolio {
+            summary: BatchSummary::from_rows(rows),
+            ratings,
+            worst: scored,
+            failed,
+        }
+    }
+
+    fn scanned(&self) -> usize {
+        self.summary.sites - self.summary.failed
+    }
+}
+
+/// Write the summary to `path`: HTML for `.html`/`.htm`, Markdown otherwise.
+/// `changes` holds the sites with findings that were not in their previous
+/// recorded scan, or `None` when no history is kept.
+pub fn write(
+    path: &Path,
+    rows: &[(String, Result<&AnalysisResult, String>)],
+    changes: Option<&[(String, Findings)]>,
+) -> Result<()> {
+    let portfolio = Portfolio::new(rows);
+    let html = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("html") | Some("htm")
+    );
+    let contents = if html {
+        render_html(&portfolio, changes)
+    } else {
+        render_markdown(&portfolio, changes)
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write executive summary to {}", path.display()))
+}
+
+fn render_markdown(portfolio: &Portfolio, changes: Option<&[(String, Findings)]>) -> String {
+    let summary = &portfolio.summary;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Privacy Portfolio Summary");
+    let _ = writeln!(out);
+    match summary.average_score {
+        Some(average) => {
+            let _ = writeln!(
+                out,
+                "**Average privacy score:** {:.0}/100 ({}) across {} sites",
+                average,
+                privacy_rating(average.round() as u32),
+                portfolio.scanned()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "No site could be scanned.");
+        }
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Rating | Sites |");
+    let _ = writeln!(out, "|--------|------:|");
+    for (rating, count) in RATINGS.iter().zip(portfolio.ratings) {
+        let _ = writeln!(out, "| {} | {} |", rating, count);
+    }
+
+    if !portfolio.worst.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Worst Offenders");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Site | Score | Trackers | Before Consent |");
+        let _ = writeln!(out, "|------|------:|---------:|---------------:|");
+        for site in &portfolio.worst {
+            let _ = writeln!(
+                out,
+                "| {} | {} ({}) | {} | {} |",
+                escape_markdown(site.url),
+                site.score,
+                privacy_rating(site.score),
+                site.trackers,
+                site.pre_consent
+            );
+        }
+    }
+
+    if !summary.common_trackers.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Most Common Trackers");
+        let _ = writeln!(out);
+        for (tracker, sites) in &summary.common_trackers {
+            let _ = writeln!(
+                out,
+                "- {} ({} of {} sites)",
+                escape_markdown(tracker),
+                sites,
+                portfolio.scanned()
+            );
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## New Since Last Run");
+    let _ = writeln!(out);
+    match changes {
+        None => {
+            let _ = writeln!(out, "Not tracked; scan with `--history` to compare runs.");
+        }
+        Some([]) => {
+            let _ = writeln!(out, "No new trackers or cookies on any site.");
+        }
+        Some(changes) => {
+            for (url, added) in changes {
+                let _ = writeln!(out, "- **{}**", escape_markdown(url));
+                if !added.trackers.is_empty() {
+                    let _ = writeln!(out, "  - Trackers: {}", join(&added.trackers));
+                }
+                if !added.cookies.is_empty() {
+                    let _ = writeln!(out, "  - Cookies: {}", join(&added.cookies));
+                }
+            }
+        }
+    }
+
+    if !portfolio.failed.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Not Scanned");
+        let _ = writeln!(out);
+        for (url, error) in &portfolio.failed {
+            let _ = writeln!(
+                out,
+                "- {}: {}",
+                escape_markdown(url),
+                escape_markdown(error)
+            );
+        }
+    }
+
+    out
+}
+
+fn render_html(portfolio: &Portfolio, changes: Option<&[(String, Findings)]>) -> String {
+    let summary = &portfolio.summary;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html lang=\"en\">");
+    let _ = writeln!(out, "<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>Privacy Portfolio Summary</title>");
+    let _ = writeln!(
+        out,
+        "<style>body{{font-family:system-ui,sans-serif;max-width:52rem;margin:2rem auto;color:#222}}\
+         table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:.3rem .6rem;text-align:left}}\
+         .score{{font-size:2.5rem;font-weight:bold}}</style>"
+    );
+    let _ = writeln!(out, "</head>");
+    let _ = writeln!(out, "<body>");
+    let _ = writeln!(out, "<h1>Privacy Portfolio Summary</h1>");
+    match summary.average_score {
+        Some(average) => {
+            let _ = writeln!(
+                out,
+                "<p><span class=\"score\">{:.0}/100</span> average privacy score ({}) across {} sites</p>",
+                average,
+                privacy_rating(average.round() as u32),
+                portfolio.scanned()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "<p>No site could be scanned.</p>");
+        }
+    }
+    let _ = writeln!(out, "<table><tr><th>Rating</th><th>Sites</th></tr>");
+    for (rating, count) in RATINGS.iter().zip(portfolio.ratings) {
+        let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", rating, count);
+    }
+    let _ = writeln!(out, "</table>");
+
+    if !portfolio.worst.is_empty() {
+        let _ = writeln!(out, "<h2>Worst Offenders</h2>");
+        let _ = writeln!(
+            out,
+            "<table><tr><th>Site</th><th>Score</th><th>Trackers</th><th>Before Consent</th></tr>"
+        );
+        for site in &portfolio.worst {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{} ({})</td><td>{}</td><td>{}</td></tr>",
+                escape_html(site.url),
+                site.score,
+                privacy_rating(site.score),
+                site.trackers,
+                site.pre_consent
+            );
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    if !summary.common_trackers.is_empty() {
+        let _ = writeln!(out, "<h2>Most Common Trackers</h2>");
+        let _ = writeln!(out, "<ul>");
+        for (tracker, sites) in &summary.common_trackers {
+            let _ = writeln!(
+                out,
+                "<li>{} ({} of {} sites)</li>",
+                escape_html(tracker),
+                sites,
+                portfolio.scanned()
+            );
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+
+    let _ = writeln!(out, "<h2>New Since Last Run</h2>");
+    match changes {
+        None => {
+            let _ = writeln!(
+                out,
+                "<p>Not tracked; scan with <code>--history</code> to compare runs.</p>"
+            );
+        }
+        Some([]) => {
+            let _ = writeln!(out, "<p>No new trackers or cookies on any site.</p>");
+        }
+        Some(changes) => {
+            let _ = writeln!(out, "<ul>");
+            for (url, added) in changes {
+                let _ = write!(out, "<li><strong>{}</strong><ul>", escape_html(url));
+                if !added.trackers.is_empty() {
+                    let _ = write!(
+                        out,
+                        "<li>Trackers: {}</li>",
+                        escape_html(&join(&added.trackers))
+                    );
+                }
+                if !added.cookies.is_empty() {
+                    let _ = write!(
+                        out,
+                        "<li>Cookies: {}</li>",
+                        escape_html(&join(&added.cookies))
+                    );
+                }
+                let _ = writeln!(out, "</ul></li>");
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+    }
+
+    if !portfolio.failed.is_empty() {
+        let _ = writeln!(out, "<h2>Not Scanned</h2>");
+        let _ = writeln!(out, "<ul>");
+        for (url, error) in &portfolio.failed {
+            let _ = writeln!(out, "<li>{}: {}</li>", escape_html(url), escape_html(error));
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+
+    let _ = writeln!(out, "</body>");
+    let _ = writeln!(out, "</html>");
+    out
+}
+
+fn join(names: &std::collections::BTreeSet<String>) -> String {
+    names
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn escape_markdown(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,171 @@
+//! Page → third party → tracker relationship graphs in GraphViz DOT or Mermaid.
+
+use crate::{AnalysisResult, INLINE_SCRIPT_SOURCE};
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NodeKind {
+    Page,
+    Domain,
+    Tracker,
+}
+
+#[derive(Default)]
+struct Graph {
+    nodes: BTreeSet<(String, NodeKind)>,
+    edges: BTreeSet<(String, String, &'static str)>,
+}
+
+impl Graph {
+    fn edge(&mut self, from: (&str, NodeKind), to: (&str, NodeKind), label: &'static str) {
+        self.nodes.insert((from.0.to_string(), from.1));
+        self.nodes.insert((to.0.to_string(), to.1));
+        self.edges
+            .insert((from.0.to_string(), to.0.to_string(), label));
+    }
+}
+
+/// Write the graph for all analyses to `path`, picking the format from the
+/// file extension.
+pub fn write(path: &Path, results: &[&AnalysisResult]) -> Result<()> {
+    let graph = build(results);
+    let mermaid = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("mmd") | Some("mermaid")
+    );
+    let contents = if mermaid {
+        render_mermaid(&graph)
+    } else {
+        render_dot(&graph)
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write graph to {}", path.display()))
+}
+
+fn build(results: &[&AnalysisResult]) -> Graph {
+    let mut graph = Graph::default();
+
+    for result in results {
+        let page = result.url.as_str();
+        let page_url = Url::parse(page).ok();
+        let page_domain = page_url.as_ref().and_then(|u| u.domain()).unwrap_or("");
+
+        graph.nodes.insert((page.to_string(), NodeKind::Page));
+
+        // Domains reached only through another script hang off that script
+        let via_scripts: HashSet<&str> = result
+            .script_dependencies
+            .iter()
+            .map(|d| d.domain.as_str())
+            .collect();
+
+        for domain in &result.third_party_requests {
+            if !via_scripts.contains(domain.as_str()) {
+                graph.edge((page, NodeKind::Page), (domain, NodeKind::Domain), "loads");
+            }
+        }
+
+        for dependency in &result.script_dependencies {
+            let script_domain = Url::parse(&dependency.script)
+                .ok()
+                .and_then(|u| u.domain().map(str::to_string))
+                .filter(|d| d != page_domain);
+            match script_domain {
+                Some(script_domain) => graph.edge(
+                    (&script_domain, NodeKind::Domain),
+                    (&dependency.domain, NodeKind::Domain),
+                    "script loads",
+                ),
+                None => graph.edge(
+                    (page, NodeKind::Page),
+                    (&dependency.domain, NodeKind::Domain),
+                    "script loads",
+                ),
+            }
+        }
+
+        for tracker in &result.trackers {
+            let label = format!("{} ({})", tracker.description, tracker.category);
+            let source_domain = if tracker.source == INLINE_SCRIPT_SOURCE {
+                None
+            } else {
+                Url::parse(&tracker.source)
+                    .ok()
+                    .and_then(|u| u.domain().map(str::to_string))
+                    .filter(|d| d != page_domain)
+            };
+            match source_domain {
+                Some(domain) => graph.edge(
+                    (&domain, NodeKind::Domain),
+                    (&label, NodeKind::Tracker),
+                    "matches",
+                ),
+                None => graph.edge(
+                    (page, NodeKind::Page),
+                    (&label, NodeKind::Tracker),
+                    "matches",
+                ),
+            }
+        }
+    }
+
+    graph
+}
+
+fn render_dot(graph: &Graph) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph recon {{");
+    let _ = writeln!(out, "    rankdir=LR;");
+    let _ = writeln!(out, "    node [fontname=\"Helvetica\"];");
+    for (name, kind) in &graph.nodes {
+        let attrs = match kind {
+            NodeKind::Page => "shape=box, style=filled, fillcolor=\"#d2aa78\"",
+            NodeKind::Domain => "shape=box",
+            NodeKind::Tracker => "shape=ellipse, style=filled, fillcolor=\"#f4cccc\"",
+        };
+        let _ = writeln!(out, "    \"{}\" [{}];", dot_escape(name), attrs);
+    }
+    for (from, to, label) in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    \"{}\" -> \"{}\" [label=\"{}\"];",
+            dot_escape(from),
+            dot_escape(to),
+            label
+        );
+    }
+    let _ = writeln!(out, "}}");
+    out
+}
+
+fn render_mermaid(graph: &Graph) -> String {
+    let ids: Vec<&String> = graph.nodes.iter().map(|(name, _)| name).collect();
+    let id_of = |name: &str| ids.iter().position(|n| n.as_str() == name).unwrap_or(0);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "graph LR");
+    for (i, (name, kind)) in graph.nodes.iter().enumerate() {
+        let label = mermaid_escape(name);
+        let _ = match kind {
+            NodeKind::Page => writeln!(out, "    n{}[[\"{}\"]]", i, label),
+            NodeKind::Domain => writeln!(out, "    n{}[\"{}\"]", i, label),
+            NodeKind::Tracker => writeln!(out, "    n{}((\"{}\"))", i, label),
+        };
+    }
+    for (from, to, label) in &graph.edges {
+        let _ = writeln!(out, "    n{} -->|{}| n{}", id_of(from), label, id_of(to));
+    }
+    out
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
@@ -0,0 +1,61 @@
+//! JSON report for scripting and downstream tooling.
+
+use crate::batch::BatchSummary;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult};
+use anyhow::Result;
+use serde::Serialize;
+
+/// The structured report shared by the JSON and YAML formats
+#[derive(Serialize)]
+pub struct Report<'a> {
+    tool: &'static str,
+    version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<BatchSummary>,
+    results: Vec<Entry<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Entry<'a> {
+    Success {
+        #[serde(flatten)]
+        result: &'a AnalysisResult,
+        privacy_score: u32,
+        rating: &'static str,
+    },
+    Failure {
+        url: &'a str,
+        error: &'a str,
+    },
+}
+
+/// Serialize every analyzed URL into a single pretty-printed JSON document.
+pub fn render(rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&build(rows))?)
+}
+
+/// Assemble the structured report for every analyzed URL.
+pub fn build<'a>(rows: &'a [(String, Result<&'a AnalysisResult, String>)]) -> Report<'a> {
+    let results = rows
+        .iter()
+        .map(|(url, result)| match result {
+            Ok(result) => {
+                let score = calculate_privacy_score(result);
+                Entry::Success {
+                    result,
+                    privacy_score: score,
+                    rating: privacy_rating(score),
+                }
+            }
+            Err(e) => Entry::Failure { url, error: e },
+        })
+        .collect();
+
+    Report {
+        tool: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        summary: (rows.len() > 1).then(|| BatchSummary::from_rows(rows)),
+        results,
+    }
+}
@@ -0,0 +1,1340 @@
+//! GitHub-flavored Markdown report, suitable for PR comments and wiki pages.
+
+use crate::baseline::Baseline;
+use crate::batch::BatchSummary;
+use crate::config;
+use crate::dataflow::DataType;
+use crate::deprecation;
+use crate::https::HttpBehavior;
+use crate::prefixes::PrefixVerdict;
+use crate::output::narrative;
+use crate::remediation;
+use crate::vendors;
+use crate::tcf;
+use crate::variants::VariantFindings;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult};
+use std::fmt::Write;
+
+/// Render every analyzed URL as Markdown, followed by a comparison table
+/// when more than one site was scanned.
+pub fn render(rows: &[(String, Result<&AnalysisResult, String>)]) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Recon Privacy Report");
+
+    for (url, result) in rows {
+        let _ = writeln!(out);
+        match result {
+            Ok(result) => render_result(&mut out, result),
+            Err(e) => {
+                let _ = writeln!(out, "## {}", escape(url));
+                let _ = writeln!(out);
+                let _ = writeln!(out, "> **Error:** {}", escape(e));
+            }
+        }
+    }
+
+    if rows.len() > 1 {
+        let _ = writeln!(out);
+        render_comparison(&mut out, rows);
+    }
+
+    out
+}
+
+fn render_result(out: &mut String, result: &AnalysisResult) {
+    let score = calculate_privacy_score(result);
+
+    let _ = writeln!(out, "## {}", escape(&result.url));
+    let _ = writeln!(out);
+    if !result.redirect_chain.is_empty() {
+        let _ = writeln!(out, "| Hop | Status | URL | Cookies set |");
+        let _ = writeln!(out, "|----:|-------:|-----|-------------|");
+        for (i, hop) in result.redirect_chain.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                i + 1,
+                hop.status,
+                escape(&hop.url),
+                escape(&hop.cookies.join(", "))
+            );
+        }
+        let _ = writeln!(out);
+    }
+    if !result.client_redirects.is_empty() {
+        for redirect in &result.client_redirects {
+            let _ = writeln!(
+                out,
+                "Followed {} from {} to {}.",
+                redirect.kind.as_str(),
+                escape(&redirect.from),
+                escape(&redirect.to)
+            );
+        }
+        let _ = writeln!(out);
+    }
+    let _ = writeln!(
+        out,
+        "**Privacy score:** {}/100 ({})",
+        score,
+        privacy_rating(score)
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", narrative::summarize(result));
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Cookies | Trackers | 3rd Party Domains |");
+    let _ = writeln!(out, "|--------:|---------:|------------------:|");
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} |",
+        result.cookies.len(),
+        result.trackers.len(),
+        result.third_party_requests.len()
+    );
+    if result.pages.len() > 1 {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Findings merged across {} pages:",
+            result.pages.len()
+        );
+        let _ = writeln!(out);
+        for page in &result.pages {
+            let _ = writeln!(out, "- {}", escape(page));
+        }
+    }
+    if !result.robots_disallowed.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Skipped {} pages disallowed by robots.txt:",
+            result.robots_disallowed.len()
+        );
+        let _ = writeln!(out);
+        for page in &result.robots_disallowed {
+            let _ = writeln!(out, "- {}", escape(page));
+        }
+    }
+    if let Some(fallback) = &result.static_fallback {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "> **Static-only analysis:** the headless browser could not be used ({}).",
+            escape(&fallback.reason)
+        );
+        let _ = writeln!(out, "> Not detected: {}.", fallback.unavailable.join(", "));
+    }
+    if let Some(usage) = result.fetch_budget.as_ref().filter(|u| u.skipped > 0) {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "> **Fetch budget exhausted:** {} script or resource requests were skipped or cut short.",
+            usage.skipped
+        );
+    }
+    if let Some(report) = &result.sampling {
+        let _ = writeln!(out);
+        let jar = if report.fresh_jar { "a fresh" } else { "a shared" };
+        let _ = writeln!(
+            out,
+            "Findings merged across {} page loads with {} cookie jar.",
+            report.samples, jar
+        );
+        let spread = &report.score;
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "**Score range:** {}-{} (mean {:.1}, std dev {:.1}){}",
+            spread.min,
+            spread.max,
+            spread.mean,
+            spread.std_dev,
+            if spread.is_volatile() {
+                " - volatile, so single-run changes may be noise"
+            } else {
+                ""
+            }
+        );
+        if !report.intermittent.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Intermittent finding | Kind | Seen in |");
+            let _ = writeln!(out, "|----------------------|------|---------|");
+            for finding in &report.intermittent {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {}/{} |",
+                    escape(&finding.name),
+                    finding.kind,
+                    finding.seen_in,
+                    report.samples
+                );
+            }
+        }
+    }
+
+    if let Some(comparison) = &result.device_comparison {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Desktop vs. Mobile");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Scores: desktop {}, mobile {}.",
+            comparison.desktop.score, comparison.mobile.score
+        );
+        let _ = writeln!(out);
+        if comparison.desktop.is_empty() && comparison.mobile.is_empty() {
+            let _ = writeln!(
+                out,
+                "Both devices received the same cookies, trackers and third-party domains."
+            );
+        } else {
+            render_variant_findings(out, &[&comparison.desktop, &comparison.mobile]);
+        }
+    }
+    if let Some(comparison) = &result.locale_comparison {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Languages and Regions");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Accept-Language | Score | Consent banner | Proxy |");
+        let _ = writeln!(out, "|-----------------|-------|----------------|-------|");
+        for locale in &comparison.locales {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                escape(&locale.findings.variant),
+                locale.findings.score,
+                locale.consent_banner.map_or("unknown", yes_no),
+                locale.proxy.as_deref().map(escape).unwrap_or_else(|| "-".to_string())
+            );
+        }
+        let _ = writeln!(out);
+        let variants: Vec<&VariantFindings> =
+            comparison.locales.iter().map(|l| &l.findings).collect();
+        if variants.iter().all(|v| v.is_empty()) {
+            let _ = writeln!(
+                out,
+                "Every language received the same cookies, trackers and third-party domains."
+            );
+        } else {
+            render_variant_findings(out, &variants);
+        }
+    }
+
+    for test in &result.signal_tests {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### {}", test.signal.title());
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "**Verdict:** {} (tested with `{}`)",
+            test.verdict.as_str(),
+            test.signal.header()
+        );
+        if let Some(declared) = test.declared {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "**Declared by the site:** {}", yes_no(declared));
+        }
+        if !test.echoed.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "**Echoed:** {}", escape(&test.echoed.join("; ")));
+        }
+        if !test.still_tracking.is_empty() {
+            let _ = writeln!(out);
+            let names: Vec<String> = test
+                .still_tracking
+                .iter()
+                .map(|name| format!("`{}`", escape(name)))
+                .collect();
+            let _ = writeln!(out, "**Still tracking:** {}", names.join(", "));
+        }
+        if !(test.only_without.is_empty() && test.only_with.is_empty()) {
+            let _ = writeln!(out);
+            render_variant_findings(out, &[&test.only_without, &test.only_with]);
+        }
+    }
+
+    // Consent-requiring items active on first load
+    if !result.pre_consent.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Pre-Consent Violations");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Active on first load, before any consent choice (likely ePrivacy Art. 5(3) breach):"
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Severity | Kind | Name | Category | Vendor |");
+        let _ = writeln!(out, "|----------|------|------|----------|--------|");
+        for violation in &result.pre_consent {
+            let _ = writeln!(
+                out,
+                "| **{}** | {} | `{}` | {} | {} |",
+                violation.severity.as_str(),
+                violation.kind,
+                escape(&violation.name),
+                escape(&violation.category),
+                escape(violation.vendor.as_deref().unwrap_or("-"))
+            );
+        }
+    }
+
+    // Session replay tools
+    if !result.session_replay.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Session Replay");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "These tools record mouse movements, clicks, keystrokes and form content. \
+             Mask all form fields and load them only after consent."
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Vendor | Evidence |");
+        let _ = writeln!(out, "|--------|----------|");
+        for replay in &result.session_replay {
+            let _ = writeln!(
+                out,
+                "| {} | {} |",
+                escape(&replay.vendor),
+                escape(&replay.evidence.join(", "))
+            );
+        }
+    }
+
+    // Payment-card skimmers
+    if !result.skimmers.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Payment Skimmer Signatures");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Script | Confidence | Indicators |");
+        let _ = writeln!(out, "|--------|------------|------------|");
+        for skimmer in &result.skimmers {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} |",
+                escape(&skimmer.source),
+                skimmer.confidence.as_str(),
+                escape(&skimmer.indicators.join(", "))
+            );
+        }
+    }
+
+    // Cookies
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Cookies");
+    let _ = writeln!(out);
+    if result.cookies.is_empty() {
+        let _ = writeln!(out, "No cookies detected on initial page load.");
+    } else {
+        let _ = writeln!(
+            out,
+            "| Name | Category | Vendor | Domain | Secure | HttpOnly | SameSite | Lifespan | Unique ID |"
+        );
+        let _ = writeln!(
+            out,
+            "|------|----------|--------|--------|:------:|:--------:|----------|----------|:---------:|"
+        );
+        for cookie in &result.cookies {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} | {} | {} | {}{} | {} |",
+                escape(&cookie.name),
+                cookie.category.as_str(),
+                cookie
+                    .vendor
+                    .as_deref()
+                    .map(escape)
+                    .unwrap_or_else(|| "-".to_string()),
+                cookie
+                    .domain
+                    .as_deref()
+                    .map(escape)
+                    .unwrap_or_else(|| "-".to_string()),
+                yes_no(cookie.secure),
+                yes_no(cookie.http_only),
+                cookie
+                    .same_site
+                    .as_deref()
+                    .map(escape)
+                    .unwrap_or_else(|| "not set".to_string()),
+                cookie.lifespan(),
+                if cookie.is_long_lived() {
+                    " (over 12 months)"
+                } else {
+                    ""
+                },
+                yes_no(cookie.identifier),
+            );
+        }
+    }
+
+    // Cookie name prefixes
+    if !result.cookie_prefixes.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Cookie Prefixes");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Cookie | Finding | Needs |");
+        let _ = writeln!(out, "|--------|---------|-------|");
+        for advice in &result.cookie_prefixes {
+            let finding = match advice.verdict {
+                PrefixVerdict::Invalid => {
+                    format!("Breaks `{}` rules; browsers will not store it", advice.prefix)
+                }
+                PrefixVerdict::Candidate => format!("Rename to `{}{}`", advice.prefix, advice.cookie),
+            };
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} |",
+                escape(&advice.cookie),
+                finding,
+                if advice.problems.is_empty() {
+                    "-".to_string()
+                } else {
+                    escape(&advice.problems.join(", "))
+                }
+            );
+        }
+    }
+
+    // Cookie jar size per domain
+    if !result.cookie_sizes.is_empty() {
+        let budget = config::cookie_budget();
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Cookie Size");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Budget: {} cookies and {} bytes of `Cookie` header per domain.",
+            budget.max_per_domain, budget.max_header_bytes
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Domain | Cookies | Header bytes | Within budget |");
+        let _ = writeln!(out, "|--------|---------|--------------|---------------|");
+        for size in &result.cookie_sizes {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                escape(&size.domain),
+                size.count,
+                size.header_bytes,
+                yes_no(!size.over_budget())
+            );
+        }
+    }
+
+    // Trackers
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Trackers");
+    let _ = writeln!(out);
+    if result.trackers.is_empty() {
+        let _ = writeln!(out, "No known trackers detected.");
+    } else {
+        let _ = writeln!(out, "| Tracker | Category | Description | Confidence |");
+        let _ = writeln!(out, "|---------|----------|-------------|------------|");
+        for tracker in &result.trackers {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                escape(&tracker.name),
+                escape(&tracker.category),
+                escape(&tracker.description),
+                tracker.confidence.as_str()
+            );
+        }
+    }
+
+    if !result.suspected_pixels.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "#### Suspected Pixels");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Domain | Signs | URL |");
+        let _ = writeln!(out, "|--------|-------|-----|");
+        for pixel in &result.suspected_pixels {
+            let _ = writeln!(
+                out,
+                "| {} | {} | `{}` |",
+                escape(&pixel.domain),
+                escape(&pixel.reasons.join(", ")),
+                escape(&pixel.url)
+            );
+        }
+    }
+
+    // Copy-pasteable fixes for the trackers above
+    let fixes = remediation::for_trackers(&result.trackers);
+    if !fixes.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Remediation");
+        for (fix, covered) in fixes {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "**{}** ({})", fix.title, escape(&covered.join(", ")));
+            let _ = writeln!(out);
+            let _ = writeln!(out, "```html");
+            let _ = writeln!(out, "{}", fix.snippet);
+            let _ = writeln!(out, "```");
+        }
+    }
+
+    // Consent banner evidence (headless mode only)
+    if let Some(banner) = &result.consent_banner {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Consent Banner");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "**Platform:** {}",
+            escape(banner.cmp.as_deref().unwrap_or("Unrecognized"))
+        );
+        let _ = writeln!(out);
+        if !banner.buttons.is_empty() {
+            let choices: Vec<String> = banner
+                .buttons
+                .iter()
+                .map(|b| format!("`{}`", escape(b)))
+                .collect();
+            let _ = writeln!(out, "**Choices offered:** {}", choices.join(", "));
+            let _ = writeln!(out);
+        }
+        let _ = writeln!(out, "> {}", escape(&banner.text));
+        if let Some(path) = &banner.screenshot {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "![Consent banner]({})", path.display());
+        }
+    }
+
+    // Reject vs. accept differential (--consent-diff)
+    if let Some(error) = &result.consent_diff_error {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "> **Consent comparison unavailable:** {}",
+            escape(error)
+        );
+    }
+    if let Some(diff) = &result.consent_diff {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Consent: Reject vs. Accept");
+        let _ = writeln!(out);
+        let button = |b: &Option<String>| match b {
+            Some(label) => format!("`{}`", escape(label)),
+            None => "_no button found_".to_string(),
+        };
+        let _ = writeln!(out, "- **Reject:** {}", button(&diff.reject_button));
+        let _ = writeln!(out, "- **Accept:** {}", button(&diff.accept_button));
+        let _ = writeln!(out);
+        let deltas = [
+            ("Tracking after rejecting", &diff.tracking_after_reject),
+            ("Only after accepting", &diff.accept_only),
+            ("Only after rejecting", &diff.reject_only),
+        ];
+        if deltas.iter().all(|(_, d)| d.is_empty()) {
+            let _ = writeln!(out, "No differences between the two states.");
+        } else {
+            let _ = writeln!(out, "| State | Kind | Name | Detail |");
+            let _ = writeln!(out, "|-------|------|------|--------|");
+            for (state, delta) in deltas {
+                for cookie in &delta.cookies {
+                    let _ = writeln!(
+                        out,
+                        "| {} | Cookie | `{}` | {} ({}) |",
+                        state,
+                        escape(&cookie.name),
+                        escape(cookie.domain.as_deref().unwrap_or("-")),
+                        cookie.category.as_str()
+                    );
+                }
+                for item in &delta.storage {
+                    let _ = writeln!(
+                        out,
+                        "| {} | Storage | `{}` | {} ({}) |",
+                        state,
+                        escape(&item.key),
+                        item.area.as_str(),
+                        item.category.as_str()
+                    );
+                }
+                for host in &delta.hosts {
+                    let _ = writeln!(out, "| {} | Request | `{}` | - |", state, escape(host));
+                }
+            }
+        }
+    }
+
+    // Declared categories from the site's consent platform
+    if let Some(check) = &result.cmp_check {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### CMP Cookie Declarations");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{} declares {} cookies in `{}`.",
+            escape(&check.cmp),
+            check.declared_cookies,
+            escape(&check.source)
+        );
+        if !check.mismatches.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Cookie | Declared | Observed |");
+            let _ = writeln!(out, "|--------|----------|----------|");
+            for mismatch in &check.mismatches {
+                let warning = if mismatch.declared_necessary { " ⚠️" } else { "" };
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {}{} | {} |",
+                    escape(&mismatch.cookie),
+                    mismatch.declared.as_str(),
+                    warning,
+                    mismatch.observed.as_str()
+                );
+            }
+        }
+        if !check.undeclared.is_empty() {
+            let names: Vec<String> = check
+                .undeclared
+                .iter()
+                .map(|n| format!("`{}`", escape(n)))
+                .collect();
+            let _ = writeln!(out);
+            let _ = writeln!(out, "**Not declared:** {}", names.join(", "));
+        }
+    }
+
+    // IAB Transparency & Consent Framework
+    if let Some(tcf) = &result.tcf {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### IAB TCF");
+        let _ = writeln!(out);
+        let api = if tcf.api_present { "present" } else { "not found" };
+        let _ = writeln!(out, "- **`__tcfapi`:** {}", api);
+        match &tcf.consent {
+            Some(consent) => {
+                let cmp = match &consent.cmp_name {
+                    Some(name) => format!("{} (ID {})", escape(name), consent.cmp_id),
+                    None => format!("ID {}", consent.cmp_id),
+                };
+                let _ = writeln!(out, "- **CMP:** {} v{}", cmp, consent.cmp_version);
+                let _ = writeln!(
+                    out,
+                    "- **Vendors:** {} consented, {} legitimate interest (vendor list v{})",
+                    consent.vendors_consented,
+                    consent.vendors_legitimate_interest,
+                    consent.vendor_list_version
+                );
+                if consent.purposes_consented.is_empty() {
+                    let _ = writeln!(out, "- **Purposes consented on first load:** none");
+                } else {
+                    let _ = writeln!(
+                        out,
+                        "- **Purposes consented on first load:** ⚠️ {} of 10",
+                        consent.purposes_consented.len()
+                    );
+                    for &purpose in &consent.purposes_consented {
+                        let _ = writeln!(out, "  {}. {}", purpose, tcf::purpose_name(purpose));
+                    }
+                }
+            }
+            None => {
+                let _ = writeln!(out, "- **`euconsent-v2` cookie:** not set on first load");
+            }
+        }
+    }
+
+    // Declared vs. observed (--cookie-policy)
+    if let Some(policy) = &result.cookie_policy {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Cookie Policy");
+        let _ = writeln!(out);
+        match &policy.url {
+            Some(url) => {
+                let _ = writeln!(
+                    out,
+                    "[Cookie policy]({}) lists {} cookies.",
+                    url,
+                    policy.declared.len()
+                );
+            }
+            None => {
+                let _ = writeln!(out, "No cookie policy found.");
+            }
+        }
+        for (label, names) in [
+            ("Set but not declared", &policy.undeclared),
+            ("Declared but not seen", &policy.unobserved),
+        ] {
+            if !names.is_empty() {
+                let names: Vec<String> =
+                    names.iter().map(|n| format!("`{}`", escape(n))).collect();
+                let _ = writeln!(out);
+                let _ = writeln!(out, "**{}:** {}", label, names.join(", "));
+            }
+        }
+    }
+
+    // Web Storage (headless mode only)
+    if !result.storage.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Web Storage");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Key | Area | Category | Vendor | Value Length |");
+        let _ = writeln!(out, "|-----|------|----------|--------|-------------:|");
+        for item in &result.storage {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} |",
+                escape(&item.key),
+                item.area.as_str(),
+                item.category.as_str(),
+                escape(item.vendor.as_deref().unwrap_or("-")),
+                item.value_length
+            );
+        }
+    }
+
+    // Third-party domains
+    let _ = writeln!(out);
+    let _ = writeln!(out, "### Third-Party Domains");
+    let _ = writeln!(out);
+    if result.third_party_requests.is_empty() {
+        let _ = writeln!(out, "No third-party domains detected.");
+    } else {
+        let _ = writeln!(out, "| # | Domain |");
+        let _ = writeln!(out, "|--:|--------|");
+        for (i, domain) in result.third_party_requests.iter().enumerate() {
+            let related = result
+                .related_set
+                .as_ref()
+                .is_some_and(|set| set.contains(domain));
+            let note = if related { " (related website set)" } else { "" };
+            let _ = writeln!(out, "| {} | {}{} |", i + 1, escape(domain), note);
+        }
+    }
+
+    // Declared Related Website Set
+    if let Some(set) = &result.related_set {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Related Website Set");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Declared in `{}`.", escape(&set.source));
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Site | Role |");
+        let _ = writeln!(out, "|------|------|");
+        let _ = writeln!(out, "| {} | Primary |", escape(&set.primary));
+        for (role, sites) in [
+            ("Associated", &set.associated_sites),
+            ("Service", &set.service_sites),
+            ("ccTLD", &set.cctld_sites),
+        ] {
+            for site in sites {
+                let _ = writeln!(out, "| {} | {} |", escape(site), role);
+            }
+        }
+    }
+
+    // Second-order script dependencies
+    if !result.script_dependencies.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Script Dependencies");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Script | Loads | URL |");
+        let _ = writeln!(out, "|--------|-------|-----|");
+        for dependency in &result.script_dependencies {
+            let _ = writeln!(
+                out,
+                "| {} | {} | `{}` |",
+                escape(&dependency.script),
+                escape(&dependency.domain),
+                escape(&dependency.url)
+            );
+        }
+    }
+
+    // Click pings and beacons
+    if !result.beacons.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Pings and Beacons");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Kind | Destination | Third-party | Tracker | Found in | Count |");
+        let _ = writeln!(out, "|------|-------------|-------------|---------|----------|------:|");
+        for beacon in &result.beacons {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {} | {} |",
+                beacon.kind.as_str(),
+                escape(beacon.domain.as_deref().unwrap_or("(built at runtime)")),
+                yes_no(beacon.third_party),
+                escape(beacon.tracker.as_deref().unwrap_or("-")),
+                escape(&beacon.source),
+                beacon.count
+            );
+        }
+    }
+
+    // Preconnect, dns-prefetch and preload hints
+    if !result.resource_hints.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Resource Hints");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Hint | Host | Third-party | Tracker |");
+        let _ = writeln!(out, "|------|------|-------------|---------|");
+        for hint in &result.resource_hints {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                hint.rel,
+                escape(&hint.domain),
+                yes_no(hint.third_party),
+                escape(hint.tracker.as_deref().unwrap_or("-"))
+            );
+        }
+    }
+
+    // WebSocket endpoints
+    if !result.websockets.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### WebSockets");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Endpoint | Third-party | Tracker | Opened | Found in |");
+        let _ = writeln!(out, "|----------|-------------|---------|--------|----------|");
+        for endpoint in &result.websockets {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                escape(&endpoint.url),
+                yes_no(endpoint.third_party),
+                escape(endpoint.tracker.as_deref().unwrap_or("-")),
+                yes_no(endpoint.observed),
+                escape(&endpoint.source)
+            );
+        }
+    }
+
+    // Font services
+    if !result.remote_fonts.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Remote Fonts");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Service | Hosts | Note |");
+        let _ = writeln!(out, "|---------|-------|------|");
+        for service in &result.remote_fonts {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                escape(&service.service),
+                escape(&service.hosts.join(", ")),
+                if service.ruled_unlawful() {
+                    "Ruled a GDPR breach without consent (LG München I, 3 O 17493/20)"
+                } else {
+                    "Receives visitor IP addresses"
+                }
+            );
+        }
+    }
+
+    // The site's own scripts
+    if let Some(scripts) = result.script_inventory.as_ref().filter(|s| !s.is_empty()) {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### First-Party Scripts");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Path | Size | SHA-256 |");
+        let _ = writeln!(out, "|------|-----:|---------|");
+        for script in scripts {
+            let _ = writeln!(
+                out,
+                "| `{}` | {}{} | `{}` |",
+                escape(&script.path),
+                script.size,
+                if script.truncated { "+" } else { "" },
+                script.sha256
+            );
+        }
+    }
+
+    // Subresource Integrity
+    if let Some(report) = &result.integrity {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Subresource Integrity");
+        let _ = writeln!(out);
+        let _ = write!(
+            out,
+            "{}% of {} external scripts and stylesheets have an `integrity` hash",
+            report.coverage,
+            report.resources.len()
+        );
+        match report.third_party_coverage {
+            Some(third_party) => {
+                let _ = writeln!(out, " ({}% of third-party ones).", third_party);
+            }
+            None => {
+                let _ = writeln!(out, ".");
+            }
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Resource | Type | Third party | Integrity |");
+        let _ = writeln!(out, "|----------|------|:-----------:|:---------:|");
+        for resource in &report.resources {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                escape(&resource.url),
+                resource.kind.as_str(),
+                yes_no(resource.third_party),
+                yes_no(resource.integrity)
+            );
+        }
+    }
+
+    // Referrer leakage
+    if !result.referrer.exposures.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Referrer Leakage");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Document policy: `{}`{}",
+            result.referrer.document_policy,
+            if result.referrer.browser_default {
+                " (browser default)"
+            } else {
+                ""
+            }
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Domain | Receives | Policy | Personal data |");
+        let _ = writeln!(out, "|--------|----------|--------|:-------------:|");
+        for exposure in &result.referrer.exposures {
+            let _ = writeln!(
+                out,
+                "| {} | {} | `{}` | {} |",
+                escape(&exposure.domain),
+                exposure
+                    .referrer
+                    .as_deref()
+                    .map(|r| format!("`{}`", escape(r)))
+                    .unwrap_or_else(|| "nothing".to_string()),
+                exposure.policy,
+                if exposure.sensitive { "Yes" } else { "-" }
+            );
+        }
+    }
+
+    // Personal data in third-party query strings
+    if !result.pii_leaks.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Personal Data in Third-Party URLs");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Domain | Parameter | Type | Value |");
+        let _ = writeln!(out, "|--------|-----------|------|-------|");
+        for leak in &result.pii_leaks {
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} | `{}` |",
+                escape(&leak.domain),
+                escape(&leak.parameter),
+                leak.kind,
+                escape(&leak.redacted_value)
+            );
+        }
+    }
+
+    // Data type × recipient matrix for RoPA/DPIA records
+    if !result.data_flows.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Data Flows");
+        let _ = writeln!(out);
+        let columns: Vec<&str> = DataType::ALL.iter().map(|t| t.as_str()).collect();
+        let _ = writeln!(out, "| Recipient | {} |", columns.join(" | "));
+        let _ = writeln!(out, "|-----------|{}", ":---:|".repeat(columns.len()));
+        for flow in &result.data_flows {
+            let cells: Vec<&str> = DataType::ALL
+                .iter()
+                .map(|&t| if flow.receives(t) { "✓" } else { "" })
+                .collect();
+            let _ = writeln!(out, "| {} | {} |", escape(&flow.recipient), cells.join(" | "));
+        }
+    }
+
+    // Who is behind the findings, with their terms for DPO review
+    let vendors = vendors::detected(result);
+    if !vendors.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Vendors");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Vendor | Country | Privacy Policy | DPA |");
+        let _ = writeln!(out, "|--------|---------|----------------|-----|");
+        let link = |url: Option<&str>| match url {
+            Some(url) => format!("[link]({})", url),
+            None => "-".to_string(),
+        };
+        for vendor in vendors {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                escape(vendor.name),
+                vendor.country,
+                link(vendor.privacy_policy),
+                link(vendor.dpa)
+            );
+        }
+    }
+
+    // Privacy declarations under /.well-known
+    if let Some(report) = &result.well_known {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Well-Known Privacy Endpoints");
+        let _ = writeln!(out);
+        let gpc = match &report.gpc {
+            Some(gpc) if gpc.gpc => "supported",
+            Some(_) => "declared as not honored",
+            None => "not declared",
+        };
+        let _ = writeln!(out, "**Global Privacy Control:** {}", gpc);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Endpoint | Found | Status |");
+        let _ = writeln!(out, "|----------|-------|-------:|");
+        for endpoint in &report.endpoints {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} |",
+                escape(&endpoint.path),
+                yes_no(endpoint.found),
+                endpoint
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            );
+        }
+    }
+
+    // Cookies set again on a repeat visit
+    if let Some(refresh) = &result.cookie_refresh {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Cookie Refresh");
+        let _ = writeln!(out);
+        if refresh.is_empty() {
+            let _ = writeln!(out, "The page sets no cookies to compare.");
+        } else {
+            let _ = writeln!(out, "| Cookie | On a repeat visit | New value |");
+            let _ = writeln!(out, "|--------|-------------------|-----------|");
+            for cookie in refresh {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} |",
+                    escape(&cookie.name),
+                    cookie.behavior.as_str(),
+                    yes_no(cookie.value_changed)
+                );
+            }
+        }
+    }
+
+    // Connection security
+    if let Some(https) = &result.https {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### HTTPS");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Property | Value |");
+        let _ = writeln!(out, "|----------|-------|");
+        let http = match https.http_behavior {
+            HttpBehavior::Upgrades => "Redirects to HTTPS",
+            HttpBehavior::Plain => "Served over plain HTTP",
+            HttpBehavior::Unreachable => "Not served",
+        };
+        let _ = writeln!(out, "| {} | {} |", escape(&https.http_url), http);
+        let hsts = https.hsts.as_ref().map_or("None".to_string(), |h| {
+            format!(
+                "max-age {} days, includeSubDomains {}, preload {}",
+                h.max_age / 86_400,
+                yes_no(h.include_subdomains),
+                yes_no(h.preload)
+            )
+        });
+        let _ = writeln!(out, "| HSTS | {} |", hsts);
+        let _ = writeln!(out, "| Preload ready | {} |", yes_no(https.preload_ready));
+        if !https.issues.is_empty() {
+            let _ = writeln!(out);
+            for issue in &https.issues {
+                let _ = writeln!(out, "- ⚠️ {}", escape(issue));
+            }
+        }
+    }
+    if let Some(report) = &result.tls {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### TLS");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Property | Value |");
+        let _ = writeln!(out, "|----------|-------|");
+        if let Some(protocol) = &report.protocol {
+            let _ = writeln!(out, "| Protocol | {} |", protocol);
+        }
+        if let Some(cipher) = &report.cipher {
+            let _ = writeln!(out, "| Cipher | `{}` |", cipher);
+        }
+        let _ = writeln!(out, "| Trusted | {} |", yes_no(report.trusted));
+        if let Some(cert) = &report.certificate {
+            let _ = writeln!(out, "| Issuer | {} |", escape(&cert.issuer));
+            let _ = writeln!(
+                out,
+                "| Expires | {} ({} days left) |",
+                cert.not_after, cert.days_left
+            );
+            let names: Vec<String> = cert.san.iter().map(|n| format!("`{}`", escape(n))).collect();
+            let _ = writeln!(out, "| Names | {} |", names.join(", "));
+            if let Some(key) = &cert.key {
+                let _ = writeln!(out, "| Key | {} |", key);
+            }
+        }
+        if !report.issues.is_empty() {
+            let _ = writeln!(out);
+            for issue in &report.issues {
+                let _ = writeln!(out, "- ⚠️ {}", escape(issue));
+            }
+        }
+    }
+
+    // Third-party cookie phase-out
+    if let Some(verdicts) = &result.cookie_readiness {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Third-Party Cookie Readiness");
+        let _ = writeln!(out);
+        if verdicts.is_empty() {
+            let _ = writeln!(out, "No cookies observed.");
+        } else {
+            let broken = deprecation::broken_integrations(verdicts);
+            if !broken.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "**Integrations that will break:** {}",
+                    escape(&broken.join(", "))
+                );
+                let _ = writeln!(out);
+            }
+            let _ = writeln!(
+                out,
+                "| Cookie | Domain | Vendor | SameSite=None | Partitioned | Status | Action |"
+            );
+            let _ = writeln!(
+                out,
+                "|--------|--------|--------|---------------|-------------|--------|--------|"
+            );
+            for verdict in verdicts {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} | {} | {} | {} |",
+                    escape(&verdict.cookie),
+                    escape(&verdict.domain),
+                    escape(verdict.vendor.as_deref().unwrap_or("-")),
+                    yes_no(verdict.same_site_none),
+                    yes_no(verdict.partitioned),
+                    verdict.status.as_str(),
+                    escape(verdict.advice.as_deref().unwrap_or("-"))
+                );
+            }
+        }
+    }
+
+    // Regulatory checklist (--compliance)
+    if let Some(report) = &result.compliance {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### {} Compliance Checklist", report.title());
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Check | Requirement | Status | Detail | Reference |");
+        let _ = writeln!(out, "|-------|-------------|--------|--------|-----------|");
+        for check in &report.checks {
+            let _ = writeln!(
+                out,
+                "| {} | {} | **{}** | {} | {} |",
+                check.id,
+                check.title,
+                check.status.as_str(),
+                escape(&check.detail),
+                check.reference
+            );
+        }
+    }
+
+    // Deprecated privacy signals
+    if !result.legacy_headers.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "### Legacy Privacy Headers");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Header | Value | Note |");
+        let _ = writeln!(out, "|--------|-------|------|");
+        for header in &result.legacy_headers {
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} |",
+                escape(&header.name),
+                escape(&header.value),
+                escape(&header.note)
+            );
+        }
+    }
+}
+
+/// What each site gained and lost since the `--baseline` snapshot, in place
+/// of the full reports.
+pub fn render_baseline_diff(
+    baseline: &Baseline,
+    rows: &[(String, Result<&AnalysisResult, String>)],
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Recon Changes Since Baseline");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Baseline: `{}`", baseline.path.display());
+
+    for (url, result) in rows {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## {}", escape(url));
+        let _ = writeln!(out);
+        let analysis = match result {
+            Ok(analysis) => analysis,
+            Err(e) => {
+                let _ = writeln!(out, "> **Error:** {}", escape(e));
+                continue;
+            }
+        };
+        let diff = baseline.diff(analysis);
+        match diff.baseline_score {
+            Some(before) => {
+                let _ = writeln!(out, "**Privacy score:** {} (baseline {})", diff.privacy_score, before);
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "**Privacy score:** {} (not in the baseline)",
+                    diff.privacy_score
+                );
+            }
+        }
+        let _ = writeln!(out);
+        if diff.is_empty() {
+            let _ = writeln!(out, "No changes.");
+            continue;
+        }
+        let _ = writeln!(out, "| Change | Kind | Name | Category |");
+        let _ = writeln!(out, "|--------|------|------|----------|");
+        for finding in &diff.delta.added {
+            let _ = writeln!(
+                out,
+                "| Added | {} | {} | {} |",
+                finding.kind,
+                escape(&finding.rule),
+                escape(&finding.category)
+            );
+        }
+        for name in &diff.newly_insecure {
+            let _ = writeln!(out, "| Lost Secure | cookie | {} | |", escape(name));
+        }
+        for finding in &diff.delta.removed {
+            let _ = writeln!(
+                out,
+                "| Removed | {} | {} | {} |",
+                finding.kind,
+                escape(&finding.rule),
+                escape(&finding.category)
+            );
+        }
+        for script in &diff.delta.scripts {
+            let _ = writeln!(
+                out,
+                "| Script {} | script | {} | First-party |",
+                script.kind.as_str(),
+                escape(&script.path)
+            );
+        }
+    }
+    out
+}
+
+fn render_comparison(out: &mut String, rows: &[(String, Result<&AnalysisResult, String>)]) {
+    let _ = writeln!(out, "## Cross-Site Summary");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| URL | Score | Cookies | Trackers | 3rd Party |");
+    let _ = writeln!(out, "|-----|------:|--------:|---------:|----------:|");
+    for (url, result) in rows {
+        match result {
+            Ok(result) => {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    escape(url),
+                    calculate_privacy_score(result),
+                    result.cookies.len(),
+                    result.trackers.len(),
+                    result.third_party_requests.len()
+                );
+            }
+            Err(_) => {
+                let _ = writeln!(out, "| {} | failed | - | - | - |", escape(url));
+            }
+        }
+    }
+
+    let summary = BatchSummary::from_rows(rows);
+    let _ = writeln!(out);
+    if let Some(average) = summary.average_score {
+        let _ = writeln!(
+            out,
+            "- **Average score:** {:.0}/100 across {} sites",
+            average,
+            summary.sites - summary.failed
+        );
+    }
+    if let (Some(best), Some(worst)) = (&summary.best, &summary.worst) {
+        let _ = writeln!(out, "- **Best:** {} ({})", escape(&best.0), best.1);
+        let _ = writeln!(out, "- **Worst:** {} ({})", escape(&worst.0), worst.1);
+    }
+    if summary.failed > 0 {
+        let _ = writeln!(out, "- **Failed:** {}", summary.failed);
+    }
+    if !summary.common_trackers.is_empty() {
+        let _ = writeln!(out, "- **Most common trackers:**");
+        for (tracker, sites) in &summary.common_trackers {
+            let _ = writeln!(
+                out,
+                "  - {} ({} of {} sites)",
+                escape(tracker),
+                sites,
+                summary.sites - summary.failed
+            );
+        }
+    }
+}
+
+/// Table of the findings only one variant of the page received.
+fn render_variant_findings(out: &mut String, variants: &[&VariantFindings]) {
+    let _ = writeln!(out, "| Only on | Finding | Kind |");
+    let _ = writeln!(out, "|---------|---------|------|");
+    for variant in variants {
+        let lists = [
+            ("cookie", &variant.cookies),
+            ("tracker", &variant.trackers),
+            ("third-party domain", &variant.third_party_domains),
+        ];
+        for (kind, names) in lists {
+            for name in names {
+                let _ = writeln!(
+                    out,
+                    "| {} | `{}` | {} |",
+                    escape(&variant.variant),
+                    escape(name),
+                    kind
+                );
+            }
+        }
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "Yes"
+    } else {
+        "No"
+    }
+}
+
+/// Escape characters that would break a Markdown table cell.
+fn escape(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
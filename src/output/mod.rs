@@ -0,0 +1,33 @@
+//! Report rendering for the supported output formats.
+
+pub mod annotate;
+pub mod executive;
+pub mod graph;
+pub mod json;
+pub mod markdown;
+pub mod narrative;
+pub mod pdf;
+pub mod sarif;
+pub mod sink;
+pub mod summary;
+pub mod terminal;
+pub mod yaml;
+
+use clap::ValueEnum;
+
+/// Output format for analysis reports
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored terminal report
+    Text,
+    /// GitHub-flavored Markdown tables
+    Markdown,
+    /// Machine-readable JSON document
+    Json,
+    /// SARIF 2.1.0 log for code-scanning pipelines
+    Sarif,
+    /// YAML document with the same structure as the JSON output
+    Yaml,
+    /// Compact JSON with only headline metrics, for dashboards
+    SummaryJson,
+}
@@ -0,0 +1,185 @@
+//! A short plain-language summary of one site's findings, for readers who do
+//! not know what a tracker or a SameSite attribute is. Each sentence comes
+//! from a template and is left out when its finding is absent.
+
+use crate::preconsent::PreConsentViolation;
+use crate::vendors;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult, CookieCategory};
+use std::collections::BTreeSet;
+
+/// "1 cookie", "3 cookies".
+fn count(n: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", n, if n == 1 { singular } else { plural })
+}
+
+/// "a", "a and b", "a, b and c".
+fn join_and(parts: &[String]) -> String {
+    match parts {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn is_advertising(violation: &PreConsentViolation) -> bool {
+    violation.category.starts_with("Marketing")
+}
+
+/// The summary paragraph for `result`.
+pub fn summarize(result: &AnalysisResult) -> String {
+    let mut sentences = Vec::new();
+
+    let companies = vendors::detected(result).len();
+    let domains = result.third_party_requests.len();
+    sentences.push(match (companies, domains) {
+        (0, 0) => "This site loads nothing from other websites.".to_string(),
+        (_, 0) => format!(
+            "This site shares visitor data with {}.",
+            count(companies, "company", "companies")
+        ),
+        (0, _) => format!(
+            "This site loads content from {}, none of them a known tracking company.",
+            count(domains, "other website", "other websites")
+        ),
+        _ => format!(
+            "This site shares visitor data with {} and loads content from {}.",
+            count(companies, "company", "companies"),
+            count(domains, "other website", "other websites")
+        ),
+    });
+
+    let by_category = |category: CookieCategory| {
+        result
+            .cookies
+            .iter()
+            .filter(|c| c.category == category)
+            .count()
+    };
+    if result.cookies.is_empty() {
+        sentences.push("It sets no cookies.".to_string());
+    } else {
+        let mut purposes = Vec::new();
+        let advertising = by_category(CookieCategory::Marketing);
+        if advertising > 0 {
+            purposes.push(format!("{} for advertising", advertising));
+        }
+        let analytics = by_category(CookieCategory::Analytics);
+        if analytics > 0 {
+            purposes.push(format!("{} for measuring visits", analytics));
+        }
+        let social = by_category(CookieCategory::Social);
+        if social > 0 {
+            purposes.push(format!("{} for social networks", social));
+        }
+        let purposes = if purposes.is_empty() {
+            String::new()
+        } else {
+            format!(", {}", join_and(&purposes))
+        };
+        sentences.push(format!(
+            "It sets {}{}.",
+            count(result.cookies.len(), "cookie", "cookies"),
+            purposes
+        ));
+    }
+
+    if !result.pre_consent.is_empty() {
+        let stored = |v: &&PreConsentViolation| v.kind != "tracker";
+        let advertising = result
+            .pre_consent
+            .iter()
+            .filter(stored)
+            .filter(|v| is_advertising(v))
+            .count();
+        let other = result.pre_consent.iter().filter(stored).count() - advertising;
+        let trackers = result.pre_consent.len() - advertising - other;
+        let mut parts = Vec::new();
+        if advertising > 0 {
+            parts.push(count(
+                advertising,
+                "advertising cookie",
+                "advertising cookies",
+            ));
+        }
+        if other > 0 {
+            parts.push(count(
+                other,
+                "other tracking cookie",
+                "other tracking cookies",
+            ));
+        }
+        if trackers > 0 {
+            parts.push(count(trackers, "tracker", "trackers"));
+        }
+        sentences.push(format!(
+            "It uses {} before visitors have agreed to them, which EU privacy rules generally do not allow.",
+            join_and(&parts)
+        ));
+    }
+
+    let recognizers = result
+        .cookies
+        .iter()
+        .filter(|c| c.identifier && c.is_long_lived())
+        .count();
+    if recognizers > 0 {
+        sentences.push(format!(
+            "Of its cookies, {} can recognize a returning visitor for more than a year.",
+            recognizers
+        ));
+    }
+
+    if !result.pii_leaks.is_empty() {
+        let kinds: BTreeSet<&str> = result.pii_leaks.iter().map(|l| l.kind.as_str()).collect();
+        let kinds: Vec<String> = kinds
+            .into_iter()
+            .map(|kind| match kind {
+                "email" => "email addresses".to_string(),
+                "phone" => "phone numbers".to_string(),
+                "name" => "names".to_string(),
+                other => other.to_string(),
+            })
+            .collect();
+        let recipients: BTreeSet<&str> =
+            result.pii_leaks.iter().map(|l| l.domain.as_str()).collect();
+        sentences.push(format!(
+            "Personal details such as {} are sent to {} in web addresses.",
+            join_and(&kinds),
+            count(recipients.len(), "other website", "other websites")
+        ));
+    }
+
+    if !result.session_replay.is_empty() {
+        let vendors: Vec<String> = result
+            .session_replay
+            .iter()
+            .map(|r| r.vendor.clone())
+            .collect();
+        sentences.push(format!(
+            "It records visits with {}, which can capture what visitors type into forms.",
+            join_and(&vendors)
+        ));
+    }
+
+    if result.remote_fonts.iter().any(|f| f.ruled_unlawful()) {
+        sentences.push(
+            "It loads fonts from Google, which tells Google each visitor's IP address; a German court has ruled this unlawful without consent."
+                .to_string(),
+        );
+    }
+
+    if !result.skimmers.is_empty() {
+        sentences.push(
+            "Scripts on the page look like payment-card skimmers and should be checked right away."
+                .to_string(),
+        );
+    }
+
+    let score = calculate_privacy_score(result);
+    sentences.push(format!(
+        "Overall it scores {} out of 100, rated {}.",
+        score,
+        privacy_rating(score)
+    ));
+    sentences.join(" ")
+}
@@ -0,0 +1,459 @@
+//! Paginated PDF report for client deliverables.
+
+use crate::output::narrative;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult, CookieCategory};
+use anyhow::{Context, Result};
+use printpdf::path::PaintMode;
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Rect, Rgb,
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+
+/// Points to millimetres
+const PT: f32 = 0.3528;
+
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+    page: usize,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let writer = PdfWriter {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+            page: 1,
+        };
+        writer.footer();
+        Ok(writer)
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.page += 1;
+        self.footer();
+    }
+
+    fn footer(&self) {
+        self.set_color(120, 120, 120);
+        self.layer.use_text(
+            format!("Recon {} - page {}", env!("CARGO_PKG_VERSION"), self.page),
+            8.0,
+            Mm(MARGIN),
+            Mm(MARGIN / 2.0),
+            &self.regular,
+        );
+        self.set_color(0, 0, 0);
+    }
+
+    /// Start a new page if fewer than `height` millimetres remain.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn set_color(&self, r: u8, g: u8, b: u8) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            None,
+        )));
+    }
+
+    fn line_height(size: f32) -> f32 {
+        size * PT * 1.45
+    }
+
+    fn text_at(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(sanitize(text), size, Mm(x), Mm(self.y), font);
+    }
+
+    /// Write a paragraph, wrapping it to the page width.
+    fn paragraph(&mut self, text: &str, size: f32, indent: f32, bold: bool) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        for line in wrap(text, max_chars(width, size)) {
+            self.ensure_space(Self::line_height(size));
+            self.y -= Self::line_height(size);
+            self.text_at(&line, size, MARGIN + indent, bold);
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(20.0);
+        self.y -= 6.0;
+        self.paragraph(text, 13.0, 0.0, true);
+        self.y -= 1.5;
+        self.set_color(210, 170, 120);
+        self.layer.add_rect(
+            Rect::new(
+                Mm(MARGIN),
+                Mm(self.y - 0.4),
+                Mm(PAGE_WIDTH - MARGIN),
+                Mm(self.y),
+            )
+            .with_mode(PaintMode::Fill),
+        );
+        self.set_color(0, 0, 0);
+        self.y -= 1.5;
+    }
+
+    /// Write one table row; each cell is truncated to its column width.
+    fn row(&mut self, cells: &[&str], columns: &[f32], bold: bool) {
+        let size = 9.0;
+        self.ensure_space(Self::line_height(size));
+        self.y -= Self::line_height(size);
+        let mut x = MARGIN;
+        for (cell, width) in cells.iter().zip(columns) {
+            let limit = max_chars(*width - 2.0, size);
+            let cell = if cell.chars().count() > limit {
+                format!(
+                    "{}...",
+                    cell.chars()
+                        .take(limit.saturating_sub(3))
+                        .collect::<String>()
+                )
+            } else {
+                cell.to_string()
+            };
+            self.text_at(&cell, size, x, bold);
+            x += width;
+        }
+    }
+
+    fn score_bar(&mut self, score: u32) {
+        let (r, g, b) = match score {
+            90..=100 => (76, 175, 80),
+            50..=89 => (230, 180, 40),
+            _ => (211, 47, 47),
+        };
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        self.ensure_space(10.0);
+        self.y -= 7.0;
+
+        self.set_color(225, 225, 225);
+        self.layer.add_rect(
+            Rect::new(Mm(MARGIN), Mm(self.y), Mm(MARGIN + width), Mm(self.y + 4.0))
+                .with_mode(PaintMode::Fill),
+        );
+        self.set_color(r, g, b);
+        self.layer.add_rect(
+            Rect::new(
+                Mm(MARGIN),
+                Mm(self.y),
+                Mm(MARGIN + width * score as f32 / 100.0),
+                Mm(self.y + 4.0),
+            )
+            .with_mode(PaintMode::Fill),
+        );
+        self.set_color(0, 0, 0);
+        self.y -= 2.0;
+    }
+
+    fn save(self, path: &Path) -> Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create PDF report {}", path.display()))?;
+        self.doc.save(&mut BufWriter::new(file))?;
+        Ok(())
+    }
+}
+
+/// Render one section per analyzed site into a PDF at `path`.
+pub fn write(path: &Path, rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<()> {
+    let mut pdf = PdfWriter::new("Recon Privacy Report")?;
+
+    for (i, (url, result)) in rows.iter().enumerate() {
+        if i > 0 {
+            pdf.new_page();
+        }
+
+        pdf.paragraph("Recon Privacy Report", 20.0, 0.0, true);
+        pdf.y -= 2.0;
+        pdf.paragraph(url, 11.0, 0.0, false);
+
+        match result {
+            Ok(result) => write_result(&mut pdf, result),
+            Err(e) => {
+                pdf.heading("Scan Failed");
+                pdf.paragraph(&format!("Error analyzing URL: {}", e), 10.0, 0.0, false);
+            }
+        }
+    }
+
+    pdf.save(path)
+}
+
+fn write_result(pdf: &mut PdfWriter, result: &AnalysisResult) {
+    let score = calculate_privacy_score(result);
+
+    pdf.heading("Summary");
+    pdf.paragraph(&narrative::summarize(result), 11.0, 0.0, false);
+
+    pdf.heading("Privacy Score");
+    pdf.paragraph(
+        &format!("{}/100 - {}", score, privacy_rating(score)),
+        16.0,
+        0.0,
+        true,
+    );
+    pdf.score_bar(score);
+    pdf.paragraph(
+        &format!(
+            "{} cookies, {} trackers and {} third-party domains detected.",
+            result.cookies.len(),
+            result.trackers.len(),
+            result.third_party_requests.len()
+        ),
+        10.0,
+        0.0,
+        false,
+    );
+
+    // Cookie table
+    pdf.heading("Cookies");
+    if result.cookies.is_empty() {
+        pdf.paragraph(
+            "No cookies detected on initial page load.",
+            10.0,
+            0.0,
+            false,
+        );
+    } else {
+        let columns = [44.0, 24.0, 40.0, 18.0, 20.0, 28.0];
+        pdf.row(
+            &[
+                "Name", "Category", "Vendor", "Secure", "HttpOnly", "SameSite",
+            ],
+            &columns,
+            true,
+        );
+        for cookie in &result.cookies {
+            pdf.row(
+                &[
+                    &cookie.name,
+                    cookie.category.as_str(),
+                    cookie.vendor.as_deref().unwrap_or("-"),
+                    yes_no(cookie.secure),
+                    yes_no(cookie.http_only),
+                    cookie.same_site.as_deref().unwrap_or("not set"),
+                ],
+                &columns,
+                false,
+            );
+        }
+    }
+
+    // Tracker list
+    pdf.heading("Trackers");
+    if result.trackers.is_empty() {
+        pdf.paragraph("No known trackers detected.", 10.0, 0.0, false);
+    } else {
+        for tracker in &result.trackers {
+            pdf.paragraph(
+                &format!(
+                    "[{}] {} ({})",
+                    tracker.category, tracker.description, tracker.name
+                ),
+                10.0,
+                2.0,
+                false,
+            );
+        }
+    }
+
+    // First-layer consent choices, kept as evidence alongside the screenshot
+    if let Some(banner) = &result.consent_banner {
+        pdf.heading("Consent Banner");
+        pdf.paragraph(
+            &format!(
+                "Platform: {}",
+                banner.cmp.as_deref().unwrap_or("Unrecognized")
+            ),
+            10.0,
+            0.0,
+            false,
+        );
+        if !banner.buttons.is_empty() {
+            pdf.paragraph(
+                &format!("Choices offered: {}", banner.buttons.join(" | ")),
+                10.0,
+                0.0,
+                false,
+            );
+        }
+        pdf.paragraph(&banner.text, 9.0, 2.0, false);
+        if let Some(path) = &banner.screenshot {
+            pdf.paragraph(
+                &format!("Screenshot: {}", path.display()),
+                9.0,
+                0.0,
+                false,
+            );
+        }
+    }
+
+    pdf.heading("Third-Party Domains");
+    if result.third_party_requests.is_empty() {
+        pdf.paragraph("No third-party domains detected.", 10.0, 0.0, false);
+    } else {
+        pdf.paragraph(&result.third_party_requests.join(", "), 10.0, 0.0, false);
+    }
+
+    pdf.heading("Recommendations");
+    for recommendation in recommendations(result) {
+        pdf.paragraph(&format!("- {}", recommendation), 10.0, 2.0, false);
+    }
+}
+
+/// Turn the findings into a short list of next steps for the site owner.
+fn recommendations(result: &AnalysisResult) -> Vec<String> {
+    let mut items = Vec::new();
+
+    let tracker_names = |categories: &[&str]| -> Vec<String> {
+        result
+            .trackers
+            .iter()
+            .filter(|t| categories.contains(&t.category.as_str()))
+            .map(|t| t.description.clone())
+            .collect()
+    };
+
+    let marketing = tracker_names(&["Marketing", "Marketing/CRM"]);
+    if !marketing.is_empty() {
+        items.push(format!(
+            "Load marketing and advertising tags only after the visitor has opted in: {}.",
+            marketing.join(", ")
+        ));
+    }
+
+    let analytics = tracker_names(&["Analytics"]);
+    if !analytics.is_empty() {
+        items.push(format!(
+            "Gate analytics behind consent or switch to a cookieless configuration: {}.",
+            analytics.join(", ")
+        ));
+    }
+
+    let non_essential: Vec<&str> = result
+        .cookies
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.category,
+                CookieCategory::Marketing | CookieCategory::Analytics | CookieCategory::Social
+            )
+        })
+        .map(|c| c.name.as_str())
+        .collect();
+    if !non_essential.is_empty() {
+        items.push(format!(
+            "Do not set non-essential cookies on the first page load: {}.",
+            non_essential.join(", ")
+        ));
+    }
+
+    let insecure: Vec<&str> = result
+        .cookies
+        .iter()
+        .filter(|c| !c.secure)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !insecure.is_empty() {
+        items.push(format!(
+            "Add the Secure attribute so these cookies are never sent over plain HTTP: {}.",
+            insecure.join(", ")
+        ));
+    }
+
+    let readable_sessions: Vec<&str> = result
+        .cookies
+        .iter()
+        .filter(|c| c.category == CookieCategory::Essential && !c.http_only)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !readable_sessions.is_empty() {
+        items.push(format!(
+            "Mark session and authentication cookies HttpOnly: {}.",
+            readable_sessions.join(", ")
+        ));
+    }
+
+    if result.third_party_requests.len() > 15 {
+        items.push(format!(
+            "Review the {} third-party domains; each one receives the visitor's IP address and browser details.",
+            result.third_party_requests.len()
+        ));
+    }
+
+    if items.is_empty() {
+        items.push("No issues found. Re-scan after each release to keep it that way.".to_string());
+    }
+
+    items
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "Yes"
+    } else {
+        "No"
+    }
+}
+
+/// Approximate Helvetica capacity of a line `width` millimetres wide.
+fn max_chars(width: f32, size: f32) -> usize {
+    (width / (size * PT * 0.5)).max(1.0) as usize
+}
+
+/// Split `text` into lines of at most `max` characters, breaking at spaces.
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// The built-in PDF fonts only cover Latin-1, so replace anything else.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| if (c as u32) < 0x100 { c } else { '?' })
+        .collect()
+}
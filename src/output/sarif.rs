@@ -0,0 +1,522 @@
+//! SARIF 2.1.0 log for code-scanning and vulnerability management pipelines.
+
+use crate::prefixes::PrefixVerdict;
+use crate::{AnalysisResult, CookieCategory, CookieInfo, TrackerInfo};
+use anyhow::Result;
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// (rule id, name, short description, SARIF level, security-severity)
+const RULES: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "RECON001",
+        "MarketingTracker",
+        "Marketing or advertising tracker loaded by the page",
+        "error",
+        "7.0",
+    ),
+    (
+        "RECON002",
+        "AnalyticsTracker",
+        "Analytics tracker loaded by the page",
+        "warning",
+        "5.0",
+    ),
+    (
+        "RECON003",
+        "SocialTracker",
+        "Social network integration loaded by the page",
+        "warning",
+        "4.0",
+    ),
+    (
+        "RECON004",
+        "ThirdPartyService",
+        "Other third-party service loaded by the page",
+        "note",
+        "2.0",
+    ),
+    (
+        "RECON005",
+        "SuspectedTrackingPixel",
+        "Image shaped like a tracking pixel from a host no tracker rule matches",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON006",
+        "ThirdPartyBeacon",
+        "Hyperlink ping or sendBeacon call sends requests to a third party",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON007",
+        "TrackerResourceHint",
+        "Resource hint preconnects to or prefetches from a known tracker",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON008",
+        "ThirdPartyWebSocket",
+        "Page connects or refers to a third-party WebSocket endpoint",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON009",
+        "RemoteFontService",
+        "Web fonts are loaded from a font service, which receives visitor IP addresses",
+        "warning",
+        "5.0",
+    ),
+    (
+        "RECON101",
+        "CookieWithoutSecure",
+        "Cookie is set without the Secure attribute",
+        "warning",
+        "5.0",
+    ),
+    (
+        "RECON102",
+        "CookieWithoutHttpOnly",
+        "Essential cookie is readable from JavaScript (no HttpOnly attribute)",
+        "warning",
+        "4.0",
+    ),
+    (
+        "RECON103",
+        "CookieSameSiteNoneWithoutSecure",
+        "Cookie uses SameSite=None without the Secure attribute",
+        "error",
+        "6.5",
+    ),
+    (
+        "RECON104",
+        "CookieWithoutSameSite",
+        "Cookie does not declare a SameSite policy",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON105",
+        "LongLivedCookie",
+        "Cookie persists longer than 12 months",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON106",
+        "InvalidCookiePrefix",
+        "Cookie uses the __Host- or __Secure- prefix without meeting its requirements",
+        "warning",
+        "5.0",
+    ),
+    (
+        "RECON107",
+        "CookiePrefixCandidate",
+        "Session cookie could use the __Host- or __Secure- prefix",
+        "note",
+        "3.0",
+    ),
+    (
+        "RECON108",
+        "CookieBudgetExceeded",
+        "Domain receives more cookies or a larger Cookie header than the budget allows",
+        "warning",
+        "3.0",
+    ),
+    (
+        "RECON201",
+        "PersonalDataInThirdPartyUrl",
+        "Personal data sent to a third party in a query string",
+        "error",
+        "8.0",
+    ),
+    (
+        "RECON202",
+        "PreConsentTracking",
+        "Cookie or tracker that needs consent is active before any consent choice",
+        "error",
+        "8.5",
+    ),
+    (
+        "RECON203",
+        "PaymentSkimmerSignature",
+        "Script matches payment-card skimmer signatures",
+        "error",
+        "9.5",
+    ),
+    (
+        "RECON204",
+        "SessionReplay",
+        "Session replay tool records keystrokes and form content",
+        "error",
+        "7.5",
+    ),
+    (
+        "RECON301",
+        "TrackerDeclaredNecessary",
+        "Tracking cookie declared strictly necessary in the consent platform",
+        "error",
+        "7.5",
+    ),
+    (
+        "RECON302",
+        "CookieCategoryMismatch",
+        "Cookie's declared consent category does not match what it does",
+        "warning",
+        "4.0",
+    ),
+    (
+        "RECON401",
+        "PlainHttpServed",
+        "Site is served over plain HTTP without redirecting to HTTPS",
+        "warning",
+        "5.0",
+    ),
+    (
+        "RECON402",
+        "MissingHsts",
+        "Page is not served with a Strict-Transport-Security header",
+        "note",
+        "3.0",
+    ),
+];
+
+/// Serialize every analyzed URL into a single SARIF log with one run.
+pub fn render(rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<String> {
+    let mut results = Vec::new();
+    let mut notifications = Vec::new();
+
+    for (url, result) in rows {
+        match result {
+            Ok(result) => {
+                for tracker in &result.trackers {
+                    results.push(tracker_result(&result.url, tracker));
+                }
+                for pixel in &result.suspected_pixels {
+                    results.push(finding(
+                        &result.url,
+                        "RECON005",
+                        format!(
+                            "Suspected tracking pixel from {} ({}): {}",
+                            pixel.domain,
+                            pixel.reasons.join(", "),
+                            pixel.url
+                        ),
+                    ));
+                }
+                for beacon in result.beacons.iter().filter(|b| b.third_party) {
+                    results.push(finding(
+                        &result.url,
+                        "RECON006",
+                        format!(
+                            "{} {} sends requests to {} ({} found in {})",
+                            beacon.count,
+                            beacon.kind.as_str(),
+                            beacon.domain.as_deref().unwrap_or(""),
+                            beacon.tracker.as_deref().unwrap_or("no tracker rule"),
+                            beacon.source
+                        ),
+                    ));
+                }
+                for hint in result
+                    .resource_hints
+                    .iter()
+                    .filter(|h| h.third_party && h.tracker.is_some())
+                {
+                    results.push(finding(
+                        &result.url,
+                        "RECON007",
+                        format!(
+                            "{} hint to {} ({})",
+                            hint.rel,
+                            hint.domain,
+                            hint.tracker.as_deref().unwrap_or("")
+                        ),
+                    ));
+                }
+                for endpoint in result.websockets.iter().filter(|e| e.third_party) {
+                    results.push(finding(
+                        &result.url,
+                        "RECON008",
+                        format!(
+                            "WebSocket to {} ({}, {})",
+                            endpoint.domain,
+                            endpoint.tracker.as_deref().unwrap_or("no tracker rule"),
+                            if endpoint.observed {
+                                "opened in the browser".to_string()
+                            } else {
+                                format!("found in {}", endpoint.source)
+                            }
+                        ),
+                    ));
+                }
+                for service in &result.remote_fonts {
+                    results.push(finding(
+                        &result.url,
+                        "RECON009",
+                        format!(
+                            "Fonts loaded from {} ({}){}",
+                            service.service,
+                            service.hosts.join(", "),
+                            if service.ruled_unlawful() {
+                                "; ruled a GDPR breach without consent by LG München I"
+                            } else {
+                                ""
+                            }
+                        ),
+                    ));
+                }
+                for cookie in &result.cookies {
+                    results.extend(cookie_results(&result.url, cookie));
+                }
+                for advice in &result.cookie_prefixes {
+                    let (rule_id, message) = match advice.verdict {
+                        PrefixVerdict::Invalid => (
+                            "RECON106",
+                            format!(
+                                "Cookie '{}' uses the {} prefix but browsers reject it: {}",
+                                advice.cookie,
+                                advice.prefix,
+                                advice.problems.join(", ")
+                            ),
+                        ),
+                        PrefixVerdict::Candidate => (
+                            "RECON107",
+                            format!(
+                                "Session cookie '{}' could be renamed with the {} prefix",
+                                advice.cookie, advice.prefix
+                            ),
+                        ),
+                    };
+                    results.push(finding(&result.url, rule_id, message));
+                }
+                for size in result.cookie_sizes.iter().filter(|s| s.over_budget()) {
+                    results.push(finding(
+                        &result.url,
+                        "RECON108",
+                        format!(
+                            "Requests to {} carry {} cookies in a {}-byte Cookie header",
+                            size.domain, size.count, size.header_bytes
+                        ),
+                    ));
+                }
+                for leak in &result.pii_leaks {
+                    results.push(finding(
+                        &result.url,
+                        "RECON201",
+                        format!(
+                            "{} receives what looks like a {} in the '{}' parameter",
+                            leak.domain, leak.kind, leak.parameter
+                        ),
+                    ));
+                }
+                for violation in &result.pre_consent {
+                    results.push(finding(
+                        &result.url,
+                        "RECON202",
+                        format!(
+                            "{} {} '{}' is active before consent ({} severity)",
+                            violation.category,
+                            violation.kind,
+                            violation.name,
+                            violation.severity.as_str().to_lowercase()
+                        ),
+                    ));
+                }
+                for skimmer in &result.skimmers {
+                    results.push(finding(
+                        &result.url,
+                        "RECON203",
+                        format!(
+                            "{} matches payment skimmer signatures ({} confidence): {}",
+                            skimmer.source,
+                            skimmer.confidence.as_str().to_lowercase(),
+                            skimmer.indicators.join(", ")
+                        ),
+                    ));
+                }
+                for replay in &result.session_replay {
+                    results.push(finding(
+                        &result.url,
+                        "RECON204",
+                        format!(
+                            "{} session replay is loaded: {}",
+                            replay.vendor,
+                            replay.evidence.join(", ")
+                        ),
+                    ));
+                }
+                for mismatch in result.cmp_check.iter().flat_map(|c| &c.mismatches) {
+                    let rule_id = if mismatch.declared_necessary {
+                        "RECON301"
+                    } else {
+                        "RECON302"
+                    };
+                    results.push(finding(
+                        &result.url,
+                        rule_id,
+                        format!(
+                            "Cookie '{}' is declared {} but looks like {}",
+                            mismatch.cookie,
+                            mismatch.declared.as_str(),
+                            mismatch.observed.as_str()
+                        ),
+                    ));
+                }
+                if let Some(https) = &result.https {
+                    if https.serves_plain_http() {
+                        results.push(finding(
+                            &result.url,
+                            "RECON401",
+                            format!("{} is served without redirecting to HTTPS", https.http_url),
+                        ));
+                    }
+                    if !https.has_hsts() {
+                        results.push(finding(
+                            &result.url,
+                            "RECON402",
+                            "No Strict-Transport-Security header".to_string(),
+                        ));
+                    }
+                }
+            }
+            Err(e) => notifications.push(json!({
+                "level": "error",
+                "message": { "text": format!("Error analyzing {}: {}", url, e) },
+            })),
+        }
+    }
+
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, name, description, level, severity)| {
+            json!({
+                "id": id,
+                "name": name,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+                "properties": {
+                    "security-severity": severity,
+                    "tags": ["privacy"],
+                },
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": notifications.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }],
+    });
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+fn tracker_result(url: &str, tracker: &TrackerInfo) -> Value {
+    let rule_id = match tracker.category.as_str() {
+        "Marketing" | "Marketing/CRM" => "RECON001",
+        "Analytics" => "RECON002",
+        "Social" => "RECON003",
+        _ => "RECON004",
+    };
+    let mut result = finding(
+        url,
+        rule_id,
+        format!(
+            "{} tracker detected: {} ({})",
+            tracker.category, tracker.description, tracker.name
+        ),
+    );
+    result["properties"] = json!({ "confidence": tracker.confidence });
+    result
+}
+
+fn cookie_results(url: &str, cookie: &CookieInfo) -> Vec<Value> {
+    let mut results = Vec::new();
+    let same_site_none = cookie.same_site.as_deref() == Some("none");
+
+    if same_site_none && !cookie.secure {
+        results.push(finding(
+            url,
+            "RECON103",
+            format!("Cookie '{}' uses SameSite=None without Secure", cookie.name),
+        ));
+    } else if !cookie.secure {
+        results.push(finding(
+            url,
+            "RECON101",
+            format!(
+                "Cookie '{}' is set without the Secure attribute",
+                cookie.name
+            ),
+        ));
+    }
+
+    if cookie.category == CookieCategory::Essential && !cookie.http_only {
+        results.push(finding(
+            url,
+            "RECON102",
+            format!("Essential cookie '{}' is set without HttpOnly", cookie.name),
+        ));
+    }
+
+    if cookie.same_site.is_none() {
+        results.push(finding(
+            url,
+            "RECON104",
+            format!("Cookie '{}' does not declare SameSite", cookie.name),
+        ));
+    }
+
+    if cookie.is_long_lived() {
+        results.push(finding(
+            url,
+            "RECON105",
+            format!(
+                "Cookie '{}' persists for {}",
+                cookie.name,
+                cookie.lifespan()
+            ),
+        ));
+    }
+
+    results
+}
+
+fn finding(url: &str, rule_id: &str, message: String) -> Value {
+    let level = RULES
+        .iter()
+        .find(|(id, ..)| *id == rule_id)
+        .map(|(_, _, _, level, _)| *level)
+        .unwrap_or("warning");
+
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": url },
+            }
+        }],
+    })
+}
@@ -0,0 +1,335 @@
+//! Destinations for the results of a run. Each sink sees every site as its
+//! scan finishes and then the whole run, so a new destination is one more
+//! sink rather than another branch at the end of `main`.
+
+use crate::baseline::{self, Baseline};
+use crate::history::{Findings, History, SiteChange};
+use crate::gate::SiteViolations;
+use crate::metrics::Metrics;
+use crate::notify::{self, Delta, NotifyOn, WebhookFormat};
+use crate::output::{
+    annotate, executive, graph, json, markdown, pdf, sarif, summary, terminal, yaml, OutputFormat,
+};
+use crate::rules::RuleSet;
+use crate::{triage, tui, AnalysisResult};
+use anyhow::Result;
+use futures::future::LocalBoxFuture;
+use owo_colors::OwoColorize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Everything a run produced, in the order the URLs were given.
+pub struct Run<'a> {
+    pub rows: &'a [(String, Result<&'a AnalysisResult, String>)],
+    /// Sites with findings their previous recorded scan did not have, or
+    /// `None` when no history is kept
+    pub changes: Option<&'a [(String, Findings)]>,
+    /// The same sites with their new findings routed to owners
+    pub site_changes: &'a [SiteChange],
+    /// Findings added and removed since each site's previous recorded scan,
+    /// when a sink asked for them
+    pub deltas: &'a [Delta],
+    /// Sites that broke a `--fail-on` condition or the privacy budget
+    pub violations: &'a [SiteViolations],
+    /// Whether sinks may print what they did, in text output
+    pub text_output: bool,
+}
+
+impl<'a> Run<'a> {
+    /// The sites that were scanned successfully.
+    pub fn analyses(&self) -> Vec<&'a AnalysisResult> {
+        self.rows
+            .iter()
+            .filter_map(|(_, r)| r.as_ref().ok().copied())
+            .collect()
+    }
+}
+
+pub trait OutputSink {
+    /// One site's scan has finished. Called in the order scans finish.
+    fn site(&self, _url: &str, _result: &Result<AnalysisResult>) {}
+
+    /// Every scan has finished.
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>>;
+}
+
+/// A line saying where a file sink wrote to, in text output.
+fn announce(run: &Run, label: &str, path: &std::path::Path) {
+    if run.text_output {
+        println!(
+            "  {} {}",
+            label.bright_green(),
+            path.display().to_string().bright_cyan()
+        );
+        println!();
+    }
+}
+
+/// The report in the chosen `--format` on standard output. With a
+/// baseline, only the changes since it are printed, except in SARIF and
+/// summary JSON.
+pub struct Stdout<'b> {
+    pub format: OutputFormat,
+    pub verbose: bool,
+    pub paging: terminal::Paging,
+    pub baseline: Option<&'b Baseline>,
+}
+
+impl OutputSink for Stdout<'_> {
+    fn site(&self, url: &str, result: &Result<AnalysisResult>) {
+        if self.format != OutputFormat::Text || self.baseline.is_some() {
+            return;
+        }
+        println!("  {} {}", "Analyzing:".bright_green(), url.bright_cyan());
+        println!();
+        match result {
+            Ok(analysis) => terminal::print_results(analysis, self.verbose, self.paging),
+            Err(e) => terminal::print_error(e),
+        }
+    }
+
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if let Some(baseline) = self.baseline {
+                match self.format {
+                    OutputFormat::Text => {
+                        terminal::print_baseline_diff(baseline, run.rows);
+                        return Ok(());
+                    }
+                    OutputFormat::Markdown => {
+                        print!("{}", markdown::render_baseline_diff(baseline, run.rows));
+                        return Ok(());
+                    }
+                    OutputFormat::Json => {
+                        let report = baseline.report(run.rows);
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                        return Ok(());
+                    }
+                    OutputFormat::Yaml => {
+                        print!("{}", serde_yaml::to_string(&baseline.report(run.rows))?);
+                        return Ok(());
+                    }
+                    OutputFormat::Sarif | OutputFormat::SummaryJson => {}
+                }
+            }
+            match self.format {
+                OutputFormat::Text => {
+                    // Side-by-side table when comparing several sites
+                    if run.rows.len() > 1 {
+                        terminal::print_comparison(run.rows);
+                    }
+                    if !run.site_changes.is_empty() {
+                        terminal::print_changes(run.site_changes);
+                    }
+                }
+                OutputFormat::Markdown => print!("{}", markdown::render(run.rows)),
+                OutputFormat::Json => println!("{}", json::render(run.rows)?),
+                OutputFormat::Sarif => println!("{}", sarif::render(run.rows)?),
+                OutputFormat::Yaml => print!("{}", yaml::render(run.rows)?),
+                OutputFormat::SummaryJson => println!("{}", summary::render(run.rows)?),
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `--save-baseline`
+pub struct SaveBaseline(pub PathBuf);
+
+impl OutputSink for SaveBaseline {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            baseline::save(&self.0, run.rows)?;
+            announce(run, "Baseline written to", &self.0);
+            Ok(())
+        })
+    }
+}
+
+/// `--report-pdf`
+pub struct PdfReport(pub PathBuf);
+
+impl OutputSink for PdfReport {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            pdf::write(&self.0, run.rows)?;
+            announce(run, "PDF report written to", &self.0);
+            Ok(())
+        })
+    }
+}
+
+/// `--executive-summary`
+pub struct ExecutiveSummary(pub PathBuf);
+
+impl OutputSink for ExecutiveSummary {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            executive::write(&self.0, run.rows, run.changes)?;
+            announce(run, "Executive summary written to", &self.0);
+            Ok(())
+        })
+    }
+}
+
+/// `--annotate`, one file per site when there are several.
+pub struct AnnotatedHtml {
+    pub path: PathBuf,
+    pub rules: Arc<RuleSet>,
+}
+
+impl OutputSink for AnnotatedHtml {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let analyses = run.analyses();
+            let mut written = HashSet::new();
+            for analysis in &analyses {
+                let file = if analyses.len() > 1 {
+                    let mut n = 1;
+                    while !written.insert(annotate::path_for(&self.path, &analysis.url, n)) {
+                        n += 1;
+                    }
+                    annotate::path_for(&self.path, &analysis.url, n)
+                } else {
+                    self.path.clone()
+                };
+                let count = annotate::write(&file, analysis, &self.rules)?;
+                if run.text_output {
+                    println!(
+                        "  {} {} ({} tags)",
+                        "Annotated HTML written to".bright_green(),
+                        file.display().to_string().bright_cyan(),
+                        count
+                    );
+                }
+            }
+            if run.text_output && !analyses.is_empty() {
+                println!();
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `--export-findings`, narrowed by `--select`
+pub struct FindingsExport {
+    pub path: PathBuf,
+    pub queries: Vec<String>,
+}
+
+impl OutputSink for FindingsExport {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let findings = triage::select(triage::collect(&run.analyses()), &self.queries);
+            triage::write(&self.path, &findings)?;
+            if run.text_output {
+                println!(
+                    "  {} {} findings to {}",
+                    "Exported".bright_green(),
+                    findings.len(),
+                    self.path.display().to_string().bright_cyan()
+                );
+                println!();
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `--triage`
+pub struct TriageView;
+
+impl OutputSink for TriageView {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let findings = triage::collect(&run.analyses());
+            tokio::task::block_in_place(|| tui::browse(findings))
+        })
+    }
+}
+
+/// `--graph`
+pub struct Graph(pub PathBuf);
+
+impl OutputSink for Graph {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            graph::write(&self.0, &run.analyses())?;
+            announce(run, "Graph written to", &self.0);
+            Ok(())
+        })
+    }
+}
+
+/// `--webhook`
+pub struct Webhook {
+    pub url: String,
+    pub notify_on: NotifyOn,
+    pub format: WebhookFormat,
+}
+
+impl OutputSink for Webhook {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let sent = match self.notify_on {
+                NotifyOn::Always => {
+                    notify::send_report(&self.url, self.format, run.rows, run.violations)
+                        .await
+                        .map(|()| true)
+                }
+                NotifyOn::Change => {
+                    notify::send_changes(&self.url, self.format, run.deltas, run.violations).await
+                }
+            }?;
+            if run.text_output && sent {
+                println!(
+                    "  {} {}",
+                    "Webhook notified:".bright_green(),
+                    self.url.bright_cyan()
+                );
+                println!();
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `--metrics-addr`: keeps the `/metrics` gauges up to date as scans finish.
+pub struct MetricsGauges(pub Arc<Metrics>);
+
+impl OutputSink for MetricsGauges {
+    fn site(&self, url: &str, result: &Result<AnalysisResult>) {
+        match result {
+            Ok(analysis) => self.0.record(url, analysis),
+            Err(_) => self.0.record_failure(url),
+        }
+    }
+
+    fn finish<'a>(&'a self, _run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// `--history`: records each successful scan, then prunes old ones.
+pub struct HistoryDb<'h> {
+    pub history: &'h History,
+    pub keep: Option<Duration>,
+}
+
+impl OutputSink for HistoryDb<'_> {
+    fn finish<'a>(&'a self, run: &'a Run<'a>) -> LocalBoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            for (url, result) in run.rows {
+                if let Ok(analysis) = result {
+                    self.history.record(url, analysis)?;
+                }
+            }
+            if let Some(keep) = self.keep {
+                self.history.prune(keep)?;
+            }
+            Ok(())
+        })
+    }
+}
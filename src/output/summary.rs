@@ -0,0 +1,139 @@
+//! Compact headline metrics per site, for dashboards that do not need the
+//! individual findings.
+
+use crate::compliance::{CheckStatus, ComplianceFramework};
+use crate::deprecation::ReadinessStatus;
+use crate::referrer::ReferrerLevel;
+use crate::{calculate_privacy_score, privacy_rating, AnalysisResult};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Serialize)]
+struct Summary<'a> {
+    tool: &'static str,
+    version: &'static str,
+    sites: Vec<SiteSummary<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SiteSummary<'a> {
+    Success {
+        url: &'a str,
+        privacy_score: u32,
+        rating: &'static str,
+        /// Cookie counts keyed by category
+        cookies: BTreeMap<&'a str, usize>,
+        /// Tracker counts keyed by category
+        trackers: BTreeMap<&'a str, usize>,
+        third_party_domains: usize,
+        /// Distinct companies behind the cookies and trackers
+        entities: usize,
+        /// Percentage of external scripts and stylesheets with an integrity hash
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sri_coverage: Option<u8>,
+        /// Headless mode was requested but only the static analysis ran
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        static_only: bool,
+        /// Pass/fail checks; checks that were not run are omitted
+        compliance: BTreeMap<&'static str, bool>,
+    },
+    Failure {
+        url: &'a str,
+        error: &'a str,
+    },
+}
+
+/// Serialize the headline metrics for every analyzed URL as JSON.
+pub fn render(rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<String> {
+    let sites = rows
+        .iter()
+        .map(|(url, result)| match result {
+            Ok(result) => site_summary(url, result),
+            Err(e) => SiteSummary::Failure { url, error: e },
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&Summary {
+        tool: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        sites,
+    })?)
+}
+
+fn site_summary<'a>(url: &'a str, result: &'a AnalysisResult) -> SiteSummary<'a> {
+    let score = calculate_privacy_score(result);
+
+    let mut cookies = BTreeMap::new();
+    for cookie in &result.cookies {
+        *cookies.entry(cookie.category.as_str()).or_default() += 1;
+    }
+    let mut trackers = BTreeMap::new();
+    for tracker in &result.trackers {
+        *trackers.entry(tracker.category.as_str()).or_default() += 1;
+    }
+
+    let entities: BTreeSet<&str> = result
+        .cookies
+        .iter()
+        .filter_map(|c| c.vendor.as_deref())
+        .chain(result.trackers.iter().map(|t| t.description.as_str()))
+        .collect();
+
+    let mut compliance = BTreeMap::new();
+    compliance.insert("secure_cookies", result.cookies.iter().all(|c| c.secure));
+    compliance.insert(
+        "no_full_url_referrers",
+        result
+            .referrer
+            .exposures
+            .iter()
+            .all(|e| e.level != ReferrerLevel::Full),
+    );
+    compliance.insert("no_pii_in_third_party_urls", result.pii_leaks.is_empty());
+    compliance.insert("no_pre_consent_tracking", result.pre_consent.is_empty());
+    compliance.insert("no_skimmer_signatures", result.skimmers.is_empty());
+    compliance.insert("no_session_replay", result.session_replay.is_empty());
+    compliance.insert("self_hosted_fonts", result.remote_fonts.is_empty());
+    compliance.insert(
+        "within_cookie_budget",
+        !result.cookie_sizes.iter().any(|s| s.over_budget()),
+    );
+    if let Some(https) = &result.https {
+        compliance.insert("https_only", !https.serves_plain_http());
+        compliance.insert("hsts_preload_ready", https.preload_ready);
+    }
+    if let Some(report) = &result.well_known {
+        compliance.insert("gpc_declared", report.gpc.as_ref().is_some_and(|g| g.gpc));
+    }
+    if let Some(check) = &result.cmp_check {
+        compliance.insert("cmp_categories_match", check.mismatches.is_empty());
+    }
+    if let Some(report) = &result.compliance {
+        let flag = match report.framework {
+            ComplianceFramework::Gdpr => "gdpr_checklist_passed",
+            ComplianceFramework::Ccpa => "ccpa_checklist_passed",
+        };
+        compliance.insert(flag, report.count(CheckStatus::Fail) == 0);
+    }
+    if let Some(verdicts) = &result.cookie_readiness {
+        compliance.insert(
+            "third_party_cookie_ready",
+            verdicts.iter().all(|v| v.status != ReadinessStatus::Blocked),
+        );
+    }
+
+    SiteSummary::Success {
+        url,
+        privacy_score: score,
+        rating: privacy_rating(score),
+        cookies,
+        trackers,
+        third_party_domains: result.third_party_requests.len(),
+        entities: entities.len(),
+        sri_coverage: result.integrity.as_ref().map(|r| r.coverage),
+        static_only: result.static_fallback.is_some(),
+        compliance,
+    }
+}
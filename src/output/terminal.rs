@@ -0,0 +1,2535 @@
+//! Colored terminal report.
+
+use crate::banner::ConsentBanner;
+use crate::baseline::Baseline;
+use crate::batch::BatchSummary;
+use crate::beacons::Beacon;
+use crate::cmp::CmpCrossCheck;
+use crate::compliance::{CheckStatus, ComplianceReport};
+use crate::consent::{ConsentDiff, StateDelta};
+use crate::config;
+use crate::cookie_policy::CookiePolicyReport;
+use crate::cookie_size::DomainCookies;
+use crate::dataflow::{DataFlow, DataType};
+use crate::deprecation::{self, CookieReadiness, ReadinessStatus};
+use crate::devices::DeviceComparison;
+use crate::fonts::RemoteFonts;
+use crate::hints::ResourceHint;
+use crate::history::{SiteChange, TrendPoint};
+use crate::https::{HttpBehavior, HttpsReport};
+use crate::integrity::IntegrityReport;
+use crate::inventory::{ScriptChangeKind, ScriptFile};
+use crate::locales::LocaleComparison;
+use crate::output::{narrative, pdf};
+use crate::owners::NewFinding;
+use crate::pixels::SuspectedPixel;
+use crate::preconsent::{PreConsentViolation, ViolationSeverity};
+use crate::prefixes::{PrefixAdvice, PrefixVerdict};
+use crate::redirects::RedirectHop;
+use crate::referrer::ReferrerLevel;
+use crate::refresh::{CookieRefresh, RefreshBehavior};
+use crate::sampling::SampleReport;
+use crate::signals::{SignalTest, SignalVerdict};
+use crate::skimmer::SkimmerFinding;
+use crate::variance::ScoreSpread;
+use crate::variants::VariantFindings;
+use crate::related_sets::RelatedWebsiteSet;
+use crate::replay::SessionReplay;
+use crate::remediation;
+use crate::tcf::{self, TcfReport};
+use crate::tls::TlsReport;
+use crate::vendors;
+use crate::websockets::WebSocketEndpoint;
+use crate::wellknown::WellKnownReport;
+use crate::{
+    calculate_privacy_score, privacy_rating, AnalysisResult, CookieCategory, CookieInfo, CookieSource,
+    TrackerInfo,
+};
+use console::Term;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use owo_colors::{AnsiColors, OwoColorize};
+use std::collections::BTreeMap;
+use std::time::{Duration, UNIX_EPOCH};
+use url::Url;
+
+pub fn print_header() {
+    let term = Term::stdout();
+    let _ = term.clear_screen();
+
+    // Light brown cookie color
+    let cookie = owo_colors::Rgb(210, 170, 120);
+
+    println!();
+    println!(
+        "  {} {}",
+        "🥷".color(cookie),
+        "Recon".white().bold()
+    );
+    println!(
+        "  {}",
+        "Website Cookie & Tracker Analyzer".bright_black()
+    );
+    println!();
+}
+
+/// A spinner for the scan of `url`, added to `progress` above `batch` when
+/// several sites are scanned, with the URL leading its line.
+pub fn create_scan_spinner(
+    progress: &MultiProgress,
+    batch: Option<&ProgressBar>,
+    url: &str,
+) -> ProgressBar {
+    // Hidden until added, so it never draws outside `progress`
+    let pb = ProgressBar::hidden();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .tick_strings(&[
+                "⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏",
+            ])
+            .template("{spinner:.cyan} {prefix}{msg}")
+            .unwrap(),
+    );
+    let pb = match batch {
+        Some(batch) => {
+            pb.set_prefix(format!("{}  ", url));
+            pb.set_message("Analyzing...");
+            progress.insert_before(batch, pb)
+        }
+        None => {
+            pb.set_message(format!("Analyzing {}...", url));
+            progress.add(pb)
+        }
+    };
+    pb.enable_steady_tick(Duration::from_millis(80));
+    pb
+}
+
+/// Overall progress of a batch, shown below the lines of the scans running.
+pub fn create_batch_bar(sites: usize) -> ProgressBar {
+    let pb = ProgressBar::new(sites as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{bar:30.cyan/bright_black} {pos}/{len} sites scanned, {elapsed} elapsed")
+            .unwrap()
+            .progress_chars("━━─"),
+    );
+    pb
+}
+
+fn print_divider() {
+    println!(
+        "{}",
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
+            .bright_black()
+    );
+}
+
+fn print_section_header(title: &str) {
+    println!();
+    println!(
+        "  {}",
+        title.bright_white().bold()
+    );
+    print_divider();
+}
+
+/// Entries printed per list when neither `--limit` nor `--verbose` is given
+const DEFAULT_LIMIT: usize = 15;
+
+/// Which entries of each long list to print, from `--limit` and `--page`.
+/// Lists are sorted worst first, so the first page shows the top offenders.
+#[derive(Debug, Clone, Copy)]
+pub struct Paging {
+    limit: Option<usize>,
+    page: usize,
+}
+
+impl Paging {
+    /// Without `--limit`, verbose output prints every entry and normal output
+    /// the first 15.
+    pub fn new(limit: Option<usize>, page: usize, verbose: bool) -> Self {
+        Paging {
+            limit: limit.or((!verbose).then_some(DEFAULT_LIMIT)),
+            page: page.max(1),
+        }
+    }
+
+    /// Index of the first entry on the page.
+    fn start(&self, total: usize) -> usize {
+        self.limit
+            .map_or(0, |limit| limit.saturating_mul(self.page - 1).min(total))
+    }
+
+    /// Index after the last entry on the page.
+    fn end(&self, total: usize) -> usize {
+        let start = self.start(total);
+        self.limit
+            .map_or(total, |limit| start.saturating_add(limit).min(total))
+    }
+
+    /// The entries on the page.
+    fn page<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        &items[self.start(items.len())..self.end(items.len())]
+    }
+
+    /// Say which entries of a list of `total` were left out, and how to see
+    /// the rest.
+    fn print_rest(&self, total: usize, indent: &str) {
+        let (start, end) = (self.start(total), self.end(total));
+        if end - start == total {
+            return;
+        }
+        if start == end {
+            println!(
+                "{}... nothing on page {} ({} in total)",
+                indent,
+                self.page,
+                total.to_string().bright_yellow()
+            );
+            return;
+        }
+        let next = if end < total {
+            format!(" (--page {} for more)", self.page + 1)
+        } else {
+            String::new()
+        };
+        let range = if end == start + 1 {
+            end.to_string()
+        } else {
+            format!("{}-{}", start + 1, end)
+        };
+        println!(
+            "{}... showing {} of {}{}",
+            indent,
+            range,
+            total.to_string().bright_yellow(),
+            next.bright_black()
+        );
+    }
+}
+
+/// Order for listing cookies and storage of each category, most invasive
+/// first.
+fn category_rank(category: &CookieCategory) -> u8 {
+    match category {
+        CookieCategory::Marketing => 0,
+        CookieCategory::Social => 1,
+        CookieCategory::Analytics => 2,
+        CookieCategory::Unknown => 3,
+        CookieCategory::Essential => 4,
+    }
+}
+
+/// Order for listing trackers of each category, most invasive first.
+fn tracker_rank(category: &str) -> u8 {
+    match category {
+        "Marketing" | "Marketing/CRM" => 0,
+        "Social" => 1,
+        "Analytics" => 2,
+        "A/B Testing" => 3,
+        _ => 4,
+    }
+}
+
+pub fn print_results(result: &AnalysisResult, verbose: bool, paging: Paging) {
+    println!();
+    print_divider();
+    println!(
+        "  {} {}",
+        "Analysis Complete:".bright_blue(),
+        result.url.bright_white().bold()
+    );
+    for redirect in &result.client_redirects {
+        println!(
+            "  {} {} {}",
+            format!("Followed {} to", redirect.kind.as_str()).bright_black(),
+            redirect.to.bright_white(),
+            format!("(from {})", redirect.from).bright_black()
+        );
+    }
+    print_divider();
+
+    // Summary stats
+    println!();
+    println!("  ╭─────────────────────────────────────────────────────────────────────────╮");
+    println!(
+        "  │  {} {:<20} {} {:<20} {} {:<15} │",
+        "Cookies:".bright_yellow(),
+        result.cookies.len(),
+        "Trackers:".bright_red(),
+        result.trackers.len(),
+        "3rd Party:".bright_blue(),
+        result.third_party_requests.len()
+    );
+    println!("  ╰─────────────────────────────────────────────────────────────────────────╯");
+    if result.pages.len() > 1 {
+        println!(
+            "  {} {} (findings merged across the site)",
+            "Pages scanned:".bright_white(),
+            result.pages.len()
+        );
+        if verbose {
+            for page in &result.pages {
+                println!("    {} {}", "→".dimmed(), page.dimmed());
+            }
+        }
+    }
+    if !result.robots_disallowed.is_empty() {
+        println!(
+            "  {} {} (disallowed by robots.txt; crawl them with --ignore-robots)",
+            "Pages skipped:".bright_white(),
+            result.robots_disallowed.len()
+        );
+        if verbose {
+            for page in &result.robots_disallowed {
+                println!("    {} {}", "✗".dimmed(), page.dimmed());
+            }
+        }
+    }
+    if let Some(fallback) = &result.static_fallback {
+        println!(
+            "  {} {}",
+            "[!] Static-only analysis:".yellow().bold(),
+            "the headless browser could not be used".yellow()
+        );
+        println!("      {} {}", "Reason:".bright_black(), fallback.reason.bright_black());
+        println!(
+            "      {} {}",
+            "Not detected:".bright_black(),
+            fallback.unavailable.join(", ")
+        );
+    }
+    if let Some(usage) = &result.fetch_budget {
+        if usage.skipped > 0 {
+            println!(
+                "  {} {} requests skipped or cut short; raise --fetch-budget-kb to fetch them",
+                "[!] Fetch budget exhausted:".yellow().bold(),
+                usage.skipped
+            );
+        } else if verbose {
+            println!(
+                "  {} {} requests, {} KB",
+                "Fetched:".bright_black(),
+                usage.requests,
+                usage.bytes / 1024
+            );
+        }
+    }
+    if let Some(usage) = result.resource_usage.as_ref().filter(|_| verbose) {
+        let memory = usage
+            .peak_memory_kb
+            .map(|kb| format!(", peak memory {} MB", kb / 1024))
+            .unwrap_or_default();
+        println!(
+            "  {} {} requests, {} KB in {:.1}s{}",
+            "Scan cost:".bright_black(),
+            usage.requests,
+            usage.bytes / 1024,
+            usage.wall_time_ms as f64 / 1000.0,
+            memory
+        );
+    }
+    if let Some(report) = &result.sampling {
+        let jar = if report.fresh_jar {
+            "fresh cookie jar each time"
+        } else {
+            "shared cookie jar"
+        };
+        println!(
+            "  {} {} ({}, {} intermittent findings)",
+            "Page loads:".bright_white(),
+            report.samples,
+            jar,
+            report.intermittent.len()
+        );
+    }
+
+    // What the findings mean, for readers new to them
+    println!();
+    for line in pdf::wrap(&narrative::summarize(result), 75) {
+        println!("  {}", line);
+    }
+
+    // Privacy Score
+    let privacy_score = calculate_privacy_score(result);
+    print_privacy_score(privacy_score);
+
+    // The legal problem comes before the inventory
+    if !result.pre_consent.is_empty() {
+        print_pre_consent(&result.pre_consent, paging);
+    }
+    if !result.skimmers.is_empty() {
+        print_skimmers(&result.skimmers);
+    }
+    if !result.session_replay.is_empty() {
+        print_session_replay(&result.session_replay, verbose);
+    }
+
+    if !result.redirect_chain.is_empty() {
+        print_redirect_chain(&result.redirect_chain);
+    }
+
+    // Cookies section
+    print_section_header("COOKIES DETECTED");
+    
+    if result.cookies.is_empty() {
+        println!("  {} No cookies detected on initial page load", "[OK]".green());
+    } else {
+        // Group cookies by category
+        let mut essential = Vec::new();
+        let mut analytics = Vec::new();
+        let mut marketing = Vec::new();
+        let mut social = Vec::new();
+        let mut unknown = Vec::new();
+
+        for cookie in &result.cookies {
+            match cookie.category {
+                CookieCategory::Essential => essential.push(cookie),
+                CookieCategory::Analytics => analytics.push(cookie),
+                CookieCategory::Marketing => marketing.push(cookie),
+                CookieCategory::Social => social.push(cookie),
+                CookieCategory::Unknown => unknown.push(cookie),
+            }
+        }
+
+        print_cookie_category(CookieCategory::Essential.as_str(), &mut essential, "green", verbose, paging);
+        print_cookie_category(CookieCategory::Analytics.as_str(), &mut analytics, "yellow", verbose, paging);
+        print_cookie_category(CookieCategory::Marketing.as_str(), &mut marketing, "red", verbose, paging);
+        print_cookie_category(CookieCategory::Social.as_str(), &mut social, "blue", verbose, paging);
+        print_cookie_category(CookieCategory::Unknown.as_str(), &mut unknown, "white", verbose, paging);
+
+        print_cookie_vendors(&result.cookies, paging);
+    }
+    if !result.cookie_prefixes.is_empty() {
+        print_cookie_prefixes(&result.cookie_prefixes);
+    }
+    if verbose || result.cookie_sizes.iter().any(|s| s.over_budget()) {
+        print_cookie_sizes(&result.cookie_sizes, paging);
+    }
+
+    // Trackers section
+    print_section_header("TRACKERS DETECTED");
+    
+    if result.trackers.is_empty() {
+        println!("  {} No known trackers detected", "[OK]".green());
+    } else {
+        let mut trackers: Vec<&TrackerInfo> = result.trackers.iter().collect();
+        trackers.sort_by_key(|t| (tracker_rank(&t.category), std::cmp::Reverse(t.confidence)));
+        for tracker in paging.page(&trackers) {
+            let category_color = match tracker.category.as_str() {
+                "Analytics" => "yellow",
+                "Marketing" => "red",
+                "Social" => "blue",
+                _ => "white",
+            };
+            
+            let prefix = match tracker.category.as_str() {
+                "Analytics" => "[ANALYTICS]",
+                "Marketing" => "[MARKETING]",
+                "Social" => "[SOCIAL]",
+                "Security" => "[SECURITY]",
+                "CDN/Security" => "[CDN]",
+                "Error Tracking" => "[ERROR]",
+                "Customer Support" => "[SUPPORT]",
+                "A/B Testing" => "[A/B TEST]",
+                "Marketing/CRM" => "[CRM]",
+                _ => "[OTHER]",
+            };
+
+            let colored_prefix = match category_color {
+                "yellow" => prefix.yellow().to_string(),
+                "red" => prefix.red().to_string(),
+                "blue" => prefix.blue().to_string(),
+                _ => prefix.white().to_string(),
+            };
+
+            if verbose {
+                println!(
+                    "  {} {}",
+                    colored_prefix,
+                    tracker.name.bright_white(),
+                );
+                println!(
+                    "       {} {}",
+                    "Description:".bright_black(),
+                    tracker.description.cyan()
+                );
+                println!(
+                    "       {} {}",
+                    "Found in:".bright_black(),
+                    tracker.source.bright_black()
+                );
+                println!(
+                    "       {} {}",
+                    "Confidence:".bright_black(),
+                    tracker.confidence.as_str().bright_black()
+                );
+                let privacy_impact = match tracker.category.as_str() {
+                    "Marketing" | "Marketing/CRM" => "High - Tracks users across websites for advertising",
+                    "Analytics" => "Medium - Collects usage data and behavior patterns",
+                    "Social" => "Medium - May share data with social networks",
+                    "A/B Testing" => "Low - Used for page optimization experiments",
+                    "Security" | "CDN/Security" => "Low - Used for site protection",
+                    "Error Tracking" => "Low - Collects error reports for debugging",
+                    "Customer Support" => "Low - Enables support chat functionality",
+                    _ => "Unknown - Impact could not be determined",
+                };
+                println!(
+                    "       {} {}",
+                    "Privacy Impact:".bright_black(),
+                    privacy_impact.bright_black()
+                );
+                println!();
+            } else {
+                println!(
+                    "  {} {} - {}",
+                    colored_prefix,
+                    tracker.name.bright_white(),
+                    tracker.description.bright_black()
+                );
+            }
+        }
+        paging.print_rest(trackers.len(), "  ");
+    }
+    if !result.suspected_pixels.is_empty() {
+        print_suspected_pixels(&result.suspected_pixels, verbose, paging);
+    }
+    if verbose {
+        print_remediation(&result.trackers);
+    }
+
+    // Third-party domains section
+    print_section_header("THIRD-PARTY DOMAINS");
+    
+    if result.third_party_requests.is_empty() {
+        println!("  {} No third-party domains detected", "[OK]".green());
+    } else {
+        // Domains serving a detected tracker first
+        let tracker_hosts: Vec<String> = result
+            .trackers
+            .iter()
+            .filter_map(|t| Url::parse(&t.source).ok()?.host_str().map(str::to_string))
+            .collect();
+        let mut domains: Vec<&String> = result.third_party_requests.iter().collect();
+        domains.sort_by_key(|domain| !tracker_hosts.contains(domain));
+        let start = paging.start(domains.len());
+        for (i, domain) in paging.page(&domains).iter().enumerate() {
+            let i = start + i;
+            // Declared as the same party, so cookies stay usable through the Storage Access API
+            if result.related_set.as_ref().is_some_and(|set| set.contains(domain)) {
+                println!(
+                    "  {}. {} {}",
+                    i + 1,
+                    domain.bright_cyan(),
+                    "(related website set)".bright_black()
+                );
+            } else {
+                println!("  {}. {}", i + 1, domain.bright_cyan());
+            }
+            if verbose {
+                // Categorize the third-party domain
+                let domain_lower = domain.to_lowercase();
+                let domain_type = if domain_lower.contains("google") || domain_lower.contains("gstatic") {
+                    ("Google Services", "Analytics, fonts, APIs, or advertising")
+                } else if domain_lower.contains("facebook") || domain_lower.contains("fbcdn") {
+                    ("Facebook/Meta", "Social plugins or tracking")
+                } else if domain_lower.contains("cloudflare") {
+                    ("Cloudflare", "CDN and security services")
+                } else if domain_lower.contains("cdn") || domain_lower.contains("akamai") || domain_lower.contains("fastly") {
+                    ("CDN", "Content delivery network")
+                } else if domain_lower.contains("analytics") || domain_lower.contains("tracking") {
+                    ("Analytics", "User tracking and analytics")
+                } else if domain_lower.contains("ads") || domain_lower.contains("doubleclick") {
+                    ("Advertising", "Ad serving and tracking")
+                } else if domain_lower.contains("twitter") || domain_lower.contains("linkedin") {
+                    ("Social Media", "Social network integration")
+                } else if domain_lower.contains("stripe") || domain_lower.contains("paypal") {
+                    ("Payment", "Payment processing")
+                } else if domain_lower.contains("sentry") || domain_lower.contains("bugsnag") {
+                    ("Error Tracking", "Error monitoring service")
+                } else {
+                    ("External", "Third-party resource")
+                };
+                println!(
+                    "      {} {} - {}",
+                    "Type:".bright_black(),
+                    domain_type.0.yellow(),
+                    domain_type.1.bright_black()
+                );
+            }
+        }
+        paging.print_rest(domains.len(), "  ");
+    }
+
+    if let Some(banner) = &result.consent_banner {
+        print_consent_banner(banner, verbose);
+    }
+    if let Some(diff) = &result.consent_diff {
+        print_consent_diff(diff);
+    }
+    if let Some(error) = &result.consent_diff_error {
+        println!(
+            "  {} {}",
+            "[!] Consent comparison unavailable:".yellow().bold(),
+            error.bright_black()
+        );
+        println!();
+    }
+    if let Some(check) = &result.cmp_check {
+        print_cmp_check(check, verbose);
+    }
+    if let Some(tcf) = &result.tcf {
+        print_tcf(tcf, verbose);
+    }
+    if let Some(policy) = &result.cookie_policy {
+        print_cookie_policy(policy, verbose);
+    }
+
+    // Identifiers persisted outside cookies
+    if !result.storage.is_empty() {
+        print_section_header("WEB STORAGE");
+        let mut storage: Vec<_> = result.storage.iter().collect();
+        storage.sort_by_key(|item| category_rank(&item.category));
+        for item in paging.page(&storage) {
+            let category = match item.category {
+                CookieCategory::Essential => item.category.as_str().green().to_string(),
+                CookieCategory::Analytics => item.category.as_str().yellow().to_string(),
+                CookieCategory::Marketing => item.category.as_str().red().to_string(),
+                CookieCategory::Social => item.category.as_str().blue().to_string(),
+                CookieCategory::Unknown => item.category.as_str().bright_black().to_string(),
+            };
+            println!(
+                "  {} {} ({})",
+                format!("[{}]", item.area.as_str()).bright_black(),
+                item.key.bright_white(),
+                category
+            );
+            if verbose {
+                if let Some(vendor) = &item.vendor {
+                    println!("       {} {}", "Vendor:".bright_black(), vendor.magenta());
+                }
+                println!(
+                    "       {} {} characters",
+                    "Value:".bright_black(),
+                    item.value_length.to_string().bright_black()
+                );
+            }
+        }
+        paging.print_rest(storage.len(), "  ");
+    }
+
+    // Trackers referenced from inside external scripts
+    if !result.script_dependencies.is_empty() {
+        print_section_header("SCRIPT DEPENDENCIES");
+        for dependency in paging.page(&result.script_dependencies) {
+            println!(
+                "  {} {} {}",
+                dependency.script.bright_black(),
+                "→".bright_black(),
+                dependency.domain.bright_cyan()
+            );
+            if verbose {
+                println!(
+                    "      {} {}",
+                    "URL:".bright_black(),
+                    dependency.url.bright_black()
+                );
+            }
+        }
+        paging.print_rest(result.script_dependencies.len(), "  ");
+    }
+
+    if !result.beacons.is_empty() {
+        print_beacons(&result.beacons, verbose, paging);
+    }
+
+    if !result.resource_hints.is_empty() {
+        print_resource_hints(&result.resource_hints, verbose, paging);
+    }
+
+    if !result.websockets.is_empty() {
+        print_websockets(&result.websockets, verbose, paging);
+    }
+
+    if !result.remote_fonts.is_empty() {
+        print_remote_fonts(&result.remote_fonts, verbose);
+    }
+
+    if let Some(scripts) = result.script_inventory.as_ref().filter(|_| verbose) {
+        print_script_inventory(scripts);
+    }
+
+    if let Some(report) = &result.integrity {
+        print_integrity(report, verbose);
+    }
+
+    if let Some(set) = &result.related_set {
+        print_related_set(set, verbose);
+    }
+
+    print_referrer_leakage(result, verbose);
+
+    // Personal data in third-party query strings
+    if !result.pii_leaks.is_empty() {
+        print_section_header("PERSONAL DATA IN THIRD-PARTY URLS");
+        for leak in paging.page(&result.pii_leaks) {
+            println!(
+                "  {} {} {} {}",
+                format!("[{}]", leak.kind.to_uppercase()).bright_red(),
+                leak.domain.bright_white(),
+                "receives".bright_black(),
+                format!("{}={}", leak.parameter, leak.redacted_value).yellow()
+            );
+            if verbose {
+                println!("       {} {}", "URL:".bright_black(), leak.url.bright_black());
+            }
+        }
+        paging.print_rest(result.pii_leaks.len(), "  ");
+    }
+
+    if !result.data_flows.is_empty() {
+        print_data_flows(&result.data_flows, paging);
+    }
+    if verbose {
+        print_vendors(result, paging);
+    }
+
+    if let Some(report) = &result.well_known {
+        print_well_known(report, verbose);
+    }
+
+    if result.tls.is_some() || result.https.is_some() {
+        print_tls(result.tls.as_ref(), result.https.as_ref(), verbose);
+    }
+
+    if let Some(refresh) = &result.cookie_refresh {
+        print_cookie_refresh(refresh, verbose);
+    }
+
+    if let Some(verdicts) = &result.cookie_readiness {
+        print_cookie_readiness(verdicts, verbose);
+    }
+
+    if let Some(comparison) = &result.device_comparison {
+        print_device_comparison(comparison);
+    }
+
+    if let Some(comparison) = &result.locale_comparison {
+        print_locale_comparison(comparison);
+    }
+
+    for test in &result.signal_tests {
+        print_signal_test(test);
+    }
+
+    if let Some(report) = &result.sampling {
+        print_sampling(report);
+    }
+
+    if let Some(report) = &result.compliance {
+        print_compliance(report, verbose);
+    }
+
+    // Deprecated privacy signals
+    if !result.legacy_headers.is_empty() {
+        print_section_header("LEGACY PRIVACY HEADERS");
+        for header in &result.legacy_headers {
+            println!(
+                "  {} {}: {}",
+                "[LEGACY]".bright_black(),
+                header.name.bright_white(),
+                header.value.cyan()
+            );
+            println!("       {}", header.note.bright_black());
+        }
+    } else if verbose {
+        print_section_header("LEGACY PRIVACY HEADERS");
+        println!("  {} No P3P, Tk, or DNT response headers", "[OK]".green());
+    }
+
+    println!();
+    print_divider();
+    if verbose {
+        println!(
+            "  {} {}",
+            "Verbose mode:".bright_green(),
+            "Showing detailed information for all items".bright_black()
+        );
+    } else {
+        println!(
+            "  {} {}",
+            "Tip:".bright_yellow(),
+            "Use -v for detailed cookie, tracker, and domain information".bright_black()
+        );
+    }
+    print_divider();
+    println!();
+}
+
+fn print_cookie_category(
+    name: &str,
+    cookies: &mut [&CookieInfo],
+    color: &str,
+    verbose: bool,
+    paging: Paging,
+) {
+    if cookies.is_empty() {
+        return;
+    }
+
+    let count_str = format!("({} cookies)", cookies.len());
+    let header = match color {
+        "green" => format!("  ├─ {} {}", name.green(), count_str.bright_black()),
+        "yellow" => format!("  ├─ {} {}", name.yellow(), count_str.bright_black()),
+        "red" => format!("  ├─ {} {}", name.red(), count_str.bright_black()),
+        "blue" => format!("  ├─ {} {}", name.blue(), count_str.bright_black()),
+        _ => format!("  ├─ {} {}", name.white(), count_str.bright_black()),
+    };
+    println!("{}", header);
+
+    // Identifiers and long-lived cookies first
+    cookies.sort_by_key(|c| (!c.identifier, !c.is_long_lived()));
+    for cookie in paging.page(cookies) {
+        let mut tags = String::new();
+        if cookie.is_long_lived() {
+            tags.push_str(&format!(" {}", format!("[LONG-LIVED: {}]", cookie.lifespan()).yellow()));
+        }
+        if cookie.identifier {
+            tags.push_str(&format!(" {}", "[UNIQUE ID]".yellow()));
+        }
+        println!(
+            "  │   • {}{}",
+            cookie.name.bright_white(),
+            tags
+        );
+        
+        if verbose {
+            // Show vendor
+            if let Some(ref vendor) = cookie.vendor {
+                println!(
+                    "  │       {} {}",
+                    "Vendor:".bright_black(),
+                    vendor.bright_magenta()
+                );
+            }
+
+            // Show domain
+            if let Some(ref domain) = cookie.domain {
+                println!(
+                    "  │       {} {}",
+                    "Domain:".bright_black(),
+                    domain.cyan()
+                );
+            }
+            
+            if let Some(ref path) = cookie.path {
+                println!(
+                    "  │       {} {}",
+                    "Path:".bright_black(),
+                    path.cyan()
+                );
+            }
+
+            let lifespan = match (&cookie.expires, cookie.max_age) {
+                (_, Some(max_age)) => format!("{} (Max-Age={})", cookie.lifespan(), max_age),
+                (Some(expires), None) => format!("{} (expires {})", cookie.lifespan(), expires),
+                (None, None) => cookie.lifespan(),
+            };
+            println!(
+                "  │       {} {}",
+                "Lifespan:".bright_black(),
+                if cookie.is_long_lived() {
+                    lifespan.yellow().to_string()
+                } else {
+                    lifespan
+                }
+            );
+
+            // Show security attributes
+            let secure_status = if cookie.secure {
+                "Yes".green().to_string()
+            } else {
+                "No".red().to_string()
+            };
+            println!(
+                "  │       {} {}",
+                "Secure:".bright_black(),
+                secure_status
+            );
+            
+            let http_only_status = if cookie.http_only {
+                "Yes".green().to_string()
+            } else {
+                "No".yellow().to_string()
+            };
+            println!(
+                "  │       {} {}",
+                "HttpOnly:".bright_black(),
+                http_only_status
+            );
+            
+            // Show SameSite
+            let same_site_value = cookie.same_site.as_deref().unwrap_or("not set");
+            let same_site_colored = match same_site_value.to_lowercase().as_str() {
+                "strict" => same_site_value.green().to_string(),
+                "lax" => same_site_value.yellow().to_string(),
+                "none" => same_site_value.red().to_string(),
+                _ => same_site_value.bright_black().to_string(),
+            };
+            println!(
+                "  │       {} {}",
+                "SameSite:".bright_black(),
+                same_site_colored
+            );
+            if cookie.partitioned {
+                println!(
+                    "  │       {} {}",
+                    "Partitioned:".bright_black(),
+                    "Yes".green()
+                );
+            }
+            if let Some(ref priority) = cookie.priority {
+                println!(
+                    "  │       {} {}",
+                    "Priority:".bright_black(),
+                    priority
+                );
+            }
+            
+            let value = format!(
+                "{} characters, ~{} bits of entropy",
+                cookie.value_length, cookie.entropy_bits
+            );
+            println!(
+                "  │       {} {}",
+                "Value:".bright_black(),
+                if cookie.identifier {
+                    format!("{} (looks like a unique identifier)", value).yellow().to_string()
+                } else {
+                    value.bright_black().to_string()
+                }
+            );
+
+            // Show the header as received so parsing can be verified
+            println!(
+                "  │       {} {}",
+                "Raw:".bright_black(),
+                cookie.raw_header.bright_black()
+            );
+            match cookie.source {
+                CookieSource::Header => {}
+                CookieSource::Browser => println!(
+                    "  │       {} {}",
+                    "Source:".bright_black(),
+                    "browser cookie store (set by JavaScript or a subresource)".bright_black()
+                ),
+                CookieSource::Subresource => println!(
+                    "  │       {} {}",
+                    "Set by:".bright_black(),
+                    cookie.set_by.as_deref().unwrap_or("").bright_black()
+                ),
+            }
+            
+            // Show category explanation
+            let category_desc = match cookie.category {
+                CookieCategory::Essential => "Required for basic site functionality",
+                CookieCategory::Analytics => "Used to track user behavior and site performance",
+                CookieCategory::Marketing => "Used for advertising and tracking across sites",
+                CookieCategory::Social => "Related to social media integrations",
+                CookieCategory::Unknown => "Purpose could not be determined",
+            };
+            println!(
+                "  │       {} {}",
+                "Purpose:".bright_black(),
+                category_desc.bright_black()
+            );
+            println!(
+                "  │       {} {}",
+                "Confidence:".bright_black(),
+                cookie.confidence.as_str().bright_black()
+            );
+            println!("  │");
+        }
+    }
+    paging.print_rest(cookies.len(), "  │   ");
+}
+
+fn print_consent_banner(banner: &ConsentBanner, verbose: bool) {
+    print_section_header("CONSENT BANNER");
+    println!(
+        "  {} {}",
+        "Platform:".bright_black(),
+        banner.cmp.as_deref().unwrap_or("Unrecognized").bright_white()
+    );
+    if !banner.buttons.is_empty() {
+        let labels: Vec<String> = banner.buttons.iter().map(|b| format!("[{}]", b)).collect();
+        println!("  {} {}", "Choices:".bright_black(), labels.join(" ").cyan());
+    }
+
+    // The full text is evidence; a preview is enough by default
+    let text = if verbose || banner.text.chars().count() <= 160 {
+        banner.text.clone()
+    } else {
+        format!("{}...", banner.text.chars().take(160).collect::<String>())
+    };
+    println!("  {} {}", "Text:".bright_black(), text.bright_black());
+
+    if let Some(path) = &banner.screenshot {
+        println!(
+            "  {} {}",
+            "Screenshot:".bright_black(),
+            path.display().to_string().bright_cyan()
+        );
+    }
+}
+
+fn print_consent_diff(diff: &ConsentDiff) {
+    print_section_header("CONSENT: REJECT VS ACCEPT");
+    let clicked = |button: &Option<String>| match button {
+        Some(label) => format!("[{}]", label).cyan().to_string(),
+        None => "no button found".yellow().to_string(),
+    };
+    println!("  {} {}", "Reject:".bright_black(), clicked(&diff.reject_button));
+    println!("  {} {}", "Accept:".bright_black(), clicked(&diff.accept_button));
+    if diff.reject_button.is_none() {
+        println!(
+            "  {} The first layer offers no way to refuse consent",
+            "[!]".red()
+        );
+    }
+
+    let tracking = &diff.tracking_after_reject;
+    if tracking.is_empty() {
+        println!("  {} No tracking seen after rejecting", "[OK]".green());
+    } else {
+        println!("  {} Tracking seen after rejecting:", "[!]".red());
+        print_delta(tracking);
+    }
+
+    if !diff.accept_only.is_empty() {
+        println!("  {}", "Only after accepting:".bright_black());
+        print_delta(&diff.accept_only);
+    }
+    if !diff.reject_only.is_empty() {
+        println!("  {}", "Only after rejecting:".bright_black());
+        print_delta(&diff.reject_only);
+    }
+}
+
+fn print_cmp_check(check: &CmpCrossCheck, verbose: bool) {
+    print_section_header("CMP COOKIE DECLARATIONS");
+    println!(
+        "  {} {} ({} cookies declared)",
+        "Platform:".bright_black(),
+        check.cmp.bright_white(),
+        check.declared_cookies
+    );
+    if verbose {
+        println!("  {} {}", "Source:".bright_black(), check.source.bright_black());
+    }
+
+    if check.mismatches.is_empty() {
+        println!(
+            "  {} Every declared cookie matches its observed category",
+            "[OK]".green()
+        );
+    }
+    for mismatch in &check.mismatches {
+        let label = if mismatch.declared_necessary {
+            "[!]".red().to_string()
+        } else {
+            "[?]".yellow().to_string()
+        };
+        println!(
+            "  {} {} declared {} but looks like {}",
+            label,
+            mismatch.cookie.bright_white(),
+            mismatch.declared.as_str().cyan(),
+            mismatch.observed.as_str().yellow()
+        );
+    }
+    if !check.undeclared.is_empty() {
+        println!(
+            "  {} {}",
+            "Not declared:".bright_black(),
+            check.undeclared.join(", ").yellow()
+        );
+    }
+}
+
+fn print_data_flows(flows: &[DataFlow], paging: Paging) {
+    print_section_header("DATA FLOWS");
+    let width = flows
+        .iter()
+        .map(|f| f.recipient.len())
+        .max()
+        .unwrap_or(0)
+        .clamp(9, 40);
+    let header: Vec<String> = DataType::ALL
+        .iter()
+        .map(|t| format!("{:^13}", t.as_str()))
+        .collect();
+    println!(
+        "  {:<width$} {}",
+        "Recipient".bright_black(),
+        header.join("").bright_black(),
+        width = width
+    );
+
+    // Recipients of the most kinds of data first
+    let mut flows: Vec<&DataFlow> = flows.iter().collect();
+    flows.sort_by_key(|flow| std::cmp::Reverse(flow.data.len()));
+    for flow in paging.page(&flows) {
+        let cells: Vec<String> = DataType::ALL
+            .iter()
+            .map(|&t| {
+                let cell = format!("{:^13}", if flow.receives(t) { "✓" } else { "·" });
+                match t {
+                    _ if !flow.receives(t) => cell.bright_black().to_string(),
+                    DataType::IpAndUserAgent => cell.bright_black().to_string(),
+                    DataType::PersonalData | DataType::FormData => cell.red().to_string(),
+                    _ => cell.yellow().to_string(),
+                }
+            })
+            .collect();
+        println!(
+            "  {:<width$} {}",
+            flow.recipient.bright_white(),
+            cells.join(""),
+            width = width
+        );
+    }
+    paging.print_rest(flows.len(), "  ");
+}
+
+fn print_vendors(result: &AnalysisResult, paging: Paging) {
+    let vendors = vendors::detected(result);
+    if vendors.is_empty() {
+        return;
+    }
+    print_section_header("VENDORS");
+    for vendor in paging.page(&vendors) {
+        println!(
+            "  {} {}",
+            vendor.name.bright_white(),
+            format!("({})", vendor.country).bright_black()
+        );
+        if let Some(url) = vendor.privacy_policy {
+            println!("       {} {}", "Privacy policy:".bright_black(), url.bright_cyan());
+        }
+        if let Some(url) = vendor.dpa {
+            println!("       {} {}", "DPA:".bright_black(), url.bright_cyan());
+        }
+    }
+    paging.print_rest(vendors.len(), "  ");
+}
+
+fn print_remediation(trackers: &[TrackerInfo]) {
+    let fixes = remediation::for_trackers(trackers);
+    if fixes.is_empty() {
+        return;
+    }
+    print_section_header("REMEDIATION");
+    for (fix, covered) in fixes {
+        println!("  {} {}", "→".bright_green(), fix.title.bright_white());
+        println!("    {} {}", "For:".bright_black(), covered.join(", ").bright_black());
+        for line in fix.snippet.lines() {
+            println!("    {}", line.cyan());
+        }
+        println!();
+    }
+}
+
+fn print_tcf(tcf: &TcfReport, verbose: bool) {
+    print_section_header("IAB TCF");
+    let api = if tcf.api_present {
+        "present".green().to_string()
+    } else {
+        "not found".bright_black().to_string()
+    };
+    println!("  {} {}", "__tcfapi:".bright_black(), api);
+    let Some(consent) = &tcf.consent else {
+        println!("  {} No euconsent-v2 cookie on first load", "[OK]".green());
+        return;
+    };
+    let cmp = match &consent.cmp_name {
+        Some(name) => format!("{} (ID {})", name, consent.cmp_id),
+        None => format!("ID {}", consent.cmp_id),
+    };
+    println!(
+        "  {} {} v{}, vendor list v{}, policy v{}",
+        "CMP:".bright_black(),
+        cmp.bright_white(),
+        consent.cmp_version,
+        consent.vendor_list_version,
+        consent.policy_version
+    );
+    println!(
+        "  {} {} consented, {} legitimate interest",
+        "Vendors:".bright_black(),
+        consent.vendors_consented.to_string().bright_yellow(),
+        consent.vendors_legitimate_interest
+    );
+    if consent.purposes_consented.is_empty() {
+        println!("  {} No purposes consented by default", "[OK]".green());
+    } else {
+        println!(
+            "  {} Consent to {} of 10 purposes recorded before any interaction",
+            "[!]".red(),
+            consent.purposes_consented.len()
+        );
+        if verbose {
+            for &purpose in &consent.purposes_consented {
+                println!("    {:>2}. {}", purpose, tcf::purpose_name(purpose));
+            }
+        }
+    }
+}
+
+fn print_cookie_policy(policy: &CookiePolicyReport, verbose: bool) {
+    print_section_header("COOKIE POLICY");
+    let Some(url) = &policy.url else {
+        println!(
+            "  {} No cookie policy found (linked or at a common path)",
+            "[!]".red()
+        );
+        return;
+    };
+    println!(
+        "  {} {} ({} cookies listed)",
+        "Policy:".bright_black(),
+        url.bright_cyan(),
+        policy.declared.len()
+    );
+    if policy.undeclared.is_empty() {
+        println!("  {} Every observed cookie is named", "[OK]".green());
+    } else {
+        println!(
+            "  {} {}",
+            "Set but not declared:".red(),
+            policy.undeclared.join(", ").bright_white()
+        );
+    }
+    if verbose && !policy.unobserved.is_empty() {
+        println!(
+            "  {} {}",
+            "Declared but not seen:".bright_black(),
+            policy.unobserved.join(", ").bright_black()
+        );
+    }
+}
+
+fn print_delta(delta: &StateDelta) {
+    for cookie in &delta.cookies {
+        println!(
+            "    {} {} {}",
+            "cookie ".bright_black(),
+            cookie.name.bright_white(),
+            format!(
+                "({}, {})",
+                cookie.domain.as_deref().unwrap_or("-"),
+                cookie.category.as_str()
+            )
+            .bright_black()
+        );
+    }
+    for item in &delta.storage {
+        println!(
+            "    {} {} {}",
+            "storage".bright_black(),
+            item.key.bright_white(),
+            format!("({}, {})", item.area.as_str(), item.category.as_str()).bright_black()
+        );
+    }
+    for host in &delta.hosts {
+        println!("    {} {}", "request".bright_black(), host.yellow());
+    }
+}
+
+fn print_cookie_refresh(refresh: &[CookieRefresh], verbose: bool) {
+    print_section_header("COOKIE REFRESH");
+
+    if refresh.is_empty() {
+        println!("  {} The page sets no cookies to compare", "[NONE]".bright_black());
+        return;
+    }
+    let once = refresh
+        .iter()
+        .filter(|r| r.behavior == RefreshBehavior::Once)
+        .count();
+    for cookie in refresh {
+        let label = match cookie.behavior {
+            RefreshBehavior::Extended => "[EXTENDED]".yellow().to_string(),
+            RefreshBehavior::Reissued => "[REISSUED]".bright_blue().to_string(),
+            RefreshBehavior::Once if verbose => "[ONCE]".green().to_string(),
+            RefreshBehavior::Once => continue,
+        };
+        let rotated = if cookie.value_changed {
+            " (new value)".bright_black().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {} {}{}",
+            label,
+            cookie.name.bright_white(),
+            cookie.behavior.as_str().bright_black(),
+            rotated
+        );
+    }
+    if !verbose && once > 0 {
+        println!("  {} {} cookie(s) set only once", "[ONCE]".green(), once);
+    }
+}
+
+fn print_script_inventory(scripts: &[ScriptFile]) {
+    print_section_header("FIRST-PARTY SCRIPTS");
+    if scripts.is_empty() {
+        println!("  {} No first-party scripts fetched", "[OK]".green());
+    }
+    for script in scripts {
+        let size = match script.size {
+            0..1024 => format!("{} bytes", script.size),
+            _ => format!("{} KB", script.size / 1024),
+        };
+        let size = if script.truncated {
+            format!("{}+", size)
+        } else {
+            size
+        };
+        println!(
+            "  {} {} {}",
+            script.path.bright_white(),
+            size.bright_black(),
+            (&script.sha256[..16]).bright_black()
+        );
+    }
+}
+
+fn print_integrity(report: &IntegrityReport, verbose: bool) {
+    print_section_header("SUBRESOURCE INTEGRITY");
+
+    let coverage = format!("{}%", report.coverage);
+    let coverage = match report.coverage {
+        100 => coverage.green().to_string(),
+        50.. => coverage.yellow().to_string(),
+        _ => coverage.red().to_string(),
+    };
+    println!(
+        "  {} {} of {} external scripts and stylesheets",
+        "Coverage:".bright_black(),
+        coverage,
+        report.resources.len()
+    );
+    if let Some(third_party) = report.third_party_coverage {
+        println!(
+            "  {} {}%",
+            "Third-party coverage:".bright_black(),
+            third_party
+        );
+    }
+
+    for resource in report.unprotected() {
+        println!(
+            "  {} {} {}",
+            "[NO SRI]".yellow(),
+            resource.url.bright_white(),
+            format!("({})", resource.kind.as_str()).bright_black()
+        );
+    }
+    if verbose {
+        for resource in report.resources.iter().filter(|r| r.integrity) {
+            println!(
+                "  {} {} {}",
+                "[OK]".green(),
+                resource.url.bright_white(),
+                format!("({})", resource.kind.as_str()).bright_black()
+            );
+        }
+    }
+}
+
+fn print_tls(report: Option<&TlsReport>, https: Option<&HttpsReport>, verbose: bool) {
+    print_section_header("TLS AND HTTPS");
+
+    if let Some(https) = https {
+        let http = match https.http_behavior {
+            HttpBehavior::Upgrades => "redirects to HTTPS".green().to_string(),
+            HttpBehavior::Plain => "served over plain HTTP".red().to_string(),
+            HttpBehavior::Unreachable => "not served".green().to_string(),
+        };
+        println!("  {} {}", "HTTP:".bright_black(), http);
+        let hsts = match &https.hsts {
+            Some(hsts) => {
+                let mut policy = vec![format!("max-age {} days", hsts.max_age / 86_400)];
+                if hsts.include_subdomains {
+                    policy.push("includeSubDomains".to_string());
+                }
+                if hsts.preload {
+                    policy.push("preload".to_string());
+                }
+                policy.join(", ")
+            }
+            None => "none".yellow().to_string(),
+        };
+        println!("  {} {}", "HSTS:".bright_black(), hsts);
+        let preload = if https.preload_ready {
+            "[PRELOAD READY]".green().to_string()
+        } else {
+            "[NOT PRELOAD READY]".bright_black().to_string()
+        };
+        println!("  {}", preload);
+        for issue in &https.issues {
+            println!("  {} {}", "[WARN]".yellow(), issue);
+        }
+    }
+
+    let Some(report) = report else {
+        return;
+    };
+    if let (Some(protocol), Some(cipher)) = (&report.protocol, &report.cipher) {
+        println!("  {} {}", protocol.bright_white(), cipher.bright_black());
+    }
+    if let Some(cert) = &report.certificate {
+        let trust = if report.trusted {
+            "[TRUSTED]".green().to_string()
+        } else {
+            "[UNTRUSTED]".red().to_string()
+        };
+        println!("  {} Issued by {}", trust, cert.issuer.bright_white());
+        println!(
+            "  {} {} ({} days left)",
+            "Expires:".bright_black(),
+            cert.not_after,
+            cert.days_left
+        );
+        if let Some(key) = &cert.key {
+            println!("  {} {}", "Key:".bright_black(), key);
+        }
+        if verbose {
+            println!("  {} {}", "Subject:".bright_black(), cert.subject);
+            println!("  {} {}", "Names:".bright_black(), cert.san.join(", "));
+        }
+    }
+    for issue in &report.issues {
+        println!("  {} {}", "[WARN]".yellow(), issue);
+    }
+}
+
+fn print_well_known(report: &WellKnownReport, verbose: bool) {
+    print_section_header("WELL-KNOWN PRIVACY ENDPOINTS");
+
+    match &report.gpc {
+        Some(gpc) if gpc.gpc => println!(
+            "  {} Declares Global Privacy Control support{}",
+            "[GPC]".green(),
+            gpc.last_update
+                .as_deref()
+                .map(|d| format!(" (updated {})", d))
+                .unwrap_or_default()
+                .bright_black()
+        ),
+        Some(_) => println!(
+            "  {} gpc.json states that GPC is not honored",
+            "[GPC]".yellow()
+        ),
+        None => println!(
+            "  {} No Global Privacy Control support declared",
+            "[GPC]".bright_black()
+        ),
+    }
+
+    for endpoint in &report.endpoints {
+        if endpoint.found {
+            println!("  {} {}", "[FOUND]".green(), endpoint.path.bright_white());
+            if verbose {
+                if let Some(content) = &endpoint.content {
+                    for line in content.lines().take(10) {
+                        println!("       {}", line.bright_black());
+                    }
+                }
+            }
+        } else if verbose {
+            let status = endpoint
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "no response".to_string());
+            println!(
+                "  {} {} {}",
+                "[MISSING]".bright_black(),
+                endpoint.path.bright_black(),
+                format!("({})", status).bright_black()
+            );
+        }
+    }
+}
+
+fn print_related_set(set: &RelatedWebsiteSet, verbose: bool) {
+    print_section_header("RELATED WEBSITE SET");
+    println!("  {} {}", "Primary:".bright_black(), set.primary.bright_white());
+    for (label, sites) in [
+        ("Associated:", &set.associated_sites),
+        ("Service:", &set.service_sites),
+        ("ccTLD:", &set.cctld_sites),
+    ] {
+        for site in sites {
+            println!("  {} {}", label.bright_black(), site.bright_cyan());
+        }
+    }
+    if verbose {
+        println!("  {} {}", "Declared in:".bright_black(), set.source.bright_black());
+    }
+}
+
+fn print_pre_consent(violations: &[PreConsentViolation], paging: Paging) {
+    print_section_header("PRE-CONSENT VIOLATIONS");
+    println!(
+        "  {}",
+        "Active on first load, before any consent choice (likely ePrivacy Art. 5(3) breach):"
+            .bright_black()
+    );
+    for violation in paging.page(violations) {
+        let severity = format!("[{}]", violation.severity.as_str());
+        let severity = match violation.severity {
+            ViolationSeverity::High => severity.bright_red().to_string(),
+            ViolationSeverity::Medium => severity.yellow().to_string(),
+        };
+        let vendor = violation
+            .vendor
+            .as_deref()
+            .map(|v| format!(" - {}", v))
+            .unwrap_or_default();
+        println!(
+            "  {} {} {}{}",
+            severity,
+            violation.name.bright_white(),
+            format!("({} {})", violation.category, violation.kind).bright_black(),
+            vendor.bright_black()
+        );
+    }
+    paging.print_rest(violations.len(), "  ");
+}
+
+fn print_cookie_prefixes(advice: &[PrefixAdvice]) {
+    print_section_header("COOKIE PREFIXES");
+    for item in advice {
+        match item.verdict {
+            PrefixVerdict::Invalid => println!(
+                "  {} {} {}",
+                "[REJECTED]".red(),
+                item.cookie.bright_white(),
+                format!("breaks {} rules; browsers will not store it", item.prefix).bright_black()
+            ),
+            PrefixVerdict::Candidate => println!(
+                "  {} {} {}",
+                "[HARDEN]".yellow(),
+                item.cookie.bright_white(),
+                format!("rename to {}{}", item.prefix, item.cookie).bright_black()
+            ),
+        }
+        if !item.problems.is_empty() {
+            println!(
+                "       {} {}",
+                "Needs:".bright_black(),
+                item.problems.join(", ")
+            );
+        }
+    }
+}
+
+fn print_cookie_sizes(sizes: &[DomainCookies], paging: Paging) {
+    if sizes.is_empty() {
+        return;
+    }
+    let budget = config::cookie_budget();
+    print_section_header("COOKIE SIZE");
+    println!(
+        "  {}",
+        format!(
+            "Sent with every request; budget {} cookies and {} bytes per domain:",
+            budget.max_per_domain, budget.max_header_bytes
+        )
+        .bright_black()
+    );
+    for size in paging.page(sizes) {
+        let status = if size.over_budget() {
+            "[OVER BUDGET]".red().to_string()
+        } else {
+            "[OK]".green().to_string()
+        };
+        let count = format!("{} cookies", size.count);
+        let bytes = format!("{} bytes", size.header_bytes);
+        println!(
+            "  {} {} {}, {}",
+            status,
+            size.domain.bright_white(),
+            if size.over_count { count.red().to_string() } else { count },
+            if size.over_size { bytes.red().to_string() } else { bytes }
+        );
+    }
+    paging.print_rest(sizes.len(), "  ");
+}
+
+fn print_skimmers(skimmers: &[SkimmerFinding]) {
+    print_section_header("PAYMENT SKIMMER SIGNATURES");
+    println!(
+        "  {}",
+        "Scripts that read card fields and send data out; check them against the deployed source:"
+            .bright_black()
+    );
+    for skimmer in skimmers {
+        println!(
+            "  {} {} {}",
+            "[SKIMMER]".bright_red().bold(),
+            skimmer.source.bright_white(),
+            format!("({} confidence)", skimmer.confidence.as_str().to_lowercase()).bright_black()
+        );
+        for indicator in &skimmer.indicators {
+            println!("       {} {}", "-".bright_black(), indicator.bright_black());
+        }
+    }
+}
+
+fn print_beacons(beacons: &[Beacon], verbose: bool, paging: Paging) {
+    print_section_header("PINGS AND BEACONS");
+    println!(
+        "  {}",
+        "Requests sent on a click or as the page closes, without the visitor seeing them:"
+            .bright_black()
+    );
+    // Third-party and tracker destinations first
+    let mut beacons: Vec<&Beacon> = beacons.iter().collect();
+    beacons.sort_by_key(|b| (b.tracker.is_none(), !b.third_party));
+    for beacon in paging.page(&beacons) {
+        let label = format!("[{}]", beacon.kind.as_str().to_uppercase());
+        let label = if beacon.third_party {
+            label.yellow().to_string()
+        } else {
+            label.bright_black().to_string()
+        };
+        let destination = beacon.domain.as_deref().unwrap_or("URL built at runtime");
+        let tracker = beacon
+            .tracker
+            .as_deref()
+            .map(|t| format!(" - {}", t))
+            .unwrap_or_default();
+        println!(
+            "  {} {}{} {}",
+            label,
+            destination.bright_white(),
+            tracker.bright_black(),
+            format!("(x{})", beacon.count).bright_black()
+        );
+        if verbose {
+            println!("       {} {}", "Found in:".bright_black(), beacon.source.bright_black());
+        }
+    }
+    paging.print_rest(beacons.len(), "  ");
+}
+
+fn print_resource_hints(hints: &[ResourceHint], verbose: bool, paging: Paging) {
+    // First-party hints only speed up the site's own assets
+    let mut hints: Vec<&ResourceHint> = hints
+        .iter()
+        .filter(|h| verbose || h.third_party)
+        .collect();
+    if hints.is_empty() {
+        return;
+    }
+    print_section_header("RESOURCE HINTS");
+    println!(
+        "  {}",
+        "Origins the page tells the browser to connect to or fetch ahead of time:".bright_black()
+    );
+    // Tracker origins first
+    hints.sort_by_key(|h| (h.tracker.is_none(), !h.third_party));
+    for hint in paging.page(&hints) {
+        let label = format!("[{}]", hint.rel.to_uppercase());
+        let label = if hint.tracker.is_some() {
+            label.yellow().to_string()
+        } else {
+            label.bright_black().to_string()
+        };
+        let tracker = hint
+            .tracker
+            .as_deref()
+            .map(|t| format!(" - {}", t))
+            .unwrap_or_default();
+        println!("  {} {}{}", label, hint.domain.bright_white(), tracker.bright_black());
+        if verbose && hint.fetches() {
+            println!("       {} {}", "URL:".bright_black(), hint.url.bright_black());
+        }
+    }
+    paging.print_rest(hints.len(), "  ");
+}
+
+fn print_websockets(endpoints: &[WebSocketEndpoint], verbose: bool, paging: Paging) {
+    print_section_header("WEBSOCKETS");
+    println!(
+        "  {}",
+        "Long-lived connections that can send data without further requests:".bright_black()
+    );
+    // Third-party and tracker endpoints first
+    let mut endpoints: Vec<&WebSocketEndpoint> = endpoints.iter().collect();
+    endpoints.sort_by_key(|e| (e.tracker.is_none(), !e.third_party, !e.observed));
+    for endpoint in paging.page(&endpoints) {
+        let label = if endpoint.third_party {
+            "[WS]".yellow().to_string()
+        } else {
+            "[WS]".bright_black().to_string()
+        };
+        let tracker = endpoint
+            .tracker
+            .as_deref()
+            .map(|t| format!(" - {}", t))
+            .unwrap_or_default();
+        let observed = if endpoint.observed {
+            " (opened)".green().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {}{}{}",
+            label,
+            endpoint.domain.bright_white(),
+            tracker.bright_black(),
+            observed
+        );
+        if verbose {
+            println!("       {} {}", "URL:".bright_black(), endpoint.url.bright_black());
+            println!("       {} {}", "Found in:".bright_black(), endpoint.source.bright_black());
+        }
+    }
+    paging.print_rest(endpoints.len(), "  ");
+}
+
+fn print_remote_fonts(fonts: &[RemoteFonts], verbose: bool) {
+    print_section_header("REMOTE FONTS");
+    println!(
+        "  {}",
+        "Font services that receive each visitor's IP address:".bright_black()
+    );
+    for service in fonts {
+        let label = if service.ruled_unlawful() {
+            "[FONTS]".red().to_string()
+        } else {
+            "[FONTS]".yellow().to_string()
+        };
+        println!(
+            "  {} {} {}",
+            label,
+            service.service.bright_white(),
+            format!("({})", service.hosts.join(", ")).bright_black()
+        );
+        if service.ruled_unlawful() {
+            println!(
+                "       {}",
+                "A German court ruled loading these without consent a GDPR breach (LG München I, 3 O 17493/20); self-host the fonts instead."
+                    .bright_black()
+            );
+        }
+        if verbose {
+            for url in &service.urls {
+                println!("       {}", url.bright_black());
+            }
+        }
+    }
+}
+
+fn print_redirect_chain(hops: &[RedirectHop]) {
+    print_section_header("REDIRECT CHAIN");
+    for (i, hop) in hops.iter().enumerate() {
+        let status = format!("[{}]", hop.status);
+        let status = if (300..400).contains(&hop.status) {
+            status.yellow().to_string()
+        } else if hop.status >= 400 {
+            status.red().to_string()
+        } else {
+            status.green().to_string()
+        };
+        println!("  {}. {} {}", i + 1, status, hop.url.bright_white());
+        if !hop.cookies.is_empty() {
+            println!(
+                "       {} {}",
+                "Sets:".bright_black(),
+                hop.cookies.join(", ").bright_black()
+            );
+        }
+    }
+}
+
+fn print_suspected_pixels(pixels: &[SuspectedPixel], verbose: bool, paging: Paging) {
+    println!();
+    println!(
+        "  {} {}",
+        "Suspected pixels".bright_white(),
+        "(images shaped like trackers, not in the tracker rules)".bright_black()
+    );
+    for pixel in paging.page(pixels) {
+        println!(
+            "  {} {} {}",
+            "[PIXEL?]".yellow(),
+            pixel.domain.bright_white(),
+            format!("({})", pixel.reasons.join(", ")).bright_black()
+        );
+        if verbose {
+            println!("       {} {}", "URL:".bright_black(), pixel.url.bright_black());
+        }
+    }
+    paging.print_rest(pixels.len(), "  ");
+}
+
+fn print_session_replay(replays: &[SessionReplay], verbose: bool) {
+    print_section_header("SESSION REPLAY");
+    println!(
+        "  {}",
+        "Records mouse movements, clicks, keystrokes and form content of every visit:"
+            .bright_black()
+    );
+    for replay in replays {
+        println!(
+            "  {} {}",
+            "[REPLAY]".bright_red(),
+            replay.vendor.bright_white()
+        );
+        if verbose {
+            for item in &replay.evidence {
+                println!("       {} {}", "-".bright_black(), item.bright_black());
+            }
+        }
+    }
+    println!(
+        "  {}",
+        "Mask all form fields in the tool's settings and load it only after consent".bright_black()
+    );
+}
+
+fn print_device_comparison(comparison: &DeviceComparison) {
+    print_section_header("DESKTOP VS. MOBILE");
+    for variant in [&comparison.desktop, &comparison.mobile] {
+        print_variant_findings(variant);
+    }
+}
+
+fn print_locale_comparison(comparison: &LocaleComparison) {
+    print_section_header("LANGUAGES AND REGIONS");
+    for locale in &comparison.locales {
+        print_variant_findings(&locale.findings);
+        if let Some(proxy) = &locale.proxy {
+            println!("    {} {}", "Proxy:".bright_black(), proxy);
+        }
+        match locale.consent_banner {
+            Some(true) => println!("    {} Consent banner shown", "[BANNER]".green()),
+            Some(false) => println!("    {} No consent banner", "[NO BANNER]".yellow()),
+            None => {}
+        }
+    }
+}
+
+fn print_signal_test(test: &SignalTest) {
+    print_section_header(&test.signal.title().to_uppercase());
+    let verdict = test.verdict.as_str().to_uppercase();
+    let verdict = match test.verdict {
+        SignalVerdict::Honored => format!("[{}]", verdict).green().to_string(),
+        SignalVerdict::Partial => format!("[{}]", verdict).yellow().to_string(),
+        SignalVerdict::Ignored => format!("[{}]", verdict).red().to_string(),
+        SignalVerdict::NothingToSuppress => format!("[{}]", verdict).bright_black().to_string(),
+    };
+    println!(
+        "  {} {} {}",
+        verdict,
+        "with".bright_black(),
+        test.signal.header().bright_white()
+    );
+    match test.declared {
+        Some(true) => println!("  {} the site declares support", "Declared:".bright_black()),
+        Some(false) => println!("  {} the site declares no support", "Declared:".bright_black()),
+        None => {}
+    }
+    for echo in &test.echoed {
+        println!("  {} {}", "Echoed:".bright_black(), echo);
+    }
+    let lists = [
+        ("Stopped by the signal:", &test.only_without),
+        ("Only with the signal:", &test.only_with),
+    ];
+    for (label, findings) in lists {
+        let names: Vec<&str> = findings
+            .cookies
+            .iter()
+            .chain(&findings.trackers)
+            .chain(&findings.third_party_domains)
+            .map(String::as_str)
+            .collect();
+        if !names.is_empty() {
+            println!("  {} {}", label.bright_black(), names.join(", "));
+        }
+    }
+    if !test.still_tracking.is_empty() {
+        println!(
+            "  {} {}",
+            "Still tracking:".bright_black(),
+            test.still_tracking.join(", ").yellow()
+        );
+    }
+}
+
+/// What only one variant of the page had, under its label and score.
+fn print_variant_findings(variant: &VariantFindings) {
+    println!(
+        "  {} {}",
+        format!("{}:", variant.variant).bright_white(),
+        format!("score {}", variant.score).bright_black()
+    );
+    if variant.is_empty() {
+        println!("    {} Nothing the others did not also get", "[SAME]".green());
+        return;
+    }
+    let lists = [
+        ("cookie", &variant.cookies),
+        ("tracker", &variant.trackers),
+        ("third-party domain", &variant.third_party_domains),
+    ];
+    for (kind, names) in lists {
+        for name in names {
+            println!(
+                "    {} {} {}",
+                "[ONLY]".yellow(),
+                name.bright_white(),
+                format!("({})", kind).bright_black()
+            );
+        }
+    }
+}
+
+fn print_sampling(report: &SampleReport) {
+    print_section_header("SAMPLING STABILITY");
+    print_score_spread(&report.score);
+    if report.intermittent.is_empty() {
+        println!(
+            "  {} Every finding appeared in all {} loads",
+            "[STABLE]".green(),
+            report.samples
+        );
+        return;
+    }
+    println!(
+        "  {}",
+        "Findings not seen on every load (all others appeared every time):".bright_black()
+    );
+    for finding in &report.intermittent {
+        println!(
+            "  {} {} {}",
+            format!("[{}/{}]", finding.seen_in, report.samples).yellow(),
+            finding.name.bright_white(),
+            format!("({})", finding.kind).bright_black()
+        );
+    }
+}
+
+fn print_score_spread(spread: &ScoreSpread) {
+    let range = format!(
+        "{}-{} (mean {:.1}, std dev {:.1})",
+        spread.min, spread.max, spread.mean, spread.std_dev
+    );
+    if spread.is_volatile() {
+        println!(
+            "  {} {} {}",
+            "Score:".bright_black(),
+            range.yellow(),
+            "volatile, so single-run changes may be noise".bright_black()
+        );
+    } else {
+        println!("  {} {}", "Score:".bright_black(), range.green());
+    }
+}
+
+fn print_compliance(report: &ComplianceReport, verbose: bool) {
+    print_section_header(&format!("{} COMPLIANCE CHECKLIST", report.title()));
+    for check in &report.checks {
+        // Pad before coloring; escape codes would throw off the width
+        let label = format!("{:<6}", format!("[{}]", check.status.as_str()));
+        let label = match check.status {
+            CheckStatus::Pass => label.green().to_string(),
+            CheckStatus::Warn => label.yellow().to_string(),
+            CheckStatus::Fail => label.bright_red().to_string(),
+            CheckStatus::NotChecked => label.bright_black().to_string(),
+        };
+        println!(
+            "  {} {} {}",
+            label,
+            check.id.bright_black(),
+            check.title.bright_white()
+        );
+        println!("         {}", check.detail.bright_black());
+        if verbose {
+            println!("         {}", check.reference.bright_black());
+        }
+    }
+    println!();
+    println!(
+        "  {} passed, {} warnings, {} failed, {} not checked",
+        report.count(CheckStatus::Pass).to_string().green(),
+        report.count(CheckStatus::Warn).to_string().yellow(),
+        report.count(CheckStatus::Fail).to_string().bright_red(),
+        report.count(CheckStatus::NotChecked)
+    );
+}
+
+fn print_cookie_readiness(verdicts: &[CookieReadiness], verbose: bool) {
+    print_section_header("THIRD-PARTY COOKIE READINESS");
+
+    let broken = deprecation::broken_integrations(verdicts);
+    if broken.is_empty() {
+        println!(
+            "  {} No cookies will be blocked by the third-party cookie phase-out",
+            "[OK]".green()
+        );
+    } else {
+        println!(
+            "  {} {}",
+            "Integrations that will break:".bright_white(),
+            broken.join(", ").bright_red()
+        );
+    }
+
+    for verdict in verdicts {
+        let label = match verdict.status {
+            ReadinessStatus::Blocked => "[BLOCKED]".bright_red().to_string(),
+            ReadinessStatus::CrossSite => "[BLOCKED WHEN EMBEDDED]".yellow().to_string(),
+            ReadinessStatus::RelatedSet => "[RELATED SET]".yellow().to_string(),
+            ReadinessStatus::Partitioned => "[PARTITIONED]".yellow().to_string(),
+            ReadinessStatus::FirstParty => "[FIRST-PARTY]".green().to_string(),
+        };
+        // First-party cookies are unaffected, so only list them on request
+        if verdict.status == ReadinessStatus::FirstParty && !verbose {
+            continue;
+        }
+        let mut attributes = vec![verdict.domain.as_str()];
+        if verdict.same_site_none {
+            attributes.push("SameSite=None");
+        }
+        attributes.push(if verdict.partitioned {
+            "partitioned"
+        } else {
+            "not partitioned"
+        });
+        println!(
+            "  {} {} {}",
+            label,
+            verdict.cookie.bright_white(),
+            format!("({})", attributes.join(", ")).bright_black()
+        );
+        if let Some(advice) = &verdict.advice {
+            println!("       {}", advice.bright_black());
+        }
+    }
+
+    if !verbose {
+        let first_party = verdicts
+            .iter()
+            .filter(|v| v.status == ReadinessStatus::FirstParty)
+            .count();
+        if first_party > 0 {
+            println!(
+                "  {} {} first-party cookies unaffected",
+                "[OK]".green(),
+                first_party
+            );
+        }
+    }
+}
+
+fn print_referrer_leakage(result: &AnalysisResult, verbose: bool) {
+    let report = &result.referrer;
+    if report.exposures.is_empty() {
+        return;
+    }
+
+    print_section_header("REFERRER LEAKAGE");
+
+    let default_note = if report.browser_default {
+        " (browser default)"
+    } else {
+        ""
+    };
+    println!(
+        "  {} {}{}",
+        "Referrer-Policy:".bright_black(),
+        report.document_policy.cyan(),
+        default_note.bright_black()
+    );
+
+    let full: Vec<_> = report
+        .exposures
+        .iter()
+        .filter(|e| e.level == ReferrerLevel::Full)
+        .collect();
+
+    if full.is_empty() {
+        println!(
+            "  {} Third parties receive at most the page origin",
+            "[OK]".green()
+        );
+    }
+
+    for exposure in &full {
+        let prefix = if exposure.sensitive {
+            "[PERSONAL DATA]".bright_red().to_string()
+        } else {
+            "[FULL URL]".yellow().to_string()
+        };
+        println!("  {} {}", prefix, exposure.domain.bright_white());
+        if verbose {
+            println!(
+                "       {} {}",
+                "Receives:".bright_black(),
+                exposure.referrer.as_deref().unwrap_or("").bright_black()
+            );
+            println!(
+                "       {} {}",
+                "Policy:".bright_black(),
+                exposure.policy.bright_black()
+            );
+        }
+    }
+
+    if verbose {
+        for exposure in report.exposures.iter().filter(|e| e.level != ReferrerLevel::Full) {
+            let label = match exposure.level {
+                ReferrerLevel::Origin => "origin only",
+                _ => "nothing",
+            };
+            println!(
+                "  {} {} {}",
+                "[OK]".green(),
+                exposure.domain.bright_white(),
+                format!("receives {} ({})", label, exposure.policy).bright_black()
+            );
+        }
+    }
+}
+
+fn print_cookie_vendors(cookies: &[CookieInfo], paging: Paging) {
+    let mut vendors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut unattributed = 0;
+
+    for cookie in cookies {
+        match cookie.vendor {
+            Some(ref vendor) => vendors.entry(vendor).or_default().push(&cookie.name),
+            None => unattributed += 1,
+        }
+    }
+
+    if vendors.is_empty() {
+        return;
+    }
+
+    print_section_header("COOKIES BY VENDOR");
+
+    // Vendors with the most cookies first
+    let mut vendors: Vec<(&str, Vec<&str>)> = vendors.into_iter().collect();
+    vendors.sort_by_key(|(_, names)| std::cmp::Reverse(names.len()));
+    for (vendor, names) in paging.page(&vendors) {
+        println!(
+            "  ├─ {} {}",
+            vendor.bright_magenta(),
+            format!("({} cookies)", names.len()).bright_black()
+        );
+        println!("  │   {}", names.join(", ").bright_white());
+    }
+    paging.print_rest(vendors.len(), "  │   ");
+
+    if unattributed > 0 {
+        println!(
+            "  └─ {} {}",
+            "Unattributed".white(),
+            format!("({} cookies)", unattributed).bright_black()
+        );
+    }
+}
+
+fn print_privacy_score(score: u32) {
+    println!();
+    let color = match score {
+        90..=100 => "green",
+        50..=89 => "yellow",
+        _ => "red",
+    };
+    let label = privacy_rating(score).to_uppercase();
+
+    let bar_width = 40;
+    let filled = (score as usize * bar_width) / 100;
+    let empty = bar_width - filled;
+
+    let bar = format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(empty)
+    );
+
+    println!("  ╭─────────────────────────────────────────────────────────────────────────╮");
+    
+    let colored_label = match color {
+        "green" => label.green().to_string(),
+        "yellow" => label.yellow().to_string(),
+        "red" => label.red().to_string(),
+        _ => label.white().to_string(),
+    };
+
+    let colored_bar = match color {
+        "green" => bar.green().to_string(),
+        "yellow" => bar.yellow().to_string(),
+        "red" => bar.red().to_string(),
+        _ => bar.white().to_string(),
+    };
+    
+    println!(
+        "  │  PRIVACY SCORE: {}/100 - {}",
+        score,
+        colored_label
+    );
+    println!("  │  [{}]", colored_bar);
+    println!("  ╰─────────────────────────────────────────────────────────────────────────╯");
+}
+
+pub fn print_error(e: &anyhow::Error) {
+    println!();
+    println!(
+        "  {} {}",
+        "[ERROR]".bright_red(),
+        format!("Error analyzing URL: {}", e).red()
+    );
+    println!();
+    println!(
+        "  {} Make sure the URL is correct and accessible",
+        "Tip:".bright_yellow()
+    );
+    println!();
+}
+
+/// What changed on each site since its previous recorded scan: whether the
+/// page itself changed, and the new findings grouped by their owner.
+/// A site's recorded scans, oldest first, with each metric's change from
+/// the scan before.
+pub fn print_trend(url: &str, points: &[TrendPoint]) {
+    print_section_header("SCAN HISTORY");
+    if points.is_empty() {
+        println!(
+            "  {} {}",
+            "No recorded scans of".bright_black(),
+            url.bright_cyan()
+        );
+        println!("  {}", "Scan with --history to start recording.".bright_black());
+        println!();
+        return;
+    }
+
+    const BLOCKS: [&str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    let sparkline: String = points
+        .iter()
+        .map(|p| BLOCKS[(p.privacy_score.min(100) as usize * 7) / 100])
+        .collect();
+    println!(
+        "  {} {}  {}",
+        url.bright_cyan(),
+        sparkline.bright_green(),
+        format!("{} scans", points.len()).bright_black()
+    );
+    println!();
+    println!(
+        "  {:<17} {:>5} {:<4} {:<10} {:>7} {:<4} {:>8} {:<4} {:>9}",
+        "Scanned (UTC)".bright_black(),
+        "Score".bright_black(),
+        "",
+        "Rating".bright_black(),
+        "Cookies".bright_black(),
+        "",
+        "Trackers".bright_black(),
+        "",
+        "3rd Party".bright_black()
+    );
+
+    // "+3" or "-2" after a metric that moved, blank when it did not
+    let change = |now: u32, before: Option<u32>| match before {
+        Some(before) if now != before => format!("{:+}", now as i64 - before as i64),
+        _ => String::new(),
+    };
+    let mut previous: Option<&TrendPoint> = None;
+    for point in points {
+        let time = UNIX_EPOCH + Duration::from_secs(point.scanned_at.max(0) as u64);
+        let time = humantime::format_rfc3339_seconds(time).to_string();
+        let score_change = change(point.privacy_score, previous.map(|p| p.privacy_score));
+        let score_color = match previous {
+            Some(p) if point.privacy_score < p.privacy_score => AnsiColors::Red,
+            _ => AnsiColors::Green,
+        };
+        println!(
+            "  {:<17} {:>5} {:<4} {:<10} {:>7} {:<4} {:>8} {:<4} {:>9} {}",
+            time[..16].replace('T', " "),
+            point.privacy_score,
+            score_change.color(score_color),
+            privacy_rating(point.privacy_score),
+            point.cookies,
+            change(point.cookies, previous.map(|p| p.cookies)).bright_black(),
+            point.trackers,
+            change(point.trackers, previous.map(|p| p.trackers)).bright_black(),
+            point.third_party_domains,
+            change(point.third_party_domains, previous.map(|p| p.third_party_domains))
+                .bright_black()
+        );
+        previous = Some(point);
+    }
+    println!();
+}
+
+pub fn print_changes(changes: &[SiteChange]) {
+    print_section_header("CHANGES SINCE LAST SCAN");
+    for change in changes {
+        let tracking = !change.findings.is_empty();
+        let summary = match (change.content_changed, tracking) {
+            (Some(true), true) => "page content and tracking changed".yellow().to_string(),
+            (Some(true), false) => "page content changed, tracking unchanged".green().to_string(),
+            (_, false) => "tracking unchanged".green().to_string(),
+            (Some(false), true) => "tracking changed without a page change"
+                .red()
+                .to_string(),
+            (None, true) => "tracking changed".yellow().to_string(),
+        };
+        if change.scripts.is_empty() {
+            println!("  {} {}", change.url.bright_cyan(), summary);
+        } else {
+            println!(
+                "  {} {}, {}",
+                change.url.bright_cyan(),
+                summary,
+                format!("{} first-party scripts changed", change.scripts.len()).yellow()
+            );
+        }
+        if let Some(volatility) = change.volatility.as_ref().filter(|v| v.is_volatile()) {
+            println!(
+                "    {} score {}-{} (std dev {:.1}), {} finding(s) come and go, so this change may be noise",
+                format!("Volatile over the last {} runs:", volatility.runs).yellow(),
+                volatility.score.min,
+                volatility.score.max,
+                volatility.score.std_dev,
+                volatility.intermittent.len()
+            );
+        }
+    }
+
+    let mut by_owner: BTreeMap<&str, Vec<(&str, &NewFinding)>> = BTreeMap::new();
+    let mut unassigned = Vec::new();
+    for change in changes {
+        for finding in &change.findings {
+            match &finding.owner {
+                Some(owner) => by_owner
+                    .entry(owner)
+                    .or_default()
+                    .push((&change.url, finding)),
+                None => unassigned.push((change.url.as_str(), finding)),
+            }
+        }
+    }
+
+    let groups = by_owner
+        .into_iter()
+        .chain((!unassigned.is_empty()).then_some(("No owner", unassigned)));
+    for (owner, findings) in groups {
+        println!();
+        println!("  {}", owner.bright_white().bold());
+        for (url, finding) in findings {
+            println!(
+                "    {} {} ({} {}) {} {}",
+                "[NEW]".yellow(),
+                finding.name.bright_white(),
+                finding.category,
+                finding.kind,
+                "on".bright_black(),
+                url.bright_cyan()
+            );
+        }
+    }
+
+    if changes.iter().any(|c| !c.scripts.is_empty()) {
+        println!();
+        println!("  {}", "First-party scripts".bright_white().bold());
+        for change in changes {
+            for script in &change.scripts {
+                let label = format!("[{}]", script.kind.as_str().to_uppercase());
+                let label = match script.kind {
+                    ScriptChangeKind::Removed => label.bright_black().to_string(),
+                    _ => label.yellow().to_string(),
+                };
+                println!(
+                    "    {} {} {} {}",
+                    label,
+                    script.path.bright_white(),
+                    "on".bright_black(),
+                    change.url.bright_cyan()
+                );
+            }
+        }
+    }
+    println!();
+}
+
+/// What each site gained and lost since the `--baseline` snapshot, in place
+/// of the full reports.
+pub fn print_baseline_diff(
+    baseline: &Baseline,
+    rows: &[(String, Result<&AnalysisResult, String>)],
+) {
+    print_section_header("CHANGES SINCE BASELINE");
+    println!(
+        "  {} {}",
+        "Baseline:".bright_black(),
+        baseline.path.display().to_string().bright_black()
+    );
+    for (url, result) in rows {
+        println!();
+        let analysis = match result {
+            Ok(analysis) => analysis,
+            Err(e) => {
+                println!("  {} {}", url.bright_cyan(), format!("could not be scanned: {}", e).red());
+                continue;
+            }
+        };
+        let diff = baseline.diff(analysis);
+        let status = if !diff.in_baseline {
+            "not in the baseline".yellow().to_string()
+        } else if diff.is_empty() {
+            "unchanged".green().to_string()
+        } else if diff.regressed() {
+            "regressed".red().to_string()
+        } else {
+            "improved".green().to_string()
+        };
+        let score = match diff.baseline_score {
+            Some(before) => format!("(score {}, was {})", diff.privacy_score, before),
+            None => format!("(score {})", diff.privacy_score),
+        };
+        println!("  {} {} {}", url.bright_cyan(), status, score.bright_black());
+        for finding in &diff.delta.added {
+            println!(
+                "    {} {} ({} {})",
+                "[NEW]".yellow(),
+                finding.rule.bright_white(),
+                finding.category,
+                finding.kind
+            );
+        }
+        for name in &diff.newly_insecure {
+            println!(
+                "    {} {} {}",
+                "[INSECURE]".red(),
+                name.bright_white(),
+                "is no longer set with Secure".bright_black()
+            );
+        }
+        for finding in &diff.delta.removed {
+            println!(
+                "    {} {} ({} {})",
+                "[GONE]".bright_black(),
+                finding.rule,
+                finding.category,
+                finding.kind
+            );
+        }
+        for script in &diff.delta.scripts {
+            let label = format!("[{}]", script.kind.as_str().to_uppercase());
+            println!("    {} {}", label.yellow(), script.path.bright_white());
+        }
+    }
+    println!();
+}
+
+pub fn print_comparison(rows: &[(String, Result<&AnalysisResult, String>)]) {
+    print_section_header("CROSS-SITE SUMMARY");
+    println!(
+        "  {:<40} {:>7} {:>8} {:>9} {:>10}",
+        "URL".bright_black(),
+        "Score".bright_black(),
+        "Cookies".bright_black(),
+        "Trackers".bright_black(),
+        "3rd Party".bright_black()
+    );
+
+    for (url, result) in rows {
+        let display_url = if url.chars().count() > 40 {
+            format!("{}...", url.chars().take(37).collect::<String>())
+        } else {
+            url.clone()
+        };
+
+        match result {
+            Ok(result) => {
+                let score = calculate_privacy_score(result);
+                let score_str = format!("{:>7}", score);
+                let colored_score = match score {
+                    90..=100 => score_str.green().to_string(),
+                    50..=89 => score_str.yellow().to_string(),
+                    _ => score_str.red().to_string(),
+                };
+                println!(
+                    "  {:<40} {} {:>8} {:>9} {:>10}",
+                    display_url.bright_white(),
+                    colored_score,
+                    result.cookies.len(),
+                    result.trackers.len(),
+                    result.third_party_requests.len()
+                );
+            }
+            Err(_) => {
+                println!(
+                    "  {:<40} {}",
+                    display_url.bright_white(),
+                    format!("{:>7}", "failed").bright_red()
+                );
+            }
+        }
+    }
+
+    let summary = BatchSummary::from_rows(rows);
+    println!();
+    if let Some(average) = summary.average_score {
+        println!(
+            "  {} {:.0}/100 across {} sites",
+            "Average score:".bright_black(),
+            average,
+            summary.sites - summary.failed
+        );
+    }
+    if let (Some(best), Some(worst)) = (&summary.best, &summary.worst) {
+        println!(
+            "  {} {} ({})   {} {} ({})",
+            "Best:".bright_black(),
+            best.0.green(),
+            best.1,
+            "Worst:".bright_black(),
+            worst.0.red(),
+            worst.1
+        );
+    }
+    if summary.failed > 0 {
+        println!(
+            "  {} {}",
+            "Failed:".bright_black(),
+            summary.failed.to_string().bright_red()
+        );
+    }
+    if !summary.common_trackers.is_empty() {
+        println!("  {}", "Most common trackers:".bright_black());
+        for (tracker, sites) in &summary.common_trackers {
+            println!(
+                "    • {} {}",
+                tracker.bright_white(),
+                format!("({} of {} sites)", sites, summary.sites - summary.failed).bright_black()
+            );
+        }
+    }
+
+    println!();
+    print_divider();
+    println!();
+}
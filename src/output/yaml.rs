@@ -0,0 +1,11 @@
+//! YAML report with the same structure as the JSON output, for reviewable
+//! snapshots in version control.
+
+use crate::output::json;
+use crate::AnalysisResult;
+use anyhow::Result;
+
+/// Serialize every analyzed URL into a single YAML document.
+pub fn render(rows: &[(String, Result<&AnalysisResult, String>)]) -> Result<String> {
+    Ok(serde_yaml::to_string(&json::build(rows))?)
+}
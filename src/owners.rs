@@ -0,0 +1,123 @@
+//! Who is responsible for which trackers and cookies, from the `[[owner]]`
+//! tables of the config file, so new findings reach the team that added them.
+
+use crate::history::Findings;
+use crate::{vendors, AnalysisResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OwnerRule {
+    /// Tracker or cookie category, e.g. "Marketing"
+    category: Option<String>,
+    /// Company or product, matched against the start of the vendor name
+    entity: Option<String>,
+    /// Where alerts go: an address, a channel or a team name
+    owner: String,
+}
+
+impl OwnerRule {
+    fn matches(&self, category: &str, entity: Option<&str>) -> bool {
+        let category_matches = self
+            .category
+            .as_ref()
+            .is_none_or(|c| category.to_lowercase().starts_with(&c.to_lowercase()));
+        let entity_matches = self.entity.as_ref().is_none_or(|e| {
+            entity.is_some_and(|entity| entity.to_lowercase().starts_with(&e.to_lowercase()))
+        });
+        category_matches && entity_matches
+    }
+}
+
+/// Owner rules in file order; the first that matches a finding wins.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Owners(Vec<OwnerRule>);
+
+/// Used when the config file routes nothing
+pub static NONE: Owners = Owners(Vec::new());
+
+impl Owners {
+    pub fn owner_of(&self, category: &str, entity: Option<&str>) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|rule| rule.matches(category, entity))
+            .map(|rule| rule.owner.as_str())
+    }
+
+    /// Rules without a category or entity would match everything.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(rule) = self
+            .0
+            .iter()
+            .find(|r| r.category.is_none() && r.entity.is_none())
+        {
+            anyhow::bail!(
+                "owner '{}' needs a `category` or an `entity` to match",
+                rule.owner
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A tracker or cookie a scan found that the previous scan did not.
+#[derive(Debug, Serialize)]
+pub struct NewFinding {
+    /// "tracker" or "cookie"
+    pub kind: &'static str,
+    pub name: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+/// Describe each added finding from the scan it came from and assign its owner.
+pub fn route(added: &Findings, result: &AnalysisResult, owners: &Owners) -> Vec<NewFinding> {
+    let mut findings = Vec::new();
+    for tracker in result
+        .trackers
+        .iter()
+        .filter(|t| added.trackers.contains(&t.description))
+    {
+        let entity = vendors::lookup(&tracker.description)
+            .map(|v| v.name.to_string())
+            .unwrap_or_else(|| tracker.description.clone());
+        findings.push(NewFinding {
+            kind: "tracker",
+            name: tracker.description.clone(),
+            category: tracker.category.clone(),
+            owner: owners
+                .owner_of(&tracker.category, Some(&tracker.description))
+                .or_else(|| owners.owner_of(&tracker.category, Some(&entity)))
+                .map(str::to_string),
+            entity: Some(entity),
+        });
+    }
+    for cookie in result
+        .cookies
+        .iter()
+        .filter(|c| added.cookies.contains(&c.name))
+    {
+        let category = cookie.category.as_str();
+        let entity = cookie.vendor.as_deref().map(|vendor| {
+            vendors::lookup(vendor)
+                .map(|v| v.name)
+                .unwrap_or(vendor)
+                .to_string()
+        });
+        findings.push(NewFinding {
+            kind: "cookie",
+            name: cookie.name.clone(),
+            category: category.to_string(),
+            owner: owners
+                .owner_of(category, cookie.vendor.as_deref())
+                .or_else(|| owners.owner_of(category, entity.as_deref()))
+                .map(str::to_string),
+            entity,
+        });
+    }
+    findings
+}
@@ -0,0 +1,144 @@
+//! Heuristics for personal data sent to third parties in query strings.
+
+use crate::is_third_party;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::collections::HashSet;
+use url::Url;
+
+// Meta tags whose content identifies the visitor or page subject by name
+const NAME_META_TAGS: &[&str] = &[
+    r#"meta[name="author"]"#,
+    r#"meta[property="profile:first_name"]"#,
+    r#"meta[property="profile:last_name"]"#,
+    r#"meta[property="profile:username"]"#,
+    r#"meta[name="user"]"#,
+    r#"meta[name="username"]"#,
+];
+
+// Query parameter names that usually carry a phone number
+const PHONE_KEYS: &[&str] = &["phone", "tel", "mobile", "msisdn", "ph"];
+
+/// A third-party request whose query string appears to contain PII.
+#[derive(Debug, Clone, Serialize)]
+pub struct PiiLeak {
+    pub domain: String,
+    pub url: String,
+    pub parameter: String,
+    /// "email", "phone" or "name"
+    pub kind: String,
+    /// The matched value with most characters masked
+    pub redacted_value: String,
+}
+
+/// Check every third-party resource URL on the page, plus any extra URLs
+/// discovered elsewhere, for PII in the query string.
+pub fn scan(html: &str, page_url: &Url, extra_urls: &[String]) -> Vec<PiiLeak> {
+    let document = Html::parse_document(html);
+    let resource_selector =
+        Selector::parse("script[src], img[src], iframe[src], link[href], source[src]").unwrap();
+
+    let names = meta_names(&document);
+    let base_domain = page_url.domain().unwrap_or("");
+
+    let mut urls: Vec<String> = document
+        .select(&resource_selector)
+        .filter_map(|e| e.value().attr("src").or_else(|| e.value().attr("href")))
+        .filter_map(|src| page_url.join(src).ok())
+        .map(|url| url.to_string())
+        .collect();
+    urls.extend(extra_urls.iter().cloned());
+
+    let email_re = Regex::new(r"(?i)[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}").unwrap();
+    let phone_re = Regex::new(r"^\+?[\d\s().-]{9,20}$").unwrap();
+
+    let mut seen = HashSet::new();
+    let mut leaks = Vec::new();
+
+    for raw in urls {
+        let Ok(url) = Url::parse(&raw) else {
+            continue;
+        };
+        let Some(domain) = url.domain() else {
+            continue;
+        };
+        if !is_third_party(domain, base_domain) {
+            continue;
+        }
+
+        for (key, value) in url.query_pairs() {
+            let value = value.trim();
+            let key_lower = key.to_ascii_lowercase();
+
+            let kind = if email_re.is_match(value) {
+                Some("email")
+            } else if looks_like_phone(&key_lower, value, &phone_re) {
+                Some("phone")
+            } else if names
+                .iter()
+                .any(|name| value.to_lowercase().contains(name.as_str()))
+            {
+                Some("name")
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                if seen.insert((domain.to_string(), key.to_string(), kind)) {
+                    leaks.push(PiiLeak {
+                        domain: domain.to_string(),
+                        url: url.to_string(),
+                        parameter: key.to_string(),
+                        kind: kind.to_string(),
+                        redacted_value: redact(value),
+                    });
+                }
+            }
+        }
+    }
+
+    leaks
+}
+
+fn meta_names(document: &Html) -> Vec<String> {
+    NAME_META_TAGS
+        .iter()
+        .filter_map(|selector| Selector::parse(selector).ok())
+        .flat_map(|selector| {
+            document
+                .select(&selector)
+                .filter_map(|e| e.value().attr("content"))
+                .map(|c| c.trim().to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .filter(|name| name.chars().count() >= 3)
+        .collect()
+}
+
+fn looks_like_phone(key: &str, value: &str, phone_re: &Regex) -> bool {
+    if !phone_re.is_match(value) {
+        return false;
+    }
+    let digits = value.chars().filter(char::is_ascii_digit).count();
+    if !(9..=15).contains(&digits) {
+        return false;
+    }
+    // Bare digit runs are usually timestamps or cache busters, so require a
+    // telling parameter name, an international prefix, or phone formatting
+    PHONE_KEYS.iter().any(|k| key == *k || key.ends_with(k))
+        || value.starts_with('+')
+        || value.contains(['(', ' ', '-'])
+}
+
+fn redact(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 4 {
+        return "*".repeat(chars.len());
+    }
+    let keep = 2;
+    let mut out: String = chars[..keep].iter().collect();
+    out.push_str(&"*".repeat(chars.len() - 2 * keep));
+    out.extend(&chars[chars.len() - keep..]);
+    out
+}
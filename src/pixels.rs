@@ -0,0 +1,161 @@
+//! Images that look like tracking pixels from their shape rather than their
+//! host: invisible or 1x1 images, images in `<noscript>` fallbacks, and
+//! image URLs carrying long opaque identifiers. This catches pixels of
+//! vendors the tracker rules do not know.
+
+use crate::is_third_party;
+use crate::rules::RuleSet;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use url::Url;
+
+/// Query strings at least this long are treated as opaque payloads
+const LONG_QUERY: usize = 100;
+
+/// Query values at least this long without separators look like identifiers
+const OPAQUE_VALUE: usize = 32;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspectedPixel {
+    pub url: String,
+    pub domain: String,
+    pub third_party: bool,
+    /// Why the image looks like a pixel
+    pub reasons: Vec<&'static str>,
+}
+
+/// Whether an image is at most 1x1 from its attributes or inline style.
+fn is_tiny(element: &ElementRef) -> bool {
+    let attr_size = |name: &str| {
+        element
+            .value()
+            .attr(name)
+            .and_then(|v| v.trim().trim_end_matches("px").parse::<u32>().ok())
+    };
+    let style = style(element);
+    let style_tiny = |property: &str| {
+        ["0", "0px", "1px"]
+            .iter()
+            .any(|v| style.contains(&format!("{}:{}", property, v)))
+    };
+    (attr_size("width").is_some_and(|w| w <= 1) && attr_size("height").is_some_and(|h| h <= 1))
+        || (style_tiny("width") && style_tiny("height"))
+}
+
+fn is_hidden(element: &ElementRef) -> bool {
+    let style = style(element);
+    element.value().attr("hidden").is_some()
+        || style.contains("display:none")
+        || style.contains("visibility:hidden")
+}
+
+/// The inline style, lowercase and without spaces.
+fn style(element: &ElementRef) -> String {
+    element
+        .value()
+        .attr("style")
+        .unwrap_or("")
+        .to_lowercase()
+        .replace(char::is_whitespace, "")
+}
+
+fn has_opaque_query(url: &Url) -> bool {
+    url.query().is_some_and(|q| q.len() >= LONG_QUERY)
+        || url.query_pairs().any(|(_, value)| {
+            value.len() >= OPAQUE_VALUE
+                && value.chars().all(|c| {
+                    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '=' | '+' | '/' | '.')
+                })
+        })
+}
+
+/// Whether the URL path names a photo or graphic rather than a GIF beacon.
+fn is_picture(url: &Url) -> bool {
+    let path = url.path().to_lowercase();
+    [".jpg", ".jpeg", ".png", ".webp", ".avif", ".svg"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+/// Suspected pixels among the page's images, leaving out those the tracker
+/// rules already match. First-party images need an opaque query string and
+/// one other sign, since spacer images are common.
+pub fn suspect(html: &str, page_url: &Url, rules: &RuleSet) -> Vec<SuspectedPixel> {
+    let img = Selector::parse("img[src]").unwrap();
+    let noscript = Selector::parse("noscript").unwrap();
+    let base_domain = page_url.domain().unwrap_or("");
+
+    // With scripting on, <noscript> content is parsed as text, so parse it
+    // again to reach its images
+    let document = Html::parse_document(html);
+    let fallbacks: Vec<Html> = document
+        .select(&noscript)
+        .map(|element| Html::parse_fragment(&element.text().collect::<String>()))
+        .collect();
+    let images = document.select(&img).map(|element| (element, false)).chain(
+        fallbacks
+            .iter()
+            .flat_map(|fragment| fragment.select(&img).map(|element| (element, true))),
+    );
+
+    let mut pixels: Vec<SuspectedPixel> = Vec::new();
+    for (element, in_noscript) in images {
+        let Some(url) = element
+            .value()
+            .attr("src")
+            .and_then(|src| page_url.join(src.trim()).ok())
+        else {
+            continue;
+        };
+        let Some(host) = url.host_str() else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https")
+            || rules.is_ignored(host)
+            || pixels.iter().any(|p| p.url == url.as_str())
+        {
+            continue;
+        }
+        let url_lower = url.as_str().to_lowercase();
+        if rules
+            .trackers
+            .iter()
+            .any(|rule| rule.matches_url(&url_lower))
+        {
+            continue;
+        }
+
+        let mut reasons = Vec::new();
+        if is_tiny(&element) {
+            reasons.push("1x1 image");
+        }
+        if is_hidden(&element) {
+            reasons.push("hidden image");
+        }
+        if in_noscript {
+            reasons.push("image in <noscript>");
+        }
+        let opaque = has_opaque_query(&url);
+        if opaque {
+            reasons.push("long opaque query string");
+        }
+
+        // Signed image CDN URLs carry long tokens too, so an opaque query on
+        // its own only counts when the URL does not name a picture
+        let third_party = is_third_party(host, base_domain);
+        let suspicious = if third_party {
+            reasons.len() >= 2 || (!opaque && !reasons.is_empty()) || (opaque && !is_picture(&url))
+        } else {
+            opaque && reasons.len() >= 2
+        };
+        if suspicious {
+            pixels.push(SuspectedPixel {
+                domain: host.to_string(),
+                url: url.to_string(),
+                third_party,
+                reasons,
+            });
+        }
+    }
+    pixels
+}
@@ -0,0 +1,113 @@
+//! Cookies, storage keys and trackers active on the first page load, before
+//! the visitor has had a chance to consent.
+
+use crate::storage::StorageItem;
+use crate::{CookieCategory, CookieInfo, TrackerInfo};
+use serde::{Deserialize, Serialize};
+
+/// Tracker categories that need consent before they load
+const CONSENT_TRACKER_CATEGORIES: &[&str] = &[
+    "Analytics",
+    "Marketing",
+    "Marketing/CRM",
+    "Social",
+    "A/B Testing",
+];
+
+/// Whether a cookie or storage category needs consent. Unknown items are
+/// left out rather than guessed at.
+pub fn needs_consent(category: &CookieCategory) -> bool {
+    matches!(
+        category,
+        CookieCategory::Analytics | CookieCategory::Marketing | CookieCategory::Social
+    )
+}
+
+/// Whether a tracker category needs consent.
+pub fn tracker_needs_consent(category: &str) -> bool {
+    CONSENT_TRACKER_CATEGORIES.contains(&category)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationSeverity {
+    /// Advertising and social tracking
+    High,
+    /// Analytics and experimentation
+    Medium,
+}
+
+impl ViolationSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViolationSeverity::High => "HIGH",
+            ViolationSeverity::Medium => "MEDIUM",
+        }
+    }
+}
+
+/// Something that needs consent but was active before any interaction, a
+/// likely breach of ePrivacy Directive Art. 5(3).
+#[derive(Debug, Clone, Serialize)]
+pub struct PreConsentViolation {
+    /// "cookie", "storage" or "tracker"
+    pub kind: &'static str,
+    pub name: String,
+    pub category: String,
+    pub vendor: Option<String>,
+    pub severity: ViolationSeverity,
+}
+
+/// Everything in the first-load findings that should have waited for consent,
+/// most severe first.
+pub fn detect(
+    cookies: &[CookieInfo],
+    storage: &[StorageItem],
+    trackers: &[TrackerInfo],
+) -> Vec<PreConsentViolation> {
+    let mut violations = Vec::new();
+
+    for cookie in cookies.iter().filter(|c| needs_consent(&c.category)) {
+        violations.push(PreConsentViolation {
+            kind: "cookie",
+            name: cookie.name.clone(),
+            category: cookie.category.as_str().to_string(),
+            vendor: cookie.vendor.clone(),
+            severity: severity(cookie.category.as_str()),
+        });
+    }
+    for item in storage.iter().filter(|s| needs_consent(&s.category)) {
+        violations.push(PreConsentViolation {
+            kind: "storage",
+            name: item.key.clone(),
+            category: item.category.as_str().to_string(),
+            vendor: item.vendor.clone(),
+            severity: severity(item.category.as_str()),
+        });
+    }
+    for tracker in trackers
+        .iter()
+        .filter(|t| tracker_needs_consent(&t.category))
+    {
+        violations.push(PreConsentViolation {
+            kind: "tracker",
+            name: tracker.name.clone(),
+            category: tracker.category.clone(),
+            vendor: Some(tracker.description.clone()),
+            severity: tracker
+                .severity
+                .unwrap_or_else(|| severity(&tracker.category)),
+        });
+    }
+
+    violations.sort_by_key(|v| v.severity == ViolationSeverity::Medium);
+    violations
+}
+
+fn severity(category: &str) -> ViolationSeverity {
+    if category.starts_with("Marketing") || category == "Social" {
+        ViolationSeverity::High
+    } else {
+        ViolationSeverity::Medium
+    }
+}
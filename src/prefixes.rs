@@ -0,0 +1,115 @@
+//! `__Host-` and `__Secure-` cookie name prefixes. Browsers refuse to store
+//! a prefixed cookie that breaks the prefix's rules, and a session cookie
+//! without a prefix can be overwritten from a subdomain or over plain HTTP.
+
+use crate::{CookieInfo, CookieSource};
+use serde::Serialize;
+
+const HOST_PREFIX: &str = "__Host-";
+const SECURE_PREFIX: &str = "__Secure-";
+
+/// Name fragments of session, login and CSRF cookies
+const SESSION_NAME_FRAGMENTS: &[&str] = &[
+    "session", "sess", "sid", "auth", "token", "csrf", "xsrf", "login", "jwt",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrefixVerdict {
+    /// Uses a prefix without meeting its requirements, so browsers reject it
+    Invalid,
+    /// A session or authentication cookie that could adopt a prefix
+    Candidate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixAdvice {
+    pub cookie: String,
+    pub verdict: PrefixVerdict,
+    /// The prefix used, or the one to adopt
+    pub prefix: &'static str,
+    /// Requirements not met; for candidates, what adopting the prefix needs
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+}
+
+/// `name` starts with `prefix`, ignoring case as browsers do.
+fn has_prefix(name: &str, prefix: &str) -> bool {
+    name.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// What `__Host-` requires that `cookie` does not do.
+fn host_problems(cookie: &CookieInfo) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !cookie.secure {
+        problems.push("set Secure".to_string());
+    }
+    if let Some(domain) = &cookie.domain {
+        problems.push(format!("drop Domain={}", domain));
+    }
+    if cookie.path.as_deref() != Some("/") {
+        problems.push("set Path=/".to_string());
+    }
+    problems
+}
+
+/// Check the prefixes of cookies the site sets in its own responses. Cookies
+/// read back from the browser passed the browser's checks already.
+pub fn check(cookies: &[CookieInfo]) -> Vec<PrefixAdvice> {
+    let mut advice = Vec::new();
+    for cookie in cookies {
+        if cookie.source == CookieSource::Browser {
+            continue;
+        }
+
+        if has_prefix(&cookie.name, HOST_PREFIX) {
+            let problems = host_problems(cookie);
+            if !problems.is_empty() {
+                advice.push(PrefixAdvice {
+                    cookie: cookie.name.clone(),
+                    verdict: PrefixVerdict::Invalid,
+                    prefix: HOST_PREFIX,
+                    problems,
+                });
+            }
+        } else if has_prefix(&cookie.name, SECURE_PREFIX) {
+            if !cookie.secure {
+                advice.push(PrefixAdvice {
+                    cookie: cookie.name.clone(),
+                    verdict: PrefixVerdict::Invalid,
+                    prefix: SECURE_PREFIX,
+                    problems: vec!["set Secure".to_string()],
+                });
+            }
+        } else if cookie.source == CookieSource::Header && is_session_cookie(&cookie.name) {
+            // Cookies shared with subdomains cannot be host-only
+            let prefix = if cookie.domain.is_some() {
+                SECURE_PREFIX
+            } else {
+                HOST_PREFIX
+            };
+            let problems = if prefix == HOST_PREFIX {
+                host_problems(cookie)
+            } else if !cookie.secure {
+                vec!["set Secure".to_string()]
+            } else {
+                Vec::new()
+            };
+            advice.push(PrefixAdvice {
+                cookie: cookie.name.clone(),
+                verdict: PrefixVerdict::Candidate,
+                prefix,
+                problems,
+            });
+        }
+    }
+    advice
+}
+
+fn is_session_cookie(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SESSION_NAME_FRAGMENTS
+        .iter()
+        .any(|fragment| name.contains(fragment))
+}
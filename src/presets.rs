@@ -0,0 +1,87 @@
+//! Named bundles of options for `--preset`, so a good scan does not need
+//! twenty flags. Options given on the command line still win.
+
+use crate::compliance::ComplianceFramework;
+use crate::Args;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, ValueEnum};
+
+/// Pages crawled by the `deep` preset
+const DEEP_MAX_PAGES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Preset {
+    /// The home page only, from its static HTML; the defaults
+    Quick,
+    /// Also download scripts and resources and probe .well-known endpoints
+    Standard,
+    /// Crawl the site in headless Chrome with every enrichment and check
+    Deep,
+    /// Headless consent comparison, cookie policy, .well-known and GDPR checks
+    Compliance,
+}
+
+impl Preset {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Preset::Quick => "quick",
+            Preset::Standard => "standard",
+            Preset::Deep => "deep",
+            Preset::Compliance => "compliance",
+        }
+    }
+
+    /// Turn on the preset's options, leaving alone any the command line set.
+    pub fn apply(self, args: &mut Args, matches: &ArgMatches) {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+        let enable = |id: &str, flag: &mut bool| {
+            if unset(id) {
+                *flag = true;
+            }
+        };
+
+        match self {
+            Preset::Quick => {}
+            Preset::Standard => {
+                enable("fetch_scripts", &mut args.fetch_scripts);
+                enable("fetch_resources", &mut args.fetch_resources);
+                enable("well_known", &mut args.well_known);
+            }
+            Preset::Deep => {
+                enable("fetch_scripts", &mut args.fetch_scripts);
+                enable("fetch_resources", &mut args.fetch_resources);
+                enable("well_known", &mut args.well_known);
+                enable("headless", &mut args.headless);
+                enable("cookie_policy", &mut args.cookie_policy);
+                enable("cookiepocalypse", &mut args.cookiepocalypse);
+                // Crawling cannot be combined with the other multi-load modes
+                let other_mode = [
+                    "sitemap",
+                    "samples",
+                    "compare_devices",
+                    "compare_locales",
+                    "test_gpc",
+                    "test_dnt",
+                ]
+                .iter()
+                .any(|id| !unset(id));
+                if !other_mode {
+                    enable("crawl", &mut args.crawl);
+                    if unset("max_pages") {
+                        args.max_pages = DEEP_MAX_PAGES;
+                    }
+                }
+            }
+            Preset::Compliance => {
+                enable("fetch_resources", &mut args.fetch_resources);
+                enable("well_known", &mut args.well_known);
+                enable("headless", &mut args.headless);
+                enable("consent_diff", &mut args.consent_diff);
+                enable("cookie_policy", &mut args.cookie_policy);
+                if unset("compliance") && args.profile.is_none() {
+                    args.compliance = Some(ComplianceFramework::Gdpr);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! A checked-in `privacy-budget.toml` capping how many cookies, trackers and
+//! third-party domains of each kind a site may have. Every overrun is
+//! reported and fails the run, so a team can hold a site to its budget and
+//! tighten it over time.
+
+use crate::{AnalysisResult, CookieCategory};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = "privacy-budget.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrivacyBudget {
+    third_party_domains: Option<usize>,
+    session_replay: Option<usize>,
+    #[serde(default)]
+    cookies: CookieLimits,
+    #[serde(default)]
+    trackers: TrackerLimits,
+}
+
+/// Most cookies of each category, and in all
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CookieLimits {
+    essential: Option<usize>,
+    analytics: Option<usize>,
+    marketing: Option<usize>,
+    social: Option<usize>,
+    unknown: Option<usize>,
+    total: Option<usize>,
+}
+
+/// Most trackers of each category, and in all. `other` covers the
+/// categories without a key of their own, such as A/B testing.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrackerLimits {
+    marketing: Option<usize>,
+    analytics: Option<usize>,
+    social: Option<usize>,
+    other: Option<usize>,
+    total: Option<usize>,
+}
+
+/// `privacy-budget.toml` in the current directory, if there is one.
+pub fn discover() -> Option<PathBuf> {
+    let local = PathBuf::from(FILE_NAME);
+    local.is_file().then_some(local)
+}
+
+impl PrivacyBudget {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read privacy budget {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Invalid privacy budget {}", path.display()))
+    }
+
+    /// Every limit `result` goes over, with what counted against it.
+    pub fn check(&self, result: &AnalysisResult) -> Vec<String> {
+        let mut overruns = Vec::new();
+        let mut check = |what: &str, limit: Option<usize>, found: Vec<&str>| {
+            if let Some(limit) = limit.filter(|limit| found.len() > *limit) {
+                overruns.push(format!(
+                    "{}: {} against a budget of {} ({})",
+                    what,
+                    found.len(),
+                    limit,
+                    found.join(", ")
+                ));
+            }
+        };
+
+        let cookies = |category: CookieCategory| -> Vec<&str> {
+            result
+                .cookies
+                .iter()
+                .filter(|c| c.category == category)
+                .map(|c| c.name.as_str())
+                .collect()
+        };
+        let limits = &self.cookies;
+        check(
+            "essential cookies",
+            limits.essential,
+            cookies(CookieCategory::Essential),
+        );
+        check(
+            "analytics cookies",
+            limits.analytics,
+            cookies(CookieCategory::Analytics),
+        );
+        check(
+            "marketing cookies",
+            limits.marketing,
+            cookies(CookieCategory::Marketing),
+        );
+        check(
+            "social cookies",
+            limits.social,
+            cookies(CookieCategory::Social),
+        );
+        check(
+            "unknown cookies",
+            limits.unknown,
+            cookies(CookieCategory::Unknown),
+        );
+        check(
+            "cookies",
+            limits.total,
+            result.cookies.iter().map(|c| c.name.as_str()).collect(),
+        );
+
+        let trackers = |categories: &[&str], other: bool| -> Vec<&str> {
+            result
+                .trackers
+                .iter()
+                .filter(|t| categories.contains(&t.category.as_str()) != other)
+                .map(|t| t.name.as_str())
+                .collect()
+        };
+        let limits = &self.trackers;
+        check(
+            "marketing trackers",
+            limits.marketing,
+            trackers(&["Marketing", "Marketing/CRM"], false),
+        );
+        check(
+            "analytics trackers",
+            limits.analytics,
+            trackers(&["Analytics"], false),
+        );
+        check(
+            "social trackers",
+            limits.social,
+            trackers(&["Social"], false),
+        );
+        check(
+            "other trackers",
+            limits.other,
+            trackers(&["Marketing", "Marketing/CRM", "Analytics", "Social"], true),
+        );
+        check(
+            "trackers",
+            limits.total,
+            result.trackers.iter().map(|t| t.name.as_str()).collect(),
+        );
+
+        check(
+            "third-party domains",
+            self.third_party_domains,
+            result
+                .third_party_requests
+                .iter()
+                .map(String::as_str)
+                .collect(),
+        );
+        check(
+            "session replay scripts",
+            self.session_replay,
+            result
+                .session_replay
+                .iter()
+                .map(|r| r.vendor.as_str())
+                .collect(),
+        );
+        overruns
+    }
+}
@@ -0,0 +1,205 @@
+//! Checks a national regulator adds on top of its framework, selected with
+//! `--profile`. The CCPA profile adds none; its checks are the framework's.
+
+use crate::compliance::{link_matching, CheckStatus, ComplianceCheck, Profile};
+use crate::{AnalysisResult, CookieCategory};
+use std::time::Duration;
+use url::Url;
+
+/// The CNIL's maximum lifetime for tracking cookies and consent records
+const CNIL_MAX_LIFETIME: Duration = Duration::from_secs(395 * 24 * 60 * 60);
+
+/// Cookie names of consent management platforms
+const CONSENT_COOKIE_HINTS: &[&str] = &[
+    "consent",
+    "optanon",
+    "didomi",
+    "axeptio",
+    "cookielawinfo",
+    "cmplz",
+    "borlabs",
+    "usercentrics",
+    "euconsent",
+];
+
+const IMPRESSUM_HINTS: &[&str] = &["impressum", "imprint", "legal notice", "legal-notice"];
+
+const COOKIE_NOTICE_HINTS: &[&str] = &["cookie"];
+
+/// The extra checks of `profile`.
+pub fn checks(
+    profile: Profile,
+    result: &AnalysisResult,
+    html: &str,
+    page_url: &Url,
+) -> Vec<ComplianceCheck> {
+    match profile {
+        Profile::Cnil => cnil(result),
+        Profile::Dsgvo => dsgvo(result, html, page_url),
+        Profile::UkIco => uk_ico(html, page_url),
+        Profile::Ccpa => Vec::new(),
+    }
+}
+
+fn cnil(result: &AnalysisResult) -> Vec<ComplianceCheck> {
+    let mut checks = Vec::new();
+
+    let too_long = |consent: bool| -> (usize, Vec<String>) {
+        let mut seen = 0;
+        let mut over = Vec::new();
+        for cookie in &result.cookies {
+            if is_consent_cookie(&cookie.name) != consent {
+                continue;
+            }
+            if !consent
+                && !matches!(
+                    cookie.category,
+                    CookieCategory::Analytics | CookieCategory::Marketing | CookieCategory::Social
+                )
+            {
+                continue;
+            }
+            seen += 1;
+            if let Some(lifetime) = cookie.lifetime().filter(|l| *l > CNIL_MAX_LIFETIME) {
+                over.push(format!(
+                    "{} ({} days)",
+                    cookie.name,
+                    lifetime.as_secs() / 86_400
+                ));
+            }
+        }
+        (seen, over)
+    };
+
+    let (tracking, over) = too_long(false);
+    let (status, detail) = if tracking == 0 {
+        (
+            CheckStatus::Pass,
+            "No tracking cookies were set".to_string(),
+        )
+    } else if over.is_empty() {
+        (
+            CheckStatus::Pass,
+            format!("All {} tracking cookies expire within 13 months", tracking),
+        )
+    } else {
+        (
+            CheckStatus::Fail,
+            format!("Live longer than 13 months: {}", over.join(", ")),
+        )
+    };
+    checks.push(ComplianceCheck {
+        id: "CNIL-01",
+        title: "Tracking cookies expire within 13 months",
+        reference: "CNIL Recommendation 2020-092, Art. 2",
+        status,
+        detail,
+    });
+
+    let (records, over) = too_long(true);
+    let (status, detail) = if records == 0 {
+        (
+            CheckStatus::NotChecked,
+            "No consent cookie of a known CMP was found".to_string(),
+        )
+    } else if over.is_empty() {
+        (
+            CheckStatus::Pass,
+            "The consent choice is kept for at most 13 months".to_string(),
+        )
+    } else {
+        (
+            CheckStatus::Warn,
+            format!(
+                "Consent is stored for longer than 13 months: {}",
+                over.join(", ")
+            ),
+        )
+    };
+    checks.push(ComplianceCheck {
+        id: "CNIL-02",
+        title: "Consent choice is renewed within 13 months",
+        reference: "CNIL Recommendation 2020-092, Art. 2",
+        status,
+        detail,
+    });
+
+    checks
+}
+
+fn dsgvo(result: &AnalysisResult, html: &str, page_url: &Url) -> Vec<ComplianceCheck> {
+    let mut checks = Vec::new();
+
+    // § 25 TDDDG exempts only what is strictly necessary, so anything that
+    // could not be classified is a risk rather than a pass
+    let unclassified: Vec<&str> = result
+        .cookies
+        .iter()
+        .filter(|c| c.category == CookieCategory::Unknown)
+        .map(|c| c.name.as_str())
+        .chain(
+            result
+                .storage
+                .iter()
+                .filter(|s| s.category == CookieCategory::Unknown)
+                .map(|s| s.key.as_str()),
+        )
+        .collect();
+    checks.push(ComplianceCheck {
+        id: "TDDDG-01",
+        title: "Only strictly necessary data is stored before consent",
+        reference: "TDDDG § 25",
+        status: if unclassified.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        },
+        detail: if unclassified.is_empty() {
+            "Every cookie and storage key set on first load is classified".to_string()
+        } else {
+            format!(
+                "Not shown to be strictly necessary: {}",
+                unclassified.join(", ")
+            )
+        },
+    });
+
+    let (status, detail) = match link_matching(html, page_url, IMPRESSUM_HINTS) {
+        Some(link) => (CheckStatus::Pass, link),
+        None => (
+            CheckStatus::Fail,
+            "No link to an Impressum found on the page".to_string(),
+        ),
+    };
+    checks.push(ComplianceCheck {
+        id: "DDG-01",
+        title: "Impressum is linked",
+        reference: "DDG § 5",
+        status,
+        detail,
+    });
+
+    checks
+}
+
+fn uk_ico(html: &str, page_url: &Url) -> Vec<ComplianceCheck> {
+    let (status, detail) = match link_matching(html, page_url, COOKIE_NOTICE_HINTS) {
+        Some(link) => (CheckStatus::Pass, link),
+        None => (
+            CheckStatus::Fail,
+            "No link to cookie information found on the page".to_string(),
+        ),
+    };
+    vec![ComplianceCheck {
+        id: "PECR-01",
+        title: "Clear and comprehensive cookie information is linked",
+        reference: "PECR Reg. 6(2)",
+        status,
+        detail,
+    }]
+}
+
+fn is_consent_cookie(name: &str) -> bool {
+    let name = name.to_lowercase();
+    CONSENT_COOKIE_HINTS.iter().any(|hint| name.contains(hint))
+}
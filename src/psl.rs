@@ -0,0 +1,50 @@
+//! Registrable domains (eTLD+1) from the Public Suffix List, so that
+//! `cdn.example.co.uk` is first-party on `www.example.co.uk` and
+//! `example.com.evil.net` is not first-party on `example.com`.
+
+use anyhow::{anyhow, Result};
+use publicsuffix::{List, Psl};
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+/// The list compiled into the binary, also shipped as `rules/public_suffix_list.dat`
+const PUBLIC_SUFFIX_LIST: &str = include_str!("../rules/public_suffix_list.dat");
+
+static LIST: OnceLock<List> = OnceLock::new();
+
+fn list() -> &'static List {
+    LIST.get_or_init(|| {
+        PUBLIC_SUFFIX_LIST
+            .parse()
+            .expect("bundled public suffix list is valid")
+    })
+}
+
+/// Use a newer list, such as one downloaded by `recon update-db`, instead
+/// of the bundled one. Must be called before the first lookup.
+pub fn use_list(contents: &str) -> Result<()> {
+    let list: List = contents
+        .parse()
+        .map_err(|e| anyhow!("Invalid public suffix list: {:?}", e))?;
+    let _ = LIST.set(list);
+    Ok(())
+}
+
+/// The registrable domain of `host`, e.g. `example.co.uk` for
+/// `cdn.example.co.uk`. Hosts that are themselves a public suffix, IP
+/// addresses and single-label names such as `localhost` are returned as-is.
+pub fn registrable_domain(host: &str) -> String {
+    let host = host.trim_start_matches('.').trim_end_matches('.').to_lowercase();
+    if host.parse::<IpAddr>().is_ok() {
+        return host;
+    }
+    match list().domain(host.as_bytes()) {
+        Some(domain) => String::from_utf8_lossy(domain.as_bytes()).into_owned(),
+        None => host,
+    }
+}
+
+/// Whether two hosts belong to the same site.
+pub fn same_site(a: &str, b: &str) -> bool {
+    registrable_domain(a) == registrable_domain(b)
+}
@@ -0,0 +1,99 @@
+//! The HTTP redirect chain in front of a page. The scan's client follows
+//! redirects silently, which hides the intermediate URLs and the cookies
+//! tracking redirectors and login flows set along the way.
+
+use crate::auth::SiteAccess;
+use crate::visitor::Visitor;
+use crate::{config, parse_cookie, throttle, usage, CookieInfo};
+use reqwest::header::{LOCATION, SET_COOKIE};
+use reqwest::redirect::Policy;
+use serde::Serialize;
+use url::Url;
+
+/// Hops followed before giving up, as many as the scan's client allows
+const MAX_HOPS: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    /// Names of the cookies this response set
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<String>,
+}
+
+/// Request `url` again without following redirects and record each hop up to
+/// and including the final response, with the cookies set by the hops before
+/// it. Cookies without a `Domain` attribute are given the host that set them
+/// when it is not the final one. A hop that fails ends the chain early.
+pub async fn trace(
+    url: &Url,
+    visitor: &Visitor,
+    access: &SiteAccess,
+) -> (Vec<RedirectHop>, Vec<CookieInfo>) {
+    let mut hops = Vec::new();
+    // (host that set it, cookie) for every hop but the last
+    let mut set_on_the_way: Vec<(String, CookieInfo)> = Vec::new();
+    let Ok(builder) = visitor.client_builder() else {
+        return (hops, Vec::new());
+    };
+    let Ok(client) = builder
+        .redirect(Policy::none())
+        .cookie_store(true)
+        .timeout(config::timeout())
+        .build()
+    else {
+        return (hops, Vec::new());
+    };
+
+    let mut current = url.clone();
+    while hops.len() < MAX_HOPS {
+        throttle::wait(&current).await;
+        let Ok(response) = access
+            .apply(client.get(current.clone()), &current, url)
+            .send()
+            .await
+        else {
+            break;
+        };
+        usage::request();
+        let set: Vec<CookieInfo> = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .map(parse_cookie)
+            .collect();
+        let next = response
+            .status()
+            .is_redirection()
+            .then(|| response.headers().get(LOCATION))
+            .flatten()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| current.join(location).ok());
+
+        hops.push(RedirectHop {
+            url: current.to_string(),
+            status: response.status().as_u16(),
+            cookies: set.iter().map(|c| c.name.clone()).collect(),
+        });
+        let Some(next) = next else {
+            break;
+        };
+        let host = current.host_str().unwrap_or("").to_string();
+        set_on_the_way.extend(set.into_iter().map(|cookie| (host.clone(), cookie)));
+        current = next;
+    }
+
+    let final_host = current.host_str().unwrap_or("");
+    let cookies = set_on_the_way
+        .into_iter()
+        .map(|(host, mut cookie)| {
+            if host != final_host && cookie.domain.is_none() {
+                cookie.domain = Some(host);
+            }
+            cookie
+        })
+        .collect();
+    (hops, cookies)
+}
@@ -0,0 +1,193 @@
+//! Estimating what each third party learns through the Referer header.
+
+use crate::is_third_party;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Policy browsers apply when the page does not set one
+const DEFAULT_POLICY: &str = "strict-origin-when-cross-origin";
+
+const VALID_POLICIES: &[&str] = &[
+    "no-referrer",
+    "no-referrer-when-downgrade",
+    "origin",
+    "origin-when-cross-origin",
+    "same-origin",
+    "strict-origin",
+    "strict-origin-when-cross-origin",
+    "unsafe-url",
+];
+
+/// How much of the page URL a request carries in its Referer header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferrerLevel {
+    None,
+    Origin,
+    Full,
+}
+
+/// What a third-party domain receives as the referrer.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferrerExposure {
+    pub domain: String,
+    /// The policy that applies to the most revealing request to this domain
+    pub policy: String,
+    pub level: ReferrerLevel,
+    /// The Referer value sent, if any
+    pub referrer: Option<String>,
+    /// The full URL sent looks like it contains personal data
+    pub sensitive: bool,
+}
+
+/// The page's referrer policy and what each third party receives.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferrerReport {
+    pub document_policy: String,
+    /// True when neither a header nor a meta tag set the policy
+    pub browser_default: bool,
+    pub exposures: Vec<ReferrerExposure>,
+}
+
+/// Work out the referrer every third-party subresource would be sent.
+pub fn analyze(page_url: &Url, header_policy: Option<&str>, html: &str) -> ReferrerReport {
+    let document = Html::parse_document(html);
+    let meta_selector = Selector::parse(r#"meta[name="referrer" i]"#).unwrap();
+    let resource_selector =
+        Selector::parse("script[src], img[src], iframe[src], link[href], video[src], audio[src]")
+            .unwrap();
+
+    // The meta tag is processed after the header, so it wins
+    let meta_policy = document
+        .select(&meta_selector)
+        .filter_map(|meta| meta.value().attr("content"))
+        .filter_map(parse_policy)
+        .next_back();
+    let explicit_policy = meta_policy.or_else(|| header_policy.and_then(parse_policy));
+    let browser_default = explicit_policy.is_none();
+    let document_policy = explicit_policy.unwrap_or_else(|| DEFAULT_POLICY.to_string());
+
+    let base_domain = page_url.domain().unwrap_or("");
+    let sensitive = looks_sensitive(page_url);
+    let mut by_domain: BTreeMap<String, ReferrerExposure> = BTreeMap::new();
+
+    for element in document.select(&resource_selector) {
+        let value = element.value();
+        let Some(target) = value.attr("src").or_else(|| value.attr("href")) else {
+            continue;
+        };
+        let Ok(target) = page_url.join(target) else {
+            continue;
+        };
+        let Some(domain) = target.domain() else {
+            continue;
+        };
+        if !is_third_party(domain, base_domain) {
+            continue;
+        }
+
+        let no_referrer_link = value.name() == "link"
+            && value.attr("rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("noreferrer"))
+            });
+        let policy = if no_referrer_link {
+            "no-referrer".to_string()
+        } else {
+            value
+                .attr("referrerpolicy")
+                .and_then(parse_policy)
+                .unwrap_or_else(|| document_policy.clone())
+        };
+
+        let level = referrer_level(&policy, page_url, &target);
+        let exposure = ReferrerExposure {
+            domain: domain.to_string(),
+            referrer: referrer_value(level, page_url),
+            sensitive: level == ReferrerLevel::Full && sensitive,
+            policy,
+            level,
+        };
+
+        match by_domain.get(domain) {
+            Some(existing) if existing.level >= exposure.level => {}
+            _ => {
+                by_domain.insert(domain.to_string(), exposure);
+            }
+        }
+    }
+
+    ReferrerReport {
+        document_policy,
+        browser_default,
+        exposures: by_domain.into_values().collect(),
+    }
+}
+
+/// Pick the last policy token the browser understands, as browsers do for
+/// comma-separated fallback lists.
+fn parse_policy(value: &str) -> Option<String> {
+    value
+        .split(',')
+        .map(|token| token.trim().to_ascii_lowercase())
+        .rfind(|token| VALID_POLICIES.contains(&token.as_str()))
+}
+
+/// Apply the Referrer Policy algorithm for a cross-origin request.
+fn referrer_level(policy: &str, page: &Url, target: &Url) -> ReferrerLevel {
+    let downgrade = page.scheme() == "https" && target.scheme() != "https";
+    match policy {
+        "no-referrer" | "same-origin" => ReferrerLevel::None,
+        "unsafe-url" => ReferrerLevel::Full,
+        "no-referrer-when-downgrade" if downgrade => ReferrerLevel::None,
+        "no-referrer-when-downgrade" => ReferrerLevel::Full,
+        "origin" | "origin-when-cross-origin" => ReferrerLevel::Origin,
+        _ if downgrade => ReferrerLevel::None,
+        _ => ReferrerLevel::Origin,
+    }
+}
+
+fn referrer_value(level: ReferrerLevel, page: &Url) -> Option<String> {
+    match level {
+        ReferrerLevel::None => None,
+        ReferrerLevel::Origin => Some(format!("{}/", page.origin().ascii_serialization())),
+        ReferrerLevel::Full => {
+            let mut full = page.clone();
+            full.set_fragment(None);
+            let _ = full.set_username("");
+            let _ = full.set_password(None);
+            Some(full.to_string())
+        }
+    }
+}
+
+/// Heuristic for URLs whose path or query carries personal data.
+fn looks_sensitive(page: &Url) -> bool {
+    let email = Regex::new(r"(?i)[a-z0-9._%+-]+(@|%40)[a-z0-9.-]+\.[a-z]{2,}").unwrap();
+    let long_number = Regex::new(r"\d{6,}").unwrap();
+    let personal_keys = [
+        "email", "mail", "user", "userid", "uid", "name", "phone", "tel", "token", "session",
+        "account", "customer", "order", "address", "zip", "dob",
+    ];
+
+    let path_and_query = format!("{}?{}", page.path(), page.query().unwrap_or(""));
+    if email.is_match(&path_and_query) {
+        return true;
+    }
+    if page
+        .path_segments()
+        .is_some_and(|mut segments| segments.any(|s| long_number.is_match(s)))
+    {
+        return true;
+    }
+    page.query_pairs().any(|(key, value)| {
+        let key = key.to_ascii_lowercase();
+        !value.is_empty()
+            && personal_keys
+                .iter()
+                .any(|k| key == *k || key.ends_with(&format!("_{}", k)))
+    })
+}
@@ -0,0 +1,91 @@
+//! Which cookies a site sets again on a repeat visit. A cookie whose expiry
+//! is pushed back on every hit never expires for a regular visitor, however
+//! short its declared lifetime.
+
+use crate::auth::SiteAccess;
+use crate::{parse_cookie, throttle, usage, CookieInfo};
+use anyhow::Result;
+use reqwest::header::SET_COOKIE;
+use serde::Serialize;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefreshBehavior {
+    /// Set again with a lifetime counted from this visit, so it never
+    /// expires while the visitor keeps coming back
+    Extended,
+    /// Set again with the same expiry, or again as a session cookie
+    Reissued,
+    /// Only set when the visitor did not have it yet
+    Once,
+}
+
+impl RefreshBehavior {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RefreshBehavior::Extended => "extended on every visit",
+            RefreshBehavior::Reissued => "reissued",
+            RefreshBehavior::Once => "set once",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CookieRefresh {
+    pub name: String,
+    pub behavior: RefreshBehavior,
+    /// Whether the repeat visit got a different value, e.g. a rotated ID
+    pub value_changed: bool,
+}
+
+/// Request `url` again with `client`, whose jar holds the cookies of the
+/// first visit, and compare the response's cookies with `first`.
+pub async fn probe(
+    client: &reqwest::Client,
+    url: &Url,
+    access: &SiteAccess,
+    first: &[CookieInfo],
+) -> Result<Vec<CookieRefresh>> {
+    throttle::wait(url).await;
+    let response = access
+        .apply(client.get(url.clone()), url, url)
+        .send()
+        .await?;
+    usage::request();
+    let again: Vec<CookieInfo> = response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .map(parse_cookie)
+        .collect();
+
+    Ok(first
+        .iter()
+        .map(|cookie| {
+            let reset = again.iter().find(|c| c.name == cookie.name);
+            let behavior = match reset {
+                None => RefreshBehavior::Once,
+                Some(reset) => {
+                    let relative = reset.max_age.is_some() || reset.expires != cookie.expires;
+                    if reset.lifetime().is_some() && relative {
+                        RefreshBehavior::Extended
+                    } else {
+                        RefreshBehavior::Reissued
+                    }
+                }
+            };
+            CookieRefresh {
+                name: cookie.name.clone(),
+                behavior,
+                value_changed: reset.is_some_and(|r| value(r) != value(cookie)),
+            }
+        })
+        .collect())
+}
+
+fn value(cookie: &CookieInfo) -> &str {
+    let pair = cookie.raw_header.split(';').next().unwrap_or_default();
+    pair.split_once('=').map_or("", |(_, v)| v.trim())
+}
@@ -0,0 +1,124 @@
+//! Detecting Related Website Set (formerly First-Party Set) declarations.
+
+use crate::{throttle, usage};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use url::Url;
+
+/// Where sites publish their set declaration, newest format first
+const WELL_KNOWN_PATHS: &[&str] = &[
+    "/.well-known/related-website-set.json",
+    "/.well-known/first-party-set.json",
+    "/.well-known/first-party-set",
+];
+
+/// A set of sites that declare themselves to be one party.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedWebsiteSet {
+    /// The declaration the members were read from
+    pub source: String,
+    pub primary: String,
+    pub associated_sites: Vec<String>,
+    pub service_sites: Vec<String>,
+    /// Country-code variants of the primary and member sites
+    pub cctld_sites: Vec<String>,
+}
+
+impl RelatedWebsiteSet {
+    /// Every site in the set, including the primary.
+    pub fn sites(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.primary)
+            .chain(&self.associated_sites)
+            .chain(&self.service_sites)
+            .chain(&self.cctld_sites)
+    }
+
+    /// Whether `domain` belongs to one of the set's sites.
+    pub fn contains(&self, domain: &str) -> bool {
+        let domain = domain.trim_start_matches('.');
+        self.sites()
+            .filter_map(|site| Url::parse(site).ok())
+            .filter_map(|site| site.host_str().map(str::to_string))
+            .any(|host| domain == host || domain.ends_with(&format!(".{}", host)))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Declaration {
+    primary: Option<String>,
+    /// First-Party Sets name for the primary
+    owner: Option<String>,
+    #[serde(default)]
+    associated_sites: Vec<String>,
+    #[serde(default)]
+    service_sites: Vec<String>,
+    /// First-Party Sets name for the member list
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default, rename = "ccTLDs")]
+    cctlds: HashMap<String, Vec<String>>,
+}
+
+/// Look for a set declaration on the page's origin. Member sites only name
+/// their primary, so the primary's declaration is fetched for the member list.
+pub async fn discover(client: &Client, page_url: &Url) -> Option<RelatedWebsiteSet> {
+    let (source, declaration) = fetch_declaration(client, page_url).await?;
+    let primary = declaration
+        .primary
+        .clone()
+        .or_else(|| declaration.owner.clone())?;
+
+    let is_member_only = declaration.associated_sites.is_empty()
+        && declaration.service_sites.is_empty()
+        && declaration.members.is_empty();
+    if is_member_only {
+        let primary_url = Url::parse(&primary).ok()?;
+        if primary_url.origin() != page_url.origin() {
+            let (source, declaration) = fetch_declaration(client, &primary_url).await?;
+            return Some(into_set(source, primary, declaration));
+        }
+    }
+
+    Some(into_set(source, primary, declaration))
+}
+
+async fn fetch_declaration(client: &Client, site: &Url) -> Option<(String, Declaration)> {
+    for path in WELL_KNOWN_PATHS {
+        let Ok(url) = site.join(path) else {
+            continue;
+        };
+        throttle::wait(&url).await;
+        let Ok(response) = client.get(url.clone()).send().await else {
+            continue;
+        };
+        usage::request();
+        if !response.status().is_success() {
+            continue;
+        }
+        let Ok(body) = response.text().await else {
+            continue;
+        };
+        usage::add_bytes(body.len());
+        if let Ok(declaration) = serde_json::from_str::<Declaration>(&body) {
+            return Some((url.to_string(), declaration));
+        }
+    }
+    None
+}
+
+fn into_set(source: String, primary: String, declaration: Declaration) -> RelatedWebsiteSet {
+    let mut associated_sites = declaration.associated_sites;
+    associated_sites.extend(declaration.members);
+    let mut cctld_sites: Vec<String> = declaration.cctlds.into_values().flatten().collect();
+    cctld_sites.sort();
+
+    RelatedWebsiteSet {
+        source,
+        primary,
+        associated_sites,
+        service_sites: declaration.service_sites,
+        cctld_sites,
+    }
+}
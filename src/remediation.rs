@@ -0,0 +1,144 @@
+//! Copy-pasteable fixes for common trackers that load without consent.
+
+use crate::preconsent;
+use crate::TrackerInfo;
+
+/// How to make a tracker wait for consent.
+#[derive(Debug, PartialEq)]
+pub struct Remediation {
+    pub title: &'static str,
+    /// HTML or JavaScript to adapt and paste into the page
+    pub snippet: &'static str,
+}
+
+const GOOGLE_CONSENT_MODE: Remediation = Remediation {
+    title: "Google Consent Mode v2: deny storage by default",
+    snippet: r#"<!-- Before the gtag.js or Google Tag Manager snippet -->
+<script>
+  window.dataLayer = window.dataLayer || [];
+  function gtag(){dataLayer.push(arguments);}
+  gtag('consent', 'default', {
+    ad_storage: 'denied',
+    ad_user_data: 'denied',
+    ad_personalization: 'denied',
+    analytics_storage: 'denied',
+    wait_for_update: 500
+  });
+</script>
+<!-- When the visitor accepts: -->
+<script>
+  gtag('consent', 'update', { analytics_storage: 'granted' });
+</script>"#,
+};
+
+const META_PIXEL: Remediation = Remediation {
+    title: "Meta Pixel: revoke consent until the visitor accepts",
+    snippet: r#"<script>
+  fbq('consent', 'revoke');   // before fbq('init', ...)
+  fbq('init', 'YOUR_PIXEL_ID');
+  fbq('track', 'PageView');
+</script>
+<!-- When the visitor accepts: fbq('consent', 'grant'); -->"#,
+};
+
+const TIKTOK_PIXEL: Remediation = Remediation {
+    title: "TikTok Pixel: hold events until the visitor accepts",
+    snippet: r#"<script>
+  ttq.holdConsent();   // before ttq.load(...)
+</script>
+<!-- When the visitor accepts: ttq.grantConsent(); on refusal: ttq.revokeConsent(); -->"#,
+};
+
+const MATOMO: Remediation = Remediation {
+    title: "Matomo: require consent before tracking",
+    snippet: r#"<script>
+  var _paq = window._paq = window._paq || [];
+  _paq.push(['requireConsent']);   // before trackPageView
+  _paq.push(['trackPageView']);
+</script>
+<!-- When the visitor accepts: _paq.push(['setConsentGiven']); -->"#,
+};
+
+const MIXPANEL: Remediation = Remediation {
+    title: "Mixpanel: start opted out",
+    snippet: r#"<script>
+  mixpanel.init('YOUR_TOKEN', { opt_out_tracking_by_default: true });
+</script>
+<!-- When the visitor accepts: mixpanel.opt_in_tracking(); -->"#,
+};
+
+const YOUTUBE: Remediation = Remediation {
+    title: "YouTube: embed from the privacy-enhanced domain",
+    snippet: r#"<iframe src="https://www.youtube-nocookie.com/embed/VIDEO_ID"
+        title="YouTube video" allowfullscreen></iframe>"#,
+};
+
+const VIMEO: Remediation = Remediation {
+    title: "Vimeo: disable session tracking in the player",
+    snippet: r#"<iframe src="https://player.vimeo.com/video/VIDEO_ID?dnt=1"
+        title="Vimeo video" allowfullscreen></iframe>"#,
+};
+
+const BLOCK_MARKETING: Remediation = Remediation {
+    title: "Block the script until marketing consent",
+    snippet: r#"<!-- Inert until the consent manager swaps the type back to text/javascript -->
+<script type="text/plain" data-cookieconsent="marketing"
+        src="https://tracker.example/script.js"></script>"#,
+};
+
+const BLOCK_STATISTICS: Remediation = Remediation {
+    title: "Block the script until statistics consent",
+    snippet: r#"<!-- Inert until the consent manager swaps the type back to text/javascript -->
+<script type="text/plain" data-cookieconsent="statistics"
+        src="https://tracker.example/script.js"></script>"#,
+};
+
+/// Vendor-specific fixes, keyed by tracker rule pattern
+const TRACKER_REMEDIATIONS: &[(&str, &Remediation)] = &[
+    ("google-analytics", &GOOGLE_CONSENT_MODE),
+    ("googletagmanager", &GOOGLE_CONSENT_MODE),
+    ("gtag", &GOOGLE_CONSENT_MODE),
+    ("doubleclick", &GOOGLE_CONSENT_MODE),
+    ("adwords", &GOOGLE_CONSENT_MODE),
+    ("facebook.*pixel", &META_PIXEL),
+    ("fbevents", &META_PIXEL),
+    ("tiktok", &TIKTOK_PIXEL),
+    ("matomo", &MATOMO),
+    ("mixpanel", &MIXPANEL),
+    ("youtube.com", &YOUTUBE),
+    ("vimeo.com", &VIMEO),
+];
+
+/// The fix for a tracker: a vendor-specific one when known, otherwise
+/// blocking the script for trackers that need consent.
+pub fn for_tracker(tracker: &TrackerInfo) -> Option<&'static Remediation> {
+    if let Some((_, remediation)) = TRACKER_REMEDIATIONS
+        .iter()
+        .find(|(name, _)| *name == tracker.name)
+    {
+        return Some(remediation);
+    }
+    if !preconsent::tracker_needs_consent(&tracker.category) {
+        return None;
+    }
+    if tracker.category == "Analytics" || tracker.category == "A/B Testing" {
+        Some(&BLOCK_STATISTICS)
+    } else {
+        Some(&BLOCK_MARKETING)
+    }
+}
+
+/// Each applicable fix once, with the descriptions of the trackers it covers.
+pub fn for_trackers(trackers: &[TrackerInfo]) -> Vec<(&'static Remediation, Vec<&str>)> {
+    let mut fixes: Vec<(&'static Remediation, Vec<&str>)> = Vec::new();
+    for tracker in trackers {
+        let Some(remediation) = for_tracker(tracker) else {
+            continue;
+        };
+        match fixes.iter_mut().find(|(r, _)| *r == remediation) {
+            Some((_, covered)) => covered.push(&tracker.description),
+            None => fixes.push((remediation, vec![&tracker.description])),
+        }
+    }
+    fixes
+}
@@ -0,0 +1,79 @@
+//! Session replay tools. They record the mouse movements, clicks, keystrokes
+//! and form content of each visit so it can be played back, which puts them
+//! well beyond counting page views.
+
+use crate::{CookieInfo, TrackerInfo};
+use serde::Serialize;
+use url::Url;
+
+/// (vendor, hosts the recording script and data use, including subdomains)
+const VENDORS: &[(&str, &[&str])] = &[
+    ("Hotjar", &["hotjar.com", "hotjar.io"]),
+    ("FullStory", &["fullstory.com"]),
+    ("Microsoft Clarity", &["clarity.ms"]),
+    ("Mouseflow", &["mouseflow.com"]),
+    ("Smartlook", &["smartlook.com", "smartlook.cloud"]),
+    ("LogRocket", &["logrocket.io", "logrocket.com", "lr-ingest.io"]),
+    ("Contentsquare", &["contentsquare.net", "contentsquare.com"]),
+    ("Inspectlet", &["inspectlet.com"]),
+    ("Lucky Orange", &["luckyorange.com", "luckyorange.net"]),
+    ("Quantum Metric", &["quantummetric.com"]),
+    ("Glassbox", &["glassboxdigital.io"]),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReplay {
+    pub vendor: String,
+    /// Hosts, trackers and cookies that gave the vendor away
+    pub evidence: Vec<String>,
+}
+
+fn host_matches(host: &str, hosts: &[&str]) -> bool {
+    let host = host.to_ascii_lowercase();
+    hosts
+        .iter()
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+}
+
+/// Each session replay vendor found among the page's third-party hosts,
+/// trackers and cookies.
+pub fn detect(
+    third_party_requests: &[String],
+    trackers: &[TrackerInfo],
+    cookies: &[CookieInfo],
+) -> Vec<SessionReplay> {
+    let mut found = Vec::new();
+    for (vendor, hosts) in VENDORS {
+        let mut evidence: Vec<String> = third_party_requests
+            .iter()
+            .filter(|host| host_matches(host, hosts))
+            .cloned()
+            .collect();
+        for tracker in trackers {
+            let source_host = Url::parse(&tracker.source)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string));
+            if tracker.description.starts_with(vendor)
+                || source_host.is_some_and(|host| host_matches(&host, hosts))
+            {
+                let item = format!("{} tracker", tracker.description);
+                if !evidence.contains(&item) {
+                    evidence.push(item);
+                }
+            }
+        }
+        for cookie in cookies {
+            if cookie.vendor.as_deref() == Some(*vendor) {
+                evidence.push(format!("{} cookie", cookie.name));
+            }
+        }
+
+        if !evidence.is_empty() {
+            found.push(SessionReplay {
+                vendor: vendor.to_string(),
+                evidence,
+            });
+        }
+    }
+    found
+}
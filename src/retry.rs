@@ -0,0 +1,75 @@
+//! Retrying page requests that failed for reasons likely to pass: timeouts,
+//! refused or dropped connections and 5xx responses. Each page gets its own
+//! retries, so one flaky site cannot use up another's.
+
+use crate::{throttle, usage};
+use reqwest::{RequestBuilder, Response};
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::hash::{BuildHasher, Hasher};
+use std::io::ErrorKind;
+use std::time::Duration;
+use url::Url;
+
+/// Wait before the first retry; it doubles with each further one
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait between two attempts
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Send the request `build` makes for `url`, retrying up to `retries` times
+/// with exponential backoff and jitter while the outcome looks transient. The
+/// last outcome is returned either way.
+pub async fn send(
+    retries: u32,
+    url: &Url,
+    build: impl Fn() -> RequestBuilder,
+) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        throttle::wait(url).await;
+        let outcome = build().send().await;
+        usage::request();
+        let transient = match &outcome {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => is_transient(e),
+        };
+        if !transient || attempt >= retries {
+            return outcome;
+        }
+        tokio::time::sleep(backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Half the exponential delay plus a random share of the other half, so
+/// parallel scans that failed together do not retry together.
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_DELAY);
+    let random = RandomState::new().build_hasher().finish();
+    let jitter = (ceiling / 2).mul_f64((random % 1000) as f64 / 1000.0);
+    ceiling / 2 + jitter
+}
+
+fn is_transient(error: &reqwest::Error) -> bool {
+    if error.is_timeout() || error.is_connect() {
+        return true;
+    }
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+                    | ErrorKind::TimedOut
+            );
+        }
+        source = cause.source();
+    }
+    false
+}
@@ -0,0 +1,202 @@
+//! Honoring robots.txt while crawling, as RFC 9309 describes it: the group
+//! for recon's product token, or the `*` group without one, and the longest
+//! matching `Allow` or `Disallow` rule for each URL.
+
+use crate::auth::SiteAccess;
+use crate::visitor::Visitor;
+use crate::{config, throttle, usage};
+use std::collections::HashMap;
+use url::Url;
+
+/// The name recon answers to in `User-agent` lines
+const PRODUCT_TOKEN: &str = "recon";
+
+/// Most of a robots.txt file read; the RFC asks crawlers to parse at least
+/// 500 KiB
+const MAX_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The rules of one robots.txt that apply to recon.
+#[derive(Debug, Clone, Default)]
+pub struct Robots {
+    rules: Vec<Rule>,
+}
+
+impl Robots {
+    pub fn parse(body: &str) -> Self {
+        // (agents, rules) for each group in the file
+        let mut groups: Vec<(Vec<String>, Vec<Rule>)> = Vec::new();
+        let mut in_agent_lines = false;
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("");
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_agent_lines {
+                        groups.push((Vec::new(), Vec::new()));
+                        in_agent_lines = true;
+                    }
+                    if let Some((agents, _)) = groups.last_mut() {
+                        agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_agent_lines = false;
+                    // An empty Disallow allows everything, as no rule does
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some((_, rules)) = groups.last_mut() {
+                        rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let rules_for = |agent: &str| -> Vec<Rule> {
+            groups
+                .iter()
+                .filter(|(agents, _)| agents.iter().any(|a| a == agent))
+                .flat_map(|(_, rules)| rules.iter().cloned())
+                .collect()
+        };
+        let named = groups
+            .iter()
+            .any(|(agents, _)| agents.iter().any(|a| a == PRODUCT_TOKEN));
+        Robots {
+            rules: rules_for(if named { PRODUCT_TOKEN } else { "*" }),
+        }
+    }
+
+    /// Rules for a site whose robots.txt could not be read because of a
+    /// server or network error, which crawlers must treat as disallowing all.
+    fn unreachable() -> Self {
+        Robots {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+        }
+    }
+
+    /// Whether recon may fetch `url`. The longest matching rule decides, and
+    /// `Allow` wins a tie.
+    pub fn allows(&self, url: &Url) -> bool {
+        if url.path() == "/robots.txt" {
+            return true;
+        }
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, &target))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Match a path pattern where `*` stands for any characters and a final `$`
+/// anchors the end; without one the pattern is a prefix.
+fn pattern_matches(pattern: &str, target: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(rest) = target.strip_prefix(parts.next().unwrap_or("")) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let mut rest = rest;
+    for (i, part) in parts.iter().enumerate() {
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// robots.txt of each origin a crawl reaches, fetched the first time one of
+/// its URLs comes up.
+pub struct RobotsCache<'a> {
+    visitor: &'a Visitor,
+    access: &'a SiteAccess,
+    by_origin: HashMap<String, Robots>,
+}
+
+impl<'a> RobotsCache<'a> {
+    pub fn new(visitor: &'a Visitor, access: &'a SiteAccess) -> Self {
+        RobotsCache {
+            visitor,
+            access,
+            by_origin: HashMap::new(),
+        }
+    }
+
+    /// Whether the robots.txt of `url`'s origin lets recon fetch it.
+    pub async fn allows(&mut self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return true;
+        };
+        let origin = url.origin().ascii_serialization();
+        if !self.by_origin.contains_key(&origin) {
+            let robots = fetch(&url, self.visitor, self.access).await;
+            self.by_origin.insert(origin.clone(), robots);
+        }
+        self.by_origin[&origin].allows(&url)
+    }
+}
+
+/// Fetch and parse the robots.txt for `url`'s origin. A missing file allows
+/// everything; a server error or failed request allows nothing.
+async fn fetch(url: &Url, visitor: &Visitor, access: &SiteAccess) -> Robots {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return Robots::default();
+    };
+    let Ok(builder) = visitor.client_builder() else {
+        return Robots::unreachable();
+    };
+    let Ok(client) = builder.timeout(config::timeout()).build() else {
+        return Robots::unreachable();
+    };
+    throttle::wait(&robots_url).await;
+    let Ok(response) = access
+        .apply(client.get(robots_url.clone()), &robots_url, url)
+        .send()
+        .await
+    else {
+        return Robots::unreachable();
+    };
+    usage::request();
+    let status = response.status();
+    if status.is_client_error() {
+        return Robots::default();
+    }
+    if !status.is_success() {
+        return Robots::unreachable();
+    }
+    let Ok(bytes) = response.bytes().await else {
+        return Robots::unreachable();
+    };
+    usage::add_bytes(bytes.len());
+    let body = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_BYTES)]);
+    Robots::parse(&body)
+}
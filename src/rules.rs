@@ -0,0 +1,209 @@
+//! Tracker detection rules, bundled with the binary or loaded from a file.
+
+use crate::disconnect::DisconnectList;
+use crate::filters::{Filter, FilterList};
+use crate::preconsent::ViolationSeverity;
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use url::Url;
+
+/// The rules compiled into the binary, also shipped as `rules/trackers.yaml`
+const BUILTIN_RULES: &str = include_str!("../rules/trackers.yaml");
+
+/// Newest rules file format this build understands
+const RULES_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    version: u32,
+    trackers: Vec<RuleDef>,
+}
+
+/// A `--trackers` file: `[[tracker]]` tables with the same fields as the
+/// YAML rules.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlRulesFile {
+    #[serde(default, alias = "trackers")]
+    tracker: Vec<RuleDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDef {
+    pattern: String,
+    category: String,
+    description: String,
+    #[serde(default)]
+    generic: bool,
+    #[serde(default)]
+    word_boundary: bool,
+    #[serde(default)]
+    scope: Scope,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    severity: Option<ViolationSeverity>,
+}
+
+/// Which part of a URL a rule is matched against.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Scope {
+    #[default]
+    Url,
+    HostAndPath,
+}
+
+/// A compiled tracker rule.
+#[derive(Debug)]
+pub struct TrackerRule {
+    /// The rule's pattern as written, used as the tracker name in reports
+    pub name: String,
+    pub category: String,
+    pub description: String,
+    /// Broad enough to match unrelated URLs, so findings are low confidence
+    pub generic: bool,
+    /// Pre-consent severity, when the rule overrides the category's
+    pub severity: Option<ViolationSeverity>,
+    regex: Regex,
+    scope: Scope,
+    exclude: Vec<Regex>,
+}
+
+impl TrackerRule {
+    /// Check a resource URL, which may be relative.
+    pub fn matches_url(&self, url: &str) -> bool {
+        let target = match self.scope {
+            Scope::Url => url.to_string(),
+            Scope::HostAndPath => match Url::parse(url) {
+                Ok(parsed) => format!("{}{}", parsed.host_str().unwrap_or(""), parsed.path()),
+                Err(_) => url.split(['?', '#']).next().unwrap_or("").to_string(),
+            },
+        };
+        self.regex.is_match(&target) && !self.exclude.iter().any(|re| re.is_match(&target))
+    }
+
+    /// Check inline script or other text content.
+    pub fn matches_content(&self, content: &str) -> bool {
+        self.regex.is_match(content)
+    }
+
+    /// Whether the rule matches the hostname itself rather than just a path.
+    pub fn matches_host(&self, host: &str) -> bool {
+        self.regex.is_match(host)
+    }
+}
+
+/// The full set of tracker rules used for a scan.
+#[derive(Debug)]
+pub struct RuleSet {
+    pub trackers: Vec<TrackerRule>,
+    /// Adblock filter lists given with `--filter-list`
+    pub filter_lists: Vec<FilterList>,
+    /// Disconnect tracking protection list given with `--disconnect`
+    pub disconnect: Option<DisconnectList>,
+    /// Hosts given with `--ignore-domain`, lowercase
+    pub ignored_domains: Vec<String>,
+}
+
+impl RuleSet {
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_RULES).expect("bundled tracker rules are valid")
+    }
+
+    /// Load rules from a YAML file, replacing the bundled set.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rules file {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid rules file {}", path.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let file: RulesFile = serde_yaml::from_str(contents)?;
+        if file.version > RULES_VERSION {
+            bail!(
+                "rules format version {} is newer than this build supports ({})",
+                file.version,
+                RULES_VERSION
+            );
+        }
+
+        let trackers = file
+            .trackers
+            .into_iter()
+            .map(compile)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RuleSet {
+            trackers,
+            filter_lists: Vec::new(),
+            disconnect: None,
+            ignored_domains: Vec::new(),
+        })
+    }
+
+    /// Add the rules from a TOML pattern file. They are checked before the
+    /// existing rules, so a specific in-house pattern names a request that a
+    /// generic bundled one would also match.
+    pub fn add_toml(&mut self, path: &Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trackers file {}", path.display()))?;
+        let file: TomlRulesFile = toml::from_str(&contents)
+            .with_context(|| format!("Invalid trackers file {}", path.display()))?;
+        let added = file
+            .tracker
+            .into_iter()
+            .map(compile)
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Invalid trackers file {}", path.display()))?;
+        self.trackers.splice(0..0, added);
+        Ok(())
+    }
+
+    /// Whether `host` or a domain it belongs to was given with `--ignore-domain`.
+    pub fn is_ignored(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.ignored_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    }
+
+    /// The first filter list entry that blocks a request to `url` from a
+    /// page on `page_domain`.
+    pub fn filter_match(&self, url: &Url, page_domain: &str) -> Option<(&FilterList, &Filter)> {
+        self.filter_lists
+            .iter()
+            .find_map(|list| list.matches(url, page_domain).map(|filter| (list, filter)))
+    }
+}
+
+fn compile(def: RuleDef) -> Result<TrackerRule> {
+    let pattern = if def.word_boundary {
+        format!(r"(?i)\b(?:{})\b", def.pattern)
+    } else {
+        format!("(?i){}", def.pattern)
+    };
+    let regex = Regex::new(&pattern)
+        .with_context(|| format!("Invalid pattern in rule '{}'", def.pattern))?;
+    let exclude = def
+        .exclude
+        .iter()
+        .map(|e| {
+            Regex::new(&format!("(?i){}", e))
+                .with_context(|| format!("Invalid exclude pattern in rule '{}'", def.pattern))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(TrackerRule {
+        name: def.pattern,
+        category: def.category,
+        description: def.description,
+        generic: def.generic,
+        severity: def.severity,
+        regex,
+        scope: def.scope,
+        exclude,
+    })
+}
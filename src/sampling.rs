@@ -0,0 +1,69 @@
+//! Loading a page several times and separating findings seen on every load
+//! from intermittent ones such as rotating ad slots.
+
+use crate::crawl::merge;
+use crate::variance::{self, IntermittentFinding, ScoreSpread};
+use crate::{analyze_url_with, build_client, AnalysisResult, ScanOptions};
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SampleReport {
+    /// Loads that completed
+    pub samples: usize,
+    /// Every load started with an empty cookie jar
+    pub fresh_jar: bool,
+    /// Findings missing from at least one load; everything else was stable
+    pub intermittent: Vec<IntermittentFinding>,
+    /// Privacy score range across the loads
+    pub score: ScoreSpread,
+}
+
+/// Analyze `url` `samples` times and merge the loads into one result. Loads
+/// share a cookie jar, like a returning visitor, unless `fresh_jar` is set.
+/// Failed loads are skipped; only a failure of every load is an error.
+pub async fn sample(
+    url: &str,
+    options: &ScanOptions,
+    samples: usize,
+    fresh_jar: bool,
+    progress: impl Fn(usize),
+) -> Result<AnalysisResult> {
+    let shared = build_client()?;
+    let mut loads = Vec::new();
+    let mut last_error = None;
+
+    for n in 1..=samples {
+        progress(n);
+        let client = if fresh_jar {
+            build_client()?
+        } else {
+            shared.clone()
+        };
+        match analyze_url_with(&client, url, options).await {
+            Ok(result) => loads.push(result),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    if loads.is_empty() {
+        return Err(last_error.expect("at least one sample is taken"));
+    }
+
+    let completed = loads.len();
+    let intermittent = variance::intermittent_findings(&loads);
+    let score = variance::score_spread(&loads).expect("at least one load succeeded");
+
+    let mut loads = loads.into_iter();
+    let mut site = loads.next().expect("at least one load succeeded");
+    for load in loads {
+        merge(&mut site, load);
+    }
+    site.pages = vec![site.url.clone()];
+    site.sampling = Some(SampleReport {
+        samples: completed,
+        fresh_jar,
+        intermittent,
+        score,
+    });
+    Ok(site)
+}
@@ -0,0 +1,62 @@
+//! Scheduling a batch of scans so that sites on different domains run side
+//! by side while no one domain gets more than a few scans at once, however
+//! the URL list is ordered.
+
+use crate::psl;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+pub struct HostLimits {
+    total: Arc<Semaphore>,
+    per_host: Mutex<HashMap<String, Arc<Semaphore>>>,
+    max_per_host: usize,
+}
+
+/// Held for the duration of one scan.
+pub struct ScanSlot {
+    _host: OwnedSemaphorePermit,
+    _total: OwnedSemaphorePermit,
+}
+
+impl HostLimits {
+    pub fn new(concurrency: usize, max_per_host: usize) -> Self {
+        HostLimits {
+            total: Arc::new(Semaphore::new(concurrency.max(1))),
+            per_host: Mutex::new(HashMap::new()),
+            max_per_host: max_per_host.max(1),
+        }
+    }
+
+    /// Wait until `url`'s domain is below its limit, then for a free slot.
+    /// Scans waiting on a busy domain do not hold a slot, so scans of other
+    /// domains queued behind them start first; both queues are first come,
+    /// first served, so each domain's URLs keep their order.
+    pub async fn acquire(&self, url: &str) -> ScanSlot {
+        let host = self.host_semaphore(url);
+        let host = host.acquire_owned().await.expect("semaphore never closed");
+        let total = self
+            .total
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore never closed");
+        ScanSlot {
+            _host: host,
+            _total: total,
+        }
+    }
+
+    fn host_semaphore(&self, url: &str) -> Arc<Semaphore> {
+        let key = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(psl::registrable_domain))
+            .unwrap_or_else(|| url.to_string());
+        let mut per_host = self.per_host.lock().unwrap();
+        per_host
+            .entry(key)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+            .clone()
+    }
+}
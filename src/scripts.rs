@@ -0,0 +1,160 @@
+//! Fetching external scripts and extracting the resources they pull in.
+
+use crate::auth::SiteAccess;
+use crate::budget::FetchBudget;
+use crate::is_third_party;
+use crate::rules::RuleSet;
+use crate::{throttle, usage};
+use regex::Regex;
+use reqwest::{Client, RequestBuilder};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use url::Url;
+
+/// Maximum number of scripts fetched per page
+const MAX_SCRIPTS: usize = 25;
+
+/// A tracker URL referenced from inside an external script.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptDependency {
+    /// The script whose source contains the reference
+    pub script: String,
+    /// The referenced URL as written in the script
+    pub url: String,
+    pub domain: String,
+}
+
+/// Collect the absolute URLs of every `<script src>` on the page.
+pub fn script_urls(html: &str, base_url: &Url) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script[src]").unwrap();
+
+    let mut seen = HashSet::new();
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("src"))
+        .filter_map(|src| base_url.join(src).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .filter(|url| seen.insert(url.to_string()))
+        .take(MAX_SCRIPTS)
+        .collect()
+}
+
+/// Download the given scripts concurrently within `budget`, reading at most
+/// `max_bytes` of each and skipping any that fail. Scripts on `site` are
+/// requested with the `--header`, `--cookie` and credential options.
+pub async fn fetch_scripts(
+    client: &Client,
+    urls: Vec<Url>,
+    max_bytes: usize,
+    budget: &Arc<FetchBudget>,
+    access: &Arc<SiteAccess>,
+    site: &Url,
+) -> Vec<(Url, String)> {
+    let mut tasks = JoinSet::new();
+    for url in urls {
+        let client = client.clone();
+        let budget = budget.clone();
+        let access = access.clone();
+        let site = site.clone();
+        tasks.spawn(usage::inherit(async move {
+            let _permit = budget.start().await?;
+            let request = access.apply(client.get(url.clone()), &url, &site);
+            let body = fetch_capped(request, &url, max_bytes, &budget).await?;
+            Some((url, body))
+        }));
+    }
+
+    let mut scripts = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(Some(script)) = joined {
+            scripts.push(script);
+        }
+    }
+    scripts
+}
+
+async fn fetch_capped(
+    request: RequestBuilder,
+    url: &Url,
+    max_bytes: usize,
+    budget: &FetchBudget,
+) -> Option<String> {
+    throttle::wait(url).await;
+    let mut response = request.send().await.ok()?;
+    usage::request();
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.ok()? {
+        usage::add_bytes(chunk.len());
+        let wanted = chunk.len().min(max_bytes - body.len());
+        let granted = budget.take(wanted);
+        body.extend_from_slice(&chunk[..granted]);
+        if granted < chunk.len() {
+            break;
+        }
+    }
+    Some(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Find string literals in script bodies that point at known tracker hosts
+/// on a third-party domain.
+pub fn extract_dependencies(
+    scripts: &[(Url, String)],
+    base_domain: &str,
+    rules: &RuleSet,
+) -> Vec<ScriptDependency> {
+    let literal_re =
+        Regex::new(r#"["'`]((?:https?:)?//[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}[^"'`\s]*)["'`]"#).unwrap();
+
+    let mut seen = HashSet::new();
+    let mut dependencies = Vec::new();
+
+    for (script_url, body) in scripts {
+        for capture in literal_re.captures_iter(body) {
+            let literal = &capture[1];
+            let absolute = if literal.starts_with("//") {
+                format!("https:{}", literal)
+            } else {
+                literal.to_string()
+            };
+
+            let Ok(url) = Url::parse(&absolute) else {
+                continue;
+            };
+            let Some(domain) = url.domain() else {
+                continue;
+            };
+            if !is_third_party(domain, base_domain) {
+                continue;
+            }
+            let is_tracker = rules
+                .trackers
+                .iter()
+                .any(|rule| rule.matches_url(&absolute))
+                || rules.filter_match(&url, base_domain).is_some()
+                || rules
+                    .disconnect
+                    .as_ref()
+                    .is_some_and(|list| list.lookup(domain).is_some());
+            if !is_tracker {
+                continue;
+            }
+            if seen.insert((script_url.to_string(), absolute.clone())) {
+                dependencies.push(ScriptDependency {
+                    script: script_url.to_string(),
+                    url: absolute,
+                    domain: domain.to_string(),
+                });
+            }
+        }
+    }
+
+    dependencies
+}
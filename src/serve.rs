@@ -0,0 +1,239 @@
+//! `recon serve`: an HTTP API that queues scans and hands back their JSON
+//! results, for dashboards that would otherwise run the binary per request.
+//! A fixed number of workers take scans off a bounded queue; once it is
+//! full, new scans are turned away with 503 instead of piling up. `/metrics`
+//! has gauges for every site scanned.
+
+use crate::metrics::Metrics;
+use crate::output::json;
+use crate::{analyze_url, normalize_url, usage, ScanOptions};
+use anyhow::{anyhow, Context, Result};
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::mpsc;
+use url::Url;
+
+/// Finished scans kept for `GET /scan/{id}`; older ones are forgotten
+const MAX_FINISHED: usize = 1000;
+
+pub struct ServeOptions {
+    pub bind: IpAddr,
+    pub port: u16,
+    pub workers: usize,
+    pub queue: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+struct Job {
+    url: String,
+    status: Status,
+    submitted_at: SystemTime,
+    /// The site's entry of the JSON report
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JobView<'a> {
+    id: u64,
+    url: &'a str,
+    status: Status,
+    submitted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'a serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+impl Job {
+    fn view(&self, id: u64) -> JobView<'_> {
+        JobView {
+            id,
+            url: &self.url,
+            status: self.status,
+            submitted_at: humantime::format_rfc3339_seconds(self.submitted_at).to_string(),
+            result: self.result.as_ref(),
+            error: self.error.as_deref(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    by_id: BTreeMap<u64, Job>,
+}
+
+impl Jobs {
+    /// Drop the oldest finished scans beyond `MAX_FINISHED`.
+    fn forget_old(&mut self) {
+        let finished: Vec<u64> = self
+            .by_id
+            .iter()
+            .filter(|(_, job)| matches!(job.status, Status::Done | Status::Failed))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &finished[..finished.len().saturating_sub(MAX_FINISHED)] {
+            self.by_id.remove(id);
+        }
+    }
+}
+
+struct Server {
+    jobs: Mutex<Jobs>,
+    queue: mpsc::Sender<u64>,
+    metrics: Arc<Metrics>,
+}
+
+#[derive(Deserialize)]
+struct ScanRequest {
+    url: String,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    let body = serde_json::json!({ "error": message.into() });
+    (status, Json(body)).into_response()
+}
+
+/// Serve the API until interrupted.
+pub async fn run(serve: &ServeOptions, options: ScanOptions) -> Result<()> {
+    let (queue, receiver) = mpsc::channel(serve.queue);
+    let metrics = Arc::new(Metrics::default());
+    let server = Arc::new(Server {
+        jobs: Mutex::new(Jobs::default()),
+        queue,
+        metrics: metrics.clone(),
+    });
+    let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+    let options = Arc::new(options);
+    for _ in 0..serve.workers {
+        tokio::spawn(work(server.clone(), receiver.clone(), options.clone()));
+    }
+
+    let app = Router::new()
+        .route("/scan", post(submit))
+        .route("/scan/{id}", get(status))
+        .with_state(server)
+        .merge(metrics.router());
+    let address = SocketAddr::new(serve.bind, serve.port);
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Failed to listen on {}", address))?;
+    println!(
+        "  Listening on http://{} with {} worker(s) and room for {} queued scans",
+        address, serve.workers, serve.queue
+    );
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .context("Server failed")
+}
+
+/// Take scans off the queue one at a time until the server stops.
+async fn work(
+    server: Arc<Server>,
+    receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<u64>>>,
+    options: Arc<ScanOptions>,
+) {
+    loop {
+        let Some(id) = receiver.lock().await.recv().await else {
+            return;
+        };
+        let url = {
+            let mut jobs = server.jobs.lock().unwrap();
+            let Some(job) = jobs.by_id.get_mut(&id) else {
+                continue;
+            };
+            job.status = Status::Running;
+            job.url.clone()
+        };
+
+        let (result, cost) = usage::measure(analyze_url(&url, &options)).await;
+        match &result {
+            Ok(analysis) => server.metrics.record(&url, analysis),
+            Err(_) => server.metrics.record_failure(&url),
+        }
+        let outcome = result.and_then(|mut analysis| {
+            analysis.resource_usage = Some(cost);
+            let rows = [(url.clone(), Ok(&analysis))];
+            let mut report = serde_json::to_value(json::build(&rows))
+                .map_err(|e| anyhow!("Failed to serialize the scan result: {}", e))?;
+            Ok(report["results"][0].take())
+        });
+
+        let mut jobs = server.jobs.lock().unwrap();
+        if let Some(job) = jobs.by_id.get_mut(&id) {
+            match outcome {
+                Ok(result) => {
+                    job.status = Status::Done;
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.status = Status::Failed;
+                    job.error = Some(e.to_string());
+                }
+            }
+        }
+        jobs.forget_old();
+    }
+}
+
+/// `POST /scan {"url": ...}`: queue a scan and answer with its id.
+async fn submit(State(server): State<Arc<Server>>, Json(request): Json<ScanRequest>) -> Response {
+    let url = normalize_url(request.url.trim());
+    if Url::parse(&url).map_or(true, |url| url.host_str().is_none()) {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!("'{}' is not a URL", request.url),
+        );
+    }
+
+    let mut jobs = server.jobs.lock().unwrap();
+    jobs.next_id += 1;
+    let id = jobs.next_id;
+    if server.queue.try_send(id).is_err() {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "The scan queue is full");
+    }
+    let job = Job {
+        url,
+        status: Status::Queued,
+        submitted_at: SystemTime::now(),
+        result: None,
+        error: None,
+    };
+    let body = Json(job.view(id)).into_response();
+    jobs.by_id.insert(id, job);
+    (
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/scan/{}", id))],
+        body,
+    )
+        .into_response()
+}
+
+/// `GET /scan/{id}`: the scan's status, and its result once done.
+async fn status(State(server): State<Arc<Server>>, Path(id): Path<u64>) -> Response {
+    let jobs = server.jobs.lock().unwrap();
+    match jobs.by_id.get(&id) {
+        Some(job) => Json(job.view(id)).into_response(),
+        None => error(StatusCode::NOT_FOUND, format!("No scan with id {}", id)),
+    }
+}
@@ -0,0 +1,221 @@
+//! Testing whether a site honors a browser privacy signal, by scanning it
+//! without and then with the signal and comparing the tracking each gets.
+
+use crate::preconsent::{needs_consent, tracker_needs_consent};
+use crate::variants::{self, VariantFindings};
+use crate::visitor::Visitor;
+use crate::{analyze_url, AnalysisResult, ScanOptions};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Signal {
+    /// Global Privacy Control, `Sec-GPC: 1`
+    Gpc,
+    /// Do Not Track, `DNT: 1`
+    Dnt,
+}
+
+impl Signal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Signal::Gpc => "gpc",
+            Signal::Dnt => "dnt",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Signal::Gpc => "Global Privacy Control",
+            Signal::Dnt => "Do Not Track",
+        }
+    }
+
+    /// The request header that carries the signal.
+    pub fn header(self) -> &'static str {
+        match self {
+            Signal::Gpc => "Sec-GPC: 1",
+            Signal::Dnt => "DNT: 1",
+        }
+    }
+
+    /// The `navigator` property scripts read the signal from.
+    fn script_property(self) -> &'static str {
+        match self {
+            Signal::Gpc => "globalPrivacyControl",
+            Signal::Dnt => "doNotTrack",
+        }
+    }
+
+    /// Response headers a site answers the signal with.
+    fn response_headers(self) -> &'static [&'static str] {
+        match self {
+            Signal::Gpc => &[],
+            Signal::Dnt => &["DNT", "Tk", "X-Do-Not-Track"],
+        }
+    }
+
+    fn send(self, visitor: &mut Visitor) {
+        match self {
+            Signal::Gpc => visitor.gpc = true,
+            Signal::Dnt => visitor.dnt = true,
+        }
+    }
+
+    /// What the site says about the signal, when `.well-known` was probed.
+    fn declared(self, baseline: &AnalysisResult) -> Option<bool> {
+        let well_known = baseline.well_known.as_ref()?;
+        match self {
+            Signal::Gpc => well_known.gpc.as_ref().map(|d| d.gpc),
+            // A published policy is the declaration; its absence says nothing
+            Signal::Dnt => well_known
+                .endpoints
+                .iter()
+                .any(|e| e.found && e.path.ends_with("dnt-policy.txt"))
+                .then_some(true),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalVerdict {
+    /// No tracking remained with the signal
+    Honored,
+    /// Some tracking went away, some remained
+    Partial,
+    /// Nothing the signal should stop went away
+    Ignored,
+    /// There was no tracking to stop in the first place
+    NothingToSuppress,
+}
+
+impl SignalVerdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignalVerdict::Honored => "honored",
+            SignalVerdict::Partial => "partially honored",
+            SignalVerdict::Ignored => "ignored",
+            SignalVerdict::NothingToSuppress => "nothing to suppress",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalTest {
+    pub signal: Signal,
+    pub verdict: SignalVerdict,
+    /// What the site declares, e.g. in `/.well-known/gpc.json`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared: Option<bool>,
+    /// Where the page reads the signal in its scripts or echoes it back in
+    /// response headers, whatever it then does with it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub echoed: Vec<String>,
+    /// Findings that went away when the signal was sent
+    pub only_without: VariantFindings,
+    /// Findings that only appeared when the signal was sent
+    pub only_with: VariantFindings,
+    /// Consent-requiring cookies and trackers still present with the signal
+    pub still_tracking: Vec<String>,
+}
+
+/// Cookies and trackers in the categories a privacy signal opts out of.
+fn tracking(result: &AnalysisResult) -> Vec<String> {
+    let cookies = result
+        .cookies
+        .iter()
+        .filter(|c| needs_consent(&c.category))
+        .map(|c| c.name.clone());
+    let trackers = result
+        .trackers
+        .iter()
+        .filter(|t| tracker_needs_consent(&t.category))
+        .map(|t| t.name.clone());
+    let mut names: Vec<String> = cookies.chain(trackers).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Where the page scanned with `signal` reads or echoes it.
+fn echoed(signal: Signal, result: &AnalysisResult) -> Vec<String> {
+    let mut found = Vec::new();
+    let property = Regex::new(&format!(r"\b{}\b", signal.script_property())).unwrap();
+    let reads = property.find_iter(&result.html).count();
+    if reads > 0 {
+        found.push(format!(
+            "page scripts read navigator.{} ({}x)",
+            signal.script_property(),
+            reads
+        ));
+    }
+    for header in &result.legacy_headers {
+        if signal
+            .response_headers()
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&header.name))
+        {
+            found.push(format!("response header {}: {}", header.name, header.value));
+        }
+    }
+    found
+}
+
+/// Analyze `url` as an ordinary visitor and once more per signal, and attach
+/// how each signal changed the page to the ordinary visitor's result.
+pub async fn test(
+    url: &str,
+    options: &ScanOptions,
+    signals: &[Signal],
+    progress: impl Fn(Option<Signal>),
+) -> Result<AnalysisResult> {
+    progress(None);
+    let mut baseline = analyze_url(url, options).await?;
+    let baseline_tracking = tracking(&baseline);
+
+    for &signal in signals {
+        progress(Some(signal));
+        let mut visitor = options.visitor.clone();
+        signal.send(&mut visitor);
+        let options = ScanOptions {
+            visitor,
+            ..options.clone()
+        };
+        let with_signal = analyze_url(url, &options)
+            .await
+            .with_context(|| format!("Scan with {} failed", signal.header()))?;
+
+        let with_label = format!("with {}", signal.as_str());
+        let mut unique = variants::unique_findings(&[
+            ("without signal", &baseline),
+            (&with_label, &with_signal),
+        ])
+        .into_iter();
+        let only_without = unique.next().expect("one entry per scan");
+        let only_with = unique.next().expect("one entry per scan");
+        let still_tracking = tracking(&with_signal);
+        let verdict = if baseline_tracking.is_empty() {
+            SignalVerdict::NothingToSuppress
+        } else if still_tracking.is_empty() {
+            SignalVerdict::Honored
+        } else if still_tracking.len() < baseline_tracking.len() {
+            SignalVerdict::Partial
+        } else {
+            SignalVerdict::Ignored
+        };
+        let test = SignalTest {
+            signal,
+            verdict,
+            declared: signal.declared(&baseline),
+            echoed: echoed(signal, &with_signal),
+            only_without,
+            only_with,
+            still_tracking,
+        };
+        baseline.signal_tests.push(test);
+    }
+    Ok(baseline)
+}
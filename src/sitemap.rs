@@ -0,0 +1,141 @@
+//! Discovering a site's pages from its sitemap.xml files.
+
+use crate::auth::SiteAccess;
+use crate::{is_third_party, throttle, usage};
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use reqwest::Client;
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+use url::Url;
+
+/// Maximum number of sitemap files read when following sitemap indexes
+const MAX_SITEMAPS: usize = 50;
+
+/// Maximum size of a single (decompressed) sitemap file
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Collect the same-site page URLs listed in the site's sitemaps.
+///
+/// Sitemaps are taken from `Sitemap:` lines in robots.txt, falling back to
+/// `/sitemap.xml`, and sitemap indexes are followed.
+pub async fn page_urls(client: &Client, site: &Url, access: &SiteAccess) -> Result<Vec<String>> {
+    let base_domain = site.domain().unwrap_or("");
+
+    let mut queue: VecDeque<Url> = robots_sitemaps(client, site, access).await.into();
+    if queue.is_empty() {
+        queue.push_back(site.join("/sitemap.xml")?);
+    }
+
+    let mut visited = HashSet::new();
+    let mut seen_pages = HashSet::new();
+    let mut pages = Vec::new();
+
+    while let Some(sitemap_url) = queue.pop_front() {
+        if visited.len() >= MAX_SITEMAPS || !visited.insert(sitemap_url.to_string()) {
+            continue;
+        }
+
+        // Only the first sitemap is required to exist; later ones may be stale
+        let xml = match fetch_sitemap(client, &sitemap_url, site, access).await {
+            Ok(xml) => xml,
+            Err(e) if visited.len() == 1 => return Err(e),
+            Err(_) => continue,
+        };
+        let document = roxmltree::Document::parse(&xml)
+            .with_context(|| format!("Invalid sitemap XML at {}", sitemap_url))?;
+
+        let root = document.root_element();
+        for loc in root
+            .descendants()
+            .filter(|node| node.has_tag_name("loc"))
+            .filter_map(|node| node.text())
+            .filter_map(|text| Url::parse(text.trim()).ok())
+        {
+            if root.has_tag_name("sitemapindex") {
+                queue.push_back(loc);
+            } else if loc
+                .domain()
+                .is_some_and(|domain| !is_third_party(domain, base_domain))
+                && seen_pages.insert(loc.to_string())
+            {
+                pages.push(loc.to_string());
+            }
+        }
+    }
+
+    if pages.is_empty() {
+        bail!("No pages listed in the sitemap for {}", site);
+    }
+    Ok(pages)
+}
+
+/// Pick `limit` URLs spread evenly across the list, keeping its order.
+pub fn sample(urls: Vec<String>, limit: usize) -> Vec<String> {
+    if urls.len() <= limit {
+        return urls;
+    }
+    (0..limit)
+        .map(|i| urls[i * urls.len() / limit].clone())
+        .collect()
+}
+
+async fn robots_sitemaps(client: &Client, site: &Url, access: &SiteAccess) -> Vec<Url> {
+    let Ok(robots_url) = site.join("/robots.txt") else {
+        return Vec::new();
+    };
+    throttle::wait(&robots_url).await;
+    let request = access.apply(client.get(robots_url.clone()), &robots_url, site);
+    let Ok(response) = request.send().await else {
+        return Vec::new();
+    };
+    usage::request();
+    if !response.status().is_success() {
+        return Vec::new();
+    }
+    let Ok(body) = response.text().await else {
+        return Vec::new();
+    };
+    usage::add_bytes(body.len());
+
+    body.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("sitemap")
+                .then(|| Url::parse(value.trim()).ok())?
+        })
+        .collect()
+}
+
+async fn fetch_sitemap(
+    client: &Client,
+    url: &Url,
+    site: &Url,
+    access: &SiteAccess,
+) -> Result<String> {
+    throttle::wait(url).await;
+    let response = access
+        .apply(client.get(url.clone()), url, site)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch sitemap {}", url))?;
+    usage::request();
+    if !response.status().is_success() {
+        bail!("Sitemap {} returned {}", url, response.status());
+    }
+    let bytes = response.bytes().await?;
+    usage::add_bytes(bytes.len());
+
+    // Gzipped sitemaps are common; detect them by magic number, not name
+    let mut xml = String::new();
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(&bytes[..])
+            .take(MAX_SITEMAP_BYTES)
+            .read_to_string(&mut xml)
+            .with_context(|| format!("Failed to decompress sitemap {}", url))?;
+    } else {
+        xml = String::from_utf8_lossy(&bytes).into_owned();
+    }
+    Ok(xml)
+}
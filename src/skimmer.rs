@@ -0,0 +1,166 @@
+//! Signatures of payment-card skimmers ("Magecart") in inline and fetched
+//! scripts. Checkout pages legitimately read card fields, so a script is only
+//! reported when it also sends data out, or carries a marker of a known
+//! skimmer kit.
+
+use crate::{Confidence, INLINE_SCRIPT_SOURCE};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::sync::OnceLock;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IndicatorKind {
+    /// Reads card fields or every form field on the page
+    FormGrab,
+    /// Sends data to a server outside the normal form submission
+    Exfiltration,
+    /// Hides its code or its destination
+    Obfuscation,
+    /// Matches a known skimmer kit
+    KnownKit,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkimmerFinding {
+    /// The script URL, or "inline script"
+    pub source: String,
+    /// Descriptions of the signatures that matched
+    pub indicators: Vec<String>,
+    pub confidence: Confidence,
+}
+
+/// (kind, description, case-insensitive regex)
+const SIGNATURES: &[(IndicatorKind, &str, &str)] = &[
+    (
+        IndicatorKind::FormGrab,
+        "reads card number, CVV or expiry fields",
+        r#"["'#\[](cc[_-]?num(ber)?|card[_-]?num(ber)?|cc[_-]?number|cvv2?|cvc|cc[_-]?exp|card[_-]?expir\w*)["'\]]"#,
+    ),
+    (
+        IndicatorKind::FormGrab,
+        "collects every input, select and textarea",
+        r#"querySelectorAll\(\s*["'][^"']*\b(input|select|textarea)\b"#,
+    ),
+    (
+        IndicatorKind::Exfiltration,
+        "sends data through an image request",
+        r"new\s+Image\s*\([^)]*\)\s*(;\s*\w+)?\.src\s*=",
+    ),
+    (
+        IndicatorKind::Exfiltration,
+        "sends data with navigator.sendBeacon",
+        r"navigator\.sendBeacon\s*\(",
+    ),
+    (
+        IndicatorKind::Exfiltration,
+        "opens a WebSocket",
+        r"new\s+WebSocket\s*\(",
+    ),
+    (
+        IndicatorKind::Exfiltration,
+        "base64-encodes collected data",
+        r"btoa\s*\(\s*(JSON\.stringify|encodeURIComponent|unescape)",
+    ),
+    (
+        IndicatorKind::Obfuscation,
+        "evaluates decoded code",
+        r"eval\s*\(\s*(atob|unescape|decodeURIComponent|String\.fromCharCode)\s*\(",
+    ),
+    (
+        IndicatorKind::Obfuscation,
+        "long hex-escaped string",
+        r"(\\x[0-9a-f]{2}){24,}",
+    ),
+    (
+        IndicatorKind::Obfuscation,
+        "builds strings from character codes",
+        r"String\.fromCharCode\s*\(\s*(\d+\s*,\s*){16,}",
+    ),
+    (
+        IndicatorKind::Obfuscation,
+        "hides a URL in base64",
+        r#"atob\s*\(\s*["']aHR0c"#,
+    ),
+    (
+        IndicatorKind::KnownKit,
+        "Inter skimmer kit functions",
+        r"\b(GetCCInfo|SaveParam|TrySend)\s*[(=:]",
+    ),
+    (
+        IndicatorKind::KnownKit,
+        "skimmer gate configuration",
+        r#"["']?Gate["']?\s*:\s*["']https?://"#,
+    ),
+];
+
+static COMPILED: OnceLock<Vec<(IndicatorKind, &str, Regex)>> = OnceLock::new();
+
+fn signatures() -> &'static [(IndicatorKind, &'static str, Regex)] {
+    COMPILED.get_or_init(|| {
+        SIGNATURES
+            .iter()
+            .map(|(kind, description, pattern)| {
+                let regex = Regex::new(&format!("(?i){}", pattern)).unwrap();
+                (*kind, *description, regex)
+            })
+            .collect()
+    })
+}
+
+/// Check one script's source.
+fn check(source: &str, content: &str) -> Option<SkimmerFinding> {
+    let matched: Vec<(IndicatorKind, &str)> = signatures()
+        .iter()
+        .filter(|(_, _, regex)| regex.is_match(content))
+        .map(|(kind, description, _)| (*kind, *description))
+        .collect();
+    let has = |kind| matched.iter().any(|(k, _)| *k == kind);
+
+    let confidence = if has(IndicatorKind::KnownKit)
+        || (has(IndicatorKind::FormGrab)
+            && has(IndicatorKind::Exfiltration)
+            && has(IndicatorKind::Obfuscation))
+    {
+        Confidence::High
+    } else if has(IndicatorKind::FormGrab) && has(IndicatorKind::Exfiltration) {
+        Confidence::Medium
+    } else {
+        return None;
+    };
+
+    Some(SkimmerFinding {
+        source: source.to_string(),
+        indicators: matched.iter().map(|(_, d)| d.to_string()).collect(),
+        confidence,
+    })
+}
+
+/// Scan the page's inline scripts and the scripts fetched with
+/// `--fetch-scripts`.
+pub fn scan(html: &str, fetched: &[(Url, String)]) -> Vec<SkimmerFinding> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script:not([src])").unwrap();
+
+    let mut findings: Vec<SkimmerFinding> = Vec::new();
+    for element in document.select(&selector) {
+        if let Some(mut finding) = check(INLINE_SCRIPT_SOURCE, &element.inner_html()) {
+            // Several inline scripts are reported as one source
+            match findings.iter_mut().find(|f| f.source == finding.source) {
+                Some(existing) => {
+                    finding
+                        .indicators
+                        .retain(|i| !existing.indicators.contains(i));
+                    existing.indicators.append(&mut finding.indicators);
+                    existing.confidence = existing.confidence.max(finding.confidence);
+                }
+                None => findings.push(finding),
+            }
+        }
+    }
+    for (url, body) in fetched {
+        findings.extend(check(url.as_str(), body));
+    }
+    findings
+}
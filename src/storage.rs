@@ -0,0 +1,78 @@
+//! Web Storage (localStorage and sessionStorage) entries seen in headless mode.
+
+use crate::{attribute_vendor, categorize_cookie, Confidence, CookieCategory};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageArea {
+    LocalStorage,
+    SessionStorage,
+}
+
+impl StorageArea {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageArea::LocalStorage => "localStorage",
+            StorageArea::SessionStorage => "sessionStorage",
+        }
+    }
+}
+
+/// One key in the page's Web Storage, categorized like a cookie name.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageItem {
+    pub area: StorageArea,
+    pub key: String,
+    /// Length of the stored value; values themselves are not reported
+    pub value_length: usize,
+    pub category: CookieCategory,
+    pub vendor: Option<String>,
+    pub confidence: Confidence,
+}
+
+/// A raw entry as read from the browser.
+#[derive(Debug, Deserialize)]
+pub struct RawStorageEntry {
+    pub area: StorageArea,
+    pub key: String,
+    pub length: usize,
+}
+
+/// Expression evaluated in the page to list both storage areas.
+pub const ENUMERATE_SCRIPT: &str = r#"(() => {
+    const entries = [];
+    for (const [area, store] of [["localStorage", window.localStorage], ["sessionStorage", window.sessionStorage]]) {
+        try {
+            for (let i = 0; i < store.length; i++) {
+                const key = store.key(i);
+                entries.push({ area, key, length: (store.getItem(key) || "").length });
+            }
+        } catch (e) {}
+    }
+    return entries;
+})()"#;
+
+/// Run storage keys through the same name patterns and vendor list as cookies.
+pub fn categorize(entries: Vec<RawStorageEntry>) -> Vec<StorageItem> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let (category, pattern_confidence) = categorize_cookie(&entry.key);
+            let vendor = attribute_vendor(&entry.key);
+            let confidence = if vendor.is_some() {
+                Confidence::High
+            } else {
+                pattern_confidence
+            };
+            StorageItem {
+                area: entry.area,
+                key: entry.key,
+                value_length: entry.length,
+                category,
+                vendor,
+                confidence,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,130 @@
+//! Requesting a page's scripts, images and iframes to see which cookies
+//! their responses set.
+
+use crate::auth::SiteAccess;
+use crate::budget::FetchBudget;
+use crate::visitor::Visitor;
+use crate::{parse_cookie, throttle, usage, CookieInfo, CookieSource};
+use anyhow::Result;
+use reqwest::header::{LOCATION, SET_COOKIE};
+use reqwest::{redirect, Client};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use url::Url;
+
+/// Maximum number of subresources requested per page
+const MAX_RESOURCES: usize = 50;
+
+/// Redirects followed per resource; cookie syncs often bounce several times
+const MAX_REDIRECTS: usize = 5;
+
+/// Collect the absolute URLs of the page's scripts, images and iframes.
+pub fn resource_urls(html: &str, base_url: &Url) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script[src], img[src], iframe[src]").unwrap();
+
+    let mut seen = HashSet::new();
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("src"))
+        .filter_map(|src| base_url.join(src).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .filter(|url| seen.insert(url.to_string()))
+        .take(MAX_RESOURCES)
+        .collect()
+}
+
+/// Request every resource within `budget` as `visitor` and return the
+/// cookies their responses set, including those on redirect hops,
+/// attributed to the responding host. Resources on `site` are requested
+/// with the `--header`, `--cookie` and credential options.
+pub async fn collect_cookies(
+    urls: Vec<Url>,
+    budget: &Arc<FetchBudget>,
+    visitor: &Visitor,
+    access: &Arc<SiteAccess>,
+    site: &Url,
+) -> Result<Vec<CookieInfo>> {
+    let client = visitor
+        .client_builder()?
+        .redirect(redirect::Policy::none())
+        .timeout(Duration::from_secs(15))
+        .build()?;
+
+    let mut tasks = JoinSet::new();
+    for url in urls {
+        let client = client.clone();
+        let budget = budget.clone();
+        let access = access.clone();
+        let site = site.clone();
+        tasks.spawn(usage::inherit(async move {
+            follow(&client, url, &budget, &access, &site).await
+        }));
+    }
+
+    let mut cookies: Vec<CookieInfo> = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        for cookie in joined.unwrap_or_default() {
+            if !cookies
+                .iter()
+                .any(|c| c.name == cookie.name && c.domain == cookie.domain)
+            {
+                cookies.push(cookie);
+            }
+        }
+    }
+    Ok(cookies)
+}
+
+async fn follow(
+    client: &Client,
+    mut url: Url,
+    budget: &FetchBudget,
+    access: &SiteAccess,
+    site: &Url,
+) -> Vec<CookieInfo> {
+    let mut cookies = Vec::new();
+
+    for _ in 0..=MAX_REDIRECTS {
+        // Every redirect hop is a request of its own
+        let Some(_permit) = budget.start().await else {
+            break;
+        };
+        throttle::wait(&url).await;
+        let request = access.apply(client.get(url.clone()), &url, site);
+        let Ok(response) = request.send().await else {
+            break;
+        };
+        usage::request();
+        let host = url.host_str().unwrap_or("").to_string();
+
+        for header in response.headers().get_all(SET_COOKIE) {
+            if let Ok(header) = header.to_str() {
+                let mut cookie = parse_cookie(header);
+                // Host-only cookies belong to whoever answered the request
+                cookie.domain.get_or_insert_with(|| host.clone());
+                cookie.source = CookieSource::Subresource;
+                cookie.set_by = Some(url.to_string());
+                cookies.push(cookie);
+            }
+        }
+
+        if !response.status().is_redirection() {
+            break;
+        }
+        let Some(next) = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| url.join(location).ok())
+        else {
+            break;
+        };
+        url = next;
+    }
+
+    cookies
+}
@@ -0,0 +1,235 @@
+//! Detecting the IAB Transparency & Consent Framework (TCF) and decoding the
+//! v2 consent string stored in the `euconsent-v2` cookie.
+
+use crate::CookieInfo;
+use serde::Serialize;
+
+/// Cookie the TCF's global and service-specific scopes store the TC string in
+const CONSENT_COOKIE: &str = "euconsent-v2";
+
+/// CMP IDs from the IAB's registered CMP list, for the common platforms
+const CMP_NAMES: &[(u16, &str)] = &[
+    (5, "Usercentrics"),
+    (6, "Sourcepoint"),
+    (7, "Didomi"),
+    (10, "Quantcast Choice"),
+    (21, "TrustArc"),
+    (28, "OneTrust"),
+    (134, "Cookiebot"),
+];
+
+/// Names of the ten TCF v2 purposes, indexed by purpose ID - 1
+const PURPOSES: &[&str] = &[
+    "Store and/or access information on a device",
+    "Use limited data to select advertising",
+    "Create profiles for personalised advertising",
+    "Use profiles to select personalised advertising",
+    "Create profiles to personalise content",
+    "Use profiles to select personalised content",
+    "Measure advertising performance",
+    "Measure content performance",
+    "Understand audiences through statistics or combinations of data",
+    "Develop and improve services",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TcfReport {
+    /// The page defines or stubs the `__tcfapi` function
+    pub api_present: bool,
+    /// The decoded `euconsent-v2` cookie, if one was set
+    pub consent: Option<TcString>,
+}
+
+/// The core segment of a TCF v2 consent string.
+#[derive(Debug, Clone, Serialize)]
+pub struct TcString {
+    pub version: u8,
+    /// Seconds since the Unix epoch
+    pub created: u64,
+    pub last_updated: u64,
+    pub cmp_id: u16,
+    pub cmp_name: Option<String>,
+    pub cmp_version: u16,
+    pub consent_language: String,
+    pub vendor_list_version: u16,
+    pub policy_version: u8,
+    /// Purpose IDs (1-10) the user is recorded as consenting to
+    pub purposes_consented: Vec<u8>,
+    /// Purpose IDs disclosed under legitimate interest
+    pub purposes_legitimate_interest: Vec<u8>,
+    pub special_features_opted_in: Vec<u8>,
+    /// Vendors with consent
+    pub vendors_consented: usize,
+    /// Vendors relying on legitimate interest
+    pub vendors_legitimate_interest: usize,
+}
+
+impl TcString {
+    /// Decode the core segment of a v2 TC string. Returns `None` for other
+    /// versions or malformed input.
+    pub fn decode(tc_string: &str) -> Option<Self> {
+        let core = tc_string.split('.').next()?;
+        let bytes = decode_base64url(core)?;
+        let mut bits = BitReader::new(&bytes);
+
+        let version = bits.read(6)? as u8;
+        if version != 2 {
+            return None;
+        }
+        let created = bits.read(36)? / 10;
+        let last_updated = bits.read(36)? / 10;
+        let cmp_id = bits.read(12)? as u16;
+        let cmp_version = bits.read(12)? as u16;
+        let _consent_screen = bits.read(6)?;
+        let consent_language: String = [bits.read(6)?, bits.read(6)?]
+            .iter()
+            .map(|&c| (b'A' + c as u8) as char)
+            .collect();
+        let vendor_list_version = bits.read(12)? as u16;
+        let policy_version = bits.read(6)? as u8;
+        let _is_service_specific = bits.read(1)?;
+        let _use_non_standard_texts = bits.read(1)?;
+        let special_features_opted_in = bits.read_flags(12)?;
+        let purposes_consented = bits.read_flags(24)?;
+        let purposes_legitimate_interest = bits.read_flags(24)?;
+        let _purpose_one_treatment = bits.read(1)?;
+        let _publisher_country = bits.read(12)?;
+        let vendors_consented = bits.read_vendor_section()?;
+        let vendors_legitimate_interest = bits.read_vendor_section()?;
+
+        Some(TcString {
+            version,
+            created,
+            last_updated,
+            cmp_id,
+            cmp_name: CMP_NAMES
+                .iter()
+                .find(|(id, _)| *id == cmp_id)
+                .map(|(_, name)| name.to_string()),
+            cmp_version,
+            consent_language,
+            vendor_list_version,
+            policy_version,
+            purposes_consented,
+            purposes_legitimate_interest,
+            special_features_opted_in,
+            vendors_consented,
+            vendors_legitimate_interest,
+        })
+    }
+}
+
+/// The name of a TCF v2 purpose.
+pub fn purpose_name(id: u8) -> &'static str {
+    PURPOSES
+        .get(usize::from(id).wrapping_sub(1))
+        .copied()
+        .unwrap_or("Unknown purpose")
+}
+
+/// Look for the TCF API in the page and the consent cookie among `cookies`.
+/// Returns `None` when the site shows no sign of the framework.
+pub fn detect(html: &str, cookies: &[CookieInfo]) -> Option<TcfReport> {
+    let api_present = html.contains("__tcfapi");
+    let consent = cookies
+        .iter()
+        .find(|c| c.name == CONSENT_COOKIE)
+        .and_then(|c| cookie_value(&c.raw_header))
+        .and_then(TcString::decode);
+
+    (api_present || consent.is_some()).then_some(TcfReport {
+        api_present,
+        consent,
+    })
+}
+
+fn cookie_value(raw_header: &str) -> Option<&str> {
+    let pair = raw_header.split(';').next()?;
+    pair.split_once('=').map(|(_, value)| value.trim())
+}
+
+/// Unpadded base64url, as used by TC strings.
+fn decode_base64url(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bit_count = 0;
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((buffer >> bit_count) as u8);
+            buffer &= (1 << bit_count) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    fn read(&mut self, count: usize) -> Option<u64> {
+        let mut value = 0u64;
+        for _ in 0..count {
+            let byte = self.bytes.get(self.position / 8)?;
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | u64::from(bit);
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    /// IDs (1-based) of the set bits in a fixed-length bit field.
+    fn read_flags(&mut self, count: usize) -> Option<Vec<u8>> {
+        let mut ids = Vec::new();
+        for id in 1..=count {
+            if self.read(1)? == 1 {
+                ids.push(id as u8);
+            }
+        }
+        Some(ids)
+    }
+
+    /// Count the vendors in a vendor consent or legitimate interest section,
+    /// which is either a bit field or a list of ID ranges.
+    fn read_vendor_section(&mut self) -> Option<usize> {
+        let max_vendor_id = self.read(16)? as usize;
+        let is_range_encoding = self.read(1)? == 1;
+        if !is_range_encoding {
+            let mut count = 0;
+            for _ in 0..max_vendor_id {
+                count += self.read(1)? as usize;
+            }
+            return Some(count);
+        }
+
+        let entries = self.read(12)?;
+        let mut count = 0;
+        for _ in 0..entries {
+            let is_range = self.read(1)? == 1;
+            let start = self.read(16)? as usize;
+            if is_range {
+                let end = self.read(16)? as usize;
+                count += end.saturating_sub(start) + 1;
+            } else {
+                count += 1;
+            }
+        }
+        Some(count)
+    }
+}
@@ -0,0 +1,49 @@
+//! Spacing out requests to the same site, so crawls and batches stay under
+//! the request rates that get scanners blocked by web application firewalls.
+//! The limit applies per registrable domain across all scans running at once.
+
+use crate::psl;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use url::Url;
+
+static PACE: OnceLock<Pace> = OnceLock::new();
+
+struct Pace {
+    interval: Duration,
+    /// When each domain may next be sent a request
+    next: Mutex<HashMap<String, Instant>>,
+}
+
+/// Keep at least `interval` between two requests to the same domain. Without
+/// a call, or with a zero interval, requests are not delayed.
+pub fn configure(interval: Duration) {
+    if !interval.is_zero() {
+        let _ = PACE.set(Pace {
+            interval,
+            next: Mutex::new(HashMap::new()),
+        });
+    }
+}
+
+/// Wait for `url`'s domain to be due another request. Waiting requests are
+/// given slots in the order they arrive.
+pub async fn wait(url: &Url) {
+    let Some(pace) = PACE.get() else {
+        return;
+    };
+    let key = url
+        .host_str()
+        .map(psl::registrable_domain)
+        .unwrap_or_default();
+    let slot = {
+        let mut next = pace.next.lock().unwrap();
+        let now = Instant::now();
+        let slot = next.get(&key).map_or(now, |due| (*due).max(now));
+        next.insert(key, slot + pace.interval);
+        slot
+    };
+    tokio::time::sleep_until(slot).await;
+}
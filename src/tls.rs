@@ -0,0 +1,267 @@
+//! The TLS connection and certificate of an HTTPS page: protocol, cipher,
+//! issuer, expiry and whether the certificate covers the host.
+
+use crate::config;
+use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls::{
+    Certificate, CertificateError, ClientConfig, OwnedTrustAnchor, ProtocolVersion, RootCertStore,
+    ServerName,
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use url::Url;
+use x509_parser::oid_registry::{OID_PKCS1_SHA1WITHRSA, OID_SIG_DSA_WITH_SHA1};
+use x509_parser::prelude::*;
+use x509_parser::public_key::PublicKey;
+
+/// Certificates expiring sooner than this are flagged
+const EXPIRY_WARNING_DAYS: i64 = 30;
+
+/// RSA keys shorter than this are flagged
+const MIN_RSA_BITS: usize = 2048;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TlsReport {
+    /// e.g. "TLS 1.3"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cipher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
+    /// Whether the chain verifies against the Mozilla root store
+    pub trusted: bool,
+    /// Expiring, expired, mismatched or weak certificates, and failed handshakes
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// HTTP date
+    pub not_after: String,
+    /// Negative once expired
+    pub days_left: i64,
+    /// DNS names in the subject alternative name extension
+    pub san: Vec<String>,
+    /// Whether one of `san` matches the page's host
+    pub covers_host: bool,
+    /// e.g. "RSA 2048" or "EC 256"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+/// Verifies as browsers would, but records the outcome instead of failing
+/// the handshake, so untrusted and expired certificates can be reported.
+struct RecordingVerifier {
+    inner: WebPkiVerifier,
+    outcome: Mutex<Option<rustls::Error>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let result = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        );
+        *self.outcome.lock().unwrap() = result.err();
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Connect to `url`'s host and report on the handshake. `None` for plain
+/// HTTP pages.
+pub async fn inspect(url: &Url) -> Option<TlsReport> {
+    if url.scheme() != "https" {
+        return None;
+    }
+    let host = url.host_str()?.to_string();
+    let port = url.port_or_known_default()?;
+    let report = match tokio::time::timeout(config::timeout(), handshake(&host, port)).await {
+        Ok(Ok(report)) => report,
+        Ok(Err(e)) => failed(format!(
+            "TLS handshake failed: {} (only TLS 1.2 and 1.3 are attempted)",
+            e
+        )),
+        Err(_) => failed("TLS handshake timed out".to_string()),
+    };
+    Some(report)
+}
+
+fn failed(issue: String) -> TlsReport {
+    TlsReport {
+        protocol: None,
+        cipher: None,
+        certificate: None,
+        trusted: false,
+        issues: vec![issue],
+    }
+}
+
+async fn handshake(host: &str, port: u16) -> anyhow::Result<TlsReport> {
+    let mut roots = RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+    let verifier = Arc::new(RecordingVerifier {
+        inner: WebPkiVerifier::new(roots, None),
+        outcome: Mutex::new(None),
+    });
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let stream = TcpStream::connect((host, port)).await?;
+    let server_name = ServerName::try_from(host)?;
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await?;
+    let (_, connection) = stream.get_ref();
+
+    let protocol = connection.protocol_version().map(|v| match v {
+        ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        other => format!("{:?}", other),
+    });
+    let cipher = connection
+        .negotiated_cipher_suite()
+        .map(|suite| format!("{:?}", suite.suite()));
+
+    let mut issues = Vec::new();
+    let verification = verifier.outcome.lock().unwrap().take();
+    let trusted = verification.is_none();
+    let certificate = connection
+        .peer_certificates()
+        .and_then(|chain| chain.first())
+        .and_then(|cert| describe(&cert.0, host, &mut issues));
+    // Expiry and name mismatches are reported from the certificate itself
+    if let Some(error) = verification {
+        let reported = matches!(
+            error,
+            rustls::Error::InvalidCertificate(
+                CertificateError::Expired | CertificateError::NotValidForName
+            )
+        );
+        if !reported {
+            issues.push(format!("Certificate is not trusted: {}", error));
+        }
+    }
+
+    Ok(TlsReport {
+        protocol,
+        cipher,
+        certificate,
+        trusted,
+        issues,
+    })
+}
+
+/// Parse the leaf certificate and note what is wrong with it.
+fn describe(der: &[u8], host: &str, issues: &mut Vec<String>) -> Option<CertificateInfo> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+
+    let not_after = cert.validity().not_after.timestamp();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let days_left = (not_after - now).div_euclid(86_400);
+    if days_left < 0 {
+        issues.push(format!("Certificate expired {} days ago", -days_left));
+    } else if days_left < EXPIRY_WARNING_DAYS {
+        issues.push(format!("Certificate expires in {} days", days_left));
+    }
+
+    let san: Vec<String> = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let covers_host = san.iter().any(|name| name_matches(name, host));
+    if !covers_host {
+        issues.push(format!("Certificate does not cover {}", host));
+    }
+
+    let key = match cert.public_key().parsed() {
+        Ok(PublicKey::RSA(rsa)) => {
+            let bits = rsa.key_size();
+            if bits < MIN_RSA_BITS {
+                issues.push(format!("Weak {}-bit RSA key", bits));
+            }
+            Some(format!("RSA {}", bits))
+        }
+        Ok(PublicKey::EC(ec)) => Some(format!("EC {}", ec.key_size())),
+        _ => None,
+    };
+    let signature = &cert.signature_algorithm.algorithm;
+    if *signature == OID_PKCS1_SHA1WITHRSA || *signature == OID_SIG_DSA_WITH_SHA1 {
+        issues.push("Certificate is signed with SHA-1".to_string());
+    }
+
+    Some(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: common_name(cert.issuer()).unwrap_or_else(|| cert.issuer().to_string()),
+        not_after: httpdate::fmt_http_date(
+            UNIX_EPOCH + Duration::from_secs(not_after.max(0) as u64),
+        ),
+        days_left,
+        san,
+        covers_host,
+        key,
+    })
+}
+
+/// "Let's Encrypt R3" rather than the whole distinguished name.
+fn common_name(name: &X509Name) -> Option<String> {
+    let cn = name.iter_common_name().next()?.as_str().ok()?;
+    match name
+        .iter_organization()
+        .next()
+        .and_then(|o| o.as_str().ok())
+    {
+        Some(org) if !cn.starts_with(org) => Some(format!("{} {}", org, cn)),
+        _ => Some(cn.to_string()),
+    }
+}
+
+/// Whether a SAN entry matches `host`; `*.` matches exactly one label.
+fn name_matches(name: &str, host: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    let host = host.to_ascii_lowercase();
+    match name.strip_prefix("*.") {
+        Some(suffix) => host
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+        None => name == host,
+    }
+}
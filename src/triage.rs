@@ -0,0 +1,211 @@
+//! One flat list of every finding across the scanned sites, narrowed with
+//! fuzzy queries and exported for triage, so a subset of a large crawl can
+//! be handed off without post-processing the full report.
+
+use crate::{vendors, AnalysisResult};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub site: String,
+    /// "cookie", "storage", "tracker" or "third-party domain"
+    pub kind: &'static str,
+    pub name: String,
+    pub category: String,
+    pub vendor: Option<String>,
+    /// Where it came from: the cookie's domain, storage area or tracker's resource URL
+    pub detail: Option<String>,
+    pub confidence: Option<String>,
+    /// Active before any consent choice
+    pub pre_consent: bool,
+}
+
+impl Finding {
+    /// Whether the finding fuzzy-matches `query`; an empty query matches all.
+    pub fn matches(&self, query: &str) -> bool {
+        fuzzy_matches(query, &self.haystack())
+    }
+
+    /// The text queries are matched against.
+    fn haystack(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {}",
+            self.kind,
+            if self.pre_consent { "pre-consent" } else { "" },
+            self.name,
+            self.category,
+            self.vendor.as_deref().unwrap_or(""),
+            self.detail.as_deref().unwrap_or(""),
+            self.site
+        )
+        .to_lowercase()
+    }
+}
+
+/// Every cookie, storage key, tracker and third-party domain of `results`.
+pub fn collect(results: &[&AnalysisResult]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for result in results {
+        let pre_consent = |kind: &str, name: &str| {
+            result
+                .pre_consent
+                .iter()
+                .any(|v| v.kind == kind && v.name == name)
+        };
+        for cookie in &result.cookies {
+            findings.push(Finding {
+                site: result.url.clone(),
+                kind: "cookie",
+                name: cookie.name.clone(),
+                category: cookie.category.as_str().to_string(),
+                vendor: cookie.vendor.clone(),
+                detail: cookie.domain.clone(),
+                confidence: Some(cookie.confidence.as_str().to_string()),
+                pre_consent: pre_consent("cookie", &cookie.name),
+            });
+        }
+        for item in &result.storage {
+            findings.push(Finding {
+                site: result.url.clone(),
+                kind: "storage",
+                name: item.key.clone(),
+                category: item.category.as_str().to_string(),
+                vendor: item.vendor.clone(),
+                detail: Some(item.area.as_str().to_string()),
+                confidence: Some(item.confidence.as_str().to_string()),
+                pre_consent: pre_consent("storage", &item.key),
+            });
+        }
+        for tracker in &result.trackers {
+            findings.push(Finding {
+                site: result.url.clone(),
+                kind: "tracker",
+                name: tracker.name.clone(),
+                category: tracker.category.clone(),
+                vendor: Some(
+                    vendors::lookup(&tracker.description)
+                        .map_or(tracker.description.as_str(), |v| v.name)
+                        .to_string(),
+                ),
+                detail: Some(tracker.source.clone()),
+                confidence: Some(tracker.confidence.as_str().to_string()),
+                pre_consent: pre_consent("tracker", &tracker.name),
+            });
+        }
+        for domain in &result.third_party_requests {
+            findings.push(Finding {
+                site: result.url.clone(),
+                kind: "third-party domain",
+                name: domain.clone(),
+                category: "Third-party".to_string(),
+                vendor: None,
+                detail: None,
+                confidence: None,
+                pre_consent: false,
+            });
+        }
+    }
+    findings
+}
+
+/// Whether every whitespace-separated term of `query` appears in `text` as
+/// a subsequence, so "gglanl" matches "google analytics".
+fn fuzzy_matches(query: &str, text: &str) -> bool {
+    query.to_lowercase().split_whitespace().all(|term| {
+        let mut chars = text.chars();
+        term.chars().all(|wanted| chars.any(|c| c == wanted))
+    })
+}
+
+/// The findings matching any of `queries`; all of them when there are none.
+pub fn select(findings: Vec<Finding>, queries: &[String]) -> Vec<Finding> {
+    if queries.is_empty() {
+        return findings;
+    }
+    findings
+        .into_iter()
+        .filter(|finding| queries.iter().any(|query| finding.matches(query)))
+        .collect()
+}
+
+/// Write `findings` to `path` as CSV when it ends in `.csv`, else as JSON.
+pub fn write(path: &Path, findings: &[Finding]) -> Result<()> {
+    let csv = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    let contents = if csv {
+        to_csv(findings)
+    } else {
+        serde_json::to_string_pretty(findings)?
+    };
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn to_csv(findings: &[Finding]) -> String {
+    let mut out = String::from("site,kind,name,category,vendor,detail,confidence,pre_consent\n");
+    for f in findings {
+        let fields = [
+            f.site.as_str(),
+            f.kind,
+            &f.name,
+            &f.category,
+            f.vendor.as_deref().unwrap_or(""),
+            f.detail.as_deref().unwrap_or(""),
+            f.confidence.as_deref().unwrap_or(""),
+            if f.pre_consent { "true" } else { "false" },
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        let _ = writeln!(out, "{}", row.join(","));
+    }
+    out
+}
+
+/// Quote `value` for CSV. Names and URLs come from the scanned sites, so a
+/// value a spreadsheet would run as a formula is prefixed with `'`.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_terms_match_as_subsequences() {
+        assert!(fuzzy_matches("", "anything"));
+        assert!(fuzzy_matches("gglanl", "google analytics"));
+        assert!(fuzzy_matches("GA cookie", "cookie _ga analytics"));
+        assert!(!fuzzy_matches("lanalg", "google analytics"));
+        assert!(!fuzzy_matches("ga pixel", "cookie _ga analytics"));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted() {
+        assert_eq!(csv_field("_ga"), "_ga");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_fields_never_start_a_formula() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-2"), "'-2");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+}
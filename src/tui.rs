@@ -0,0 +1,266 @@
+//! `--triage`: an interactive terminal view of every finding of a run, for
+//! slicing a large crawl without leaving the terminal. Typing after `/`
+//! filters the list fuzzily, space marks findings, and `e` writes the marked
+//! ones (or the filtered list when none are marked) to CSV or JSON.
+
+use crate::triage::{self, Finding};
+use anyhow::{bail, Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Cell, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::Path;
+
+const DEFAULT_EXPORT: &str = "findings.csv";
+
+/// What typed characters go to.
+enum Input {
+    /// Keys move, mark and export
+    Browse,
+    /// Keys edit the filter
+    Filter,
+    /// Keys edit the export path
+    Export(String),
+}
+
+struct Triage {
+    findings: Vec<Finding>,
+    query: String,
+    /// Indexes into `findings` of those matching `query`, in order
+    shown: Vec<usize>,
+    marked: HashSet<usize>,
+    table: TableState,
+    input: Input,
+    /// The outcome of the last export
+    status: Option<String>,
+}
+
+impl Triage {
+    fn new(findings: Vec<Finding>) -> Self {
+        let mut triage = Triage {
+            findings,
+            query: String::new(),
+            shown: Vec::new(),
+            marked: HashSet::new(),
+            table: TableState::default(),
+            input: Input::Browse,
+            status: None,
+        };
+        triage.refilter();
+        triage
+    }
+
+    fn refilter(&mut self) {
+        self.shown = (0..self.findings.len())
+            .filter(|&i| self.findings[i].matches(&self.query))
+            .collect();
+        let selected = match self.table.selected() {
+            _ if self.shown.is_empty() => None,
+            Some(row) => Some(row.min(self.shown.len() - 1)),
+            None => Some(0),
+        };
+        self.table.select(selected);
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.table.selected().and_then(|row| self.shown.get(row).copied())
+    }
+
+    fn move_by(&mut self, rows: isize) {
+        if self.shown.is_empty() {
+            return;
+        }
+        let row = self.table.selected().unwrap_or(0) as isize + rows;
+        self.table
+            .select(Some(row.clamp(0, self.shown.len() as isize - 1) as usize));
+    }
+
+    /// The findings an export writes: the marked ones, else those shown.
+    fn selection(&self) -> Vec<Finding> {
+        let wanted: Vec<usize> = if self.marked.is_empty() {
+            self.shown.clone()
+        } else {
+            let mut marked: Vec<usize> = self.marked.iter().copied().collect();
+            marked.sort_unstable();
+            marked
+        };
+        wanted.iter().map(|&i| self.findings[i].clone()).collect()
+    }
+
+    fn export(&mut self, path: &str) {
+        let findings = self.selection();
+        self.status = Some(match triage::write(Path::new(path), &findings) {
+            Ok(()) => format!("Exported {} findings to {}", findings.len(), path),
+            Err(e) => format!("{:#}", e),
+        });
+    }
+
+    /// Handle one key press; `false` once the user quits.
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            return false;
+        }
+        match &mut self.input {
+            Input::Browse => match code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Char('/') => {
+                    self.status = None;
+                    self.input = Input::Filter;
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+                KeyCode::PageDown => self.move_by(20),
+                KeyCode::PageUp => self.move_by(-20),
+                KeyCode::Home | KeyCode::Char('g') => self.move_by(isize::MIN / 2),
+                KeyCode::End | KeyCode::Char('G') => self.move_by(isize::MAX / 2),
+                KeyCode::Char(' ') => {
+                    if let Some(i) = self.current() {
+                        if !self.marked.remove(&i) {
+                            self.marked.insert(i);
+                        }
+                        self.move_by(1);
+                    }
+                }
+                KeyCode::Char('a') => self.marked.extend(self.shown.iter().copied()),
+                KeyCode::Char('n') => self.marked.clear(),
+                KeyCode::Char('e') => self.input = Input::Export(DEFAULT_EXPORT.to_string()),
+                _ => {}
+            },
+            Input::Filter => match code {
+                KeyCode::Enter | KeyCode::Esc => self.input = Input::Browse,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.refilter();
+                }
+                KeyCode::Down => self.move_by(1),
+                KeyCode::Up => self.move_by(-1),
+                _ => {}
+            },
+            Input::Export(path) => match code {
+                KeyCode::Esc => self.input = Input::Browse,
+                KeyCode::Enter => {
+                    let path = std::mem::take(path);
+                    self.input = Input::Browse;
+                    if !path.trim().is_empty() {
+                        self.export(path.trim());
+                    }
+                }
+                KeyCode::Backspace => {
+                    path.pop();
+                }
+                KeyCode::Char(c) => path.push(c),
+                _ => {}
+            },
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, list, prompt, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Line::from(format!(
+                " {} of {} findings, {} marked",
+                self.shown.len(),
+                self.findings.len(),
+                self.marked.len()
+            ))
+            .bold(),
+            title,
+        );
+
+        let rows = self.shown.iter().map(|&i| {
+            let finding = &self.findings[i];
+            let mark = if self.marked.contains(&i) { "[x]" } else { "[ ]" };
+            let row = Row::new([
+                Cell::from(mark),
+                Cell::from(finding.kind),
+                Cell::from(finding.name.as_str()),
+                Cell::from(finding.category.as_str()),
+                Cell::from(finding.vendor.as_deref().unwrap_or("")),
+                Cell::from(if finding.pre_consent { "pre-consent" } else { "" }),
+                Cell::from(finding.site.as_str()),
+            ]);
+            if self.marked.contains(&i) {
+                row.fg(Color::Yellow)
+            } else {
+                row
+            }
+        });
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(18),
+            Constraint::Fill(2),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+            Constraint::Length(11),
+            Constraint::Fill(2),
+        ];
+        let header = Row::new([
+            "", "Kind", "Name", "Category", "Vendor", "Consent", "Site",
+        ])
+        .add_modifier(Modifier::UNDERLINED);
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, list, &mut self.table);
+
+        let prompt_line = match &self.input {
+            Input::Filter => Line::from(format!(" /{}", self.query)),
+            Input::Export(path) => Line::from(format!(" Export to: {}", path)),
+            Input::Browse => match &self.status {
+                Some(status) => Line::from(format!(" {}", status)).fg(Color::Green),
+                None if !self.query.is_empty() => {
+                    Line::from(format!(" /{}", self.query)).fg(Color::DarkGray)
+                }
+                None => Line::default(),
+            },
+        };
+        frame.render_widget(prompt_line, prompt);
+
+        let keys = match self.input {
+            Input::Browse => {
+                " / filter  space mark  a mark shown  n unmark all  e export  q quit"
+            }
+            Input::Filter => " Type to filter, every term fuzzy-matched  enter/esc done",
+            Input::Export(_) => " .csv for CSV, anything else for JSON  enter write  esc cancel",
+        };
+        frame.render_widget(Line::from(keys).fg(Color::DarkGray), help);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.key(key.code, key.modifiers) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Browse `findings` until the user quits.
+pub fn browse(findings: Vec<Finding>) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        bail!("--triage needs an interactive terminal");
+    }
+    let mut terminal = ratatui::try_init().context("Failed to start the triage view")?;
+    let result = Triage::new(findings).run(&mut terminal);
+    ratatui::restore();
+    result
+}
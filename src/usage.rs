@@ -0,0 +1,76 @@
+//! What a scan itself costs: the requests it made, the bytes it read, how
+//! long it took and how much memory the process needed, for sizing the
+//! machines that run recon and spotting sites that are expensive to scan.
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+tokio::task_local! {
+    static METER: Arc<Meter>;
+}
+
+#[derive(Debug, Default)]
+struct Meter {
+    requests: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    /// HTTP requests made by recon itself, not by the headless browser
+    pub requests: usize,
+    /// Response body bytes read
+    pub bytes: usize,
+    pub wall_time_ms: u64,
+    /// Peak resident memory of the whole process so far, in KB; only
+    /// available on Linux
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_memory_kb: Option<u64>,
+}
+
+/// Count one request of the current scan. Does nothing outside [`measure`].
+pub fn request() {
+    let _ = METER.try_with(|meter| meter.requests.fetch_add(1, Ordering::Relaxed));
+}
+
+/// Count body bytes read by the current scan.
+pub fn add_bytes(bytes: usize) {
+    let _ = METER.try_with(|meter| meter.bytes.fetch_add(bytes, Ordering::Relaxed));
+}
+
+/// Carry the current scan's meter into `future`, for spawned tasks.
+pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let meter = METER.try_with(Arc::clone).ok();
+    async move {
+        match meter {
+            Some(meter) => METER.scope(meter, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Run `future` and report what it used.
+pub async fn measure<F: Future>(future: F) -> (F::Output, ResourceUsage) {
+    let meter = Arc::new(Meter::default());
+    let started = Instant::now();
+    let output = METER.scope(meter.clone(), future).await;
+    let usage = ResourceUsage {
+        requests: meter.requests.load(Ordering::Relaxed),
+        bytes: meter.bytes.load(Ordering::Relaxed),
+        wall_time_ms: started.elapsed().as_millis() as u64,
+        peak_memory_kb: peak_memory_kb(),
+    };
+    (output, usage)
+}
+
+/// The process's high-water mark of resident memory.
+fn peak_memory_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
@@ -0,0 +1,29 @@
+//! Named User-Agent presets for `--ua`, since sites serve different tag sets
+//! to desktop browsers, phones and crawlers.
+
+use crate::BROWSER_USER_AGENT;
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum UserAgentPreset {
+    /// Chrome on Windows, the default
+    ChromeDesktop,
+    /// Safari on an iPhone
+    Iphone,
+    /// Google's desktop crawler
+    Googlebot,
+}
+
+impl UserAgentPreset {
+    pub fn user_agent(self) -> &'static str {
+        match self {
+            UserAgentPreset::ChromeDesktop => BROWSER_USER_AGENT,
+            UserAgentPreset::Iphone => {
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1"
+            }
+            UserAgentPreset::Googlebot => {
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"
+            }
+        }
+    }
+}
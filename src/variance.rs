@@ -0,0 +1,156 @@
+//! Variance of findings and scores across repeated scans of the same page,
+//! whether `--samples` loads or `watch` runs, to tell genuine changes from
+//! programmatic-ad noise.
+
+use crate::{calculate_privacy_score, AnalysisResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A score range at least this wide is reported as volatile
+const VOLATILE_SCORE_RANGE: u32 = 10;
+
+/// A finding that did not appear in every run.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntermittentFinding {
+    /// "cookie", "tracker" or "third-party domain"
+    pub kind: &'static str,
+    pub name: String,
+    /// How many of the runs it was seen in
+    pub seen_in: usize,
+}
+
+/// Spread of the privacy score across runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreSpread {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl ScoreSpread {
+    pub fn from_scores(scores: &[u32]) -> Option<Self> {
+        let min = *scores.iter().min()?;
+        let max = *scores.iter().max()?;
+        let n = scores.len() as f64;
+        let mean = scores.iter().map(|&s| s as f64).sum::<f64>() / n;
+        let variance = scores
+            .iter()
+            .map(|&s| (s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        Some(ScoreSpread {
+            min,
+            max,
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// Whether the score moves enough between runs that a single change
+    /// should not be read as a regression.
+    pub fn is_volatile(&self) -> bool {
+        self.max - self.min >= VOLATILE_SCORE_RANGE
+    }
+}
+
+/// Score spread across `runs`, or `None` when there are none.
+pub fn score_spread(runs: &[AnalysisResult]) -> Option<ScoreSpread> {
+    let scores: Vec<u32> = runs.iter().map(calculate_privacy_score).collect();
+    ScoreSpread::from_scores(&scores)
+}
+
+/// Score spread and intermittent findings of a site across its recent
+/// `watch` runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Volatility {
+    /// How many runs the statistics cover, including the latest
+    pub runs: usize,
+    pub score: ScoreSpread,
+    pub intermittent: Vec<IntermittentFinding>,
+}
+
+impl Volatility {
+    /// Whether the site changes enough between runs that a single change
+    /// may be noise.
+    pub fn is_volatile(&self) -> bool {
+        self.score.is_volatile() || !self.intermittent.is_empty()
+    }
+}
+
+type FindingKey = (&'static str, String);
+
+/// The cookies, trackers and third-party domains of a run.
+pub fn findings_of(run: &AnalysisResult) -> Vec<FindingKey> {
+    let mut seen: Vec<FindingKey> = Vec::new();
+    for cookie in &run.cookies {
+        seen.push(("cookie", cookie_key(&cookie.name, cookie.domain.as_deref())));
+    }
+    seen.extend(run.trackers.iter().map(|t| ("tracker", t.name.clone())));
+    seen.extend(
+        run.third_party_requests
+            .iter()
+            .map(|d| ("third-party domain", d.clone())),
+    );
+    seen
+}
+
+/// The same, read back from a stored JSON report.
+pub fn findings_in_report(report: &serde_json::Value) -> Vec<FindingKey> {
+    let list = |field: &str| report[field].as_array().into_iter().flatten();
+    let mut seen: Vec<FindingKey> = Vec::new();
+    for cookie in list("cookies") {
+        if let Some(name) = cookie["name"].as_str() {
+            seen.push(("cookie", cookie_key(name, cookie["domain"].as_str())));
+        }
+    }
+    seen.extend(
+        list("trackers")
+            .filter_map(|t| t["name"].as_str())
+            .map(|name| ("tracker", name.to_string())),
+    );
+    seen.extend(
+        list("third_party_requests")
+            .filter_map(|d| d.as_str())
+            .map(|domain| ("third-party domain", domain.to_string())),
+    );
+    seen
+}
+
+fn cookie_key(name: &str, domain: Option<&str>) -> String {
+    match domain {
+        Some(domain) => format!("{} ({})", name, domain),
+        None => name.to_string(),
+    }
+}
+
+/// Cookies, trackers and third-party domains missing from at least one run,
+/// sorted by kind and name.
+pub fn intermittent_findings(runs: &[AnalysisResult]) -> Vec<IntermittentFinding> {
+    let runs: Vec<Vec<FindingKey>> = runs.iter().map(findings_of).collect();
+    intermittent(runs)
+}
+
+/// Findings missing from at least one of `runs`, each given as the findings
+/// of one run.
+pub fn intermittent(runs: Vec<Vec<FindingKey>>) -> Vec<IntermittentFinding> {
+    let total = runs.len();
+    let mut counts: BTreeMap<FindingKey, usize> = BTreeMap::new();
+    for mut seen in runs {
+        seen.sort();
+        seen.dedup();
+        for key in seen {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, seen_in)| *seen_in < total)
+        .map(|((kind, name), seen_in)| IntermittentFinding {
+            kind,
+            name,
+            seen_in,
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+//! Findings that only appear in one variant of a page, when the same page is
+//! scanned as different visitors (devices, locales) and compared.
+
+use crate::{calculate_privacy_score, AnalysisResult};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// What one variant of the page had that none of the others did.
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantFindings {
+    /// e.g. "desktop" or "mobile"
+    pub variant: String,
+    pub score: u32,
+    pub cookies: Vec<String>,
+    pub trackers: Vec<String>,
+    pub third_party_domains: Vec<String>,
+}
+
+impl VariantFindings {
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty() && self.trackers.is_empty() && self.third_party_domains.is_empty()
+    }
+}
+
+struct Seen {
+    cookies: BTreeSet<String>,
+    trackers: BTreeSet<String>,
+    third_party_domains: BTreeSet<String>,
+}
+
+impl Seen {
+    fn of(result: &AnalysisResult) -> Self {
+        Seen {
+            cookies: result
+                .cookies
+                .iter()
+                .map(|cookie| match &cookie.domain {
+                    Some(domain) => format!("{} ({})", cookie.name, domain),
+                    None => cookie.name.clone(),
+                })
+                .collect(),
+            trackers: result.trackers.iter().map(|t| t.name.clone()).collect(),
+            third_party_domains: result.third_party_requests.iter().cloned().collect(),
+        }
+    }
+}
+
+/// For each labelled run, the cookies, trackers and third-party domains no
+/// other run had, in the order the runs were given.
+pub fn unique_findings(runs: &[(&str, &AnalysisResult)]) -> Vec<VariantFindings> {
+    let seen: Vec<Seen> = runs.iter().map(|(_, result)| Seen::of(result)).collect();
+    let only_in = |index: usize, pick: fn(&Seen) -> &BTreeSet<String>| -> Vec<String> {
+        pick(&seen[index])
+            .iter()
+            .filter(|item| {
+                seen.iter()
+                    .enumerate()
+                    .all(|(other, s)| other == index || !pick(s).contains(*item))
+            })
+            .cloned()
+            .collect()
+    };
+
+    runs.iter()
+        .enumerate()
+        .map(|(index, (label, result))| VariantFindings {
+            variant: label.to_string(),
+            score: calculate_privacy_score(result),
+            cookies: only_in(index, |s| &s.cookies),
+            trackers: only_in(index, |s| &s.trackers),
+            third_party_domains: only_in(index, |s| &s.third_party_domains),
+        })
+        .collect()
+}
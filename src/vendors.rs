@@ -0,0 +1,199 @@
+//! Bundled metadata about the companies behind known cookies and trackers:
+//! where they are based and where their privacy and data processing terms live.
+
+use crate::AnalysisResult;
+
+pub struct Vendor {
+    /// Matched against the start of cookie vendor names and tracker descriptions
+    pub name: &'static str,
+    /// ISO 3166 country of the company's headquarters
+    pub country: &'static str,
+    pub privacy_policy: Option<&'static str>,
+    /// Data processing agreement or terms, for vendors that publish one
+    pub dpa: Option<&'static str>,
+}
+
+const fn vendor(
+    name: &'static str,
+    country: &'static str,
+    privacy_policy: Option<&'static str>,
+    dpa: Option<&'static str>,
+) -> Vendor {
+    Vendor {
+        name,
+        country,
+        privacy_policy,
+        dpa,
+    }
+}
+
+const GOOGLE_PRIVACY: Option<&str> = Some("https://policies.google.com/privacy");
+const GOOGLE_DPA: Option<&str> = Some("https://business.safety.google/processorterms/");
+const META_PRIVACY: Option<&str> = Some("https://www.facebook.com/privacy/policy/");
+const META_DPA: Option<&str> = Some("https://www.facebook.com/legal/terms/dataprocessing");
+const MICROSOFT_PRIVACY: Option<&str> = Some("https://privacy.microsoft.com/privacystatement");
+const MICROSOFT_DPA: Option<&str> = Some(
+    "https://www.microsoft.com/licensing/docs/view/Microsoft-Products-and-Services-Data-Protection-Addendum-DPA",
+);
+
+const VENDORS: &[Vendor] = &[
+    vendor("Google", "US", GOOGLE_PRIVACY, GOOGLE_DPA),
+    vendor("YouTube", "US", GOOGLE_PRIVACY, GOOGLE_DPA),
+    vendor("Facebook", "US", META_PRIVACY, META_DPA),
+    vendor("Instagram", "US", META_PRIVACY, META_DPA),
+    vendor("Meta", "US", META_PRIVACY, META_DPA),
+    vendor("Microsoft", "US", MICROSOFT_PRIVACY, MICROSOFT_DPA),
+    vendor(
+        "LinkedIn",
+        "US",
+        Some("https://www.linkedin.com/legal/privacy-policy"),
+        Some("https://legal.linkedin.com/dpa"),
+    ),
+    vendor("X (Twitter)", "US", Some("https://x.com/en/privacy"), None),
+    vendor("Twitter", "US", Some("https://x.com/en/privacy"), None),
+    vendor(
+        "Pinterest",
+        "US",
+        Some("https://policy.pinterest.com/en/privacy-policy"),
+        None,
+    ),
+    vendor(
+        "Snapchat",
+        "US",
+        Some("https://values.snap.com/privacy/privacy-policy"),
+        None,
+    ),
+    vendor(
+        "TikTok",
+        "CN",
+        Some("https://www.tiktok.com/legal/page/eea/privacy-policy/en"),
+        None,
+    ),
+    vendor(
+        "Amazon",
+        "US",
+        Some("https://aws.amazon.com/privacy/"),
+        Some("https://d1.awsstatic.com/legal/aws-dpa/aws-dpa.pdf"),
+    ),
+    vendor(
+        "Adobe",
+        "US",
+        Some("https://www.adobe.com/privacy/policy.html"),
+        None,
+    ),
+    vendor(
+        "Amplitude",
+        "US",
+        Some("https://amplitude.com/privacy"),
+        Some("https://amplitude.com/dpa"),
+    ),
+    vendor("Bugsnag", "US", None, None),
+    vendor(
+        "Cloudflare",
+        "US",
+        Some("https://www.cloudflare.com/privacypolicy/"),
+        Some("https://www.cloudflare.com/cloudflare-customer-dpa/"),
+    ),
+    vendor("Criteo", "FR", Some("https://www.criteo.com/privacy/"), None),
+    vendor("Drift", "US", None, None),
+    vendor(
+        "FullStory",
+        "US",
+        Some("https://www.fullstory.com/legal/privacy-policy/"),
+        Some("https://www.fullstory.com/legal/dpa/"),
+    ),
+    vendor("Heap", "US", Some("https://www.heap.io/privacy"), None),
+    vendor(
+        "Hotjar",
+        "MT",
+        Some("https://www.hotjar.com/legal/policies/privacy/"),
+        Some("https://www.hotjar.com/legal/support/dpa/"),
+    ),
+    vendor(
+        "HubSpot",
+        "US",
+        Some("https://legal.hubspot.com/privacy-policy"),
+        Some("https://legal.hubspot.com/dpa"),
+    ),
+    vendor(
+        "Intercom",
+        "US",
+        Some("https://www.intercom.com/legal/privacy"),
+        Some("https://www.intercom.com/legal/data-processing-agreement"),
+    ),
+    vendor("Marketo", "US", Some("https://www.adobe.com/privacy/policy.html"), None),
+    vendor(
+        "Mixpanel",
+        "US",
+        Some("https://mixpanel.com/legal/privacy-policy/"),
+        Some("https://mixpanel.com/legal/dpa/"),
+    ),
+    vendor(
+        "Optimizely",
+        "US",
+        Some("https://www.optimizely.com/legal/privacy-notice/"),
+        None,
+    ),
+    vendor("Outbrain", "US", Some("https://www.outbrain.com/legal/privacy"), None),
+    vendor("Pardot", "US", None, None),
+    vendor("Quantcast", "US", Some("https://www.quantcast.com/privacy/"), None),
+    vendor(
+        "Segment",
+        "US",
+        Some("https://segment.com/legal/privacy/"),
+        Some("https://segment.com/legal/dpa/"),
+    ),
+    vendor(
+        "Sentry",
+        "US",
+        Some("https://sentry.io/privacy/"),
+        Some("https://sentry.io/legal/dpa/"),
+    ),
+    vendor(
+        "Shopify",
+        "CA",
+        Some("https://www.shopify.com/legal/privacy"),
+        Some("https://www.shopify.com/legal/dpa"),
+    ),
+    vendor(
+        "Stripe",
+        "US",
+        Some("https://stripe.com/privacy"),
+        Some("https://stripe.com/legal/dpa"),
+    ),
+    vendor(
+        "Taboola",
+        "US",
+        Some("https://www.taboola.com/policies/privacy-policy"),
+        None,
+    ),
+    vendor("Vimeo", "US", Some("https://vimeo.com/privacy"), None),
+    vendor("VWO", "IN", Some("https://vwo.com/privacy-policy/"), None),
+    vendor("Yandex", "RU", Some("https://yandex.com/legal/confidential/"), None),
+    vendor("Zendesk", "US", None, None),
+    vendor("hCaptcha", "US", Some("https://www.hcaptcha.com/privacy"), None),
+    vendor("OneTrust", "US", Some("https://www.onetrust.com/privacy/"), None),
+];
+
+/// The vendor a cookie vendor name or tracker description belongs to.
+pub fn lookup(name: &str) -> Option<&'static Vendor> {
+    VENDORS.iter().find(|v| name.starts_with(v.name))
+}
+
+/// Each known vendor behind the page's cookies and trackers, once, in order
+/// of first appearance.
+pub fn detected(result: &AnalysisResult) -> Vec<&'static Vendor> {
+    let names = result
+        .cookies
+        .iter()
+        .filter_map(|c| c.vendor.as_deref())
+        .chain(result.trackers.iter().map(|t| t.description.as_str()));
+
+    let mut vendors: Vec<&'static Vendor> = Vec::new();
+    for vendor in names.filter_map(lookup) {
+        if !vendors.iter().any(|v| v.name == vendor.name) {
+            vendors.push(vendor);
+        }
+    }
+    vendors
+}
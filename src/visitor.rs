@@ -0,0 +1,67 @@
+//! Who a scan pretends to be: the device, the language and region a page is
+//! requested from, and the privacy signals sent. Sites serve different tags
+//! to each.
+
+use crate::config;
+use crate::devices::Device;
+use crate::locales::Locale;
+use anyhow::Result;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, USER_AGENT};
+use reqwest::{ClientBuilder, Proxy};
+use url::Url;
+
+/// The default visitor presents the configured User-Agent and nothing else.
+#[derive(Debug, Clone, Default)]
+pub struct Visitor {
+    pub device: Option<Device>,
+    pub locale: Option<Locale>,
+    /// Send `Sec-GPC: 1`, as browsers with Global Privacy Control on do
+    pub gpc: bool,
+    /// Send `DNT: 1`, the older Do Not Track signal
+    pub dnt: bool,
+}
+
+impl Visitor {
+    pub fn user_agent(&self) -> &'static str {
+        self.device
+            .map_or_else(config::user_agent, Device::user_agent)
+    }
+
+    pub fn accept_language(&self) -> Option<&str> {
+        self.locale.as_ref().map(|l| l.language.as_str())
+    }
+
+    /// Proxy that every request, and the headless browser, goes through.
+    pub fn proxy(&self) -> Option<&Url> {
+        self.locale.as_ref().and_then(|l| l.proxy.as_ref())
+    }
+
+    /// Privacy signal headers sent with every request.
+    pub fn signal_headers(&self) -> Vec<(&'static str, &'static str)> {
+        let mut headers = Vec::new();
+        if self.gpc {
+            headers.push(("Sec-GPC", "1"));
+        }
+        if self.dnt {
+            headers.push(("DNT", "1"));
+        }
+        headers
+    }
+
+    /// An HTTP client builder presenting this visitor on every request.
+    pub fn client_builder(&self) -> Result<ClientBuilder> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_str(self.user_agent())?);
+        if let Some(language) = self.accept_language() {
+            headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_str(language)?);
+        }
+        for (name, value) in self.signal_headers() {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        let mut builder = reqwest::Client::builder().default_headers(headers);
+        if let Some(proxy) = self.proxy() {
+            builder = builder.proxy(Proxy::all(proxy.as_str())?);
+        }
+        Ok(builder)
+    }
+}
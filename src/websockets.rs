@@ -0,0 +1,99 @@
+//! WebSocket endpoints. Chat widgets and some analytics send their data over
+//! a WebSocket, which never shows up as a script, image or frame, so the
+//! endpoints are taken from `ws://` and `wss://` strings in scripts and, with
+//! `--headless`, from the connections the browser actually opened.
+
+use crate::rules::RuleSet;
+use crate::{is_third_party, INLINE_SCRIPT_SOURCE};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::sync::OnceLock;
+use url::Url;
+
+static ENDPOINT: OnceLock<Regex> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebSocketEndpoint {
+    pub url: String,
+    pub domain: String,
+    pub third_party: bool,
+    /// The tracker rule the endpoint matches, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<String>,
+    /// The script the endpoint string was found in, or the page for
+    /// connections seen only in the browser
+    pub source: String,
+    /// The headless browser saw the page open this connection
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub observed: bool,
+}
+
+/// Endpoints named in the page's inline scripts and in `fetched` scripts,
+/// together with the connections in `observed` the browser opened.
+pub fn find(
+    html: &str,
+    page_url: &Url,
+    fetched: &[(Url, String)],
+    observed: &[String],
+    rules: &RuleSet,
+) -> Vec<WebSocketEndpoint> {
+    let base_domain = page_url.domain().unwrap_or("");
+    let mut endpoints: Vec<WebSocketEndpoint> = Vec::new();
+
+    let mut add = |raw: &str, source: &str, seen: bool| {
+        let Ok(url) = Url::parse(raw) else {
+            return;
+        };
+        let Some(host) = url.host_str() else {
+            return;
+        };
+        if !matches!(url.scheme(), "ws" | "wss") || rules.is_ignored(host) {
+            return;
+        }
+        // Query strings carry session tokens, so compare without them
+        let mut key = url.clone();
+        key.set_query(None);
+        key.set_fragment(None);
+        if let Some(existing) = endpoints.iter_mut().find(|e| e.url == key.as_str()) {
+            existing.observed |= seen;
+            return;
+        }
+        let lower = key.as_str().to_lowercase();
+        endpoints.push(WebSocketEndpoint {
+            url: key.to_string(),
+            domain: host.to_string(),
+            third_party: is_third_party(host, base_domain),
+            tracker: rules
+                .trackers
+                .iter()
+                .find(|rule| rule.matches_url(&lower))
+                .map(|rule| rule.description.clone()),
+            source: source.to_string(),
+            observed: seen,
+        });
+    };
+
+    // Template literals ("wss://${host}") are left out, as their host is
+    // only known at runtime
+    let endpoint = ENDPOINT.get_or_init(|| {
+        Regex::new(r#"\bwss?://[A-Za-z0-9.-]+(?::\d+)?(?:/[^\s"'`\\<>$]*)?"#).unwrap()
+    });
+    let document = Html::parse_document(html);
+    let inline = Selector::parse("script:not([src])").unwrap();
+    for element in document.select(&inline) {
+        for m in endpoint.find_iter(&element.inner_html()) {
+            add(m.as_str(), INLINE_SCRIPT_SOURCE, false);
+        }
+    }
+    for (script_url, body) in fetched {
+        for m in endpoint.find_iter(body) {
+            add(m.as_str(), script_url.as_str(), false);
+        }
+    }
+    for url in observed {
+        add(url, page_url.as_str(), true);
+    }
+
+    endpoints
+}
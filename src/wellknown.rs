@@ -0,0 +1,97 @@
+//! Probing `.well-known` endpoints where sites declare privacy practices.
+
+use crate::{throttle, usage};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Endpoints probed when no list is given
+pub const DEFAULT_PATHS: &[&str] = &[
+    "/.well-known/gpc.json",
+    "/.well-known/dnt-policy.txt",
+    "/.well-known/privacy.txt",
+    "/.well-known/dnt/",
+];
+
+/// Longest excerpt of an endpoint's body kept in the report
+const MAX_CONTENT_CHARS: usize = 2000;
+
+/// The result of requesting one endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct WellKnownEndpoint {
+    pub path: String,
+    pub url: String,
+    pub found: bool,
+    pub status: Option<u16>,
+    /// The start of the response body, for endpoints that were found
+    pub content: Option<String>,
+}
+
+/// A site's Global Privacy Control support resource.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpcDeclaration {
+    pub gpc: bool,
+    #[serde(rename = "lastUpdate", default)]
+    pub last_update: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WellKnownReport {
+    pub endpoints: Vec<WellKnownEndpoint>,
+    /// Parsed from `/.well-known/gpc.json` when present and valid
+    pub gpc: Option<GpcDeclaration>,
+}
+
+/// Request each path on the page's origin and record what is there.
+pub async fn probe(client: &Client, page_url: &Url, paths: &[String]) -> WellKnownReport {
+    let mut endpoints = Vec::new();
+    let mut gpc = None;
+
+    for path in paths {
+        let Ok(url) = page_url.join(path) else {
+            continue;
+        };
+        let (status, body) = fetch(client, &url).await;
+        let found = body.is_some();
+
+        if path.ends_with("gpc.json") {
+            if let Some(body) = &body {
+                gpc = serde_json::from_str::<GpcDeclaration>(body).ok();
+            }
+        }
+
+        endpoints.push(WellKnownEndpoint {
+            path: path.clone(),
+            url: url.to_string(),
+            found,
+            status,
+            content: body.map(|b| b.trim().chars().take(MAX_CONTENT_CHARS).collect()),
+        });
+    }
+
+    WellKnownReport { endpoints, gpc }
+}
+
+async fn fetch(client: &Client, url: &Url) -> (Option<u16>, Option<String>) {
+    throttle::wait(url).await;
+    let Ok(response) = client.get(url.clone()).send().await else {
+        return (None, None);
+    };
+    usage::request();
+    let status = response.status();
+
+    // Many sites answer unknown paths with their HTML 404 page and a 200
+    let is_html = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"));
+    if !status.is_success() || is_html {
+        return (Some(status.as_u16()), None);
+    }
+
+    let body = response.text().await.ok();
+    usage::add_bytes(body.as_ref().map_or(0, String::len));
+    (Some(status.as_u16()), body)
+}